@@ -227,11 +227,11 @@ fn bench_parallel_extraction(c: &mut Criterion) {
     // Sequential extraction (-j1)
     group.bench_function("sequential_j1", |b| {
         b.iter(|| {
-            let config = WalkerConfig {
-                extract_comments: true,
-                parallel_workers: 1, // Sequential
-                ..Default::default()
-            };
+            let config = WalkerConfig::builder()
+                .extract_comments(true)
+                .parallel_workers(1) // Sequential
+                .build()
+                .unwrap();
             let walker = StreamingWalker::new(config);
             let mut formatter = StreamingFormatter::new(OutputConfig::default());
             let _ = walker.walk_streaming(black_box(repo.path()), &mut formatter);
@@ -241,11 +241,11 @@ fn bench_parallel_extraction(c: &mut Criterion) {
     // Parallel extraction with auto-detect workers (-j0)
     group.bench_function("parallel_j0_auto", |b| {
         b.iter(|| {
-            let config = WalkerConfig {
-                extract_comments: true,
-                parallel_workers: 0, // Auto-detect
-                ..Default::default()
-            };
+            let config = WalkerConfig::builder()
+                .extract_comments(true)
+                .parallel_workers(0) // Auto-detect
+                .build()
+                .unwrap();
             let walker = StreamingWalker::new(config);
             let mut formatter = StreamingFormatter::new(OutputConfig::default());
             let _ = walker.walk_streaming(black_box(repo.path()), &mut formatter);
@@ -255,11 +255,11 @@ fn bench_parallel_extraction(c: &mut Criterion) {
     // Parallel extraction with 4 workers
     group.bench_function("parallel_j4", |b| {
         b.iter(|| {
-            let config = WalkerConfig {
-                extract_comments: true,
-                parallel_workers: 4,
-                ..Default::default()
-            };
+            let config = WalkerConfig::builder()
+                .extract_comments(true)
+                .parallel_workers(4)
+                .build()
+                .unwrap();
             let walker = StreamingWalker::new(config);
             let mut formatter = StreamingFormatter::new(OutputConfig::default());
             let _ = walker.walk_streaming(black_box(repo.path()), &mut formatter);
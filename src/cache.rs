@@ -0,0 +1,458 @@
+//! On-disk cache of extraction results
+//!
+//! Re-running fruit on a large repository re-extracts comments, types, TODOs
+//! and imports from files that have not changed. `ExtractionCache` remembers
+//! each result keyed by path, invalidating it when the file's modification
+//! time or size or the extraction settings change. The cache is loaded once
+//! per walk and written back, without the files that no longer exist, when
+//! the walk finishes.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use crate::comments::extract_first_comment_with_config;
+use crate::file_utils::ExtractionConfig;
+use crate::imports::{FileImports, extract_imports_with_config};
+use crate::todos::{TodoItem, TodoMatcher, extract_todos_with_config};
+use crate::types::{TypeSignature, extract_type_signatures_with_config};
+
+/// Cache file name inside the fruit cache directory.
+const CACHE_FILE_NAME: &str = "cache.bin";
+
+/// Identity of a file's contents, as far as the cache is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+struct FileStamp {
+    mtime_secs: u64,
+    mtime_nanos: u32,
+    size: u64,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Option<Self> {
+        let meta = path.metadata().ok()?;
+        let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            mtime_secs: mtime.as_secs(),
+            mtime_nanos: mtime.subsec_nanos(),
+            size: meta.len(),
+        })
+    }
+}
+
+/// Cached TODO markers as (marker_type, text, line, assignee).
+type CachedTodos = Vec<(String, String, usize, Option<String>)>;
+
+/// Cached imports as (external, std, internal).
+type CachedImports = (Vec<String>, Vec<String>, Vec<String>);
+
+/// Cached results for one file. The outer `Option` of each field is `None`
+/// until that kind of extraction has run for the current stamp and settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CacheEntry {
+    stamp: FileStamp,
+    /// Settings the results were extracted with
+    extraction: ExtractionConfig,
+    comment: Option<Option<String>>,
+    types: Option<Option<Vec<TypeSignature>>>,
+    todos: Option<Option<CachedTodos>>,
+    imports: Option<Option<CachedImports>>,
+}
+
+/// Serialized form of the cache file.
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    /// Version of fruit that wrote the cache; extractors change between
+    /// releases, so caches from other versions are discarded.
+    version: String,
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+/// Extraction results shared across a walk (including parallel workers).
+///
+/// A disabled cache calls straight through to the extractors, so walkers can
+/// use the same code path whether or not caching is on.
+#[derive(Debug, Default)]
+pub struct ExtractionCache {
+    /// Where the cache is persisted; `None` when caching is disabled
+    path: Option<PathBuf>,
+    entries: Mutex<HashMap<PathBuf, CacheEntry>>,
+    dirty: AtomicBool,
+    /// Markers matched by `todos`; only the default markers are cached
+    todo_matcher: TodoMatcher,
+    /// Settings passed to the extractors on a cache miss
+    extraction: ExtractionConfig,
+}
+
+impl ExtractionCache {
+    /// A cache that never stores anything.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Default cache file location: `$XDG_CACHE_HOME/fruit/cache.bin`,
+    /// falling back to `~/.cache/fruit/cache.bin`.
+    pub fn default_path() -> Option<PathBuf> {
+        let cache_dir = std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+        Some(cache_dir.join("fruit").join(CACHE_FILE_NAME))
+    }
+
+    /// Load the cache at the default location, or a disabled cache if no
+    /// location can be determined.
+    pub fn load_default() -> Self {
+        Self::default_path().map(Self::load).unwrap_or_default()
+    }
+
+    /// Load the cache stored at `path`. A missing, unreadable or outdated
+    /// cache file yields an empty cache that will be written to `path`.
+    pub fn load(path: PathBuf) -> Self {
+        let entries = fs::read(&path)
+            .ok()
+            .and_then(|bytes| bincode::deserialize::<CacheFile>(&bytes).ok())
+            .filter(|file| file.version == env!("CARGO_PKG_VERSION"))
+            .map(|file| file.entries)
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            entries: Mutex::new(entries),
+            dirty: AtomicBool::new(false),
+            todo_matcher: TodoMatcher::default(),
+            extraction: ExtractionConfig::default(),
+        }
+    }
+
+    /// Match TODOs with `matcher` instead of the default markers.
+    pub fn with_todo_matcher(mut self, matcher: TodoMatcher) -> Self {
+        self.todo_matcher = matcher;
+        self
+    }
+
+    /// Extract with `config`, e.g. a different maximum file size.
+    pub fn with_extraction_config(mut self, config: ExtractionConfig) -> Self {
+        self.extraction = config;
+        self
+    }
+
+    /// Delete the cache file at the default location, if any.
+    pub fn clear() -> io::Result<()> {
+        let Some(path) = Self::default_path() else {
+            return Ok(());
+        };
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether results are being cached.
+    pub fn is_enabled(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// Write the cache back to disk if anything changed since it was loaded,
+    /// dropping the entries of files that no longer exist.
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let mut entries = self.lock_entries();
+        let cached = entries.len();
+        entries.retain(|file, _| file.exists());
+        let pruned = entries.len() != cached;
+        if !self.dirty.swap(false, Ordering::SeqCst) && !pruned {
+            return Ok(());
+        }
+
+        let file = CacheFile {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            entries: entries.clone(),
+        };
+        drop(entries);
+        let bytes = bincode::serialize(&file).map_err(io::Error::other)?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // Write to a temporary file first so a concurrent run never reads a partial cache
+        let tmp_path = path.with_extension(format!("tmp.{}", std::process::id()));
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, path)
+    }
+
+    /// First comment of a file (see `extract_first_comment`).
+    pub fn first_comment(&self, path: &Path) -> Option<String> {
+        self.get_or_extract(
+            path,
+            |e| &mut e.comment,
+            |p| extract_first_comment_with_config(p, &self.extraction),
+        )
+    }
+
+    /// Type signatures of a file (see `extract_type_signatures`).
+    pub fn type_signatures(&self, path: &Path) -> Option<Vec<TypeSignature>> {
+        self.get_or_extract(
+            path,
+            |e| &mut e.types,
+            |p| extract_type_signatures_with_config(p, 0, &self.extraction),
+        )
+    }
+
+    /// Type signatures of a file with up to `context_lines` lines of code
+    /// after each one. Context is not cached, so any lines bypass the cache.
+    pub fn type_signatures_with_context(
+        &self,
+        path: &Path,
+        context_lines: usize,
+    ) -> Option<Vec<TypeSignature>> {
+        if context_lines > 0 {
+            return extract_type_signatures_with_config(path, context_lines, &self.extraction);
+        }
+        self.type_signatures(path)
+    }
+
+    /// TODO/FIXME markers of a file (see `extract_todos`).
+    ///
+    /// Results for custom markers are not cached, since the cache file does
+    /// not record which markers produced an entry. The type and assignee
+    /// filters are applied after the lookup, so cached entries hold every TODO.
+    pub fn todos(&self, path: &Path) -> Option<Vec<TodoItem>> {
+        if !self.todo_matcher.is_default() {
+            return self
+                .todo_matcher
+                .extract_with_config(path, &self.extraction);
+        }
+        let todos = self.get_or_extract(
+            path,
+            |e| &mut e.todos,
+            |p| {
+                extract_todos_with_config(p, &self.extraction).map(|items| {
+                    items
+                        .into_iter()
+                        .map(|t| (t.marker_type, t.text, t.line, t.assignee))
+                        .collect()
+                })
+            },
+        )?;
+        self.todo_matcher.retain_selected(
+            todos
+                .into_iter()
+                .map(|(marker_type, text, line, assignee)| TodoItem {
+                    marker_type,
+                    text,
+                    line,
+                    assignee,
+                })
+                .collect(),
+        )
+    }
+
+    /// Imports of a file (see `extract_imports`).
+    pub fn imports(&self, path: &Path) -> Option<FileImports> {
+        let (external, std, internal) = self.get_or_extract(
+            path,
+            |e| &mut e.imports,
+            |p| {
+                extract_imports_with_config(p, &self.extraction)
+                    .map(|i| (i.external, i.std, i.internal))
+            },
+        )?;
+        Some(FileImports {
+            external,
+            std,
+            internal,
+            filtered: false,
+        })
+    }
+
+    /// Return the cached result in `slot` if the file and the extraction
+    /// settings are unchanged, otherwise run `extract` and remember its result.
+    fn get_or_extract<T: Clone>(
+        &self,
+        path: &Path,
+        slot: fn(&mut CacheEntry) -> &mut Option<Option<T>>,
+        extract: impl FnOnce(&Path) -> Option<T>,
+    ) -> Option<T> {
+        if !self.is_enabled() {
+            return extract(path);
+        }
+        let Some(stamp) = FileStamp::of(path) else {
+            return extract(path);
+        };
+
+        if let Some(entry) = self.lock_entries().get_mut(path)
+            && entry.stamp == stamp
+            && entry.extraction == self.extraction
+            && let Some(cached) = slot(entry)
+        {
+            return cached.clone();
+        }
+
+        // Extract without holding the lock so parallel workers don't serialize
+        let result = extract(path);
+
+        let mut entries = self.lock_entries();
+        let entry = entries.entry(path.to_path_buf()).or_default();
+        if entry.stamp != stamp || entry.extraction != self.extraction {
+            *entry = CacheEntry {
+                stamp,
+                extraction: self.extraction,
+                ..CacheEntry::default()
+            };
+        }
+        *slot(entry) = Some(result.clone());
+        self.dirty.store(true, Ordering::SeqCst);
+        result
+    }
+
+    fn lock_entries(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, CacheEntry>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use tempfile::TempDir;
+
+    fn counted(calls: &Cell<usize>, value: &str) -> Option<String> {
+        calls.set(calls.get() + 1);
+        Some(value.to_string())
+    }
+
+    #[test]
+    fn test_cache_hit_skips_extraction() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("main.rs");
+        fs::write(&file, "//! Entry\n").unwrap();
+
+        let cache = ExtractionCache::load(dir.path().join("cache.bin"));
+        let calls = Cell::new(0);
+        let first = cache.get_or_extract(&file, |e| &mut e.comment, |_| counted(&calls, "a"));
+        let second = cache.get_or_extract(&file, |e| &mut e.comment, |_| counted(&calls, "b"));
+
+        assert_eq!(first.as_deref(), Some("a"));
+        assert_eq!(second.as_deref(), Some("a"));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_cache_invalidated_when_size_changes() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("main.rs");
+        fs::write(&file, "//! Entry\n").unwrap();
+
+        let cache = ExtractionCache::load(dir.path().join("cache.bin"));
+        let calls = Cell::new(0);
+        cache.get_or_extract(&file, |e| &mut e.comment, |_| counted(&calls, "a"));
+        fs::write(&file, "//! Entry point\n").unwrap();
+        let after = cache.get_or_extract(&file, |e| &mut e.comment, |_| counted(&calls, "b"));
+
+        assert_eq!(after.as_deref(), Some("b"));
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("main.rs");
+        fs::write(&file, "//! Entry\n// TODO: more\nuse crate::foo;\n").unwrap();
+        let cache_path = dir.path().join("nested").join("cache.bin");
+
+        let cache = ExtractionCache::load(cache_path.clone());
+        assert_eq!(cache.first_comment(&file).as_deref(), Some("Entry"));
+        assert_eq!(cache.todos(&file).unwrap()[0].text, "more");
+        assert_eq!(cache.imports(&file).unwrap().internal, vec!["foo"]);
+        cache.save().unwrap();
+
+        let reloaded = ExtractionCache::load(cache_path);
+        let calls = Cell::new(0);
+        let comment = reloaded.get_or_extract(&file, |e| &mut e.comment, |_| counted(&calls, "x"));
+        assert_eq!(comment.as_deref(), Some("Entry"));
+        assert_eq!(calls.get(), 0);
+        assert_eq!(reloaded.todos(&file).unwrap()[0].line, 2);
+    }
+
+    #[test]
+    fn test_cache_invalidated_when_extraction_config_changes() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("main.rs");
+        fs::write(&file, "//! Entry point\n").unwrap();
+        let cache_path = dir.path().join("cache.bin");
+
+        let small =
+            ExtractionCache::load(cache_path.clone()).with_extraction_config(ExtractionConfig {
+                max_file_size: 4,
+                ..ExtractionConfig::default()
+            });
+        assert_eq!(small.first_comment(&file), None);
+        small.save().unwrap();
+
+        let default = ExtractionCache::load(cache_path);
+        assert_eq!(default.first_comment(&file).as_deref(), Some("Entry point"));
+    }
+
+    #[test]
+    fn test_save_prunes_deleted_files() {
+        let dir = TempDir::new().unwrap();
+        let kept = dir.path().join("kept.rs");
+        let deleted = dir.path().join("deleted.rs");
+        fs::write(&kept, "//! Kept\n").unwrap();
+        fs::write(&deleted, "//! Deleted\n").unwrap();
+        let cache_path = dir.path().join("cache.bin");
+
+        let cache = ExtractionCache::load(cache_path.clone());
+        cache.first_comment(&kept);
+        cache.first_comment(&deleted);
+        cache.save().unwrap();
+
+        fs::remove_file(&deleted).unwrap();
+        ExtractionCache::load(cache_path.clone()).save().unwrap();
+
+        let reloaded = ExtractionCache::load(cache_path);
+        let entries = reloaded.lock_entries();
+        assert!(entries.contains_key(&kept));
+        assert!(!entries.contains_key(&deleted));
+    }
+
+    #[test]
+    fn test_disabled_cache_always_extracts() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("main.rs");
+        fs::write(&file, "//! Entry\n").unwrap();
+
+        let cache = ExtractionCache::disabled();
+        let calls = Cell::new(0);
+        cache.get_or_extract(&file, |e| &mut e.comment, |_| counted(&calls, "a"));
+        cache.get_or_extract(&file, |e| &mut e.comment, |_| counted(&calls, "a"));
+        assert_eq!(calls.get(), 2);
+        assert!(cache.save().is_ok());
+    }
+
+    #[test]
+    fn test_extraction_config_limits_file_size() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("main.rs");
+        fs::write(&file, "//! Entry point\n").unwrap();
+
+        let small = ExtractionCache::disabled().with_extraction_config(ExtractionConfig {
+            max_file_size: 4,
+            ..ExtractionConfig::default()
+        });
+        assert_eq!(small.first_comment(&file), None);
+        assert_eq!(
+            ExtractionCache::disabled().first_comment(&file),
+            Some("Entry point".to_string())
+        );
+    }
+}
@@ -24,10 +24,20 @@
 //! - **Java/Kotlin/Swift**: JavaDoc `/** */` comments (filters `@` annotations)
 //! - **PHP**: PHPDoc `/** */` after `<?php` tag, or `//` and `#` comments
 //! - **C#**: XML doc `///` comments (skips `<tag>` elements), then `/* */` blocks
+//! - **Nix**: `#` or `/* */` comments (skips the `{ pkgs, ... }:` and `let`
+//!   preamble), then `meta.description`
+//! - **Markdown**: The first `# ` heading, then the first paragraph
+//! - **Manifests**: The `description` field (see `extractors::manifest`)
 
 use std::path::Path;
+use std::sync::LazyLock;
 
-use crate::file_utils::read_source_file;
+use regex::Regex;
+
+use crate::extractors::extract_manifest_description;
+use crate::file_utils::{ExtractionConfig, has_binary_extension, read_source_file};
+use crate::language::Language;
+use crate::string_utils::{strip_any_prefix_and_trim, strip_comment_prefix};
 
 /// Extract the first documentation comment from a source file.
 ///
@@ -48,6 +58,13 @@ use crate::file_utils::read_source_file;
 /// | `.java`, `.kt`, `.kts`, `.swift` | Java/Kotlin/Swift | `/** */` |
 /// | `.php` | PHP | `/** */`, `//`, `#` |
 /// | `.cs` | C# | `///`, `/* */` |
+/// | `.yaml`, `.yml` | YAML | `#` comments in the first document |
+/// | `Dockerfile`, `Dockerfile.*`, `.dockerfile` | Dockerfile | `#` comments after parser directives |
+/// | `Makefile`, `GNUmakefile`, `.mk` | Makefile | `#` or `##` comments at the top |
+/// | `.clj`, `.cljs`, `.cljc` | Clojure | `ns` docstring, `;;` comments at the top |
+/// | `.pl`, `.pm`, `.t` | Perl | POD `NAME` or `DESCRIPTION` section, `#` comments |
+/// | `.md`, `.mdx` | Markdown | `# ` title, else first paragraph |
+/// | `.toml`, `package.json`, `setup.py` | Manifests | `description` field |
 ///
 /// # Returns
 ///
@@ -56,10 +73,28 @@ use crate::file_utils::read_source_file;
 ///
 /// # File Size Limit
 ///
-/// Files larger than 1MB are skipped to prevent memory issues
-/// when processing large generated or binary files with code extensions.
+/// Files larger than 1MB (or the limit set by `set_max_file_size`) are skipped
+/// to prevent memory issues when processing large generated or binary files
+/// with code extensions. Use `extract_first_comment_with_config` to pass the
+/// limit explicitly.
 pub fn extract_first_comment(path: &Path) -> Option<String> {
-    let (content, extension) = read_source_file(path)?;
+    extract_first_comment_with_config(path, &ExtractionConfig::global())
+}
+
+/// Like `extract_first_comment`, skipping files over `config.max_file_size`.
+pub fn extract_first_comment_with_config(path: &Path, config: &ExtractionConfig) -> Option<String> {
+    // A manifest's description says more than any comment it may have
+    if let Some(description) = extract_manifest_description(path, config) {
+        return Some(description);
+    }
+
+    let Some((content, extension)) = read_source_file(path, config.max_file_size) else {
+        return if config.generic_comments {
+            extract_unknown_file_comment(path, config.max_file_size)
+        } else {
+            None
+        };
+    };
 
     // Extension is already normalized to lowercase by read_source_file
     match extension {
@@ -70,16 +105,75 @@ pub fn extract_first_comment(path: &Path) -> Option<String> {
         "c" | "cpp" => extract_c_comment(&content),
         "rb" => extract_ruby_comment(&content),
         "sh" => extract_shell_comment(&content),
+        "yaml" => extract_yaml_comment(&content),
+        "dockerfile" => extract_dockerfile_comment(&content),
+        "mk" => extract_makefile_comment(&content),
         // Java, Kotlin, Swift use JavaDoc-style /** */ comments
         "java" | "kt" | "swift" => extract_javadoc_comment(&content),
         // PHP uses PHPDoc /** */ and also # comments
         "php" => extract_php_comment(&content),
         // C# uses /// XML doc comments
         "cs" => extract_csharp_comment(&content),
+        "nix" => extract_nix_comment(&content),
+        "clj" => extract_clojure_comment(&content),
+        "pl" => extract_perl_comment(&content),
+        // Markdown has no comments; its title or first paragraph summarizes it
+        "md" => extract_markdown_summary(&content),
+        _ if config.generic_comments => extract_generic_comment(&content),
         _ => None,
     }
 }
 
+/// Canonical extensions of the languages `extract_first_comment` has an
+/// extractor for.
+const COMMENT_EXTENSIONS: &[&str] = &[
+    "rs",
+    "py",
+    "js",
+    "ts",
+    "go",
+    "c",
+    "cpp",
+    "rb",
+    "sh",
+    "yaml",
+    "dockerfile",
+    "mk",
+    "java",
+    "kt",
+    "swift",
+    "php",
+    "cs",
+    "nix",
+    "clj",
+    "pl",
+    "md",
+];
+
+/// Whether `extract_first_comment` has an extractor for `language`.
+pub fn supports_comments(language: Language) -> bool {
+    COMMENT_EXTENSIONS.contains(&language.extension())
+}
+
+/// Read a file of no known language and extract its generic comment.
+/// Files that are too large, binary or not UTF-8 text yield `None`.
+fn extract_unknown_file_comment(path: &Path, max_size: u64) -> Option<String> {
+    if has_binary_extension(path) || path.metadata().ok()?.len() > max_size {
+        return None;
+    }
+    let content = std::fs::read_to_string(path).ok()?;
+    extract_generic_comment(&content)
+}
+
+/// Extract a leading comment using common conventions, for files without a
+/// language-specific extractor.
+///
+/// Tries `#` line comments (skipping a `#!` line), then `//` line comments
+/// and `/* */` block comments at the start of the file.
+pub fn extract_generic_comment(content: &str) -> Option<String> {
+    extract_shell_comment(content).or_else(|| extract_c_comment(content))
+}
+
 /// Extract Rust documentation comments.
 ///
 /// Priority order:
@@ -93,8 +187,7 @@ fn extract_rust_comment(content: &str) -> Option<String> {
     let mut doc_lines = Vec::new();
     for line in &lines {
         let trimmed = line.trim();
-        if trimmed.starts_with("//!") {
-            let comment = trimmed.strip_prefix("//!").unwrap_or("").trim();
+        if let Some(comment) = strip_comment_prefix(trimmed, "//!") {
             doc_lines.push(comment);
         } else if !trimmed.is_empty() && !trimmed.starts_with("//") {
             break;
@@ -109,9 +202,8 @@ fn extract_rust_comment(content: &str) -> Option<String> {
     let mut in_doc_comment = false;
     for line in &lines {
         let trimmed = line.trim();
-        if trimmed.starts_with("///") {
+        if let Some(comment) = strip_comment_prefix(trimmed, "///") {
             in_doc_comment = true;
-            let comment = trimmed.strip_prefix("///").unwrap_or("").trim();
             doc_lines.push(comment);
         } else if in_doc_comment
             || (!trimmed.is_empty()
@@ -128,17 +220,17 @@ fn extract_rust_comment(content: &str) -> Option<String> {
 
     // Look for /* */ block comments at the top
     let trimmed = content.trim_start();
-    if trimmed.starts_with("/*") {
-        if let Some(end) = trimmed.find("*/") {
-            let block = &trimmed[2..end];
-            let cleaned: Vec<&str> = block
-                .lines()
-                .map(|l| l.trim().trim_start_matches('*').trim())
-                .filter(|l| !l.is_empty())
-                .collect();
-            if !cleaned.is_empty() {
-                return Some(cleaned.join("\n"));
-            }
+    if trimmed.starts_with("/*")
+        && let Some(end) = trimmed.find("*/")
+    {
+        let block = &trimmed[2..end];
+        let cleaned: Vec<&str> = block
+            .lines()
+            .map(|l| l.trim().trim_start_matches('*').trim())
+            .filter(|l| !l.is_empty())
+            .collect();
+        if !cleaned.is_empty() {
+            return Some(cleaned.join("\n"));
         }
     }
 
@@ -174,7 +266,7 @@ fn extract_python_docstring(content: &str) -> Option<String> {
     for quote in ["\"\"\"", "'''"] {
         if rest.starts_with(quote) {
             let after_quote = &rest[3..];
-            if let Some(end) = after_quote.find(quote) {
+            if let Some(end) = find_closing_quote(after_quote, quote) {
                 let doc = after_quote[..end].trim();
                 if !doc.is_empty() {
                     // Return the full docstring, cleaned up
@@ -188,6 +280,20 @@ fn extract_python_docstring(content: &str) -> Option<String> {
     None
 }
 
+/// Find the `quote` that closes a Python string body, skipping backslash
+/// escapes (so `\"""` does not close it, but `\\"""` does).
+fn find_closing_quote(body: &str, quote: &str) -> Option<usize> {
+    let mut chars = body.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if body[i..].starts_with(quote) {
+            return Some(i);
+        }
+    }
+    None
+}
+
 /// Extract JavaScript/TypeScript comments.
 ///
 /// Priority order:
@@ -197,17 +303,17 @@ fn extract_js_comment(content: &str) -> Option<String> {
     let trimmed = content.trim_start();
 
     // Check for JSDoc /** ... */
-    if trimmed.starts_with("/**") {
-        if let Some(end) = trimmed.find("*/") {
-            let block = &trimmed[3..end];
-            let cleaned: Vec<&str> = block
-                .lines()
-                .map(|l| l.trim().trim_start_matches('*').trim())
-                .filter(|l| !l.is_empty() && *l != "/")
-                .collect();
-            if !cleaned.is_empty() {
-                return Some(cleaned.join("\n"));
-            }
+    if trimmed.starts_with("/**")
+        && let Some(end) = trimmed.find("*/")
+    {
+        let block = &trimmed[3..end];
+        let cleaned: Vec<&str> = block
+            .lines()
+            .map(|l| l.trim().trim_start_matches('*').trim())
+            .filter(|l| !l.is_empty() && *l != "/")
+            .collect();
+        if !cleaned.is_empty() {
+            return Some(cleaned.join("\n"));
         }
     }
 
@@ -215,8 +321,7 @@ fn extract_js_comment(content: &str) -> Option<String> {
     let mut comment_lines = Vec::new();
     for line in trimmed.lines() {
         let t = line.trim();
-        if t.starts_with("//") {
-            let comment = t.strip_prefix("//").unwrap_or("").trim();
+        if let Some(comment) = strip_comment_prefix(t, "//") {
             comment_lines.push(comment);
         } else if t.is_empty() {
             continue;
@@ -242,23 +347,22 @@ fn extract_go_comment(content: &str) -> Option<String> {
 
     for line in content.lines() {
         let trimmed = line.trim();
-        if trimmed.starts_with("//") {
-            let comment = trimmed.strip_prefix("//").unwrap_or("").trim();
+        if let Some(comment) = strip_comment_prefix(trimmed, "//") {
             comment_lines.push(comment);
         } else if trimmed.starts_with("/*") {
             // Block comment - find matching pair from the start of this line
             // This handles multiple block comments correctly
-            if let Some(start_idx) = content.find("/*") {
-                if let Some(relative_end) = content[start_idx + 2..].find("*/") {
-                    let block = &content[start_idx + 2..start_idx + 2 + relative_end];
-                    let cleaned: Vec<&str> = block
-                        .lines()
-                        .map(|l| l.trim().trim_start_matches('*').trim())
-                        .filter(|l| !l.is_empty())
-                        .collect();
-                    if !cleaned.is_empty() {
-                        return Some(cleaned.join("\n"));
-                    }
+            if let Some(start_idx) = content.find("/*")
+                && let Some(relative_end) = content[start_idx + 2..].find("*/")
+            {
+                let block = &content[start_idx + 2..start_idx + 2 + relative_end];
+                let cleaned: Vec<&str> = block
+                    .lines()
+                    .map(|l| l.trim().trim_start_matches('*').trim())
+                    .filter(|l| !l.is_empty())
+                    .collect();
+                if !cleaned.is_empty() {
+                    return Some(cleaned.join("\n"));
                 }
             }
             break;
@@ -284,17 +388,17 @@ fn extract_c_comment(content: &str) -> Option<String> {
     let trimmed = content.trim_start();
 
     // Block comment /* */
-    if trimmed.starts_with("/*") {
-        if let Some(end) = trimmed.find("*/") {
-            let block = &trimmed[2..end];
-            let cleaned: Vec<&str> = block
-                .lines()
-                .map(|l| l.trim().trim_start_matches('*').trim())
-                .filter(|l| !l.is_empty())
-                .collect();
-            if !cleaned.is_empty() {
-                return Some(cleaned.join("\n"));
-            }
+    if trimmed.starts_with("/*")
+        && let Some(end) = trimmed.find("*/")
+    {
+        let block = &trimmed[2..end];
+        let cleaned: Vec<&str> = block
+            .lines()
+            .map(|l| l.trim().trim_start_matches('*').trim())
+            .filter(|l| !l.is_empty())
+            .collect();
+        if !cleaned.is_empty() {
+            return Some(cleaned.join("\n"));
         }
     }
 
@@ -302,8 +406,7 @@ fn extract_c_comment(content: &str) -> Option<String> {
     let mut comment_lines = Vec::new();
     for line in trimmed.lines() {
         let t = line.trim();
-        if t.starts_with("//") {
-            let comment = t.strip_prefix("//").unwrap_or("").trim();
+        if let Some(comment) = strip_comment_prefix(t, "//") {
             comment_lines.push(comment);
         } else if t.is_empty() {
             continue;
@@ -343,9 +446,8 @@ fn extract_ruby_comment(content: &str) -> Option<String> {
         {
             continue;
         }
-        if trimmed.starts_with('#') {
+        if let Some(comment) = strip_comment_prefix(trimmed, "#") {
             past_preamble = true;
-            let comment = trimmed.strip_prefix('#').unwrap_or("").trim();
             comment_lines.push(comment);
         } else if trimmed.is_empty() {
             if past_preamble && !comment_lines.is_empty() {
@@ -378,9 +480,8 @@ fn extract_shell_comment(content: &str) -> Option<String> {
         if trimmed.starts_with("#!") {
             continue;
         }
-        if trimmed.starts_with('#') {
+        if let Some(comment) = strip_comment_prefix(trimmed, "#") {
             past_shebang = true;
-            let comment = trimmed.strip_prefix('#').unwrap_or("").trim();
             comment_lines.push(comment);
         } else if trimmed.is_empty() {
             if past_shebang && !comment_lines.is_empty() {
@@ -398,6 +499,113 @@ fn extract_shell_comment(content: &str) -> Option<String> {
     None
 }
 
+/// Tool directives written as YAML comments, which describe nothing.
+const YAML_DIRECTIVE_PREFIXES: [&str; 5] = ["yaml-language-server:", "vim:", "vi:", "ex:", "-*-"];
+
+/// Extract YAML comments (`.yaml`, `.yml`).
+///
+/// Collects the `#` comments at the top of the first document, skipping a
+/// leading `---` separator, `%YAML` directives and tool directives such as
+/// `# yaml-language-server: $schema=...` or `# vim:` modelines. Stops at the
+/// first empty line after comments start, or where the next document begins.
+fn extract_yaml_comment(content: &str) -> Option<String> {
+    let mut comment_lines = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed == "---" || trimmed.starts_with('%') {
+            if !comment_lines.is_empty() {
+                // End of the first document
+                break;
+            }
+            continue;
+        }
+        if let Some(comment) = strip_comment_prefix(trimmed, "#") {
+            if YAML_DIRECTIVE_PREFIXES
+                .iter()
+                .any(|prefix| comment.starts_with(prefix))
+            {
+                continue;
+            }
+            comment_lines.push(comment);
+        } else if trimmed.is_empty() {
+            if !comment_lines.is_empty() {
+                break;
+            }
+        } else {
+            break;
+        }
+    }
+    if comment_lines.iter().any(|l| !l.is_empty()) {
+        return Some(comment_lines.join("\n"));
+    }
+    None
+}
+
+/// Whether a comment is a Dockerfile parser directive like `syntax=docker/dockerfile:1`.
+fn is_dockerfile_directive(comment: &str) -> bool {
+    comment.split_once('=').is_some_and(|(key, _)| {
+        matches!(
+            key.trim().to_ascii_lowercase().as_str(),
+            "syntax" | "escape" | "check"
+        )
+    })
+}
+
+/// Extract Dockerfile comments.
+///
+/// Collects the `#` comments at the top of the file, skipping parser
+/// directives (`# syntax=`, `# escape=`, `# check=`). Stops at the first
+/// empty line after comments start.
+fn extract_dockerfile_comment(content: &str) -> Option<String> {
+    let mut comment_lines = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some(comment) = strip_comment_prefix(trimmed, "#") {
+            if !is_dockerfile_directive(comment) {
+                comment_lines.push(comment);
+            }
+        } else if trimmed.is_empty() {
+            if !comment_lines.is_empty() {
+                break;
+            }
+        } else {
+            break;
+        }
+    }
+    if comment_lines.iter().any(|l| !l.is_empty()) {
+        return Some(comment_lines.join("\n"));
+    }
+    None
+}
+
+/// Extract Makefile comments.
+///
+/// Collects the `#` or `##` comments at the top of the file. Self-documenting
+/// Makefiles mark descriptions with `##`, so both are accepted. Stops at the
+/// first empty line after comments start.
+fn extract_makefile_comment(content: &str) -> Option<String> {
+    let mut comment_lines = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some(comment) = trimmed.strip_prefix('#') {
+            comment_lines.push(comment.trim_start_matches('#').trim());
+        } else if trimmed.is_empty() {
+            if !comment_lines.is_empty() {
+                break;
+            }
+        } else {
+            break;
+        }
+    }
+    if comment_lines.iter().any(|l| !l.is_empty()) {
+        return Some(comment_lines.join("\n"));
+    }
+    None
+}
+
 /// Extract JavaDoc-style comments (Java, Kotlin, Swift).
 ///
 /// Priority order:
@@ -410,18 +618,18 @@ fn extract_javadoc_comment(content: &str) -> Option<String> {
     let trimmed = content.trim_start();
 
     // Check for JavaDoc/KDoc/Swift doc /** ... */
-    if trimmed.starts_with("/**") {
-        if let Some(end) = trimmed.find("*/") {
-            let block = &trimmed[3..end];
-            let cleaned: Vec<&str> = block
-                .lines()
-                .map(|l| l.trim().trim_start_matches('*').trim())
-                // Filter out @-annotations like @param, @return, @author
-                .filter(|l| !l.is_empty() && !l.starts_with('@'))
-                .collect();
-            if !cleaned.is_empty() {
-                return Some(cleaned.join("\n"));
-            }
+    if trimmed.starts_with("/**")
+        && let Some(end) = trimmed.find("*/")
+    {
+        let block = &trimmed[3..end];
+        let cleaned: Vec<&str> = block
+            .lines()
+            .map(|l| l.trim().trim_start_matches('*').trim())
+            // Filter out @-annotations like @param, @return, @author
+            .filter(|l| !l.is_empty() && !l.starts_with('@'))
+            .collect();
+        if !cleaned.is_empty() {
+            return Some(cleaned.join("\n"));
         }
     }
 
@@ -429,8 +637,7 @@ fn extract_javadoc_comment(content: &str) -> Option<String> {
     let mut comment_lines = Vec::new();
     for line in trimmed.lines() {
         let t = line.trim();
-        if t.starts_with("//") {
-            let comment = t.strip_prefix("//").unwrap_or("").trim();
+        if let Some(comment) = strip_comment_prefix(t, "//") {
             comment_lines.push(comment);
         } else if t.is_empty() {
             continue;
@@ -468,18 +675,18 @@ fn extract_php_comment(content: &str) -> Option<String> {
     let trimmed = content.trim_start();
 
     // Check for PHPDoc /** ... */
-    if trimmed.starts_with("/**") {
-        if let Some(end) = trimmed.find("*/") {
-            let block = &trimmed[3..end];
-            let cleaned: Vec<&str> = block
-                .lines()
-                .map(|l| l.trim().trim_start_matches('*').trim())
-                // Filter out @-annotations like @param, @return
-                .filter(|l| !l.is_empty() && !l.starts_with('@'))
-                .collect();
-            if !cleaned.is_empty() {
-                return Some(cleaned.join("\n"));
-            }
+    if trimmed.starts_with("/**")
+        && let Some(end) = trimmed.find("*/")
+    {
+        let block = &trimmed[3..end];
+        let cleaned: Vec<&str> = block
+            .lines()
+            .map(|l| l.trim().trim_start_matches('*').trim())
+            // Filter out @-annotations like @param, @return
+            .filter(|l| !l.is_empty() && !l.starts_with('@'))
+            .collect();
+        if !cleaned.is_empty() {
+            return Some(cleaned.join("\n"));
         }
     }
 
@@ -487,11 +694,9 @@ fn extract_php_comment(content: &str) -> Option<String> {
     let mut comment_lines = Vec::new();
     for line in trimmed.lines() {
         let t = line.trim();
-        if t.starts_with("//") {
-            let comment = t.strip_prefix("//").unwrap_or("").trim();
-            comment_lines.push(comment);
-        } else if t.starts_with('#') && !t.starts_with("#[") {
-            let comment = t.strip_prefix('#').unwrap_or("").trim();
+        if let Some((_, comment)) = strip_any_prefix_and_trim(t, &["//", "#"])
+            && !t.starts_with("#[")
+        {
             comment_lines.push(comment);
         } else if t.is_empty() {
             continue;
@@ -521,16 +726,11 @@ fn extract_csharp_comment(content: &str) -> Option<String> {
     let mut doc_lines = Vec::new();
     for line in trimmed.lines() {
         let t = line.trim();
-        if t.starts_with("///") {
-            let comment = t.strip_prefix("///").unwrap_or("").trim();
-            // Skip XML tags like <summary>, </summary>, <param>, etc.
-            if !comment.starts_with('<') && !comment.ends_with('>') {
+        if let Some((marker, comment)) = strip_any_prefix_and_trim(t, &["///", "//"]) {
+            // Skip XML tags like <summary>, </summary>, <param>, etc. in `///` lines
+            if marker == 1 || (!comment.starts_with('<') && !comment.ends_with('>')) {
                 doc_lines.push(comment);
             }
-        } else if t.starts_with("//") {
-            // Regular comment
-            let comment = t.strip_prefix("//").unwrap_or("").trim();
-            doc_lines.push(comment);
         } else if t.is_empty() || t.starts_with("using ") || t.starts_with("[") {
             continue;
         } else {
@@ -542,23 +742,245 @@ fn extract_csharp_comment(content: &str) -> Option<String> {
     }
 
     // Also check for /* */ block comments
-    if trimmed.starts_with("/*") {
-        if let Some(end) = trimmed.find("*/") {
-            let block = &trimmed[2..end];
-            let cleaned: Vec<&str> = block
-                .lines()
-                .map(|l| l.trim().trim_start_matches('*').trim())
-                .filter(|l| !l.is_empty())
-                .collect();
-            if !cleaned.is_empty() {
-                return Some(cleaned.join("\n"));
+    if trimmed.starts_with("/*")
+        && let Some(end) = trimmed.find("*/")
+    {
+        let block = &trimmed[2..end];
+        let cleaned: Vec<&str> = block
+            .lines()
+            .map(|l| l.trim().trim_start_matches('*').trim())
+            .filter(|l| !l.is_empty())
+            .collect();
+        if !cleaned.is_empty() {
+            return Some(cleaned.join("\n"));
+        }
+    }
+
+    None
+}
+
+/// `meta.description = "...";` or `description` inside a `meta = { ... }` set.
+static NIX_META_DESCRIPTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"\bmeta\s*(?:\.\s*|=\s*(?:with\s+[\w.]+\s*;\s*)?\{[^}]*?\b)description\s*=\s*"((?:[^"\\]|\\.)*)""#,
+    )
+    .expect("NIX_META_DESCRIPTION regex is invalid")
+});
+
+/// Extract Nix comments.
+///
+/// Priority order:
+/// 1. `#` line comments at the top
+/// 2. `/* */` block comments at the top
+/// 3. The package's `meta.description`
+///
+/// Comments may follow a preamble: the function's argument set
+/// (`{ pkgs, lib, ... }:`, possibly over several lines), `pkgs:` style
+/// arguments, `with lib;` and `let`.
+fn extract_nix_comment(content: &str) -> Option<String> {
+    let mut lines = content.lines().map(str::trim).peekable();
+    let mut in_arguments = false;
+    while let Some(&t) = lines.peek() {
+        // Argument sets run up to `}:`; their defaults use `?`, not `=`
+        let is_preamble = if in_arguments || (t.starts_with('{') && !t.contains('=')) {
+            in_arguments = !t.contains("}:");
+            true
+        } else {
+            t.is_empty()
+                || t == "let"
+                || (t.starts_with("with ") && t.ends_with(';'))
+                || is_nix_lambda_header(t)
+        };
+        if !is_preamble {
+            break;
+        }
+        lines.next();
+    }
+
+    let mut comment_lines = Vec::new();
+    let mut block: Option<Vec<&str>> = None;
+    for t in lines {
+        if let Some(block_lines) = &mut block {
+            match t.split_once("*/") {
+                Some((last, _)) => {
+                    block_lines.push(last.trim_start_matches('*').trim());
+                    break;
+                }
+                None => block_lines.push(t.trim_start_matches('*').trim()),
+            }
+        } else if let Some(comment) = strip_comment_prefix(t, "#") {
+            comment_lines.push(comment);
+        } else if comment_lines.is_empty()
+            && let Some(rest) = t.strip_prefix("/*")
+        {
+            if let Some((inner, _)) = rest.split_once("*/") {
+                block = Some(vec![inner.trim()]);
+                break;
             }
+            block = Some(vec![rest.trim_start_matches('*').trim()]);
+        } else {
+            break;
         }
     }
+    if comment_lines.iter().any(|l| !l.is_empty()) {
+        return Some(comment_lines.join("\n"));
+    }
+    let cleaned: Vec<&str> = block
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|l| !l.is_empty())
+        .collect();
+    if !cleaned.is_empty() {
+        return Some(cleaned.join("\n"));
+    }
+
+    let description = NIX_META_DESCRIPTION.captures(content)?.get(1)?.as_str();
+    (!description.trim().is_empty()).then(|| description.replace("\\\"", "\""))
+}
 
+/// Whether `line` only names function arguments, as in `pkgs:` or `self: super:`.
+fn is_nix_lambda_header(line: &str) -> bool {
+    line.ends_with(':')
+        && line.split(':').map(str::trim).all(|arg| {
+            arg.chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '\''))
+        })
+}
+
+/// The docstring of an `(ns name "docstring" ...)` form, allowing metadata
+/// like `^:no-doc` before the name.
+static CLOJURE_NS_DOCSTRING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?m)^\s*\(ns\s+(?:\^\S+\s+)*[^\s()\[\]{}"]+\s+"((?:[^"\\]|\\.)*)""#)
+        .expect("CLOJURE_NS_DOCSTRING regex is invalid")
+});
+
+/// Extract Clojure/ClojureScript comments.
+///
+/// Priority order:
+/// 1. The docstring of the `ns` form
+/// 2. `;` comments at the top (after a shebang), keeping only the `;;`
+///    (or deeper) lines when there are any, since single `;` usually marks
+///    a margin remark
+///
+/// Stops at the first empty line after comments start.
+fn extract_clojure_comment(content: &str) -> Option<String> {
+    if let Some(docstring) = CLOJURE_NS_DOCSTRING
+        .captures(content)
+        .and_then(|c| c.get(1))
+    {
+        let lines: Vec<&str> = docstring.as_str().lines().map(str::trim).collect();
+        let text = lines.join("\n").trim().replace("\\\"", "\"");
+        if !text.is_empty() {
+            return Some(text);
+        }
+    }
+
+    // Each line as (whether it is a `;;` section comment, its text)
+    let mut comment_lines = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("#!") {
+            continue;
+        }
+        if let Some((marker, comment)) = strip_any_prefix_and_trim(trimmed, &[";;", ";"]) {
+            comment_lines.push((marker == 0, comment.trim_start_matches(';').trim_start()));
+        } else if trimmed.is_empty() {
+            if !comment_lines.is_empty() {
+                break;
+            }
+        } else {
+            break;
+        }
+    }
+    let has_sections = comment_lines.iter().any(|&(section, _)| section);
+    let kept: Vec<&str> = comment_lines
+        .into_iter()
+        .filter(|&(section, _)| section || !has_sections)
+        .map(|(_, comment)| comment)
+        .collect();
+    if kept.iter().any(|l| !l.is_empty()) {
+        return Some(kept.join("\n"));
+    }
     None
 }
 
+/// Extract Perl comments.
+///
+/// Priority order:
+/// 1. The POD `=head1 NAME` section, else the `=head1 DESCRIPTION` section
+/// 2. `#` comments at the top (after a shebang), as for shell scripts
+fn extract_perl_comment(content: &str) -> Option<String> {
+    perl_pod_section(content, "NAME")
+        .or_else(|| perl_pod_section(content, "DESCRIPTION"))
+        .or_else(|| extract_shell_comment(content))
+}
+
+/// The non-empty lines of the POD section `=head1 <heading>`, up to the
+/// next `=head1` or `=cut`. Other directives such as `=over` and `=item`
+/// are skipped.
+fn perl_pod_section(content: &str, heading: &str) -> Option<String> {
+    let mut lines = content.lines().skip_while(|line| {
+        line.strip_prefix("=head1")
+            .is_none_or(|title| !title.trim().eq_ignore_ascii_case(heading))
+    });
+    lines.next()?;
+    let text: Vec<&str> = lines
+        .take_while(|line| !line.starts_with("=head1") && !line.starts_with("=cut"))
+        .filter(|line| !line.starts_with('='))
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    (!text.is_empty()).then(|| text.join("\n"))
+}
+
+/// Extract a summary from Markdown content.
+///
+/// Returns the first `# ` heading without its markers, or failing that the
+/// first paragraph of prose. YAML front matter, fenced code blocks and MDX
+/// `import`/`export` lines are skipped.
+pub fn extract_markdown_summary(content: &str) -> Option<String> {
+    let mut lines: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+    let mut body = content.lines().map(str::trim);
+
+    // YAML front matter: `---` on the first line up to the next `---`
+    let mut first = body.next();
+    if first == Some("---") {
+        body.by_ref().find(|t| *t == "---");
+        first = body.next();
+    }
+
+    for t in first.into_iter().chain(body) {
+        if t.starts_with("```") || t.starts_with("~~~") {
+            in_fence = !in_fence;
+            // A fence ends any paragraph in progress
+            lines.push("");
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        if let Some(title) = t.strip_prefix("# ") {
+            let title = title.trim_end_matches('#').trim();
+            if !title.is_empty() {
+                return Some(title.to_string());
+            }
+        }
+        lines.push(t);
+    }
+
+    // No title: use the first paragraph, skipping other headings
+    let paragraph = lines
+        .split(|t| t.is_empty() || t.starts_with('#'))
+        .find(|p| {
+            !p.is_empty()
+                && !p
+                    .iter()
+                    .all(|t| t.starts_with("import ") || t.starts_with("export "))
+        })?;
+    Some(paragraph.join("\n"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -745,6 +1167,78 @@ public class Program {}
         );
     }
 
+    #[test]
+    fn test_python_docstring_with_escapes() {
+        let content = r#""""Docstring with \"escaped\" quotes and \""" inside."""
+import os
+"#;
+        assert_eq!(
+            extract_python_docstring(content),
+            Some(r#"Docstring with \"escaped\" quotes and \""" inside."#.to_string())
+        );
+
+        // An escaped backslash does not escape the closing quotes
+        let content = r#""""Ends with a backslash \\"""
+x = '"""'
+"#;
+        assert_eq!(
+            extract_python_docstring(content),
+            Some(r"Ends with a backslash \\".to_string())
+        );
+    }
+
+    #[test]
+    fn test_crlf_line_endings() {
+        type Extractor = fn(&str) -> Option<String>;
+        let cases: [(Extractor, &str, &str); 10] = [
+            (
+                extract_rust_comment,
+                "//! Module doc\r\n//! More\r\nfn main() {}\r\n",
+                "Module doc\nMore",
+            ),
+            (
+                extract_rust_comment,
+                "/* Block\r\n * doc */\r\nfn main() {}\r\n",
+                "Block\ndoc",
+            ),
+            (
+                extract_python_docstring,
+                "\"\"\"\r\nDoc line\r\nMore\r\n\"\"\"\r\n",
+                "Doc line\nMore",
+            ),
+            (
+                extract_js_comment,
+                "/**\r\n * JS doc\r\n */\r\nexport {}\r\n",
+                "JS doc",
+            ),
+            (
+                extract_go_comment,
+                "// Package doc\r\npackage main\r\n",
+                "Package doc",
+            ),
+            (extract_c_comment, "// C doc\r\nint main() {}\r\n", "C doc"),
+            (
+                extract_ruby_comment,
+                "# Ruby doc\r\nclass A; end\r\n",
+                "Ruby doc",
+            ),
+            (
+                extract_shell_comment,
+                "#!/bin/sh\r\n# Shell doc\r\necho\r\n",
+                "Shell doc",
+            ),
+            (extract_php_comment, "<?php\r\n// PHP doc\r\n", "PHP doc"),
+            (
+                extract_csharp_comment,
+                "/// C# doc\r\nclass A {}\r\n",
+                "C# doc",
+            ),
+        ];
+        for (extract, content, expected) in cases {
+            assert_eq!(extract(content).as_deref(), Some(expected), "{:?}", content);
+        }
+    }
+
     #[test]
     fn test_python_docstring_with_shebang() {
         let content =
@@ -822,6 +1316,182 @@ public class Program {}
         );
     }
 
+    #[test]
+    fn test_yaml_comment() {
+        let content = "# yaml-language-server: $schema=https://json.schemastore.org/github-workflow\n# Run the test suite on every push\nname: CI\n";
+        assert_eq!(
+            extract_yaml_comment(content),
+            Some("Run the test suite on every push".to_string())
+        );
+
+        let content = "%YAML 1.2\n---\n# Deployment settings\n# for production\n\nreplicas: 3\n";
+        assert_eq!(
+            extract_yaml_comment(content),
+            Some("Deployment settings\nfor production".to_string())
+        );
+    }
+
+    #[test]
+    fn test_yaml_comment_first_document_only() {
+        let content = "# First document\n---\n# Second document\nkey: value\n";
+        assert_eq!(
+            extract_yaml_comment(content),
+            Some("First document".to_string())
+        );
+
+        let content = "key: value\n---\n# Second document\n";
+        assert_eq!(extract_yaml_comment(content), None);
+        assert_eq!(extract_yaml_comment("# vim: set ts=2:\nkey: value\n"), None);
+    }
+
+    #[test]
+    fn test_dockerfile_comment() {
+        let content = "# syntax=docker/dockerfile:1\n# escape=`\n# Build image for the API server\nFROM rust:1.80\n";
+        assert_eq!(
+            extract_dockerfile_comment(content),
+            Some("Build image for the API server".to_string())
+        );
+        assert_eq!(
+            extract_dockerfile_comment("FROM alpine\n# Not a header\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_makefile_comment() {
+        let content = "## Build automation\n## for the CLI\n\nbuild: ## Compile\n\tcargo build\n";
+        assert_eq!(
+            extract_makefile_comment(content),
+            Some("Build automation\nfor the CLI".to_string())
+        );
+        assert_eq!(extract_makefile_comment("all:\n\t# not a header\n"), None);
+    }
+
+    #[test]
+    fn test_nix_comment() {
+        assert_eq!(
+            extract_nix_comment(
+                "# Development shell\n# for the CLI\n{ pkgs ? import <nixpkgs> { } }:\n"
+            ),
+            Some("Development shell\nfor the CLI".to_string())
+        );
+        assert_eq!(
+            extract_nix_comment(
+                "{ config, lib,\n  pkgs, ... }:\n\nwith lib;\nlet\n  # Backup service module\n  cfg = config.services.backup;\nin { }\n"
+            ),
+            Some("Backup service module".to_string())
+        );
+        assert_eq!(
+            extract_nix_comment("self: super:\n/*\n * Overlay pinning tools\n */\n{ }\n"),
+            Some("Overlay pinning tools".to_string())
+        );
+        assert_eq!(
+            extract_nix_comment(
+                "{ stdenv }:\nstdenv.mkDerivation {\n  pname = \"fruit\";\n  meta = with lib; {\n    description = \"Tree but just the \\\"juicy\\\" bits\";\n  };\n}\n"
+            ),
+            Some("Tree but just the \"juicy\" bits".to_string())
+        );
+        assert_eq!(
+            extract_nix_comment("{ lib }:\n{\n  meta.description = \"Helpers\";\n}\n"),
+            Some("Helpers".to_string())
+        );
+        assert_eq!(
+            extract_nix_comment("{ pkgs }:\npkgs.hello\n# Not a header\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_clojure_comment() {
+        assert_eq!(
+            extract_clojure_comment(
+                ";; Not preferred\n(ns ^:no-doc my.app.core\n  \"Entry point for the app.\n\n  Starts the \\\"server\\\".\"\n  (:require [clojure.string :as str]))\n"
+            ),
+            Some("Entry point for the app.\n\nStarts the \"server\".".to_string())
+        );
+        assert_eq!(
+            extract_clojure_comment(
+                "#!/usr/bin/env bb\n;;;; Build tasks\n;; for the CLI\n; margin note\n\n(ns build)\n"
+            ),
+            Some("Build tasks\nfor the CLI".to_string())
+        );
+        assert_eq!(
+            extract_clojure_comment("; Only single\n; semicolons\n(ns app (:require [x]))\n"),
+            Some("Only single\nsemicolons".to_string())
+        );
+        assert_eq!(
+            extract_clojure_comment("(ns app)\n\n;; Not a header\n(defn f [])\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_perl_comment() {
+        assert_eq!(
+            extract_perl_comment(
+                "#!/usr/bin/perl\n# Not preferred\npackage My::App;\n\n1;\n__END__\n\n=pod\n\n=head1 NAME\n\nMy::App - Serve the app\n\n=head1 SYNOPSIS\n\n  use My::App;\n\n=cut\n"
+            ),
+            Some("My::App - Serve the app".to_string())
+        );
+        assert_eq!(
+            extract_perl_comment(
+                "=head1 DESCRIPTION\n\nParses the config.\n\n=over 4\n\n=item * Strict mode\n\n=back\n\n=cut\n\nsub parse {}\n"
+            ),
+            Some("Parses the config.".to_string())
+        );
+        assert_eq!(
+            extract_perl_comment(
+                "#!/usr/bin/env perl\n# Deploy script\n# for staging\n\nuse strict;\n"
+            ),
+            Some("Deploy script\nfor staging".to_string())
+        );
+        assert_eq!(extract_perl_comment("use strict;\n# Not a header\n"), None);
+    }
+
+    #[test]
+    fn test_generic_comment() {
+        assert_eq!(
+            extract_generic_comment("#!/usr/bin/env tool\n# Deploy config\nkey = 1\n"),
+            Some("Deploy config".to_string())
+        );
+        assert_eq!(
+            extract_generic_comment("// Shader helpers\nvoid main() {}\n"),
+            Some("Shader helpers".to_string())
+        );
+        assert_eq!(
+            extract_generic_comment("/* Grammar rules */\nrule: x;\n"),
+            Some("Grammar rules".to_string())
+        );
+        assert_eq!(extract_generic_comment("key = 1\n# not a header\n"), None);
+    }
+
+    #[test]
+    fn test_generic_comments_fallback_is_opt_in() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let unknown = dir.path().join("pipeline.conf");
+        std::fs::write(&unknown, "# Build pipeline\nstage = test\n").unwrap();
+        // A known language without a comment extractor
+        let toml = dir.path().join("settings.toml");
+        std::fs::write(&toml, "# Editor settings\ntheme = \"dark\"\n").unwrap();
+
+        let config = ExtractionConfig::default();
+        assert_eq!(extract_first_comment_with_config(&unknown, &config), None);
+        assert_eq!(extract_first_comment_with_config(&toml, &config), None);
+
+        let config = ExtractionConfig {
+            generic_comments: true,
+            ..config
+        };
+        assert_eq!(
+            extract_first_comment_with_config(&unknown, &config),
+            Some("Build pipeline".to_string())
+        );
+        assert_eq!(
+            extract_first_comment_with_config(&toml, &config),
+            Some("Editor settings".to_string())
+        );
+    }
+
     #[test]
     fn test_ruby_multiple_magic_comments() {
         let content =
@@ -863,4 +1533,37 @@ public class Program {}
         assert_eq!(extract_python_docstring("def foo(): pass"), None);
         assert_eq!(extract_js_comment("function foo() {}"), None);
     }
+
+    #[test]
+    fn test_markdown_title() {
+        let content = "# Getting Started\n\nInstall the tool first.\n";
+        assert_eq!(
+            extract_markdown_summary(content),
+            Some("Getting Started".to_string())
+        );
+        // Closing hashes are dropped, and the title wins over earlier prose
+        assert_eq!(
+            extract_markdown_summary("Intro text\n\n# Title #\n"),
+            Some("Title".to_string())
+        );
+    }
+
+    #[test]
+    fn test_markdown_first_paragraph() {
+        let content = "## Overview\n\nFirst line\nsecond line\n\nLater paragraph\n";
+        assert_eq!(
+            extract_markdown_summary(content),
+            Some("First line\nsecond line".to_string())
+        );
+        assert_eq!(extract_markdown_summary("\n\n"), None);
+    }
+
+    #[test]
+    fn test_markdown_skips_front_matter_code_and_mdx_imports() {
+        let content = "---\ntitle: Ignored\n---\nimport Chart from './chart'\n\n```\n# not a title\n```\nProse here\n";
+        assert_eq!(
+            extract_markdown_summary(content),
+            Some("Prose here".to_string())
+        );
+    }
 }
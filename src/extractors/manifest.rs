@@ -0,0 +1,269 @@
+//! Package manifest description extraction
+//!
+//! Manifests rarely carry a header comment, but their `description` field
+//! says what the package is. Supported files:
+//!
+//! - **TOML** (`Cargo.toml`, `pyproject.toml`, ...): `description` in the
+//!   `[package]`, `[project]` or `[tool.poetry]` section
+//! - **`package.json`**: the top-level `"description"` field
+//! - **`setup.py`**: the `description=` keyword argument
+//!
+//! TOML is matched line by line rather than fully parsed, but basic, literal
+//! and multi-line strings are all understood.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::file_utils::ExtractionConfig;
+
+/// Sections whose `description` key describes the whole package
+const TOML_PACKAGE_SECTIONS: &[&str] = &["package", "project", "tool.poetry"];
+
+static TOML_DESCRIPTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^description\s*=\s*(.*)$").expect("TOML_DESCRIPTION regex is invalid")
+});
+
+// `\b` keeps `long_description=` from matching
+static SETUP_PY_DESCRIPTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\bdescription\s*=\s*(?:"([^"]*)"|'([^']*)')"#)
+        .expect("SETUP_PY_DESCRIPTION regex is invalid")
+});
+
+/// Extract the `description` of a package manifest, chosen by file name.
+///
+/// Returns `None` for other files, manifests without a description, and
+/// files over `config.max_file_size`.
+pub fn extract_manifest_description(path: &Path, config: &ExtractionConfig) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let extract: fn(&str) -> Option<String> = match name {
+        "package.json" => extract_package_json_description,
+        "setup.py" => extract_setup_py_description,
+        _ if name.to_ascii_lowercase().ends_with(".toml") => extract_toml_description,
+        _ => return None,
+    };
+
+    if path.metadata().ok()?.len() > config.max_file_size {
+        return None;
+    }
+    let content = std::fs::read_to_string(path).ok()?;
+    extract(&content)
+}
+
+/// Extract `description` from the package section of TOML `content`.
+pub fn extract_toml_description(content: &str) -> Option<String> {
+    let mut in_package = false;
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        let t = line.trim();
+        if let Some(section) = t.strip_prefix('[') {
+            let section = section.split(']').next().unwrap_or("").trim();
+            in_package = TOML_PACKAGE_SECTIONS.contains(&section);
+            continue;
+        }
+        if !in_package {
+            continue;
+        }
+        if let Some(caps) = TOML_DESCRIPTION.captures(t) {
+            let value = caps.get(1).map_or("", |m| m.as_str());
+            return parse_toml_string(value, &mut lines).filter(|d| !d.is_empty());
+        }
+    }
+
+    None
+}
+
+/// Parse the TOML string starting at `value`, reading on through `rest` for
+/// multi-line strings. Returns `None` for other values, like `{ workspace = true }`.
+fn parse_toml_string<'a>(value: &str, rest: &mut impl Iterator<Item = &'a str>) -> Option<String> {
+    for quote in ["\"\"\"", "'''"] {
+        let Some(body) = value.strip_prefix(quote) else {
+            continue;
+        };
+        // Collect lines until the closing delimiter
+        let mut text = body.to_string();
+        while !text.contains(quote) {
+            text.push('\n');
+            text.push_str(rest.next()?);
+        }
+        let text = &text[..text.find(quote)?];
+        let text = if quote == "\"\"\"" {
+            unescape_toml(text)
+        } else {
+            text.to_string()
+        };
+        let lines: Vec<&str> = text.lines().map(str::trim).collect();
+        return Some(lines.join("\n").trim().to_string());
+    }
+
+    if let Some(body) = value.strip_prefix('"') {
+        let end = closing_quote(body)?;
+        return Some(unescape_toml(&body[..end]));
+    }
+    if let Some(body) = value.strip_prefix('\'') {
+        return body.find('\'').map(|end| body[..end].to_string());
+    }
+    None
+}
+
+/// Byte offset of the first `"` in `body` not escaped by a backslash.
+fn closing_quote(body: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in body.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Resolve the common escapes of a TOML basic string.
+fn unescape_toml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other @ ('"' | '\\')) => out.push(other),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Extract the top-level `"description"` of `package.json` content.
+pub fn extract_package_json_description(content: &str) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(content).ok()?;
+    json.get("description")?
+        .as_str()
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+}
+
+/// Extract the `description=` keyword argument of `setup.py` content.
+pub fn extract_setup_py_description(content: &str) -> Option<String> {
+    let caps = SETUP_PY_DESCRIPTION.captures(content)?;
+    caps.get(1)
+        .or_else(|| caps.get(2))
+        .map(|m| m.as_str().trim().to_string())
+        .filter(|d| !d.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cargo_toml_description() {
+        let content = "[package]\nname = \"fruit\"\ndescription = \"Tree but just the juicy bits\"\n\n[dependencies]\ndescription = \"not this\"\n";
+        assert_eq!(
+            extract_toml_description(content),
+            Some("Tree but just the juicy bits".to_string())
+        );
+    }
+
+    #[test]
+    fn test_toml_description_outside_package_is_ignored() {
+        let content = "[dependencies]\ndescription = \"nope\"\n";
+        assert_eq!(extract_toml_description(content), None);
+        // Inherited from the workspace: nothing to show
+        let content =
+            "[package]\ndescription.workspace = true\ndescription = { workspace = true }\n";
+        assert_eq!(extract_toml_description(content), None);
+    }
+
+    #[test]
+    fn test_toml_string_kinds() {
+        let content = "[project]\ndescription = 'C:\\no\\escapes'\n";
+        assert_eq!(
+            extract_toml_description(content),
+            Some("C:\\no\\escapes".to_string())
+        );
+
+        let content = "[tool.poetry]\ndescription = \"Says \\\"hi\\\"\"\n";
+        assert_eq!(
+            extract_toml_description(content),
+            Some("Says \"hi\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_toml_multiline_description() {
+        let content =
+            "[package]\ndescription = \"\"\"\n  First line\n  second line\n\"\"\"\nname = \"x\"\n";
+        assert_eq!(
+            extract_toml_description(content),
+            Some("First line\nsecond line".to_string())
+        );
+
+        let content = "[package]\ndescription = '''One line'''\n";
+        assert_eq!(
+            extract_toml_description(content),
+            Some("One line".to_string())
+        );
+
+        // Unterminated strings yield nothing
+        assert_eq!(
+            extract_toml_description("[package]\ndescription = \"\"\"\nnever closed\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_package_json_description() {
+        let content =
+            r#"{"name": "app", "description": "A web app", "nested": {"description": "no"}}"#;
+        assert_eq!(
+            extract_package_json_description(content),
+            Some("A web app".to_string())
+        );
+        assert_eq!(extract_package_json_description(r#"{"name": "app"}"#), None);
+        assert_eq!(extract_package_json_description("not json"), None);
+    }
+
+    #[test]
+    fn test_setup_py_description() {
+        let content = "setup(\n    name='tool',\n    long_description=open('README').read(),\n    description=\"Does things\",\n)\n";
+        assert_eq!(
+            extract_setup_py_description(content),
+            Some("Does things".to_string())
+        );
+        assert_eq!(
+            extract_setup_py_description("setup(description='Single')"),
+            Some("Single".to_string())
+        );
+        assert_eq!(extract_setup_py_description("setup(name='x')"), None);
+    }
+
+    #[test]
+    fn test_extract_manifest_description_by_file_name() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let manifest = dir.path().join("package.json");
+        std::fs::write(&manifest, r#"{"description": "From disk"}"#).unwrap();
+        assert_eq!(
+            extract_manifest_description(&manifest, &ExtractionConfig::default()),
+            Some("From disk".to_string())
+        );
+
+        let other = dir.path().join("data.json");
+        std::fs::write(&other, r#"{"description": "Not a manifest"}"#).unwrap();
+        assert_eq!(
+            extract_manifest_description(&other, &ExtractionConfig::default()),
+            None
+        );
+    }
+}
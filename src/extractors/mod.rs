@@ -0,0 +1,7 @@
+//! Metadata extractors for files that are not source code
+//!
+//! - `manifest`: The `description` of package manifests such as `Cargo.toml`
+
+pub mod manifest;
+
+pub use manifest::extract_manifest_description;
@@ -3,23 +3,65 @@
 //! This module provides common file I/O patterns used across the codebase
 //! for reading source files with size limits and extension detection.
 
+use std::fs::File;
+use std::io::Read;
 use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 
+use serde::{Deserialize, Serialize};
+
+use crate::language::Language;
+
 /// Default maximum file size for extraction operations (1MB).
 /// Files larger than this are skipped to prevent excessive memory usage.
 pub const DEFAULT_MAX_FILE_SIZE: u64 = 1_000_000;
 
-/// Global configurable max file size. Set via `set_max_file_size()`.
+/// Settings shared by the extractors, passed explicitly to each extraction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractionConfig {
+    /// Files larger than this many bytes are skipped
+    pub max_file_size: u64,
+    /// Fall back to `extract_generic_comment` for files without a comment extractor
+    pub generic_comments: bool,
+    /// Include private methods in type signatures (Python `_` names, Ruby `private` sections)
+    pub show_private: bool,
+}
+
+impl Default for ExtractionConfig {
+    fn default() -> Self {
+        Self {
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            generic_comments: false,
+            show_private: false,
+        }
+    }
+}
+
+impl ExtractionConfig {
+    /// The settings used by the path-only extractors like `extract_first_comment`,
+    /// which read the process-global limit from `set_max_file_size`.
+    pub fn global() -> Self {
+        Self {
+            max_file_size: get_max_file_size(),
+            generic_comments: false,
+            show_private: false,
+        }
+    }
+}
+
+/// Global max file size, used only by the path-only extractors.
 static MAX_FILE_SIZE: AtomicU64 = AtomicU64::new(DEFAULT_MAX_FILE_SIZE);
 
-/// Set the maximum file size for extraction operations.
-/// This affects all subsequent calls to `read_source_file`.
+/// Set the maximum file size used by the path-only extractors such as
+/// `extract_first_comment`.
+#[deprecated(
+    note = "process-global; pass an `ExtractionConfig` to the `*_with_config` extractors or use `WalkerConfigBuilder::max_file_size`"
+)]
 pub fn set_max_file_size(size: u64) {
     MAX_FILE_SIZE.store(size, Ordering::SeqCst);
 }
 
-/// Get the current maximum file size setting.
+/// Get the maximum file size used by the path-only extractors.
 pub fn get_max_file_size() -> u64 {
     MAX_FILE_SIZE.load(Ordering::SeqCst)
 }
@@ -30,98 +72,153 @@ pub fn get_max_file_size() -> u64 {
 /// normalizing variants to their canonical form (e.g., ".RS" -> "rs").
 /// Returns `None` for unrecognized extensions.
 pub fn normalize_extension(ext: &str) -> Option<&'static str> {
-    let ext_lower = ext.to_lowercase();
-    match ext_lower.as_str() {
-        // Rust
-        "rs" => Some("rs"),
-        // Python
-        "py" | "pyw" | "pyi" => Some("py"),
-        // JavaScript
-        "js" | "jsx" | "mjs" | "cjs" => Some("js"),
-        // TypeScript
-        "ts" | "tsx" | "mts" | "cts" => Some("ts"),
-        // Go
-        "go" => Some("go"),
-        // C
-        "c" | "h" => Some("c"),
-        // C++
-        "cpp" | "cxx" | "cc" | "hpp" | "hxx" | "hh" => Some("cpp"),
-        // C#
-        "cs" => Some("cs"),
-        // Java
-        "java" => Some("java"),
-        // Ruby
-        "rb" => Some("rb"),
-        // PHP
-        "php" => Some("php"),
-        // Shell
-        "sh" | "bash" | "zsh" | "fish" => Some("sh"),
-        // Swift
-        "swift" => Some("swift"),
-        // Kotlin
-        "kt" | "kts" => Some("kt"),
-        // Scala
-        "scala" | "sc" => Some("scala"),
-        // Lua
-        "lua" => Some("lua"),
-        // Perl
-        "pl" | "pm" => Some("pl"),
-        // R
-        "r" => Some("r"),
-        // Julia
-        "jl" => Some("jl"),
-        // Dart
-        "dart" => Some("dart"),
-        // Elixir
-        "ex" | "exs" => Some("ex"),
-        // Erlang
-        "erl" | "hrl" => Some("erl"),
-        // Haskell
-        "hs" | "lhs" => Some("hs"),
-        // OCaml
-        "ml" | "mli" => Some("ml"),
-        // F#
-        "fs" | "fsi" | "fsx" => Some("fs"),
-        // Clojure
-        "clj" | "cljs" | "cljc" | "edn" => Some("clj"),
-        // Zig
-        "zig" => Some("zig"),
-        // Vue
-        "vue" => Some("vue"),
-        // Svelte
-        "svelte" => Some("svelte"),
-        // Unrecognized extension
-        _ => None,
-    }
+    Language::from_extension(ext).map(|language| language.extension())
+}
+
+/// Maximum number of bytes read when looking for a `#!` line or modeline.
+const SHEBANG_PEEK_BYTES: u64 = 256;
+
+/// Detect the language of a file from its first line (`#!` line or editor
+/// modeline, see `Language::from_content`) without reading the whole file.
+fn detect_content_language(path: &Path) -> Option<Language> {
+    let mut head = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(SHEBANG_PEEK_BYTES)
+        .read_to_end(&mut head)
+        .ok()?;
+    let head = String::from_utf8_lossy(&head);
+    Language::from_content(head.lines().next()?)
 }
 
 /// Read a source file if it meets size requirements.
 ///
+/// The language is detected from the file name (`Dockerfile`, `Makefile`) or
+/// extension, falling back to the `#!` line or an editor modeline on the
+/// first line for files without an extension.
+///
 /// Returns `None` if:
-/// - File is larger than the configured MAX_FILE_SIZE
-/// - File has no extension
-/// - Extension is not valid UTF-8
-/// - Extension is not a recognized source file type
+/// - File is larger than `max_size` bytes
+/// - The language cannot be detected
 /// - File cannot be read
 ///
-/// Returns `Some((content, extension))` on success.
-/// The extension is normalized to lowercase for case-insensitive matching.
-pub fn read_source_file(path: &Path) -> Option<(String, &'static str)> {
+/// Returns `Some((content, extension))` on success, where the extension is
+/// the canonical lowercase extension of the detected language.
+pub fn read_source_file(path: &Path, max_size: u64) -> Option<(String, &'static str)> {
     // Check file size first
-    if let Ok(metadata) = path.metadata() {
-        if metadata.len() > get_max_file_size() {
-            return None;
-        }
+    if let Ok(metadata) = path.metadata()
+        && metadata.len() > max_size
+    {
+        return None;
     }
 
-    // Get extension and normalize to lowercase
-    let extension = path.extension()?.to_str()?;
-    let ext_static = normalize_extension(extension)?;
+    // Only extension-less files are probed for a `#!` line or modeline, so
+    // unrecognized files like images or data are not opened
+    let language = match Language::from_path(path) {
+        Some(language) => language,
+        None if path.extension().is_none() => detect_content_language(path)?,
+        None => return None,
+    };
 
     // Read content
     let content = std::fs::read_to_string(path).ok()?;
 
-    Some((content, ext_static))
+    Some((content, language.extension()))
+}
+
+/// Extensions of common binary formats: images, audio and video, archives,
+/// compiled code, fonts, office documents and databases.
+const BINARY_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "ico", "icns", "webp", "tif", "tiff", "psd", "mp3", "mp4",
+    "m4a", "wav", "ogg", "flac", "avi", "mov", "mkv", "webm", "zip", "tar", "gz", "tgz", "bz2",
+    "xz", "zst", "7z", "rar", "jar", "war", "iso", "dmg", "deb", "rpm", "exe", "dll", "so",
+    "dylib", "a", "o", "obj", "lib", "bin", "class", "pyc", "pyo", "wasm", "ttf", "otf", "woff",
+    "woff2", "eot", "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "sqlite", "sqlite3", "db",
+];
+
+/// Whether `ext` (without the dot, any case) is the extension of a common
+/// binary format, whose files are listed but never read for metadata.
+pub fn is_likely_binary_extension(ext: &str) -> bool {
+    BINARY_EXTENSIONS
+        .iter()
+        .any(|binary| binary.eq_ignore_ascii_case(ext))
+}
+
+/// Whether the extension of `path` is a common binary format (see
+/// `is_likely_binary_extension`).
+pub fn has_binary_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(is_likely_binary_extension)
+}
+
+/// Number of leading lines searched for a generated-file marker.
+const GENERATED_MARKER_LINES: usize = 20;
+
+/// Maximum number of bytes read when looking for a generated-file marker.
+const GENERATED_PEEK_BYTES: u64 = 4096;
+
+/// Phrases in a leading comment that mark a file as generated, lowercased.
+const GENERATED_MARKERS: &[&str] = &[
+    "code generated",
+    "do not edit",
+    "@generated",
+    "generated file",
+    "auto-generated",
+    "autogenerated",
+    "automatically generated",
+];
+
+/// Comment openers that generated-file markers appear after.
+const COMMENT_OPENERS: &[&str] = &["//", "#", "/*", "*", "--", ";", "<!--", "%"];
+
+/// Whether `content` is marked as generated by a tool: one of its first
+/// lines is a comment such as `// Code generated by protoc. DO NOT EDIT.`,
+/// `# DO NOT EDIT`, `/* GENERATED FILE */` or `// @generated`.
+///
+/// ```
+/// use fruit::file_utils::detect_generated;
+///
+/// assert!(detect_generated("// Code generated by stringer. DO NOT EDIT.\npackage main\n"));
+/// assert!(!detect_generated("//! Hand-written parser\n"));
+/// ```
+pub fn detect_generated(content: &str) -> bool {
+    content.lines().take(GENERATED_MARKER_LINES).any(|line| {
+        let t = line.trim_start();
+        COMMENT_OPENERS.iter().any(|opener| t.starts_with(opener)) && {
+            let lower = t.to_lowercase();
+            GENERATED_MARKERS
+                .iter()
+                .any(|marker| lower.contains(marker))
+        }
+    })
+}
+
+/// Whether the file at `path` is marked as generated (see
+/// `detect_generated`). Only the start of the file is read, and binary
+/// formats are not read at all.
+pub fn is_generated_file(path: &Path) -> bool {
+    if has_binary_extension(path) {
+        return false;
+    }
+    let mut head = Vec::new();
+    let read =
+        File::open(path).and_then(|file| file.take(GENERATED_PEEK_BYTES).read_to_end(&mut head));
+    read.is_ok() && detect_generated(&String::from_utf8_lossy(&head))
+}
+
+/// Characters per token in the `--tokens` estimate, roughly matching GPT-4 tokenization.
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Approximate number of LLM tokens in a text file (characters / 4, rounded up).
+/// Returns `None` for files that cannot be read as UTF-8 text, and for
+/// binary formats without reading them.
+pub fn estimate_tokens(path: &Path) -> Option<usize> {
+    if has_binary_extension(path) {
+        return None;
+    }
+    let content = std::fs::read_to_string(path).ok()?;
+    Some(content.chars().count().div_ceil(CHARS_PER_TOKEN))
 }
 
 #[cfg(test)]
@@ -136,26 +233,125 @@ mod tests {
         let file_path = dir.path().join("test.rs");
         fs::write(&file_path, "fn main() {}").unwrap();
 
-        let result = read_source_file(&file_path);
+        let result = read_source_file(&file_path, DEFAULT_MAX_FILE_SIZE);
         assert!(result.is_some());
         let (content, ext) = result.unwrap();
         assert_eq!(content, "fn main() {}");
         assert_eq!(ext, "rs");
     }
 
+    #[test]
+    fn test_estimate_tokens() {
+        let dir = TempDir::new().unwrap();
+        let text = dir.path().join("notes.txt");
+        fs::write(&text, "héllo wörld").unwrap();
+        assert_eq!(estimate_tokens(&text), Some(3));
+
+        let binary = dir.path().join("image.bin");
+        fs::write(&binary, [0xff, 0xfe, 0x00]).unwrap();
+        assert_eq!(estimate_tokens(&binary), None);
+    }
+
+    #[test]
+    fn test_detect_generated() {
+        for content in [
+            "// Code generated by protoc-gen-go. DO NOT EDIT.\n",
+            "# DO NOT EDIT: generated by alembic\n",
+            "/* GENERATED FILE */\nint x;\n",
+            "// @generated by yarn\n",
+            "// This file is auto-generated\n",
+            "#!/usr/bin/env python\n# Automatically generated by setup.py\n",
+            "<!-- Autogenerated from schema.yaml -->\n",
+        ] {
+            assert!(detect_generated(content), "{}", content);
+        }
+
+        assert!(!detect_generated(
+            "//! Parser for generated code\nfn main() {}\n"
+        ));
+        assert!(!detect_generated("let s = \"DO NOT EDIT\";\n"));
+        let late = format!(
+            "{}// Code generated\n",
+            "x\n".repeat(GENERATED_MARKER_LINES)
+        );
+        assert!(!detect_generated(&late));
+    }
+
+    #[test]
+    fn test_is_likely_binary_extension() {
+        assert!(is_likely_binary_extension("png"));
+        assert!(is_likely_binary_extension("WASM"));
+        assert!(is_likely_binary_extension("so"));
+        assert!(!is_likely_binary_extension("svg"));
+        assert!(!is_likely_binary_extension("rs"));
+        assert!(has_binary_extension(Path::new("assets/logo.PNG")));
+        assert!(!has_binary_extension(Path::new("Makefile")));
+    }
+
     #[test]
     fn test_read_source_file_no_extension() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("LICENSE");
+        fs::write(&file_path, "MIT License").unwrap();
+
+        let result = read_source_file(&file_path, DEFAULT_MAX_FILE_SIZE);
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_read_source_file_special_file_names() {
         let dir = TempDir::new().unwrap();
         let file_path = dir.path().join("Makefile");
         fs::write(&file_path, "all: build").unwrap();
+        assert_eq!(
+            read_source_file(&file_path, DEFAULT_MAX_FILE_SIZE)
+                .unwrap()
+                .1,
+            "mk"
+        );
 
-        let result = read_source_file(&file_path);
-        assert!(result.is_none());
+        let file_path = dir.path().join("Dockerfile");
+        fs::write(&file_path, "FROM rust").unwrap();
+        assert_eq!(
+            read_source_file(&file_path, DEFAULT_MAX_FILE_SIZE)
+                .unwrap()
+                .1,
+            "dockerfile"
+        );
+    }
+
+    #[test]
+    fn test_read_source_file_shebang_fallback() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("deploy");
+        fs::write(&file_path, "#!/usr/bin/env bash\n# Deploy the app\n").unwrap();
+
+        let (content, ext) = read_source_file(&file_path, DEFAULT_MAX_FILE_SIZE).unwrap();
+        assert_eq!(ext, "sh");
+        assert!(content.contains("Deploy the app"));
+    }
+
+    #[test]
+    fn test_read_source_file_modeline_fallback() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("build");
+        fs::write(&file_path, "# vim: ft=python\n\"\"\"Build helpers.\"\"\"\n").unwrap();
+        assert_eq!(
+            read_source_file(&file_path, DEFAULT_MAX_FILE_SIZE)
+                .unwrap()
+                .1,
+            "py"
+        );
+
+        // Files with an unknown extension are never opened
+        let file_path = dir.path().join("build.txt");
+        fs::write(&file_path, "# vim: ft=python\n").unwrap();
+        assert!(read_source_file(&file_path, DEFAULT_MAX_FILE_SIZE).is_none());
     }
 
     #[test]
     fn test_read_source_file_nonexistent() {
-        let result = read_source_file(Path::new("/nonexistent/file.rs"));
+        let result = read_source_file(Path::new("/nonexistent/file.rs"), DEFAULT_MAX_FILE_SIZE);
         assert!(result.is_none());
     }
 
@@ -166,7 +362,7 @@ mod tests {
         // Test uppercase extension
         let file_path = dir.path().join("test.RS");
         fs::write(&file_path, "fn main() {}").unwrap();
-        let result = read_source_file(&file_path);
+        let result = read_source_file(&file_path, DEFAULT_MAX_FILE_SIZE);
         assert!(result.is_some(), "should recognize .RS as .rs");
         let (_, ext) = result.unwrap();
         assert_eq!(ext, "rs", "extension should be normalized to lowercase");
@@ -174,12 +370,22 @@ mod tests {
         // Test mixed case extension
         let file_path = dir.path().join("test.Py");
         fs::write(&file_path, "print('hello')").unwrap();
-        let result = read_source_file(&file_path);
+        let result = read_source_file(&file_path, DEFAULT_MAX_FILE_SIZE);
         assert!(result.is_some(), "should recognize .Py as .py");
         let (_, ext) = result.unwrap();
         assert_eq!(ext, "py", "extension should be normalized to lowercase");
     }
 
+    #[test]
+    fn test_read_source_file_respects_max_size() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("big.rs");
+        fs::write(&file_path, "fn main() {}").unwrap();
+
+        assert!(read_source_file(&file_path, 5).is_none());
+        assert!(read_source_file(&file_path, 12).is_some());
+    }
+
     #[test]
     fn test_normalize_extension() {
         // Basic lowercase
@@ -214,7 +420,7 @@ mod tests {
         let file_path = dir.path().join("data.xyz");
         fs::write(&file_path, "some data").unwrap();
 
-        let result = read_source_file(&file_path);
+        let result = read_source_file(&file_path, DEFAULT_MAX_FILE_SIZE);
         assert!(
             result.is_none(),
             "unrecognized extension should return None"
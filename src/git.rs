@@ -1,9 +1,13 @@
 //! Git repository integration and gitignore filtering
 
-use git2::{Repository, Status};
+use git2::{
+    Commit, Delta, ObjectType, Repository, Sort, Status, Tree, TreeWalkMode, TreeWalkResult,
+};
 use ignore::WalkBuilder;
-use std::collections::HashSet;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Filter based on .gitignore patterns (respects nested .gitignore files).
 /// This is the default behavior - shows files that aren't ignored by gitignore.
@@ -14,9 +18,35 @@ use std::path::{Path, PathBuf};
 /// - .git/info/exclude
 /// - Parent directory .gitignore files
 pub struct GitignoreFilter {
-    included_files: HashSet<PathBuf>,
-    included_dirs: HashSet<PathBuf>,
+    rules: IgnoreRules,
     repo_root: PathBuf,
+    /// Directory names rejected without consulting the rules (none by default)
+    always_ignored: Vec<String>,
+}
+
+/// Build and dependency directory names to pass to
+/// `GitignoreFilter::with_always_ignored`.
+pub const DEFAULT_ALWAYS_IGNORED: &[&str] = &[
+    "node_modules",
+    "target",
+    ".git",
+    "__pycache__",
+    ".tox",
+    "venv",
+    ".venv",
+    "dist",
+    "build",
+];
+
+/// How a `GitignoreFilter` decides whether a path is ignored.
+enum IgnoreRules {
+    /// Every non-ignored path, collected by walking the repository up front
+    Snapshot {
+        included_files: HashSet<PathBuf>,
+        included_dirs: HashSet<PathBuf>,
+    },
+    /// One matcher per `.gitignore` file, deepest directory first
+    Matchers(Vec<Gitignore>),
 }
 
 impl GitignoreFilter {
@@ -57,12 +87,73 @@ impl GitignoreFilter {
         }
 
         Some(Self {
-            included_files,
-            included_dirs,
+            rules: IgnoreRules::Snapshot {
+                included_files,
+                included_dirs,
+            },
             repo_root,
+            always_ignored: Vec::new(),
         })
     }
 
+    /// Build a filter from every `.gitignore` under `root` and in its parent
+    /// directories, up to the enclosing repository root (if any).
+    ///
+    /// Unlike `new`, this does not require a `.git` directory and nothing is
+    /// walked up front: `is_included` applies the rules of the closest
+    /// `.gitignore` that matches the path, falling back to its parents.
+    /// Only `.gitignore` files are read, not global or `.git/info/exclude` rules.
+    pub fn new_with_parent_gitignores(root: &Path) -> Option<Self> {
+        let root = root.canonicalize().ok()?;
+        let repo_root = Self::find_repo_root(&root).unwrap_or_else(|| root.clone());
+
+        let mut matchers = Vec::new();
+        // Parent directories, stopping at the repository root
+        if root.starts_with(&repo_root) {
+            for dir in root.ancestors().skip(1) {
+                if !dir.starts_with(&repo_root) {
+                    break;
+                }
+                matchers.extend(Self::load_gitignore(dir));
+            }
+        }
+        // The root itself and every directory below it
+        let walker = WalkBuilder::new(&root)
+            .standard_filters(false)
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .build();
+        for entry in walker.flatten() {
+            if entry.file_type().is_some_and(|t| t.is_dir()) {
+                matchers.extend(Self::load_gitignore(entry.path()));
+            }
+        }
+
+        // Deeper directories take precedence over their parents
+        matchers.sort_by_key(|m| std::cmp::Reverse(m.path().components().count()));
+
+        Some(Self {
+            rules: IgnoreRules::Matchers(matchers),
+            repo_root,
+            always_ignored: Vec::new(),
+        })
+    }
+
+    /// Parse `dir/.gitignore`, if it exists and has any rules.
+    fn load_gitignore(dir: &Path) -> Option<Gitignore> {
+        let file = dir.join(".gitignore");
+        if !file.is_file() {
+            return None;
+        }
+        let mut builder = GitignoreBuilder::new(dir);
+        if builder.add(file).is_some() {
+            return None;
+        }
+        builder
+            .build()
+            .ok()
+            .filter(|gitignore| !gitignore.is_empty())
+    }
+
     fn find_repo_root(path: &Path) -> Option<PathBuf> {
         let mut current = if path.is_file() {
             path.parent()?.to_path_buf()
@@ -83,32 +174,241 @@ impl GitignoreFilter {
         }
     }
 
+    /// Reject directories with these names (such as `DEFAULT_ALWAYS_IGNORED`)
+    /// without consulting the rules. Off by default: a name alone cannot tell
+    /// a tracked `build/` from an ignored one, so only opt in when every
+    /// directory with these names should be hidden.
+    pub fn with_always_ignored<S: Into<String>>(
+        mut self,
+        names: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.always_ignored = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Whether `path` is a directory with one of the always-ignored names.
+    /// This only looks at the name, so it is much cheaper than `is_included`.
+    pub fn is_definitely_ignored(&self, path: &Path) -> bool {
+        path.file_name()
+            .is_some_and(|name| self.always_ignored.iter().any(|n| name == n.as_str()))
+            && path.is_dir()
+    }
+
     /// Check if a path should be included (not ignored by .gitignore).
     pub fn is_included(&self, path: &Path) -> bool {
+        // Opted-in directory names skip the rules entirely
+        if self.is_definitely_ignored(path) {
+            return false;
+        }
+
         // Canonicalize the path for comparison
         let path = match path.canonicalize() {
             Ok(p) => p,
             Err(_) => path.to_path_buf(),
         };
 
+        let (included_files, included_dirs) = match &self.rules {
+            IgnoreRules::Snapshot {
+                included_files,
+                included_dirs,
+            } => (included_files, included_dirs),
+            IgnoreRules::Matchers(matchers) => return Self::matchers_include(matchers, &path),
+        };
+
         // Direct file check - O(1)
-        if self.included_files.contains(&path) {
+        if included_files.contains(&path) {
             return true;
         }
 
         // Directory check - O(1)
         if path.is_dir() {
-            return self.included_dirs.contains(&path);
+            return included_dirs.contains(&path);
         }
 
         false
     }
 
+    /// Check if a path is ignored by .gitignore (the opposite of `is_included`).
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        !self.is_included(path)
+    }
+
+    /// Apply the closest `.gitignore` with a rule for `path` (or one of its parents).
+    fn matchers_include(matchers: &[Gitignore], path: &Path) -> bool {
+        let is_dir = path.is_dir();
+        for gitignore in matchers {
+            if !path.starts_with(gitignore.path()) || path == gitignore.path() {
+                continue;
+            }
+            let matched = gitignore.matched_path_or_any_parents(path, is_dir);
+            if matched.is_ignore() {
+                return false;
+            }
+            if matched.is_whitelist() {
+                return true;
+            }
+        }
+        true
+    }
+
     pub fn repo_root(&self) -> &Path {
         &self.repo_root
     }
 }
 
+/// Files changed between `revision` and `HEAD` in the repository containing `path`.
+///
+/// Returns `Ok(None)` when `path` is not inside a git repository, so callers
+/// can fall back to showing every file. Paths are absolute and canonical.
+pub fn changed_files_since(
+    path: &Path,
+    revision: &str,
+) -> Result<Option<HashSet<PathBuf>>, git2::Error> {
+    let Ok(repo) = Repository::discover(path) else {
+        return Ok(None);
+    };
+    let Some(workdir) = repo.workdir() else {
+        return Ok(None);
+    };
+    let repo_root = workdir
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.to_path_buf());
+
+    let old_tree = repo.revparse_single(revision)?.peel_to_tree()?;
+    let head_tree = repo.head()?.peel_to_tree()?;
+    let diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&head_tree), None)?;
+
+    let changed = diff
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().map(|p| repo_root.join(p)))
+        .collect();
+    Ok(Some(changed))
+}
+
+/// The last commit that touched a file, for `--last-commit`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitCommitInfo {
+    /// Abbreviated commit hash
+    pub hash: String,
+    /// First line of the commit message
+    pub message: String,
+    /// Author name
+    pub author: String,
+    /// Commit time
+    pub timestamp: SystemTime,
+}
+
+impl GitCommitInfo {
+    fn from_commit(commit: &Commit) -> Result<Self, git2::Error> {
+        let short_id = commit.as_object().short_id()?;
+        let seconds = commit.time().seconds();
+        let timestamp = if seconds >= 0 {
+            UNIX_EPOCH + Duration::from_secs(seconds as u64)
+        } else {
+            UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+        };
+        Ok(Self {
+            hash: short_id.as_str().unwrap_or_default().to_string(),
+            message: commit.summary().unwrap_or_default().to_string(),
+            author: commit.author().name().unwrap_or_default().to_string(),
+            timestamp,
+        })
+    }
+
+    /// The commit as `git log --oneline` shows it, e.g. "abc1234 Fix bug in auth".
+    pub fn oneline(&self) -> String {
+        format!("{} {}", self.hash, self.message)
+    }
+}
+
+/// The last commit that touched each file of `HEAD` in the repository containing `path`.
+///
+/// History is walked once from `HEAD`, newest first, until every file has been
+/// seen. As with `git log -- <file>`, a merge only counts as touching a file
+/// when the file differs from all of its parents.
+///
+/// Returns `Ok(None)` when `path` is not inside a git repository. Paths are
+/// absolute and canonical.
+pub fn last_commits(path: &Path) -> Result<Option<HashMap<PathBuf, GitCommitInfo>>, git2::Error> {
+    let Ok(repo) = Repository::discover(path) else {
+        return Ok(None);
+    };
+    let Some(workdir) = repo.workdir() else {
+        return Ok(None);
+    };
+    let repo_root = workdir
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.to_path_buf());
+
+    // A repository without commits has no history yet
+    let Ok(head) = repo.head() else {
+        return Ok(Some(HashMap::new()));
+    };
+    let mut remaining = HashSet::new();
+    head.peel_to_tree()?
+        .walk(TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() == Some(ObjectType::Blob) {
+                remaining.insert(PathBuf::from(format!(
+                    "{}{}",
+                    dir,
+                    entry.name().unwrap_or_default()
+                )));
+            }
+            TreeWalkResult::Ok
+        })?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.set_sorting(Sort::TIME)?;
+
+    let mut commits = HashMap::new();
+    for oid in revwalk {
+        if remaining.is_empty() {
+            break;
+        }
+        let commit = repo.find_commit(oid?)?;
+        let tree = commit.tree()?;
+
+        // Files changed relative to every parent (just the root tree's files
+        // for a root commit)
+        let mut touched: Option<HashSet<PathBuf>> = None;
+        let parents: Vec<Option<Tree>> = if commit.parent_count() == 0 {
+            vec![None]
+        } else {
+            commit
+                .parents()
+                .map(|parent| parent.tree().map(Some))
+                .collect::<Result<_, _>>()?
+        };
+        for parent_tree in parents {
+            let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+            let changed: HashSet<PathBuf> = diff
+                .deltas()
+                .filter(|delta| delta.status() != Delta::Deleted)
+                .filter_map(|delta| delta.new_file().path().map(Path::to_path_buf))
+                .collect();
+            touched = Some(match touched {
+                Some(touched) => touched.intersection(&changed).cloned().collect(),
+                None => changed,
+            });
+        }
+
+        let touched: Vec<PathBuf> = touched
+            .into_iter()
+            .flatten()
+            .filter(|file| remaining.remove(file))
+            .collect();
+        if touched.is_empty() {
+            continue;
+        }
+        let info = GitCommitInfo::from_commit(&commit)?;
+        for file in touched {
+            commits.insert(repo_root.join(file), info.clone());
+        }
+    }
+    Ok(Some(commits))
+}
+
 /// Filter based on git tracking status (files in the git index).
 /// Use this with --tracked flag to show only git-tracked files.
 pub struct GitFilter {
@@ -157,11 +457,12 @@ impl GitFilter {
         for entry in statuses.iter() {
             let status = entry.status();
             // Include files that are tracked (not new/untracked)
-            if !status.contains(Status::WT_NEW) && !status.contains(Status::IGNORED) {
-                if let Some(path) = entry.path() {
-                    let full_path = repo_root.join(path);
-                    tracked.insert(full_path);
-                }
+            if !status.contains(Status::WT_NEW)
+                && !status.contains(Status::IGNORED)
+                && let Some(path) = entry.path()
+            {
+                let full_path = repo_root.join(path);
+                tracked.insert(full_path);
             }
         }
 
@@ -194,6 +495,14 @@ mod tests {
     use super::*;
     use crate::test_utils::TestRepo;
 
+    #[test]
+    fn test_filters_are_thread_safe() {
+        // Walkers evaluate filters from rayon worker threads
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<GitignoreFilter>();
+        assert_send_sync::<GitFilter>();
+    }
+
     #[test]
     fn test_tracked_file() {
         let repo = TestRepo::with_git();
@@ -234,6 +543,30 @@ mod tests {
         assert!(!filter.is_included(&repo.path().join("debug.log")));
     }
 
+    #[test]
+    fn test_gitignore_always_ignored_directories() {
+        let repo = TestRepo::with_git();
+        repo.add_file("node_modules/left-pad/index.js", "module.exports = 1;");
+        repo.add_file("build/script.sh", "echo hi");
+        repo.add_file("src/build.rs", "fn main() {}");
+
+        // Nothing is rejected by name unless asked for
+        let filter = GitignoreFilter::new(repo.path()).unwrap();
+        assert!(!filter.is_definitely_ignored(&repo.path().join("build")));
+        assert!(filter.is_included(&repo.path().join("build")));
+
+        let filter = filter.with_always_ignored(DEFAULT_ALWAYS_IGNORED.iter().copied());
+        assert!(filter.is_definitely_ignored(&repo.path().join("node_modules")));
+        assert!(!filter.is_included(&repo.path().join("build")));
+        // Only directories are matched by name
+        assert!(!filter.is_definitely_ignored(&repo.path().join("src/build.rs")));
+        assert!(filter.is_included(&repo.path().join("src")));
+
+        let filter = filter.with_always_ignored(["node_modules"]);
+        assert!(filter.is_included(&repo.path().join("build")));
+        assert!(!filter.is_included(&repo.path().join("node_modules")));
+    }
+
     #[test]
     fn test_gitignore_directory() {
         let repo = TestRepo::with_git();
@@ -306,4 +639,80 @@ mod tests {
         // important.log should be included (negation pattern)
         assert!(filter.is_included(&repo.path().join("important.log")));
     }
+
+    #[test]
+    fn test_parent_gitignores_closest_rule_wins() {
+        let repo = TestRepo::with_git();
+        repo.add_untracked(".gitignore", "*.log\n");
+        repo.add_untracked("vendor/.gitignore", "!keep.log\n*.tmp\n");
+        repo.add_untracked("vendor/keep.log", "log");
+        repo.add_untracked("vendor/drop.log", "log");
+        repo.add_untracked("vendor/cache.tmp", "temp");
+        repo.add_untracked("cache.tmp", "temp");
+
+        let filter = GitignoreFilter::new_with_parent_gitignores(repo.path()).unwrap();
+
+        assert!(filter.is_included(&repo.path().join("vendor/keep.log")));
+        assert!(!filter.is_included(&repo.path().join("vendor/drop.log")));
+        assert!(!filter.is_included(&repo.path().join("vendor/cache.tmp")));
+        // Rules of a subdirectory do not apply to its parent
+        assert!(filter.is_included(&repo.path().join("cache.tmp")));
+    }
+
+    #[test]
+    fn test_parent_gitignores_from_subdirectory() {
+        let repo = TestRepo::with_git();
+        repo.add_untracked(".gitignore", "*.log\nbuild/\n");
+        repo.add_untracked("src/main.rs", "fn main() {}");
+        repo.add_untracked("src/debug.log", "log");
+        repo.add_untracked("src/build/out.rs", "fn out() {}");
+
+        let filter = GitignoreFilter::new_with_parent_gitignores(&repo.path().join("src")).unwrap();
+
+        assert!(filter.is_included(&repo.path().join("src/main.rs")));
+        assert!(!filter.is_included(&repo.path().join("src/debug.log")));
+        assert!(!filter.is_included(&repo.path().join("src/build/out.rs")));
+    }
+
+    #[test]
+    fn test_parent_gitignores_without_git() {
+        let dir = TestRepo::new();
+        dir.add_untracked(".gitignore", "*.log\n");
+        dir.add_untracked("main.rs", "fn main() {}");
+        dir.add_untracked("debug.log", "log");
+
+        assert!(GitignoreFilter::new(dir.path()).is_none());
+        let filter = GitignoreFilter::new_with_parent_gitignores(dir.path()).unwrap();
+        assert!(filter.is_included(&dir.path().join("main.rs")));
+        assert!(!filter.is_included(&dir.path().join("debug.log")));
+    }
+
+    #[test]
+    fn test_last_commits() {
+        let repo = TestRepo::with_git();
+        repo.add_file("a.rs", "fn a() {}");
+        repo.add_file("b.rs", "fn b() {}");
+        repo.commit("Add a and b");
+        repo.add_file("a.rs", "fn a() { todo!() }");
+        repo.commit("Change a");
+        repo.add_untracked("c.rs", "fn c() {}");
+
+        let commits = last_commits(repo.path()).unwrap().unwrap();
+        let root = repo.path().canonicalize().unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[&root.join("a.rs")].message, "Change a");
+        assert_eq!(commits[&root.join("a.rs")].author, "Test");
+        assert_eq!(commits[&root.join("b.rs")].message, "Add a and b");
+        assert!(
+            commits[&root.join("b.rs")]
+                .oneline()
+                .ends_with(" Add a and b")
+        );
+    }
+
+    #[test]
+    fn test_last_commits_outside_repository() {
+        let dir = TestRepo::new();
+        assert!(last_commits(dir.path()).unwrap().is_none());
+    }
 }
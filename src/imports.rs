@@ -8,7 +8,8 @@ use serde::Serialize;
 use std::path::Path;
 use std::sync::LazyLock;
 
-use crate::file_utils::read_source_file;
+use crate::file_utils::{ExtractionConfig, read_source_file};
+use crate::language::Language;
 
 /// Categorized imports from a source file.
 #[derive(Debug, Clone, Default, Serialize)]
@@ -22,6 +23,10 @@ pub struct FileImports {
     /// Internal/project imports
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub internal: Vec<String>,
+    /// Whether only the internal imports are shown in the tree; serialized
+    /// only when set
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub filtered: bool,
 }
 
 impl FileImports {
@@ -29,11 +34,42 @@ impl FileImports {
         self.external.is_empty() && self.std.is_empty() && self.internal.is_empty()
     }
 
+    /// Whether any import (of any kind) contains `module` as a substring.
+    pub fn contains_module(&self, module: &str) -> bool {
+        self.external
+            .iter()
+            .chain(&self.std)
+            .chain(&self.internal)
+            .any(|import| import.contains(module))
+    }
+
+    /// Like `contains_module`, but ignoring case.
+    pub fn contains_module_ignore_case(&self, module: &str) -> bool {
+        let module = module.to_lowercase();
+        self.external
+            .iter()
+            .chain(&self.std)
+            .chain(&self.internal)
+            .any(|import| import.to_lowercase().contains(&module))
+    }
+
     /// Get total number of imports
     pub fn total(&self) -> usize {
         self.external.len() + self.std.len() + self.internal.len()
     }
 
+    /// Keep only the first `max` imports, in `summary` order (external, then
+    /// std, then internal), returning how many were dropped.
+    pub fn truncate(&mut self, max: usize) -> usize {
+        let dropped = self.total().saturating_sub(max);
+        let mut remaining = max;
+        for imports in [&mut self.external, &mut self.std, &mut self.internal] {
+            imports.truncate(remaining);
+            remaining -= imports.len();
+        }
+        dropped
+    }
+
     /// Get a summary string for display
     pub fn summary(&self) -> String {
         let mut parts = Vec::new();
@@ -52,7 +88,12 @@ impl FileImports {
 
 /// Extract imports from a file.
 pub fn extract_imports(path: &Path) -> Option<FileImports> {
-    let (content, extension) = read_source_file(path)?;
+    extract_imports_with_config(path, &ExtractionConfig::global())
+}
+
+/// Like `extract_imports`, skipping files over `config.max_file_size`.
+pub fn extract_imports_with_config(path: &Path, config: &ExtractionConfig) -> Option<FileImports> {
+    let (content, extension) = read_source_file(path, config.max_file_size)?;
 
     // Extension is already normalized to lowercase by read_source_file
     let imports = match extension {
@@ -61,12 +102,21 @@ pub fn extract_imports(path: &Path) -> Option<FileImports> {
         "js" => extract_javascript_imports(&content),
         "py" => extract_python_imports(&content),
         "go" => extract_go_imports(&content),
+        "pl" => extract_perl_imports(&content),
         _ => None,
     };
 
     imports.filter(|i| !i.is_empty())
 }
 
+/// Whether `extract_imports` understands files in `language`.
+pub fn supports_imports(language: Language) -> bool {
+    matches!(
+        language.extension(),
+        "rs" | "ts" | "js" | "py" | "go" | "pl"
+    )
+}
+
 // =============================================================================
 // Rust import extraction
 // =============================================================================
@@ -80,11 +130,11 @@ fn extract_rust_imports(content: &str) -> Option<FileImports> {
     for line in content.lines() {
         let trimmed = line.trim();
 
-        if let Some(caps) = RUST_USE.captures(trimmed) {
-            if let Some(use_path) = caps.get(1) {
-                let path_str = use_path.as_str().trim();
-                categorize_rust_import(path_str, &mut imports);
-            }
+        if let Some(caps) = RUST_USE.captures(trimmed)
+            && let Some(use_path) = caps.get(1)
+        {
+            let path_str = use_path.as_str().trim();
+            categorize_rust_import(path_str, &mut imports);
         }
     }
 
@@ -120,7 +170,7 @@ fn categorize_rust_import(path: &str, imports: &mut FileImports) {
 
 /// Simplify a Rust path for display (e.g., std::path::Path -> path::Path)
 fn simplify_path(path: &str) -> String {
-    // For std/core/alloc/crate/self, remove the prefix
+    // For std/core/alloc/crate, remove the prefix
     if let Some(stripped) = path.strip_prefix("std::") {
         stripped.to_string()
     } else if let Some(stripped) = path.strip_prefix("core::") {
@@ -129,10 +179,8 @@ fn simplify_path(path: &str) -> String {
         stripped.to_string()
     } else if let Some(stripped) = path.strip_prefix("crate::") {
         stripped.to_string()
-    } else if let Some(stripped) = path.strip_prefix("self::") {
-        stripped.to_string()
     } else {
-        // Keep super:: and other prefixes for clarity
+        // Keep self::, super:: and other prefixes for clarity
         path.to_string()
     }
 }
@@ -199,17 +247,17 @@ fn extract_typescript_imports(content: &str) -> Option<FileImports> {
         let trimmed = line.trim();
 
         // Check import statements
-        if let Some(caps) = TS_IMPORT.captures(trimmed) {
-            if let Some(module) = caps.get(1) {
-                categorize_js_import(module.as_str(), &mut imports);
-            }
+        if let Some(caps) = TS_IMPORT.captures(trimmed)
+            && let Some(module) = caps.get(1)
+        {
+            categorize_js_import(module.as_str(), &mut imports);
         }
 
         // Check export from statements
-        if let Some(caps) = TS_EXPORT_FROM.captures(trimmed) {
-            if let Some(module) = caps.get(1) {
-                categorize_js_import(module.as_str(), &mut imports);
-            }
+        if let Some(caps) = TS_EXPORT_FROM.captures(trimmed)
+            && let Some(module) = caps.get(1)
+        {
+            categorize_js_import(module.as_str(), &mut imports);
         }
     }
 
@@ -223,24 +271,24 @@ fn extract_javascript_imports(content: &str) -> Option<FileImports> {
         let trimmed = line.trim();
 
         // Check ES6 import statements
-        if let Some(caps) = TS_IMPORT.captures(trimmed) {
-            if let Some(module) = caps.get(1) {
-                categorize_js_import(module.as_str(), &mut imports);
-            }
+        if let Some(caps) = TS_IMPORT.captures(trimmed)
+            && let Some(module) = caps.get(1)
+        {
+            categorize_js_import(module.as_str(), &mut imports);
         }
 
         // Check require() calls
-        if let Some(caps) = JS_REQUIRE.captures(trimmed) {
-            if let Some(module) = caps.get(1) {
-                categorize_js_import(module.as_str(), &mut imports);
-            }
+        if let Some(caps) = JS_REQUIRE.captures(trimmed)
+            && let Some(module) = caps.get(1)
+        {
+            categorize_js_import(module.as_str(), &mut imports);
         }
 
         // Check export from statements
-        if let Some(caps) = TS_EXPORT_FROM.captures(trimmed) {
-            if let Some(module) = caps.get(1) {
-                categorize_js_import(module.as_str(), &mut imports);
-            }
+        if let Some(caps) = TS_EXPORT_FROM.captures(trimmed)
+            && let Some(module) = caps.get(1)
+        {
+            categorize_js_import(module.as_str(), &mut imports);
         }
     }
 
@@ -495,10 +543,10 @@ fn extract_python_imports(content: &str) -> Option<FileImports> {
         }
 
         // Check "import X" statements
-        if let Some(caps) = PY_IMPORT.captures(trimmed) {
-            if let Some(module) = caps.get(1) {
-                categorize_python_import(module.as_str(), false, &mut imports);
-            }
+        if let Some(caps) = PY_IMPORT.captures(trimmed)
+            && let Some(module) = caps.get(1)
+        {
+            categorize_python_import(module.as_str(), false, &mut imports);
         }
 
         // Check "from X import Y" statements
@@ -553,10 +601,10 @@ fn extract_go_imports(content: &str) -> Option<FileImports> {
         let trimmed = line.trim();
 
         // Single-line import
-        if let Some(caps) = GO_IMPORT_SINGLE.captures(trimmed) {
-            if let Some(pkg) = caps.get(1) {
-                categorize_go_import(pkg.as_str(), &mut imports);
-            }
+        if let Some(caps) = GO_IMPORT_SINGLE.captures(trimmed)
+            && let Some(pkg) = caps.get(1)
+        {
+            categorize_go_import(pkg.as_str(), &mut imports);
         }
 
         // Import block start
@@ -572,12 +620,11 @@ fn extract_go_imports(content: &str) -> Option<FileImports> {
         }
 
         // Inside import block
-        if in_import_block {
-            if let Some(caps) = GO_IMPORT_BLOCK_LINE.captures(trimmed) {
-                if let Some(pkg) = caps.get(1) {
-                    categorize_go_import(pkg.as_str(), &mut imports);
-                }
-            }
+        if in_import_block
+            && let Some(caps) = GO_IMPORT_BLOCK_LINE.captures(trimmed)
+            && let Some(pkg) = caps.get(1)
+        {
+            categorize_go_import(pkg.as_str(), &mut imports);
         }
     }
 
@@ -611,10 +658,405 @@ fn categorize_go_import(pkg: &str, imports: &mut FileImports) {
     }
 }
 
+// =============================================================================
+// Perl import extraction
+// =============================================================================
+
+// `use Module qw(...)` or `require Module`
+static PERL_USE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:use|require)\s+([A-Za-z_][\w:]*)").expect("PERL_USE regex is invalid")
+});
+
+// `require 'lib/helpers.pl'`, which loads a file of the project
+static PERL_REQUIRE_FILE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^require\s*\(?\s*['"]([^'"]+)['"]"#).expect("PERL_REQUIRE_FILE regex is invalid")
+});
+
+// Commonly used modules that ship with Perl itself
+const PERL_CORE_MODULES: &[&str] = &[
+    "B",
+    "Benchmark",
+    "Carp",
+    "Config",
+    "Cwd",
+    "Data::Dumper",
+    "Digest::MD5",
+    "Digest::SHA",
+    "DynaLoader",
+    "Encode",
+    "English",
+    "Env",
+    "Errno",
+    "Exporter",
+    "Fcntl",
+    "File::Basename",
+    "File::Copy",
+    "File::Find",
+    "File::Path",
+    "File::Spec",
+    "File::Spec::Functions",
+    "File::Temp",
+    "File::stat",
+    "FindBin",
+    "Getopt::Long",
+    "Getopt::Std",
+    "HTTP::Tiny",
+    "IO::File",
+    "IO::Handle",
+    "IO::Select",
+    "IO::Socket",
+    "IO::Socket::INET",
+    "IPC::Open2",
+    "IPC::Open3",
+    "JSON::PP",
+    "List::Util",
+    "MIME::Base64",
+    "Math::BigFloat",
+    "Math::BigInt",
+    "POSIX",
+    "Pod::Usage",
+    "Scalar::Util",
+    "Socket",
+    "Storable",
+    "Symbol",
+    "Sys::Hostname",
+    "Term::ANSIColor",
+    "Test::Builder",
+    "Test::More",
+    "Test::Simple",
+    "Text::Abbrev",
+    "Text::ParseWords",
+    "Text::Wrap",
+    "Tie::Hash",
+    "Time::HiRes",
+    "Time::Local",
+    "Time::Piece",
+    "Unicode::Normalize",
+];
+
+fn extract_perl_imports(content: &str) -> Option<FileImports> {
+    let mut imports = FileImports::default();
+    let mut in_pod = false;
+
+    for line in content.lines() {
+        if in_pod {
+            in_pod = !line.starts_with("=cut");
+            continue;
+        }
+        let pod_directive = line
+            .strip_prefix('=')
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_alphabetic()));
+        if pod_directive {
+            in_pod = !line.starts_with("=cut");
+            continue;
+        }
+        if matches!(line.trim_end(), "__END__" | "__DATA__") {
+            break;
+        }
+
+        let trimmed = line.trim();
+
+        if let Some(caps) = PERL_REQUIRE_FILE.captures(trimmed)
+            && let Some(file) = caps.get(1)
+        {
+            if !imports.internal.contains(&file.as_str().to_string()) {
+                imports.internal.push(file.as_str().to_string());
+            }
+        } else if let Some(caps) = PERL_USE.captures(trimmed)
+            && let Some(module) = caps.get(1)
+        {
+            categorize_perl_import(module.as_str(), &mut imports);
+        }
+    }
+
+    Some(imports)
+}
+
+fn categorize_perl_import(module: &str, imports: &mut FileImports) {
+    // `use v5.36;` only requires a Perl version
+    if module
+        .strip_prefix('v')
+        .is_some_and(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+    {
+        return;
+    }
+    // Pragmas like `strict` and `warnings` are lowercase
+    let is_core =
+        PERL_CORE_MODULES.contains(&module) || module.starts_with(|c: char| c.is_ascii_lowercase());
+    let list = if is_core {
+        &mut imports.std
+    } else {
+        &mut imports.external
+    };
+    if !list.contains(&module.to_string()) {
+        list.push(module.to_string());
+    }
+}
+
+// =============================================================================
+// Import resolution
+// =============================================================================
+//
+// Graph output, `--imports-depth` and `--import-graph` follow each internal
+// import to a file in the walked tree by comparing the import against each
+// file's module path.
+
+/// File stems that stand for their enclosing directory as a module.
+const MODULE_INDEX_STEMS: [&str; 3] = ["mod", "index", "__init__"];
+
+/// Module path of a file: its relative path without the extension, with
+/// index files (`mod.rs`, `index.ts`, `__init__.py`, ...) standing for their directory.
+pub(crate) fn module_key(path: &str) -> String {
+    let without_ext = match path.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() && !stem.ends_with('/') => stem,
+        _ => path,
+    };
+    match without_ext.rsplit_once('/') {
+        Some((dir, stem)) if MODULE_INDEX_STEMS.contains(&stem) => dir.to_string(),
+        None if MODULE_INDEX_STEMS.contains(&without_ext) => String::new(),
+        _ => without_ext.to_string(),
+    }
+}
+
+/// Resolve an internal import string to the index of the file it refers to.
+///
+/// Relative paths (`./util`, `../lib/api`) and Python relative modules
+/// (`.models.user`, `..config`) are resolved against the importing file's
+/// directory. In Rust files, `super::` and `self::` paths are resolved from
+/// the importing module and other paths from the crate root. Any other module
+/// path (`app/internal/db`) matches the file whose module path ends with it;
+/// a path that several files end with resolves to nothing.
+pub(crate) fn resolve_import(import: &str, from: &str, module_keys: &[String]) -> Option<usize> {
+    let from_dir = parent_dir(from);
+    if import.starts_with("./") || import.starts_with("../") {
+        let target = join_relative(from_dir, import.split('/'))?;
+        return exact_key(&module_key(&target), module_keys);
+    }
+
+    // Drop grouped items (`output::{a, b}`)
+    let base = import.split('{').next().unwrap_or(import);
+    if from.ends_with(".rs") {
+        return resolve_rust_import(base, from, module_keys);
+    }
+    if let Some(module) = base.strip_prefix('.') {
+        // Each dot after the first climbs one package
+        let name = module.trim_start_matches('.');
+        let ups = std::iter::repeat_n("..", module.len() - name.len());
+        let target = join_relative(from_dir, ups.chain(name.split('.')))?;
+        return exact_key(&target, module_keys);
+    }
+
+    let segments: Vec<&str> = base.split(['.', '/']).filter(|s| !s.is_empty()).collect();
+    for len in (1..=segments.len()).rev() {
+        let wanted = segments[..len].join("/");
+        if let Some(index) = exact_key(&wanted, module_keys) {
+            return Some(index);
+        }
+        let mut matches = module_keys.iter().enumerate().filter(|(_, key)| {
+            key.strip_suffix(&wanted)
+                .is_some_and(|rest| rest.ends_with('/'))
+        });
+        match (matches.next(), matches.next()) {
+            (Some((index, _)), None) => return Some(index),
+            (Some(_), Some(_)) => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Resolve a Rust `use` path, with `crate::` already stripped for crate paths.
+///
+/// The longest prefix of the path naming a module file wins, so
+/// `tree::SizeStyle` resolves to `tree.rs` or `tree/mod.rs` under the crate root.
+fn resolve_rust_import(path: &str, from: &str, module_keys: &[String]) -> Option<usize> {
+    let mut segments = path
+        .split("::")
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .peekable();
+    let mut module = match segments.peek() {
+        Some(&("self" | "super")) => rust_module_path(from),
+        _ => rust_crate_root(from, module_keys)?,
+    };
+    while let Some(&prefix) = segments.peek() {
+        match prefix {
+            "crate" => module = rust_crate_root(from, module_keys)?,
+            "self" => {}
+            "super" => module = parent_dir(&module).to_string(),
+            _ => break,
+        }
+        segments.next();
+    }
+
+    let names: Vec<&str> = segments.filter(|s| *s != "*").collect();
+    (1..=names.len()).rev().find_map(|len| {
+        let target = join_relative(&module, names[..len].iter().copied())?;
+        exact_key(&target, module_keys)
+    })
+}
+
+/// Module path of a Rust file: `mod.rs`, `lib.rs` and `main.rs` stand for
+/// their directory, other files for their path without the extension.
+fn rust_module_path(path: &str) -> String {
+    let key = module_key(path);
+    match key.rsplit_once('/') {
+        Some((dir, "lib" | "main")) => dir.to_string(),
+        None if matches!(key.as_str(), "lib" | "main") => String::new(),
+        _ => key,
+    }
+}
+
+/// The nearest directory above `from` that holds a `lib.rs` or `main.rs`.
+fn rust_crate_root(from: &str, module_keys: &[String]) -> Option<String> {
+    let mut dir = parent_dir(from);
+    loop {
+        let is_root = ["lib", "main"].iter().any(|stem| {
+            let key = join_relative(dir, std::iter::once(*stem)).unwrap_or_default();
+            module_keys.contains(&key)
+        });
+        if is_root {
+            return Some(dir.to_string());
+        }
+        if dir.is_empty() {
+            return None;
+        }
+        dir = parent_dir(dir);
+    }
+}
+
+/// The directory part of a `/`-separated path, or `""` at the top.
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// Join `parts` onto `dir`, following `..` and ignoring `.` and empty parts.
+/// Returns `None` when `..` climbs above the top.
+fn join_relative<'a>(dir: &'a str, parts: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let mut joined: Vec<&str> = dir.split('/').filter(|p| !p.is_empty()).collect();
+    for part in parts {
+        match part {
+            "." | "" => {}
+            ".." => {
+                joined.pop()?;
+            }
+            _ => joined.push(part),
+        }
+    }
+    Some(joined.join("/"))
+}
+
+/// Index of the file whose module path is exactly `target`.
+fn exact_key(target: &str, module_keys: &[String]) -> Option<usize> {
+    module_keys.iter().position(|key| key == target)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn keys(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| module_key(p)).collect()
+    }
+
+    #[test]
+    fn test_module_key() {
+        assert_eq!(module_key("src/output/config.rs"), "src/output/config");
+        assert_eq!(module_key("src/output/mod.rs"), "src/output");
+        assert_eq!(module_key("app/models/__init__.py"), "app/models");
+        assert_eq!(module_key("index.ts"), "");
+        assert_eq!(module_key(".gitignore"), ".gitignore");
+    }
+
+    #[test]
+    fn test_resolve_rust_imports() {
+        let keys = keys(&[
+            "src/main.rs",
+            "src/output/mod.rs",
+            "src/output/config.rs",
+            "src/output/json.rs",
+        ]);
+        let from = "src/main.rs";
+        assert_eq!(
+            resolve_import("output::config::OutputConfig", from, &keys),
+            Some(2)
+        );
+        assert_eq!(
+            resolve_import("output::{config, json}", from, &keys),
+            Some(1)
+        );
+        assert_eq!(
+            resolve_import("super::config", "src/output/json.rs", &keys),
+            Some(2)
+        );
+        assert_eq!(
+            resolve_import("self::json", "src/output/mod.rs", &keys),
+            Some(3)
+        );
+        assert_eq!(
+            resolve_import("super::*", "src/output/json.rs", &keys),
+            None
+        );
+        assert_eq!(resolve_import("missing::Thing", from, &keys), None);
+    }
+
+    #[test]
+    fn test_resolve_rust_imports_from_crate_root() {
+        // `tree` names both a crate module and a sibling of output/config.rs
+        let keys = keys(&[
+            "src/lib.rs",
+            "src/output/mod.rs",
+            "src/output/config.rs",
+            "src/output/tree.rs",
+            "src/tree/mod.rs",
+        ]);
+        let from = "src/output/config.rs";
+        assert_eq!(resolve_import("tree::SizeStyle", from, &keys), Some(4));
+        assert_eq!(
+            resolve_import("super::tree::TreeFormatter", from, &keys),
+            Some(3)
+        );
+        assert_eq!(
+            resolve_import("self::tree", "src/output/mod.rs", &keys),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_resolve_ambiguous_suffix() {
+        let keys = keys(&["a/app/util.go", "b/app/util.go", "b/app/db.go"]);
+        assert_eq!(resolve_import("app/util", "main.go", &keys), None);
+        assert_eq!(resolve_import("app/db", "main.go", &keys), Some(2));
+    }
+
+    #[test]
+    fn test_resolve_relative_imports() {
+        let keys = keys(&["src/app.ts", "src/util.ts", "lib/api/index.js"]);
+        assert_eq!(resolve_import("./util", "src/app.ts", &keys), Some(1));
+        assert_eq!(resolve_import("./util.ts", "src/app.ts", &keys), Some(1));
+        assert_eq!(resolve_import("../lib/api", "src/app.ts", &keys), Some(2));
+        assert_eq!(resolve_import("../../outside", "src/app.ts", &keys), None);
+    }
+
+    #[test]
+    fn test_resolve_python_imports() {
+        let keys = keys(&[
+            "app/main.py",
+            "app/models/__init__.py",
+            "app/models/user.py",
+        ]);
+        assert_eq!(
+            resolve_import(".models.user", "app/main.py", &keys),
+            Some(2)
+        );
+        assert_eq!(resolve_import(".models", "app/main.py", &keys), Some(1));
+        assert_eq!(resolve_import(".", "app/models/user.py", &keys), Some(1));
+        assert_eq!(
+            resolve_import("..main", "app/models/user.py", &keys),
+            Some(0)
+        );
+    }
+
     #[test]
     fn test_rust_imports() {
         let content = r#"
@@ -695,12 +1137,50 @@ import (
         );
     }
 
+    #[test]
+    fn test_perl_imports() {
+        let content = r#"
+use strict;
+use warnings;
+use v5.36;
+use 5.010;
+use POSIX qw(floor ceil);
+use File::Spec;
+use Moose;
+use Mojo::UserAgent;
+use Moose;
+require Try::Tiny;
+require 'lib/helpers.pl';
+require("config.pl");
+
+=head1 SYNOPSIS
+
+use Not::Imported;
+
+=cut
+
+__END__
+use After::End;
+"#;
+        let imports = extract_perl_imports(content).unwrap();
+        assert_eq!(
+            imports.std,
+            vec!["strict", "warnings", "POSIX", "File::Spec"]
+        );
+        assert_eq!(
+            imports.external,
+            vec!["Moose", "Mojo::UserAgent", "Try::Tiny"]
+        );
+        assert_eq!(imports.internal, vec!["lib/helpers.pl", "config.pl"]);
+    }
+
     #[test]
     fn test_imports_summary() {
         let imports = FileImports {
             external: vec!["clap".to_string(), "serde".to_string()],
             std: vec!["path".to_string(), "io".to_string()],
             internal: vec!["git".to_string()],
+            filtered: false,
         };
         let summary = imports.summary();
         assert!(summary.contains("clap"));
@@ -708,4 +1188,36 @@ import (
         assert!(summary.contains("std::{path, io}"));
         assert!(summary.contains("crate::{git}"));
     }
+
+    #[test]
+    fn test_imports_truncate() {
+        let mut imports = FileImports {
+            external: vec!["clap".to_string(), "serde".to_string()],
+            std: vec!["path".to_string(), "io".to_string()],
+            internal: vec!["git".to_string()],
+            filtered: false,
+        };
+        assert_eq!(imports.truncate(3), 2);
+        assert_eq!(imports.summary(), "clap, serde, std::{path}");
+        assert_eq!(imports.truncate(10), 0);
+        assert_eq!(imports.total(), 3);
+    }
+
+    #[test]
+    fn test_contains_module() {
+        let imports = FileImports {
+            external: vec!["serde_json".to_string()],
+            std: vec!["io".to_string()],
+            internal: vec!["git".to_string()],
+            filtered: false,
+        };
+        assert!(imports.contains_module("serde"));
+        assert!(imports.contains_module("io"));
+        assert!(imports.contains_module("git"));
+        assert!(!imports.contains_module("clap"));
+        assert!(!imports.contains_module("Serde"));
+        assert!(imports.contains_module_ignore_case("Serde"));
+        assert!(imports.contains_module_ignore_case("GIT"));
+        assert!(!imports.contains_module_ignore_case("Clap"));
+    }
 }
@@ -0,0 +1,701 @@
+//! Source language detection
+//!
+//! Maps files to a `Language` by extension, by well-known file names
+//! (`Dockerfile`, `Makefile`), by the interpreter named in a `#!` line or
+//! an editor modeline, or by MIME type. Extractors dispatch on
+//! `Language::extension`, the canonical extension for each language.
+
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Vim modeline: `vim: ft=python`, `vi: set filetype=sh:`, `ex: ft=ruby`.
+static VIM_MODELINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(?:vim?|ex):.*\b(?:ft|filetype)=([\w+#-]+)")
+        .expect("VIM_MODELINE regex is invalid")
+});
+
+/// Emacs modeline: `-*- mode: python; coding: utf-8 -*-` or the short form `-*- python -*-`.
+static EMACS_MODELINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"-\*-\s*(?:(?:.*;)?\s*mode:\s*([\w+#-]+)|([\w+#-]+))\s*(?:;.*)?-\*-")
+        .expect("EMACS_MODELINE regex is invalid")
+});
+
+/// What fruit extracts from the files of a language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LanguageCapabilities {
+    /// A file-level comment or description
+    pub comments: bool,
+    /// Type signatures (`--types`)
+    pub types: bool,
+    /// Imports (`--imports`)
+    pub imports: bool,
+    /// TODO and FIXME markers (`--todos`)
+    pub todos: bool,
+}
+
+/// A recognized source language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Go,
+    C,
+    Cpp,
+    CSharp,
+    Java,
+    Ruby,
+    Php,
+    Shell,
+    Swift,
+    Kotlin,
+    Scala,
+    Lua,
+    Perl,
+    R,
+    Julia,
+    Dart,
+    Elixir,
+    Erlang,
+    Haskell,
+    OCaml,
+    FSharp,
+    Clojure,
+    Zig,
+    Nix,
+    Vue,
+    Svelte,
+    Dockerfile,
+    Make,
+    Yaml,
+    Toml,
+    Markdown,
+}
+
+impl Language {
+    /// Every language, in declaration order.
+    const ALL: [Language; 35] = [
+        Language::Rust,
+        Language::Python,
+        Language::JavaScript,
+        Language::TypeScript,
+        Language::Go,
+        Language::C,
+        Language::Cpp,
+        Language::CSharp,
+        Language::Java,
+        Language::Ruby,
+        Language::Php,
+        Language::Shell,
+        Language::Swift,
+        Language::Kotlin,
+        Language::Scala,
+        Language::Lua,
+        Language::Perl,
+        Language::R,
+        Language::Julia,
+        Language::Dart,
+        Language::Elixir,
+        Language::Erlang,
+        Language::Haskell,
+        Language::OCaml,
+        Language::FSharp,
+        Language::Clojure,
+        Language::Zig,
+        Language::Nix,
+        Language::Vue,
+        Language::Svelte,
+        Language::Dockerfile,
+        Language::Make,
+        Language::Yaml,
+        Language::Toml,
+        Language::Markdown,
+    ];
+
+    /// All languages, e.g. for listing the values of `--lang`.
+    pub fn all() -> &'static [Language] {
+        &Self::ALL
+    }
+
+    /// File extensions (lowercase, without the dot) recognized by
+    /// `from_extension`, canonical extension first.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &["rs"],
+            Language::Python => &["py", "pyw", "pyi"],
+            Language::JavaScript => &["js", "jsx", "mjs", "cjs"],
+            Language::TypeScript => &["ts", "tsx", "mts", "cts"],
+            Language::Go => &["go"],
+            Language::C => &["c", "h"],
+            Language::Cpp => &["cpp", "cxx", "cc", "hpp", "hxx", "hh"],
+            Language::CSharp => &["cs"],
+            Language::Java => &["java"],
+            Language::Ruby => &["rb"],
+            Language::Php => &["php"],
+            Language::Shell => &["sh", "bash", "zsh", "fish"],
+            Language::Swift => &["swift"],
+            Language::Kotlin => &["kt", "kts"],
+            Language::Scala => &["scala", "sc"],
+            Language::Lua => &["lua"],
+            Language::Perl => &["pl", "pm", "t"],
+            Language::R => &["r"],
+            Language::Julia => &["jl"],
+            Language::Dart => &["dart"],
+            Language::Elixir => &["ex", "exs"],
+            Language::Erlang => &["erl", "hrl"],
+            Language::Haskell => &["hs", "lhs"],
+            Language::OCaml => &["ml", "mli"],
+            Language::FSharp => &["fs", "fsi", "fsx"],
+            Language::Clojure => &["clj", "cljs", "cljc", "edn"],
+            Language::Zig => &["zig"],
+            Language::Nix => &["nix"],
+            Language::Vue => &["vue"],
+            Language::Svelte => &["svelte"],
+            Language::Dockerfile => &["dockerfile"],
+            Language::Make => &["mk", "mak"],
+            Language::Yaml => &["yaml", "yml"],
+            Language::Toml => &["toml"],
+            Language::Markdown => &["md", "mdx", "markdown"],
+        }
+    }
+
+    /// What fruit extracts from files of this language. TODO markers are
+    /// found in the comments of every language.
+    pub fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities {
+            comments: crate::comments::supports_comments(*self),
+            types: crate::types::supports_types(*self),
+            imports: crate::imports::supports_imports(*self),
+            todos: true,
+        }
+    }
+
+    /// Detect a language from a file extension (case-insensitive, without the dot).
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        let ext_lower = ext.to_lowercase();
+        let language = match ext_lower.as_str() {
+            "rs" => Language::Rust,
+            "py" | "pyw" | "pyi" => Language::Python,
+            "js" | "jsx" | "mjs" | "cjs" => Language::JavaScript,
+            "ts" | "tsx" | "mts" | "cts" => Language::TypeScript,
+            "go" => Language::Go,
+            "c" | "h" => Language::C,
+            "cpp" | "cxx" | "cc" | "hpp" | "hxx" | "hh" => Language::Cpp,
+            "cs" => Language::CSharp,
+            "java" => Language::Java,
+            "rb" => Language::Ruby,
+            "php" => Language::Php,
+            "sh" | "bash" | "zsh" | "fish" => Language::Shell,
+            "swift" => Language::Swift,
+            "kt" | "kts" => Language::Kotlin,
+            "scala" | "sc" => Language::Scala,
+            "lua" => Language::Lua,
+            "pl" | "pm" | "t" => Language::Perl,
+            "r" => Language::R,
+            "jl" => Language::Julia,
+            "dart" => Language::Dart,
+            "ex" | "exs" => Language::Elixir,
+            "erl" | "hrl" => Language::Erlang,
+            "hs" | "lhs" => Language::Haskell,
+            "ml" | "mli" => Language::OCaml,
+            "fs" | "fsi" | "fsx" => Language::FSharp,
+            "clj" | "cljs" | "cljc" | "edn" => Language::Clojure,
+            "zig" => Language::Zig,
+            "nix" => Language::Nix,
+            "vue" => Language::Vue,
+            "svelte" => Language::Svelte,
+            "dockerfile" => Language::Dockerfile,
+            "mk" | "mak" => Language::Make,
+            "yaml" | "yml" => Language::Yaml,
+            "toml" => Language::Toml,
+            "md" | "mdx" | "markdown" => Language::Markdown,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// Detect a language from well-known file names that carry no extension.
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        match name {
+            "Dockerfile" | "Containerfile" => Some(Language::Dockerfile),
+            "Makefile" | "makefile" | "GNUmakefile" => Some(Language::Make),
+            // Variants like Dockerfile.dev
+            _ if name.starts_with("Dockerfile.") => Some(Language::Dockerfile),
+            _ => None,
+        }
+    }
+
+    /// Detect a language from a path's file name or extension.
+    ///
+    /// This does not read the file; see `from_shebang` for scripts without
+    /// a recognized extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        Self::from_file_name(name).or_else(|| Self::from_extension(path.extension()?.to_str()?))
+    }
+
+    /// Detect a language from a `#!` interpreter line at the start of `content`.
+    ///
+    /// Handles both direct paths (`#!/bin/bash`) and `env` indirection
+    /// (`#!/usr/bin/env python3`). Interpreter versions are ignored.
+    pub fn from_shebang(content: &str) -> Option<Self> {
+        let line = content.lines().next()?.strip_prefix("#!")?;
+        let mut words = line.split_whitespace();
+        let mut interpreter = words.next()?.rsplit('/').next()?;
+
+        if interpreter == "env" {
+            // Skip env flags (-S, -i) and VAR=value assignments
+            interpreter = words.find(|w| !w.starts_with('-') && !w.contains('='))?;
+            interpreter = interpreter.rsplit('/').next()?;
+        }
+
+        // python3.11 -> python, node18 -> node
+        let name = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+
+        let language = match name {
+            "python" | "pypy" => Language::Python,
+            "node" | "nodejs" => Language::JavaScript,
+            "deno" | "bun" | "ts-node" | "tsx" => Language::TypeScript,
+            "sh" | "bash" | "zsh" | "fish" | "dash" | "ksh" | "ash" => Language::Shell,
+            "ruby" => Language::Ruby,
+            "perl" => Language::Perl,
+            "php" => Language::Php,
+            "lua" | "luajit" => Language::Lua,
+            "Rscript" => Language::R,
+            "julia" => Language::Julia,
+            "elixir" => Language::Elixir,
+            "escript" => Language::Erlang,
+            "runghc" | "runhaskell" | "stack" => Language::Haskell,
+            "ocaml" => Language::OCaml,
+            "dart" => Language::Dart,
+            "swift" => Language::Swift,
+            "scala" => Language::Scala,
+            "make" => Language::Make,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// Detect a language from the first line of a file: a `#!` interpreter
+    /// line, or a vim (`// vim: ft=python`) or emacs (`# -*- mode: ruby -*-`)
+    /// modeline. Modeline names are parsed like `FromStr`, so both language
+    /// names and extensions (`ft=sh`) work.
+    pub fn from_content(first_line: &str) -> Option<Self> {
+        if first_line.starts_with("#!") {
+            return Self::from_shebang(first_line);
+        }
+        let captures = VIM_MODELINE
+            .captures(first_line)
+            .or_else(|| EMACS_MODELINE.captures(first_line))?;
+        let name = captures.get(1).or_else(|| captures.get(2))?.as_str();
+        name.parse().ok()
+    }
+
+    /// Detect a language from a MIME type such as `text/x-rust` or
+    /// `application/javascript; charset=utf-8`.
+    ///
+    /// For callers that already know a file's MIME type (from a web server,
+    /// `file --mime-type`, ...); fruit itself never sniffs MIME types.
+    pub fn from_mime_type(mime: &str) -> Option<Self> {
+        let essence = mime.split(';').next()?.trim().to_ascii_lowercase();
+        let (kind, subtype) = essence.split_once('/')?;
+        if !matches!(kind, "text" | "application") {
+            return None;
+        }
+        let subtype = subtype.strip_prefix("x-").unwrap_or(subtype);
+
+        let language = match subtype {
+            "rust" | "rustsrc" => Language::Rust,
+            "python" | "python3" | "script.python" => Language::Python,
+            "javascript" | "ecmascript" | "node" => Language::JavaScript,
+            "typescript" => Language::TypeScript,
+            "go" | "golang" => Language::Go,
+            "c" | "csrc" | "chdr" => Language::C,
+            "c++" | "c++src" | "c++hdr" => Language::Cpp,
+            "csharp" => Language::CSharp,
+            "java" | "java-source" => Language::Java,
+            "ruby" => Language::Ruby,
+            "php" | "httpd-php" => Language::Php,
+            "sh" | "shellscript" | "shell" | "bash" | "zsh" => Language::Shell,
+            "swift" => Language::Swift,
+            "kotlin" => Language::Kotlin,
+            "scala" => Language::Scala,
+            "lua" => Language::Lua,
+            "perl" | "perl-script" => Language::Perl,
+            "r" | "rsrc" => Language::R,
+            "julia" => Language::Julia,
+            "dart" => Language::Dart,
+            "elixir" => Language::Elixir,
+            "erlang" => Language::Erlang,
+            "haskell" => Language::Haskell,
+            "ocaml" => Language::OCaml,
+            "fsharp" => Language::FSharp,
+            "clojure" => Language::Clojure,
+            "zig" => Language::Zig,
+            "nix" => Language::Nix,
+            "vue" => Language::Vue,
+            "svelte" => Language::Svelte,
+            "dockerfile" => Language::Dockerfile,
+            "makefile" => Language::Make,
+            "yaml" => Language::Yaml,
+            "toml" => Language::Toml,
+            "markdown" => Language::Markdown,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// Canonical lowercase extension that extractors dispatch on.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Language::Rust => "rs",
+            Language::Python => "py",
+            Language::JavaScript => "js",
+            Language::TypeScript => "ts",
+            Language::Go => "go",
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::CSharp => "cs",
+            Language::Java => "java",
+            Language::Ruby => "rb",
+            Language::Php => "php",
+            Language::Shell => "sh",
+            Language::Swift => "swift",
+            Language::Kotlin => "kt",
+            Language::Scala => "scala",
+            Language::Lua => "lua",
+            Language::Perl => "pl",
+            Language::R => "r",
+            Language::Julia => "jl",
+            Language::Dart => "dart",
+            Language::Elixir => "ex",
+            Language::Erlang => "erl",
+            Language::Haskell => "hs",
+            Language::OCaml => "ml",
+            Language::FSharp => "fs",
+            Language::Clojure => "clj",
+            Language::Zig => "zig",
+            Language::Nix => "nix",
+            Language::Vue => "vue",
+            Language::Svelte => "svelte",
+            Language::Dockerfile => "dockerfile",
+            Language::Make => "mk",
+            Language::Yaml => "yaml",
+            Language::Toml => "toml",
+            Language::Markdown => "md",
+        }
+    }
+
+    /// Lowercase language identifier, as accepted by `--lang` and `FromStr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::Python => "python",
+            Language::JavaScript => "javascript",
+            Language::TypeScript => "typescript",
+            Language::Go => "go",
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::CSharp => "csharp",
+            Language::Java => "java",
+            Language::Ruby => "ruby",
+            Language::Php => "php",
+            Language::Shell => "shell",
+            Language::Swift => "swift",
+            Language::Kotlin => "kotlin",
+            Language::Scala => "scala",
+            Language::Lua => "lua",
+            Language::Perl => "perl",
+            Language::R => "r",
+            Language::Julia => "julia",
+            Language::Dart => "dart",
+            Language::Elixir => "elixir",
+            Language::Erlang => "erlang",
+            Language::Haskell => "haskell",
+            Language::OCaml => "ocaml",
+            Language::FSharp => "fsharp",
+            Language::Clojure => "clojure",
+            Language::Zig => "zig",
+            Language::Nix => "nix",
+            Language::Vue => "vue",
+            Language::Svelte => "svelte",
+            Language::Dockerfile => "dockerfile",
+            Language::Make => "make",
+            Language::Yaml => "yaml",
+            Language::Toml => "toml",
+            Language::Markdown => "markdown",
+        }
+    }
+
+    /// Canonical language name (`Rust`, `JavaScript`, `C++`), as shown by
+    /// `Display`. `FromStr` parses it back case-insensitively.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Language::Rust => "Rust",
+            Language::Python => "Python",
+            Language::JavaScript => "JavaScript",
+            Language::TypeScript => "TypeScript",
+            Language::Go => "Go",
+            Language::C => "C",
+            Language::Cpp => "C++",
+            Language::CSharp => "C#",
+            Language::Java => "Java",
+            Language::Ruby => "Ruby",
+            Language::Php => "PHP",
+            Language::Shell => "Shell",
+            Language::Swift => "Swift",
+            Language::Kotlin => "Kotlin",
+            Language::Scala => "Scala",
+            Language::Lua => "Lua",
+            Language::Perl => "Perl",
+            Language::R => "R",
+            Language::Julia => "Julia",
+            Language::Dart => "Dart",
+            Language::Elixir => "Elixir",
+            Language::Erlang => "Erlang",
+            Language::Haskell => "Haskell",
+            Language::OCaml => "OCaml",
+            Language::FSharp => "F#",
+            Language::Clojure => "Clojure",
+            Language::Zig => "Zig",
+            Language::Nix => "Nix",
+            Language::Vue => "Vue",
+            Language::Svelte => "Svelte",
+            Language::Dockerfile => "Dockerfile",
+            Language::Make => "Makefile",
+            Language::Yaml => "YAML",
+            Language::Toml => "TOML",
+            Language::Markdown => "Markdown",
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.display_name())
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    /// Parse a language name (`rust`), common alias (`golang`, `c++`) or
+    /// extension (`rs`), case-insensitively.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        let language = match lower.as_str() {
+            "rust" => Language::Rust,
+            "python" => Language::Python,
+            "javascript" => Language::JavaScript,
+            "typescript" => Language::TypeScript,
+            "go" => Language::Go,
+            "c" => Language::C,
+            "cpp" => Language::Cpp,
+            "csharp" => Language::CSharp,
+            "java" => Language::Java,
+            "ruby" => Language::Ruby,
+            "php" => Language::Php,
+            "shell" => Language::Shell,
+            "swift" => Language::Swift,
+            "kotlin" => Language::Kotlin,
+            "scala" => Language::Scala,
+            "lua" => Language::Lua,
+            "perl" => Language::Perl,
+            "r" => Language::R,
+            "julia" => Language::Julia,
+            "dart" => Language::Dart,
+            "elixir" => Language::Elixir,
+            "erlang" => Language::Erlang,
+            "haskell" => Language::Haskell,
+            "ocaml" => Language::OCaml,
+            "fsharp" => Language::FSharp,
+            "clojure" => Language::Clojure,
+            "zig" => Language::Zig,
+            "nix" => Language::Nix,
+            "vue" => Language::Vue,
+            "svelte" => Language::Svelte,
+            "dockerfile" => Language::Dockerfile,
+            "make" => Language::Make,
+            "yaml" => Language::Yaml,
+            "toml" => Language::Toml,
+            "markdown" => Language::Markdown,
+            "golang" => Language::Go,
+            "c++" => Language::Cpp,
+            "c#" => Language::CSharp,
+            "f#" => Language::FSharp,
+            "bash" | "zsh" => Language::Shell,
+            "docker" => Language::Dockerfile,
+            "makefile" => Language::Make,
+            _ => {
+                return Language::from_extension(&lower)
+                    .ok_or_else(|| format!("unknown language '{}'", s.trim()));
+            }
+        };
+        Ok(language)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_shebang_env() {
+        assert_eq!(
+            Language::from_shebang("#!/usr/bin/env python\nprint(1)"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            Language::from_shebang("#!/usr/bin/env python3.11\n"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            Language::from_shebang("#!/usr/bin/env node\n"),
+            Some(Language::JavaScript)
+        );
+        assert_eq!(
+            Language::from_shebang("#!/usr/bin/env -S deno run --allow-net\n"),
+            Some(Language::TypeScript)
+        );
+    }
+
+    #[test]
+    fn test_from_shebang_direct_path() {
+        assert_eq!(
+            Language::from_shebang("#!/bin/bash\n"),
+            Some(Language::Shell)
+        );
+        assert_eq!(
+            Language::from_shebang("#! /bin/sh -e\n"),
+            Some(Language::Shell)
+        );
+        assert_eq!(
+            Language::from_shebang("#!/usr/bin/perl -w\n"),
+            Some(Language::Perl)
+        );
+        assert_eq!(
+            Language::from_shebang("#!/usr/bin/make -f\n"),
+            Some(Language::Make)
+        );
+    }
+
+    #[test]
+    fn test_from_shebang_rejects_non_shebangs() {
+        assert_eq!(Language::from_shebang("# comment\n#!/bin/bash"), None);
+        assert_eq!(Language::from_shebang("#!/usr/bin/env cobol\n"), None);
+        assert_eq!(Language::from_shebang(""), None);
+    }
+
+    #[test]
+    fn test_from_content_modelines() {
+        let cases = [
+            ("#!/usr/bin/env python3", Some(Language::Python)),
+            ("// vim: ft=python", Some(Language::Python)),
+            ("# vi: set filetype=sh :", Some(Language::Shell)),
+            ("/* vim: set ts=4 ft=rust: */", Some(Language::Rust)),
+            ("# -*- mode: ruby -*-", Some(Language::Ruby)),
+            ("# -*- coding: utf-8; mode: perl -*-", Some(Language::Perl)),
+            ("; -*- clojure -*-", Some(Language::Clojure)),
+            ("# -*- coding: utf-8 -*-", None),
+            ("// vim: ft=cobol", None),
+            ("fn main() {}", None),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(Language::from_content(line), expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_from_mime_type() {
+        let cases = [
+            ("text/x-rust", Some(Language::Rust)),
+            ("text/x-python", Some(Language::Python)),
+            ("application/javascript", Some(Language::JavaScript)),
+            ("text/javascript; charset=utf-8", Some(Language::JavaScript)),
+            ("Application/X-SH", Some(Language::Shell)),
+            ("text/x-c++src", Some(Language::Cpp)),
+            ("application/yaml", Some(Language::Yaml)),
+            ("text/markdown", Some(Language::Markdown)),
+            ("text/plain", None),
+            ("image/x-rust", None),
+            ("rust", None),
+        ];
+        for (mime, expected) in cases {
+            assert_eq!(Language::from_mime_type(mime), expected, "{}", mime);
+        }
+    }
+
+    #[test]
+    fn test_display_from_str_round_trip() {
+        for &language in Language::all() {
+            assert_eq!(language.to_string().parse::<Language>(), Ok(language));
+            assert_eq!(language.name().parse::<Language>(), Ok(language));
+        }
+        assert_eq!(Language::JavaScript.to_string(), "JavaScript");
+        assert_eq!(Language::Cpp.to_string(), "C++");
+        assert_eq!("Python".parse::<Language>(), Ok(Language::Python));
+        assert_eq!("golang".parse::<Language>(), Ok(Language::Go));
+        assert_eq!("c++".parse::<Language>(), Ok(Language::Cpp));
+        assert_eq!("rs".parse::<Language>(), Ok(Language::Rust));
+        assert_eq!(
+            "cobol".parse::<Language>(),
+            Err("unknown language 'cobol'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extensions_match_from_extension() {
+        for &language in Language::all() {
+            assert_eq!(language.extensions()[0], language.extension());
+            for ext in language.extensions() {
+                assert_eq!(Language::from_extension(ext), Some(language), "{}", ext);
+            }
+        }
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let rust = Language::Rust.capabilities();
+        assert!(rust.comments && rust.types && rust.imports && rust.todos);
+        let yaml = Language::Yaml.capabilities();
+        assert!(yaml.comments && !yaml.types && !yaml.imports && yaml.todos);
+        let lua = Language::Lua.capabilities();
+        assert!(!lua.comments && !lua.types && !lua.imports && lua.todos);
+        let perl = Language::from_extension("t").unwrap().capabilities();
+        assert!(perl.comments && perl.types && perl.imports && perl.todos);
+    }
+
+    #[test]
+    fn test_from_path_special_file_names() {
+        assert_eq!(
+            Language::from_path(Path::new("docker/Dockerfile")),
+            Some(Language::Dockerfile)
+        );
+        assert_eq!(
+            Language::from_path(Path::new("Dockerfile.dev")),
+            Some(Language::Dockerfile)
+        );
+        assert_eq!(
+            Language::from_path(Path::new("Makefile")),
+            Some(Language::Make)
+        );
+        assert_eq!(
+            Language::from_path(Path::new("rules.mk")),
+            Some(Language::Make)
+        );
+        assert_eq!(
+            Language::from_path(Path::new("src/main.RS")),
+            Some(Language::Rust)
+        );
+        assert_eq!(
+            Language::from_path(Path::new("flake.nix")),
+            Some(Language::Nix)
+        );
+        assert_eq!(Language::from_path(Path::new("README")), None);
+    }
+}
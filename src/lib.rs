@@ -1,12 +1,18 @@
 //! Fruit - A tree command that respects .gitignore and shows file comments
 
+pub mod cache;
 pub mod comments;
+pub mod extractors;
 pub mod file_utils;
 pub mod git;
 pub mod imports;
+pub mod language;
 pub mod metadata;
 pub mod output;
+pub mod plugin;
+pub mod profiles;
 pub mod stats;
+pub mod string_utils;
 pub mod todos;
 pub mod tree;
 pub mod types;
@@ -14,21 +20,33 @@ pub mod types;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
+pub use cache::ExtractionCache;
 pub use comments::extract_first_comment;
-pub use git::{GitFilter, GitignoreFilter};
+pub use extractors::extract_manifest_description;
+pub use file_utils::ExtractionConfig;
+pub use git::{GitCommitInfo, GitFilter, GitignoreFilter};
 pub use imports::{FileImports, extract_imports};
+pub use language::{Language, LanguageCapabilities};
 pub use metadata::{
-    CommentExtractor, LineStyle, MetadataBlock, MetadataConfig, MetadataExtractor, MetadataLine,
-    MetadataOrder,
+    CommentExtractor, ExtractorChain, LineStyle, MetadataBlock, MetadataConfig, MetadataExtractor,
+    MetadataLine, MetadataOrder,
 };
+#[cfg(feature = "templates")]
+pub use output::TemplateFormatter;
 pub use output::{
-    MarkdownFormatter, OutputConfig, StreamingFormatter, TreeFormatter, print_json, print_markdown,
+    DotFormatter, JSON_SCHEMA, MarkdownFormatter, MermaidFormatter, OrgFormatter, OutputConfig,
+    OutputStats, PlainListFormatter, PlainTextFormatter, StreamingFormatter, SummaryOnlyFormatter,
+    Theme, TreeFormatter, color_choice, print_dot, print_json, print_json_streaming,
+    print_markdown, print_mermaid, print_org,
 };
+pub use plugin::Plugin;
 pub use stats::{
-    CodebaseStats, LanguageStats, StatsCollector, StatsConfig, print_stats, print_stats_json,
+    CodebaseStats, CycleError, FileStats, ImportGraph, LanguageStats, StatsCollector, StatsConfig,
+    print_stats, print_stats_json,
 };
-pub use todos::{TodoItem, extract_todos};
+pub use todos::{TodoItem, TodoMatcher, extract_todos};
 pub use tree::{
-    FileFilter, StreamingOutput, StreamingWalker, TreeNode, TreeWalker, WalkerConfig, format_size,
+    ChildCount, FileFilter, FlattenVisitor, NodeFlags, SizeStyle, StreamingOutput, StreamingWalker,
+    TreeNode, TreeVisitor, TreeWalker, WalkerConfig, WalkerConfigBuilder, format_size,
 };
-pub use types::{TypeExtractor, extract_type_signatures};
+pub use types::{TypeExtractor, TypeSignature, extract_type_signatures};
@@ -1,16 +1,29 @@
 //! CLI entry point for fruit
 
-use std::io::IsTerminal;
+use std::io::{self, IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::mpsc;
 use std::time::{Duration, SystemTime};
 
-use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, ValueEnum};
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgMatches, CommandFactory, FromArgMatches, Parser, ValueEnum};
+#[cfg(feature = "templates")]
+use fruit::TemplateFormatter;
+use fruit::file_utils::DEFAULT_MAX_FILE_SIZE;
+use fruit::git::DEFAULT_ALWAYS_IGNORED;
+use fruit::profiles::{self, Profile, ProfileValue};
+use fruit::string_utils::parse_file_size;
 use fruit::{
-    CodebaseStats, GitignoreFilter, MarkdownFormatter, MetadataConfig, MetadataOrder, OutputConfig,
-    StatsCollector, StatsConfig, StreamingFormatter, StreamingWalker, TreeWalker, WalkerConfig,
-    print_json, print_markdown, print_stats, print_stats_json,
+    CodebaseStats, ExtractionCache, GitignoreFilter, JSON_SCHEMA, Language, MarkdownFormatter,
+    MermaidFormatter, MetadataConfig, MetadataOrder, OrgFormatter, OutputConfig,
+    PlainListFormatter, PlainTextFormatter, SizeStyle, StatsCollector, StatsConfig,
+    StreamingFormatter, StreamingWalker, SummaryOnlyFormatter, Theme, TreeNode, TreeWalker,
+    WalkerConfig, color_choice, print_dot, print_json, print_json_streaming, print_markdown,
+    print_mermaid, print_org, print_stats, print_stats_json,
 };
+use indicatif::{ProgressBar, ProgressIterator, ProgressStyle};
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
 /// Color output mode
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
@@ -24,22 +37,42 @@ enum ColorMode {
     Never,
 }
 
+/// File size format for `--format-size`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+enum SizeFormat {
+    /// Binary prefixes, 1K = 1024 bytes
+    #[default]
+    Binary,
+    /// SI prefixes, 1kB = 1000 bytes
+    Si,
+    /// Raw byte counts
+    Bytes,
+    /// Hide file sizes
+    None,
+}
+
 /// Determine whether to use color output based on mode and environment.
-fn should_use_color(mode: ColorMode) -> bool {
+/// With `ansi_only`, the terminal type in `TERM` is not consulted.
+fn should_use_color(mode: ColorMode, ansi_only: bool) -> bool {
     match mode {
         ColorMode::Always => true,
         ColorMode::Never => false,
         ColorMode::Auto => {
             // Respect NO_COLOR environment variable (https://no-color.org/)
-            if std::env::var_os("NO_COLOR").is_some() {
+            if no_color() {
                 return false;
             }
-            // Respect FORCE_COLOR environment variable
-            if std::env::var_os("FORCE_COLOR").is_some() {
+            // Respect FORCE_COLOR, and CLICOLOR_FORCE and CLICOLOR
+            // (https://bixense.com/clicolors/)
+            if std::env::var_os("FORCE_COLOR").is_some() || env_flag("CLICOLOR_FORCE") == Some(true)
+            {
                 return true;
             }
+            if env_flag("CLICOLOR") == Some(false) {
+                return false;
+            }
             // Respect TERM=dumb
-            if std::env::var("TERM").map(|t| t == "dumb").unwrap_or(false) {
+            if !ansi_only && std::env::var("TERM").map(|t| t == "dumb").unwrap_or(false) {
                 return false;
             }
             // Check if stdout is a TTY
@@ -48,6 +81,20 @@ fn should_use_color(mode: ColorMode) -> bool {
     }
 }
 
+/// A clicolor environment variable: unset or empty is `None`, `0` is off and
+/// anything else is on.
+fn env_flag(name: &str) -> Option<bool> {
+    std::env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(|value| value != "0")
+}
+
+/// Whether `NO_COLOR` asks for no colors. Per https://no-color.org/, only a
+/// non-empty value counts.
+fn no_color() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "fruit")]
 #[command(about = "A tree command that respects .gitignore and shows file comments")]
@@ -61,14 +108,42 @@ struct Args {
     #[arg(short, long)]
     all: bool,
 
+    /// Show files and directories ignored by .gitignore, dimmed and marked
+    /// [ignored], instead of hiding them (ignored directories are not expanded)
+    #[arg(long = "show-git-ignored", conflicts_with_all = ["all", "json"])]
+    show_git_ignored: bool,
+
+    /// Skip build and dependency directories (node_modules, target, dist, ...)
+    /// by name, even when no .gitignore lists them
+    #[arg(long = "skip-build-dirs", conflicts_with = "all")]
+    skip_build_dirs: bool,
+
     /// Descend only N levels deep
     #[arg(short = 'L', long = "level")]
     level: Option<usize>,
 
+    /// Hide entries less than N levels deep, showing deeper entries by their
+    /// path under the root (e.g. src/tree)
+    #[arg(long = "min-depth", value_name = "N", conflicts_with_all = ["json", "dot"])]
+    min_depth: Option<usize>,
+
     /// List directories only
     #[arg(short = 'd', long = "dirs-only")]
     dirs_only: bool,
 
+    /// Show directories even if every file under them is gitignored (or untracked with --tracked)
+    /// Combine with -d to show the full directory skeleton
+    #[arg(
+        long = "show-empty-dirs",
+        alias = "no-prune",
+        overrides_with = "no_empty_dirs"
+    )]
+    show_empty_dirs: bool,
+
+    /// Hide directories whose files are all gitignored (default)
+    #[arg(long = "no-empty-dirs", overrides_with = "show_empty_dirs")]
+    no_empty_dirs: bool,
+
     /// Show full comment, not just first line
     #[arg(short = 'f', long = "full-comment")]
     full_comment: bool,
@@ -81,6 +156,11 @@ struct Args {
     #[arg(long = "color", value_name = "WHEN", default_value = "auto")]
     color: ColorMode,
 
+    /// Write colors as raw ANSI escape codes, for terminals that support them
+    /// but are not detected as color-capable
+    #[arg(long = "ansi-only")]
+    ansi_only: bool,
+
     /// Show file comments (enabled by default unless -t is specified)
     #[arg(short = 'c', long = "comments")]
     comments: bool,
@@ -94,6 +174,19 @@ struct Args {
     #[arg(short = 't', long = "types")]
     types: bool,
 
+    /// Show the line number of each type signature (requires --types)
+    #[arg(long = "line-numbers", requires = "types")]
+    line_numbers: bool,
+
+    /// Show N lines of code after each type signature (requires --types)
+    #[arg(
+        long = "context-lines",
+        value_name = "N",
+        default_value_t = 0,
+        requires = "types"
+    )]
+    context_lines: usize,
+
     /// Show TODO/FIXME/HACK/XXX markers from comments
     /// When specified, extracts task markers and displays them beneath file entries
     #[arg(long = "todos")]
@@ -103,11 +196,66 @@ struct Args {
     #[arg(long = "todos-only", requires = "todos")]
     todos_only: bool,
 
+    /// TODO markers to match instead of the defaults (e.g. TODO,FIXME,OPTIMIZE)
+    #[arg(long = "todo-markers", value_name = "MARKERS", value_delimiter = ',')]
+    todo_markers: Vec<String>,
+
+    /// Only show TODO markers of these types (e.g. SAFETY,TODO,FIXME)
+    #[arg(
+        long = "todos-types",
+        value_name = "TYPES",
+        value_delimiter = ',',
+        requires = "todos"
+    )]
+    todos_types: Vec<String>,
+
+    /// Show only files that import MODULE (substring match, e.g. serde)
+    #[arg(long = "imports-from", value_name = "MODULE")]
+    imports_from: Option<String>,
+
+    /// Show only files with at least one import
+    #[arg(long = "imports-only")]
+    imports_only: bool,
+
+    /// Ignore case in pattern-matching filters (currently --imports-from)
+    #[arg(long = "ignore-case")]
+    ignore_case: bool,
+
+    /// Only show TODOs assigned to this user, as in `TODO(@alice): ...` (requires --todos)
+    #[arg(long = "todos-assignee", value_name = "USER", requires = "todos")]
+    todos_assignee: Option<String>,
+
     /// Show import/dependency statements from source files
     /// Extracts and categorizes imports (external, std, internal)
     #[arg(short = 'i', long = "imports")]
     imports: bool,
 
+    /// Follow internal imports N levels deep and show the external imports
+    /// they bring in as transitive dependencies (requires --imports)
+    #[arg(
+        long = "imports-depth",
+        value_name = "N",
+        default_value_t = 0,
+        requires = "imports",
+        conflicts_with_all = ["json", "dot", "template"]
+    )]
+    imports_depth: usize,
+
+    /// Show at most N imports per file, followed by a count of the rest
+    /// (requires --imports; JSON lists just the first N)
+    #[arg(long = "max-imports", value_name = "N", requires = "imports")]
+    max_imports: Option<usize>,
+
+    /// Show only internal (relative/project) imports, hiding external and
+    /// std ones; JSON keeps all categories and marks them "filtered"
+    /// (requires --imports)
+    #[arg(long = "relative-imports-only", requires = "imports")]
+    relative_imports_only: bool,
+
+    /// Only extract metadata from these languages (e.g. rust,python,go)
+    #[arg(long = "lang", value_name = "LANGS", value_delimiter = ',')]
+    lang: Vec<Language>,
+
     /// Wrap comments at column width (default: 100, 0 to disable)
     #[arg(short = 'w', long = "wrap", default_value = "100")]
     wrap: usize,
@@ -120,6 +268,69 @@ struct Args {
     #[arg(long = "markdown", short = 'm', conflicts_with = "json")]
     markdown: bool,
 
+    /// In Markdown output, fold type signatures into a collapsible <details>
+    /// section for files with more than N of them [default: 3]
+    #[arg(
+        long = "markdown-collapse-types",
+        value_name = "N",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "3",
+        requires = "markdown"
+    )]
+    markdown_collapse_types: Option<usize>,
+
+    /// Output as an Emacs org-mode outline (directories as headlines)
+    #[arg(long = "org", conflicts_with_all = ["json", "markdown"])]
+    org: bool,
+
+    /// Output a Mermaid dependency graph of imports between project files
+    #[arg(long = "mermaid", conflicts_with_all = ["json", "markdown", "org"])]
+    mermaid: bool,
+
+    /// Include external and standard library imports as nodes in the Mermaid graph
+    #[arg(long = "mermaid-all-imports", requires = "mermaid")]
+    mermaid_all_imports: bool,
+
+    /// Output a GraphViz dependency graph (pipe into `dot -Tsvg`)
+    #[arg(long = "dot", conflicts_with_all = ["json", "markdown", "org", "mermaid"])]
+    dot: bool,
+
+    /// Render the tree through a Tera template FILE, which receives the JSON
+    /// tree as `tree` (requires the `templates` feature)
+    #[arg(
+        long = "template",
+        value_name = "FILE",
+        conflicts_with_all = ["json", "markdown", "org", "mermaid", "dot", "files_only", "watch", "stats"]
+    )]
+    template: Option<PathBuf>,
+
+    /// Cache extraction results on disk and reuse them for unchanged files
+    #[arg(long = "cache")]
+    cache: bool,
+
+    /// Delete the extraction cache before running
+    #[arg(long = "clear-cache")]
+    clear_cache: bool,
+
+    /// Print only file paths, one per line, with no tree or summary
+    #[arg(
+        long = "files-only",
+        conflicts_with_all = ["dirs_only", "json", "markdown", "org", "mermaid", "dot"]
+    )]
+    files_only: bool,
+
+    /// Print only the "N directories, M files" line, without the tree
+    #[arg(
+        long = "summary-only",
+        conflicts_with_all = ["json", "markdown", "org", "mermaid", "dot", "files_only", "compact", "ascii", "template", "no_counts", "watch", "stats"]
+    )]
+    summary_only: bool,
+
+    /// Redraw the tree whenever files under the path change (Ctrl+C to exit)
+    #[arg(long = "watch", conflicts_with_all = ["json", "markdown", "org"])]
+    watch: bool,
+
     /// Prefix for metadata lines (e.g., "# " or "// ")
     #[arg(short = 'p', long = "prefix")]
     prefix: Option<String>,
@@ -137,10 +348,73 @@ struct Args {
     #[arg(long = "no-lines", requires = "stats")]
     no_lines: bool,
 
+    /// Count TODO/FIXME markers when showing stats and list the files with the most
+    #[arg(long = "stats-todos", requires = "stats")]
+    stats_todos: bool,
+
+    /// Build a dependency graph from internal imports when showing stats and
+    /// report circular imports, the most depended-on files and orphans
+    #[arg(long = "import-graph", requires_all = ["stats", "imports"])]
+    import_graph: bool,
+
+    /// Like --import-graph, also reporting the order to load files in so
+    /// each comes after its imports, and groups of files that import each other
+    #[arg(long = "analysis-graph", requires_all = ["stats", "imports"])]
+    analysis_graph: bool,
+
+    /// Show the number of direct children next to each directory
+    #[arg(long = "count")]
+    count: bool,
+
+    /// Omit the final "N directories, M files" line
+    #[arg(long = "no-counts", visible_alias = "no-totals")]
+    no_counts: bool,
+
+    /// Pack sibling files without metadata onto shared lines, separated by
+    /// ` · ` and fitted to the terminal width ($COLUMNS)
+    #[arg(
+        long = "compact",
+        conflicts_with_all = ["json", "markdown", "org", "mermaid", "dot", "files_only"]
+    )]
+    compact: bool,
+
+    /// Draw the tree with ASCII characters (`|-- `) and no colors; used
+    /// automatically when TERM=dumb unless colors are forced
+    #[arg(
+        long = "ascii",
+        conflicts_with_all = ["json", "markdown", "org", "mermaid", "dot", "files_only", "compact"]
+    )]
+    ascii: bool,
+
+    /// Color theme: default, dark, light, solarized, gruvbox, monochrome
+    #[arg(long = "theme", value_name = "NAME", default_value = "default", value_parser = Theme::NAMES)]
+    theme: String,
+
+    /// Color directory names by nesting depth (blue, cyan, green, yellow, ...)
+    #[arg(long = "depth-colors")]
+    depth_colors: bool,
+
     /// Show file sizes next to filenames
     #[arg(short = 's', long = "size")]
     size: bool,
 
+    /// How to write file sizes: binary (1K = 1024), si (1kB = 1000), bytes,
+    /// or none to hide them
+    #[arg(long = "format-size", value_name = "STYLE", default_value = "binary")]
+    format_size: SizeFormat,
+
+    /// Separate top-level directories with a blank line (a horizontal rule in markdown)
+    #[arg(long = "separator")]
+    separator: bool,
+
+    /// Show the approximate LLM token count (characters / 4) of each file and in total
+    #[arg(long = "tokens", conflicts_with_all = ["json", "dot"])]
+    tokens: bool,
+
+    /// Stop listing files once their estimated tokens would exceed N
+    #[arg(long = "max-tokens", value_name = "N", conflicts_with_all = ["json", "dot"])]
+    max_tokens: Option<usize>,
+
     /// Only show files modified more recently than DURATION ago
     /// Duration format: 30s, 5m, 1h, 7d, 2w, 3M, 1y
     #[arg(long = "newer", value_name = "DURATION")]
@@ -151,10 +425,69 @@ struct Args {
     #[arg(long = "older", value_name = "DURATION")]
     older: Option<String>,
 
+    /// Only show files changed between REVISION and HEAD (e.g. HEAD~10, v1.2.0)
+    #[arg(long = "since", value_name = "REVISION")]
+    since: Option<String>,
+
+    /// Show the last commit that touched each file (hash and subject)
+    #[arg(long = "last-commit", conflicts_with = "json")]
+    last_commit: bool,
+
+    /// Prepend PREFIX to displayed paths (e.g., the repository name)
+    /// Replaces the root label in tree output and prefixes JSON/markdown paths
+    #[arg(long = "prefix-path", value_name = "PREFIX")]
+    prefix_path: Option<String>,
+
+    /// Display absolute paths (root label, JSON/markdown paths, --files-only)
+    #[arg(long = "absolute", conflicts_with = "prefix_path")]
+    absolute: bool,
+
+    /// Display paths relative to BASE instead of the scanned directory
+    /// (e.g. `fruit src --relative-to .`). Paths outside BASE are shown absolute
+    #[arg(long = "relative-to", value_name = "BASE", conflicts_with_all = ["prefix_path", "absolute"])]
+    relative_to: Option<PathBuf>,
+
     /// Maximum file size for comment/type extraction (default: 1MB)
     /// Files larger than this are skipped. Use suffixes: K, M, G (e.g., 5M for 5MB)
     #[arg(long = "max-file-size", value_name = "SIZE")]
     max_file_size: Option<String>,
+
+    /// Show leading #, // or /* */ comments of files in unsupported languages
+    #[arg(long = "generic-comments")]
+    generic_comments: bool,
+
+    /// Show generated files (marked `Code generated`, `DO NOT EDIT`,
+    /// `@generated`, ...) dimmed and without metadata
+    #[arg(long = "skip-generated")]
+    skip_generated: bool,
+
+    /// Include private methods in type signatures (Python and Perl `_`
+    /// names, Ruby methods after `private`, Clojure `defn-` and `^:private`,
+    /// Java and C# `private` members)
+    #[arg(long = "show-private")]
+    show_private: bool,
+
+    /// Apply the flags saved as profile NAME in ~/.config/fruit/profiles.toml;
+    /// flags given on the command line take precedence
+    #[arg(long = "profile", value_name = "NAME")]
+    profile: Option<String>,
+
+    /// List the profiles in ~/.config/fruit/profiles.toml
+    #[arg(long = "list-profiles")]
+    list_profiles: bool,
+
+    /// Save the flags of this invocation as profile NAME
+    #[arg(long = "save-profile", value_name = "NAME")]
+    save_profile: Option<String>,
+
+    /// List the supported languages, their extensions and what is extracted
+    /// from them
+    #[arg(long = "list-languages")]
+    list_languages: bool,
+
+    /// Print the JSON Schema of the --json output
+    #[arg(long = "json-schema")]
+    json_schema: bool,
 }
 
 /// Parse a duration string like "1h", "7d", "2w" into a Duration.
@@ -170,65 +503,273 @@ fn parse_duration_string(s: &str) -> Result<Duration, String> {
     humantime::parse_duration(s.trim()).map_err(|e| e.to_string())
 }
 
-/// Parse a file size string like "5M", "100K", "1G" into bytes.
-/// Supports suffixes: K/KB (1024), M/MB (1024^2), G/GB (1024^3)
-/// Without suffix, interprets as bytes.
-fn parse_file_size(s: &str) -> Result<u64, String> {
-    let s = s.trim().to_uppercase();
-    let (num_str, multiplier) = if let Some(n) = s.strip_suffix("GB") {
-        (n, 1024 * 1024 * 1024)
-    } else if let Some(n) = s.strip_suffix('G') {
-        (n, 1024 * 1024 * 1024)
-    } else if let Some(n) = s.strip_suffix("MB") {
-        (n, 1024 * 1024)
-    } else if let Some(n) = s.strip_suffix('M') {
-        (n, 1024 * 1024)
-    } else if let Some(n) = s.strip_suffix("KB") {
-        (n, 1024)
-    } else if let Some(n) = s.strip_suffix('K') {
-        (n, 1024)
-    } else {
-        (s.as_str(), 1)
-    };
+/// Determine metadata order based on which flag appeared first in argv
+fn get_metadata_order(matches: &ArgMatches) -> MetadataOrder {
+    let flags = [
+        ("comments", MetadataOrder::CommentsFirst),
+        ("types", MetadataOrder::TypesFirst),
+        ("todos", MetadataOrder::TodoFirst),
+        ("imports", MetadataOrder::ImportFirst),
+    ];
+    // Flags left at their default still have an index, so only count those
+    // given on the command line
+    let given: Vec<_> = flags
+        .into_iter()
+        .filter(|(id, _)| matches.value_source(id) == Some(ValueSource::CommandLine))
+        .filter_map(|(id, order)| Some((matches.index_of(id)?, order)))
+        .collect();
+
+    // Order only matters when several kinds were requested explicitly
+    if given.len() < 2 {
+        return MetadataOrder::CommentsFirst;
+    }
+    given
+        .into_iter()
+        .min_by_key(|&(index, _)| index)
+        .map_or(MetadataOrder::CommentsFirst, |(_, order)| order)
+}
 
-    let num: u64 = num_str
-        .trim()
-        .parse()
-        .map_err(|_| format!("invalid number: {}", num_str))?;
+/// Arguments that are never saved to or applied from a profile.
+const NON_PROFILE_ARGS: [&str; 6] = [
+    "path",
+    "profile",
+    "list_profiles",
+    "save_profile",
+    "list_languages",
+    "json_schema",
+];
 
-    Ok(num * multiplier)
+/// Location of the profiles file, or exit if there is no config directory.
+fn profiles_path() -> PathBuf {
+    profiles::default_path().unwrap_or_else(|| {
+        eprintln!("fruit: cannot locate the config directory (set HOME or XDG_CONFIG_HOME)");
+        process::exit(1);
+    })
 }
 
-/// Determine metadata order based on which flag appeared first in argv
-fn get_metadata_order(matches: &ArgMatches) -> MetadataOrder {
-    let comments_index = matches.index_of("comments");
-    let types_index = matches.index_of("types");
+/// Load the profiles file, or exit if it cannot be read or parsed.
+fn load_profiles(path: &Path) -> Vec<Profile> {
+    profiles::load_profiles(path).unwrap_or_else(|e| {
+        eprintln!("fruit: invalid profiles file {}: {}", path.display(), e);
+        process::exit(1);
+    })
+}
+
+/// Print the table of `--list-languages`: each language with its extensions
+/// and a mark for each kind of metadata fruit extracts from it.
+fn print_languages(choice: ColorChoice) -> io::Result<()> {
+    const COLUMNS: [&str; 4] = ["Comments", "Types", "Imports", "TODOs"];
+
+    let rows: Vec<(&str, String, [bool; 4])> = Language::all()
+        .iter()
+        .map(|language| {
+            let extensions: Vec<String> = language
+                .extensions()
+                .iter()
+                .map(|ext| format!(".{}", ext))
+                .collect();
+            let caps = language.capabilities();
+            (
+                language.display_name(),
+                extensions.join(", "),
+                [caps.comments, caps.types, caps.imports, caps.todos],
+            )
+        })
+        .collect();
+    let name_width = rows.iter().map(|row| row.0.len()).fold(8, usize::max);
+    let ext_width = rows.iter().map(|row| row.1.len()).fold(10, usize::max);
 
-    match (comments_index, types_index) {
-        (Some(c), Some(t)) if c < t => MetadataOrder::CommentsFirst,
-        (Some(c), Some(t)) if t < c => MetadataOrder::TypesFirst,
-        _ => MetadataOrder::CommentsFirst, // default
+    let mut stdout = StandardStream::stdout(choice);
+    stdout.set_color(ColorSpec::new().set_bold(true))?;
+    write!(
+        stdout,
+        "{:<name_width$}  {:<ext_width$}  {}",
+        "Language",
+        "Extensions",
+        COLUMNS.join("  ")
+    )?;
+    stdout.reset()?;
+    writeln!(stdout)?;
+
+    for (name, extensions, supported) in rows {
+        write!(stdout, "{:<name_width$}  {:<ext_width$}", name, extensions)?;
+        for (i, (column, supported)) in COLUMNS.iter().zip(supported).enumerate() {
+            let (mark, color) = if supported {
+                ("✓", Color::Green)
+            } else {
+                ("-", Color::Ansi256(245))
+            };
+            write!(stdout, "  ")?;
+            stdout.set_color(ColorSpec::new().set_fg(Some(color)))?;
+            write!(stdout, "{}", mark)?;
+            stdout.reset()?;
+            if i + 1 < COLUMNS.len() {
+                write!(stdout, "{}", " ".repeat(column.len() - 1))?;
+            }
+        }
+        writeln!(stdout)?;
+    }
+    Ok(())
+}
+
+/// Parse the command line again with the flags of profile `name` in front.
+/// Flags given on the command line, and flags conflicting with them, are
+/// not taken from the profile.
+fn apply_profile(name: &str, cli_matches: &ArgMatches) -> ArgMatches {
+    let path = profiles_path();
+    let profiles = load_profiles(&path);
+    let Some(profile) = profiles.iter().find(|profile| profile.name == name) else {
+        eprintln!("fruit: no profile '{}' in {}", name, path.display());
+        process::exit(1);
+    };
+
+    let command = Args::command();
+    let given: Vec<&clap::Arg> = command
+        .get_arguments()
+        .filter(|arg| {
+            cli_matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine)
+        })
+        .collect();
+    let conflicts = |a: &clap::Arg, b: &clap::Arg| {
+        command
+            .get_arg_conflicts_with(a)
+            .iter()
+            .any(|arg| arg.get_id() == b.get_id())
+            || command
+                .get_arg_conflicts_with(b)
+                .iter()
+                .any(|arg| arg.get_id() == a.get_id())
+    };
+
+    let mut argv: Vec<std::ffi::OsString> = std::env::args_os().take(1).collect();
+    for (key, value) in &profile.settings {
+        let id = key.replace('-', "_");
+        let long = key.replace('_', "-");
+        let arg = command.get_arguments().find(|arg| {
+            !NON_PROFILE_ARGS.contains(&arg.get_id().as_str())
+                && (arg.get_id() == id.as_str() || arg.get_long() == Some(long.as_str()))
+        });
+        let Some((arg, long)) = arg.and_then(|arg| Some((arg, arg.get_long()?))) else {
+            eprintln!("fruit: profile '{}' has unknown flag '{}'", name, key);
+            process::exit(1);
+        };
+        if given
+            .iter()
+            .any(|cli_arg| cli_arg.get_id() == arg.get_id() || conflicts(cli_arg, arg))
+        {
+            continue;
+        }
+        argv.extend(value.to_args(long).into_iter().map(Into::into));
+    }
+    argv.extend(std::env::args_os().skip(1));
+
+    Args::command()
+        .try_get_matches_from(argv)
+        .unwrap_or_else(|e| e.exit())
+}
+
+/// The flags given on the command line as a profile named `name`.
+fn profile_from_matches(name: &str, matches: &ArgMatches) -> Profile {
+    let command = Args::command();
+    let mut settings = Vec::new();
+    for arg in command.get_arguments() {
+        let id = arg.get_id().as_str();
+        if NON_PROFILE_ARGS.contains(&id)
+            || arg.get_long().is_none()
+            || matches.value_source(id) != Some(ValueSource::CommandLine)
+        {
+            continue;
+        }
+        let values: Vec<ProfileValue> = matches
+            .get_raw(id)
+            .into_iter()
+            .flatten()
+            .map(|raw| {
+                let raw = raw.to_string_lossy();
+                raw.parse().map_or_else(
+                    |_| ProfileValue::String(raw.to_string()),
+                    ProfileValue::Integer,
+                )
+            })
+            .collect();
+        let value = match arg.get_action() {
+            ArgAction::SetTrue => ProfileValue::Bool(true),
+            ArgAction::Append => ProfileValue::Array(values),
+            _ => match values.into_iter().next() {
+                Some(value) => value,
+                None => continue,
+            },
+        };
+        settings.push((id.to_string(), value));
+    }
+    Profile {
+        name: name.to_string(),
+        settings,
     }
 }
 
 fn main() {
-    let matches = Args::command().get_matches();
+    let mut matches = Args::command().get_matches();
+    if let Some(name) = matches.get_one::<String>("profile").cloned() {
+        matches = apply_profile(&name, &matches);
+    }
     let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| {
         eprintln!("fruit: argument parsing error: {}", e);
         process::exit(1);
     });
 
-    // Configure max file size for extraction if specified
-    if let Some(ref size_str) = args.max_file_size {
-        match parse_file_size(size_str) {
-            Ok(size) => {
-                fruit::file_utils::set_max_file_size(size);
-            }
-            Err(e) => {
-                eprintln!("fruit: invalid --max-file-size '{}': {}", size_str, e);
-                process::exit(1);
-            }
+    #[cfg(not(feature = "templates"))]
+    if args.template.is_some() {
+        eprintln!("fruit: --template requires fruit to be built with the `templates` feature");
+        process::exit(1);
+    }
+
+    if args.list_languages {
+        let use_color = should_use_color(args.color, args.ansi_only);
+        if let Err(e) = print_languages(color_choice(use_color, args.ansi_only)) {
+            eprintln!("fruit: error writing output: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    if args.json_schema {
+        println!("{}", JSON_SCHEMA);
+        return;
+    }
+
+    if args.list_profiles {
+        for profile in load_profiles(&profiles_path()) {
+            println!("{}", profile.name);
+        }
+        return;
+    }
+
+    if let Some(name) = &args.save_profile {
+        let path = profiles_path();
+        // Validate the existing file first, so a broken one is not overwritten
+        load_profiles(&path);
+        if let Err(e) = profiles::save_profile(&path, &profile_from_matches(name, &matches)) {
+            eprintln!("fruit: cannot save profile to {}: {}", path.display(), e);
+            process::exit(1);
         }
+        eprintln!("fruit: saved profile '{}' to {}", name, path.display());
+    }
+
+    // Maximum file size for extraction, passed to the walkers and stats collector
+    let max_file_size = match &args.max_file_size {
+        None => DEFAULT_MAX_FILE_SIZE,
+        Some(size_str) => parse_file_size(size_str).unwrap_or_else(|e| {
+            eprintln!("fruit: invalid --max-file-size '{}': {}", size_str, e);
+            process::exit(1);
+        }),
+    };
+
+    if args.clear_cache
+        && let Err(e) = ExtractionCache::clear()
+    {
+        eprintln!("fruit: cannot clear cache: {}", e);
+        process::exit(1);
     }
 
     // Determine what metadata to show:
@@ -272,22 +813,75 @@ fn main() {
         SystemTime::now() - duration
     });
 
-    let walker_config = WalkerConfig {
-        show_all: args.all,
-        max_depth: args.level,
-        dirs_only: args.dirs_only,
-        extract_comments: show_comments,
-        extract_types: show_types,
-        extract_todos: show_todos,
-        todos_only: args.todos_only,
-        extract_imports: args.imports,
-        show_size: args.size,
-        ignore_patterns: args.ignore.clone(),
-        parallel_workers: args.jobs,
-        newer_than,
-        older_than,
+    // Outside a git repository there is no history to compare, so show all files
+    let changed_files = args.since.as_ref().and_then(|revision| {
+        fruit::git::changed_files_since(&args.path, revision).unwrap_or_else(|e| {
+            eprintln!(
+                "fruit: invalid --since revision '{}': {}",
+                revision,
+                e.message()
+            );
+            process::exit(1);
+        })
+    });
+
+    // Outside a git repository there are no commits to show
+    let last_commits = if args.last_commit {
+        fruit::git::last_commits(&args.path).unwrap_or_else(|e| {
+            eprintln!("fruit: failed to read git history: {}", e.message());
+            process::exit(1);
+        })
+    } else {
+        None
     };
 
+    let walker_config = WalkerConfig::builder()
+        .show_all(args.all)
+        .max_depth(args.level)
+        .min_depth(args.min_depth)
+        .dirs_only(args.dirs_only)
+        .prune_empty_dirs(!args.show_empty_dirs)
+        .show_git_ignored(args.show_git_ignored)
+        // File lists and summaries show no metadata; TODOs may still be needed for --todos-only
+        .extract_comments(show_comments && !args.files_only && !args.summary_only)
+        .extract_types(show_types && !args.files_only && !args.summary_only)
+        .extract_todos(show_todos)
+        .todos_only(args.todos_only)
+        .imports_from(args.imports_from.clone())
+        .imports_only(args.imports_only)
+        .ignore_case(args.ignore_case)
+        .custom_markers(args.todo_markers.clone())
+        .todo_assignee(args.todos_assignee.clone())
+        .todo_types(args.todos_types.clone())
+        .extract_imports((args.imports && !args.files_only && !args.summary_only) || args.dot)
+        .imports_depth(args.imports_depth)
+        .max_imports(args.max_imports)
+        .imports_relative_only(args.relative_imports_only)
+        .show_size(args.size && args.format_size != SizeFormat::None)
+        .count_children(args.count)
+        .separator(args.separator)
+        .show_line_numbers(args.line_numbers)
+        .context_lines(args.context_lines)
+        .show_tokens(args.tokens)
+        .max_tokens(args.max_tokens)
+        .use_cache(args.cache)
+        .max_file_size(max_file_size)
+        .generic_comments(args.generic_comments)
+        .skip_generated(args.skip_generated)
+        .show_private(args.show_private)
+        .language_filter((!args.lang.is_empty()).then(|| args.lang.clone()))
+        .ignore_patterns(args.ignore.clone())
+        .parallel_workers(args.jobs)
+        .newer_than(newer_than)
+        .older_than(older_than)
+        .changed_files(changed_files)
+        .last_commits(last_commits)
+        .build()
+        .unwrap_or_else(|e| {
+            eprintln!("fruit: invalid options: {}", e);
+            process::exit(1);
+        });
+
     let root = if args.path.is_absolute() {
         args.path.clone()
     } else {
@@ -296,33 +890,161 @@ fn main() {
             .join(&args.path)
     };
 
+    // --relative-to is a path prefix: the scanned directory relative to the base
+    let (path_prefix, root_path) = match &args.relative_to {
+        Some(base) => {
+            let base = base.canonicalize().unwrap_or_else(|e| {
+                eprintln!("fruit: cannot access '{}': {}", base.display(), e);
+                process::exit(1);
+            });
+            rebase_root(&root, &base)
+        }
+        None => (
+            args.prefix_path.clone(),
+            // Canonicalize so `./src` and symlinked roots display as real absolute paths
+            if args.absolute {
+                root.canonicalize().ok()
+            } else {
+                None
+            },
+        ),
+    };
+
+    let metadata_config = MetadataConfig {
+        comments: show_comments,
+        types: show_types,
+        todos: show_todos,
+        full: full_mode,
+        prefix: args.prefix.clone(),
+        order: get_metadata_order(&matches),
+        max_lines: None,
+    };
+
+    let use_color = !args.markdown
+        && !args.org
+        && !args.mermaid
+        && !args.files_only
+        && should_use_color(args.color, args.ansi_only);
+    let output_config = OutputConfig {
+        use_color,
+        ansi_only: args.ansi_only,
+        metadata: metadata_config,
+        wrap_width: if args.wrap == 0 {
+            None
+        } else {
+            Some(args.wrap)
+        },
+        path_prefix,
+        root_path,
+        show_dir_counts: args.count,
+        // The value parser restricts names to known themes
+        theme: Theme::from_name(&args.theme).unwrap_or_default(),
+        depth_colors: args.depth_colors && use_color,
+        collapse_types: args.markdown_collapse_types,
+        show_summary: !args.no_counts,
+        compact: args.compact,
+        size_style: match args.format_size {
+            SizeFormat::Si => SizeStyle::Si,
+            SizeFormat::Bytes => SizeStyle::Bytes,
+            SizeFormat::Binary | SizeFormat::None => SizeStyle::Binary,
+        },
+    };
+
+    if args.watch {
+        watch(&args, &root, &walker_config, &output_config);
+    }
+
+    if let Err(e) = run(&args, &root, walker_config, output_config) {
+        eprintln!("fruit: error writing output: {}", e);
+        process::exit(1);
+    }
+}
+
+/// The path prefix (or, failing that, absolute root path) that displays paths
+/// under `root` relative to `base`. `base` must be canonical.
+fn rebase_root(root: &Path, base: &Path) -> (Option<String>, Option<PathBuf>) {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    match root.strip_prefix(base) {
+        Ok(relative) if relative.as_os_str().is_empty() => (Some(".".to_string()), None),
+        Ok(relative) => (Some(relative.to_string_lossy().into_owned()), None),
+        Err(_) => (None, Some(root)),
+    }
+}
+
+/// Render `tree` through the `--template` file at `path` and print it.
+#[cfg(feature = "templates")]
+fn print_template(path: &Path, tree: &TreeNode, config: &OutputConfig) -> io::Result<()> {
+    let formatter = TemplateFormatter::from_file(path, config.clone()).unwrap_or_else(|e| {
+        eprintln!("fruit: invalid template '{}': {}", path.display(), e);
+        process::exit(1);
+    });
+    let output = formatter.format(tree).unwrap_or_else(|e| {
+        eprintln!("fruit: cannot render template '{}': {}", path.display(), e);
+        process::exit(1);
+    });
+    print!("{}", output);
+    Ok(())
+}
+
+#[cfg(not(feature = "templates"))]
+fn print_template(_path: &Path, _tree: &TreeNode, _config: &OutputConfig) -> io::Result<()> {
+    unreachable!("--template is rejected at startup without the `templates` feature")
+}
+
+/// Walk `root` and print it in the output mode selected by `args`.
+fn run(
+    args: &Args,
+    root: &Path,
+    walker_config: WalkerConfig,
+    output_config: OutputConfig,
+) -> io::Result<()> {
     // Handle different output modes
-    let result = if args.stats {
+    if args.stats {
         // Stats mode: collect and display codebase statistics
         let stats_config = StatsConfig {
             count_lines: !args.no_lines,
+            count_todos: args.stats_todos,
+            build_import_graph: args.import_graph || args.analysis_graph,
+            analyze_import_graph: args.analysis_graph,
+            // Comment coverage is only part of the JSON report
+            count_comments: args.json,
+            extraction: walker_config.extraction,
+            ..StatsConfig::default()
         };
-        let stats = collect_stats(&root, &args, stats_config);
+        let stats = collect_stats(root, args, stats_config);
 
         if args.json {
             print_stats_json(&stats)
         } else {
-            print_stats(&stats, should_use_color(args.color))
+            let use_color = should_use_color(args.color, args.ansi_only);
+            print_stats(&stats, color_choice(use_color, args.ansi_only))
         }
-    } else if args.json {
-        // JSON output requires full tree in memory (for serialization)
+    } else if args.json || args.dot || args.template.is_some() {
+        // GraphViz and template output require the full tree in memory, and so
+        // does JSON for small trees, where buffering is cheap
         let mut walker = TreeWalker::new(walker_config);
 
         // Set up gitignore filter unless --all is specified
         if !args.all {
-            if let Some(filter) = GitignoreFilter::new(&args.path) {
+            if let Some(filter) = gitignore_filter(args) {
                 walker = walker.with_gitignore_filter(filter);
             } else {
                 eprintln!("fruit: warning: not a git repository, showing all files");
             }
         }
 
-        let tree = match walker.walk(&root) {
+        if args.json && has_more_files_than(root, JSON_STREAMING_MIN_FILES) {
+            if !print_json_streaming(&walker, root, &output_config)? {
+                eprintln!(
+                    "fruit: cannot access '{}': No such file or directory",
+                    args.path.display()
+                );
+                process::exit(1);
+            }
+            return Ok(());
+        }
+
+        let tree = match walker.walk(root) {
             Some(t) => t,
             None => {
                 eprintln!(
@@ -332,46 +1054,80 @@ fn main() {
                 process::exit(1);
             }
         };
-        print_json(&tree)
+        if args.dot {
+            print_dot(&tree, &output_config)
+        } else if let Some(template) = &args.template {
+            print_template(template, &tree, &output_config)
+        } else {
+            print_json(&tree, &output_config)
+        }
     } else {
         // Use streaming walker for console/markdown output - much lower memory usage
         let mut walker = StreamingWalker::new(walker_config);
 
         // Set up gitignore filter unless --all is specified
         if !args.all {
-            if let Some(filter) = GitignoreFilter::new(&args.path) {
+            if let Some(filter) = gitignore_filter(args) {
                 walker = walker.with_gitignore_filter(filter);
             } else {
                 eprintln!("fruit: warning: not a git repository, showing all files");
             }
         }
+        if env_flag("FRUIT_DEBUG_TIMING") == Some(true) {
+            walker = walker.with_timing();
+        }
 
-        let metadata_config = MetadataConfig {
-            comments: show_comments,
-            types: show_types,
-            todos: show_todos,
-            full: full_mode,
-            prefix: args.prefix.clone(),
-            order: get_metadata_order(&matches),
-        };
+        // Dumb terminals cannot draw box characters, so fall back to ASCII
+        // unless colors were asked for explicitly
+        let dumb_terminal = std::env::var("TERM").is_ok_and(|t| t == "dumb");
+        let ascii = args.ascii
+            || (dumb_terminal
+                && !args.compact
+                && !args.ansi_only
+                && !matches!(args.color, ColorMode::Always));
 
-        let output_config = OutputConfig {
-            use_color: if args.markdown {
-                false
-            } else {
-                should_use_color(args.color)
-            },
-            metadata: metadata_config,
-            wrap_width: if args.wrap == 0 {
-                None
-            } else {
-                Some(args.wrap)
-            },
-        };
-
-        if args.markdown {
+        let result = if args.summary_only {
+            let mut formatter = SummaryOnlyFormatter::new();
+            match walker.walk_streaming(root, &mut formatter) {
+                Ok(Some(_)) => Ok(()),
+                Ok(None) => {
+                    eprintln!(
+                        "fruit: cannot access '{}': No such file or directory",
+                        args.path.display()
+                    );
+                    process::exit(1);
+                }
+                Err(e) => Err(e),
+            }
+        } else if args.files_only {
+            let mut formatter = PlainListFormatter::new(output_config);
+            match walker.walk_streaming(root, &mut formatter) {
+                Ok(Some(_)) => Ok(()),
+                Ok(None) => {
+                    eprintln!(
+                        "fruit: cannot access '{}': No such file or directory",
+                        args.path.display()
+                    );
+                    process::exit(1);
+                }
+                Err(e) => Err(e),
+            }
+        } else if args.mermaid {
+            let mut formatter = MermaidFormatter::new(root, args.mermaid_all_imports);
+            match walker.walk_streaming(root, &mut formatter) {
+                Ok(Some(_)) => print_mermaid(&formatter),
+                Ok(None) => {
+                    eprintln!(
+                        "fruit: cannot access '{}': No such file or directory",
+                        args.path.display()
+                    );
+                    process::exit(1);
+                }
+                Err(e) => Err(e),
+            }
+        } else if args.markdown {
             let mut formatter = MarkdownFormatter::new(output_config);
-            match walker.walk_streaming(&root, &mut formatter) {
+            match walker.walk_streaming(root, &mut formatter) {
                 Ok(Some(_)) => print_markdown(&formatter),
                 Ok(None) => {
                     eprintln!(
@@ -382,9 +1138,35 @@ fn main() {
                 }
                 Err(e) => Err(e),
             }
+        } else if args.org {
+            let mut formatter = OrgFormatter::new(output_config);
+            match walker.walk_streaming(root, &mut formatter) {
+                Ok(Some(_)) => print_org(&formatter),
+                Ok(None) => {
+                    eprintln!(
+                        "fruit: cannot access '{}': No such file or directory",
+                        args.path.display()
+                    );
+                    process::exit(1);
+                }
+                Err(e) => Err(e),
+            }
+        } else if ascii {
+            let mut formatter = PlainTextFormatter::new(output_config);
+            match walker.walk_streaming(root, &mut formatter) {
+                Ok(Some(_)) => Ok(()),
+                Ok(None) => {
+                    eprintln!(
+                        "fruit: cannot access '{}': No such file or directory",
+                        args.path.display()
+                    );
+                    process::exit(1);
+                }
+                Err(e) => Err(e),
+            }
         } else {
             let mut formatter = StreamingFormatter::new(output_config);
-            match walker.walk_streaming(&root, &mut formatter) {
+            match walker.walk_streaming(root, &mut formatter) {
                 Ok(Some(_)) => Ok(()),
                 Ok(None) => {
                     eprintln!(
@@ -395,20 +1177,127 @@ fn main() {
                 }
                 Err(e) => Err(e),
             }
+        };
+
+        // Goes to stderr so the output itself is unchanged
+        if let Some(stats) = walker.output_stats() {
+            eprintln!("{}", stats);
         }
-    };
+        result
+    }
+}
 
-    if let Err(e) = result {
-        eprintln!("fruit: error writing output: {}", e);
+/// Delay after the last file system event before redrawing in `--watch` mode.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Clear the screen and move the cursor to the top-left corner.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Redraw the tree every time something under `root` changes, until interrupted.
+fn watch(
+    args: &Args,
+    root: &Path,
+    walker_config: &WalkerConfig,
+    output_config: &OutputConfig,
+) -> ! {
+    use notify::{RecursiveMode, Watcher};
+
+    if let Err(e) = ctrlc::set_handler(|| {
+        // Reset colors in case we were interrupted mid-line
+        println!("\x1b[0m");
+        let _ = io::stdout().flush();
+        process::exit(0);
+    }) {
+        eprintln!("fruit: cannot install Ctrl+C handler: {}", e);
         process::exit(1);
     }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).unwrap_or_else(|e| {
+        eprintln!("fruit: cannot start file watcher: {}", e);
+        process::exit(1);
+    });
+    if let Err(e) = watcher.watch(root, RecursiveMode::Recursive) {
+        eprintln!("fruit: cannot watch '{}': {}", args.path.display(), e);
+        process::exit(1);
+    }
+
+    loop {
+        print!("{}", CLEAR_SCREEN);
+        if let Err(e) = run(args, root, walker_config.clone(), output_config.clone()) {
+            eprintln!("fruit: error writing output: {}", e);
+            process::exit(1);
+        }
+        let _ = io::stdout().flush();
+
+        if !wait_for_change(&rx) {
+            eprintln!("fruit: file watcher stopped unexpectedly");
+            process::exit(1);
+        }
+        if !root.exists() {
+            eprintln!(
+                "fruit: '{}' was removed, stopping watch",
+                args.path.display()
+            );
+            process::exit(1);
+        }
+    }
+}
+
+/// Block until a file changes, then until events have been quiet for
+/// `WATCH_DEBOUNCE`. Returns false if the watcher has shut down.
+fn wait_for_change(rx: &mpsc::Receiver<notify::Result<notify::Event>>) -> bool {
+    loop {
+        match rx.recv() {
+            Ok(Ok(event)) if is_relevant_change(&event) => break,
+            Ok(_) => continue,
+            Err(_) => return false,
+        }
+    }
+    while rx.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+    true
+}
+
+/// Ignore reads (including our own walk) and changes inside `.git`.
+fn is_relevant_change(event: &notify::Event) -> bool {
+    !event.kind.is_access()
+        && event
+            .paths
+            .iter()
+            .any(|path| !path.components().any(|c| c.as_os_str() == ".git"))
+}
+
+/// Trees with more files than this are written as JSON while walking them,
+/// instead of being built in memory first.
+const JSON_STREAMING_MIN_FILES: usize = 10_000;
+
+/// Whether more than `limit` non-ignored files are under `root`. Stops
+/// counting once the limit is passed.
+fn has_more_files_than(root: &Path, limit: usize) -> bool {
+    ignore::WalkBuilder::new(root)
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .nth(limit)
+        .is_some()
+}
+
+/// The gitignore filter for `args.path`, if it is inside a git repository.
+/// With `--skip-build-dirs`, well-known build directories are rejected by name.
+fn gitignore_filter(args: &Args) -> Option<GitignoreFilter> {
+    let filter = GitignoreFilter::new(&args.path)?;
+    Some(if args.skip_build_dirs {
+        filter.with_always_ignored(DEFAULT_ALWAYS_IGNORED.iter().copied())
+    } else {
+        filter
+    })
 }
 
 /// Collect codebase statistics by walking the directory tree.
 fn collect_stats(root: &Path, args: &Args, stats_config: StatsConfig) -> CodebaseStats {
     use ignore::WalkBuilder;
 
-    let mut collector = StatsCollector::new(stats_config);
+    let mut collector = StatsCollector::new(stats_config).with_root(root);
 
     let walker = if args.all {
         WalkBuilder::new(root)
@@ -428,20 +1317,50 @@ fn collect_stats(root: &Path, args: &Args, stats_config: StatsConfig) -> Codebas
             .build()
     };
 
-    for entry in walker.flatten() {
-        let path = entry.path();
+    // Spin while listing, since the total is only known once the listing is done
+    let progress = if should_show_progress(args) {
+        let style = ProgressStyle::with_template("{spinner} listing files: {pos} found")
+            .expect("listing spinner template is invalid");
+        let spinner = ProgressBar::new_spinner().with_style(style);
+        spinner.enable_steady_tick(Duration::from_millis(100));
+        spinner
+    } else {
+        ProgressBar::hidden()
+    };
 
-        // Skip the root directory itself
-        if path == root {
-            continue;
-        }
+    // List entries first so the progress bar knows the total and can estimate
+    // the remaining time; reading and analyzing the files dominates the run
+    let entries: Vec<_> = walker
+        .flatten()
+        .filter(|entry| entry.path() != root)
+        .progress_with(progress.clone())
+        .collect();
 
+    let style =
+        ProgressStyle::with_template("{spinner} {pos}/{len} files [{bar:30}] {per_sec}, ETA {eta}")
+            .expect("progress bar template is invalid")
+            .progress_chars("=> ");
+    progress.set_style(style);
+    progress.set_length(entries.len() as u64);
+    progress.reset();
+
+    for entry in entries.iter().progress_with(progress.clone()) {
+        let path = entry.path();
         if path.is_dir() {
             collector.record_directory();
         } else if path.is_file() {
             collector.record_file(path);
         }
     }
+    // Clear the bar so the stats are printed on a clean terminal
+    progress.finish_and_clear();
 
     collector.finalize()
 }
+
+/// Whether `--stats` shows a progress bar: only when stderr is an interactive
+/// terminal, and never for JSON output or when `NO_COLOR` is set to a
+/// non-empty value.
+fn should_show_progress(args: &Args) -> bool {
+    !args.json && !no_color() && io::stderr().is_terminal()
+}
@@ -8,7 +8,7 @@ use std::path::Path;
 use termcolor::Color;
 
 /// Style for how a metadata line should be displayed.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum LineStyle {
     /// Standard comment display (dim gray)
     #[default]
@@ -17,8 +17,14 @@ pub enum LineStyle {
     TypeSignature,
     /// TODO/FIXME marker display
     Todo,
+    /// Rust audit annotation display (SAFETY, SOUNDNESS, ...)
+    Annotation,
     /// Import/dependency display
     Import,
+    /// Output of a user-defined `Plugin`, tagged with the plugin name
+    Custom(String),
+    /// `... (N more)` line standing in for lines cut by `MetadataConfig::max_lines`
+    Truncation,
 }
 
 impl LineStyle {
@@ -28,13 +34,16 @@ impl LineStyle {
             LineStyle::Comment => Color::Black,
             LineStyle::TypeSignature => Color::Cyan,
             LineStyle::Todo => Color::Yellow,
+            LineStyle::Annotation => Color::Blue,
             LineStyle::Import => Color::Magenta,
+            LineStyle::Custom(_) => Color::Green,
+            LineStyle::Truncation => Color::Black,
         }
     }
 
     /// Whether this style should use intense/bright colors.
     pub fn is_intense(&self) -> bool {
-        matches!(self, LineStyle::Comment)
+        matches!(self, LineStyle::Comment | LineStyle::Truncation)
     }
 }
 
@@ -49,6 +58,10 @@ pub struct MetadataLine {
     pub symbol_name: Option<String>,
     /// Indentation level (number of spaces) for hierarchy display
     pub indent: usize,
+    /// Source line this entry refers to, shown as a dimmed `:N` suffix
+    pub line_number: Option<usize>,
+    /// URL the line links to in terminals that support OSC 8 hyperlinks
+    pub link: Option<String>,
 }
 
 impl MetadataLine {
@@ -59,6 +72,8 @@ impl MetadataLine {
             style: LineStyle::Comment,
             symbol_name: None,
             indent: 0,
+            line_number: None,
+            link: None,
         }
     }
 
@@ -69,6 +84,8 @@ impl MetadataLine {
             style,
             symbol_name: None,
             indent: 0,
+            line_number: None,
+            link: None,
         }
     }
 
@@ -84,8 +101,16 @@ impl MetadataLine {
             style,
             symbol_name: Some(symbol_name.into()),
             indent,
+            line_number: None,
+            link: None,
         }
     }
+
+    /// Make the line a hyperlink to `url` in terminals that support it.
+    pub fn with_link(mut self, url: impl Into<String>) -> Self {
+        self.link = Some(url.into());
+        self
+    }
 }
 
 /// A block of metadata lines to display beneath a file.
@@ -99,6 +124,8 @@ pub struct MetadataBlock {
     pub todo_lines: Vec<MetadataLine>,
     /// Import/dependency lines
     pub import_lines: Vec<MetadataLine>,
+    /// Lines produced by user-defined plugins
+    pub custom_lines: Vec<MetadataLine>,
 }
 
 impl MetadataBlock {
@@ -109,49 +136,144 @@ impl MetadataBlock {
 
     /// Create a metadata block with only comment lines.
     pub fn from_comments(text: &str) -> Self {
-        let comment_lines = text
-            .lines()
-            .map(|line| MetadataLine::new(line.to_string()))
-            .collect();
-        Self {
-            comment_lines,
-            type_lines: Vec::new(),
-            todo_lines: Vec::new(),
-            import_lines: Vec::new(),
-        }
+        Self::new().with_comment(text)
     }
 
-    /// Create a metadata block with only type lines (signature, symbol_name, indent tuples).
-    pub fn from_types(signatures: Vec<(String, String, usize)>) -> Self {
-        let type_lines = signatures
-            .into_iter()
-            .map(|(sig, sym, indent)| {
-                MetadataLine::with_symbol(sig, LineStyle::TypeSignature, sym, indent)
-            })
-            .collect();
-        Self {
-            comment_lines: Vec::new(),
-            type_lines,
-            todo_lines: Vec::new(),
-            import_lines: Vec::new(),
+    /// Create a metadata block holding the summary of Markdown `content`
+    /// (its `# ` title or first paragraph). Empty if there is neither.
+    pub fn from_markdown_summary(content: &str) -> Self {
+        match crate::comments::extract_markdown_summary(content) {
+            Some(summary) => Self::from_comments(&summary),
+            None => Self::new(),
         }
     }
 
+    /// Create a metadata block with only type lines.
+    pub fn from_types(signatures: Vec<crate::types::TypeSignature>) -> Self {
+        Self::new().with_types(signatures)
+    }
+
     /// Create a metadata block with only TODO lines.
     pub fn from_todos(todos: &[crate::todos::TodoItem]) -> Self {
-        let todo_lines = todos
-            .iter()
-            .map(|todo| {
-                let content = format!("{}: {} (line {})", todo.marker_type, todo.text, todo.line);
-                MetadataLine::with_style(content, LineStyle::Todo)
-            })
-            .collect();
-        Self {
-            comment_lines: Vec::new(),
-            type_lines: Vec::new(),
-            todo_lines,
-            import_lines: Vec::new(),
+        Self::new().with_todos(todos)
+    }
+
+    /// Append one comment line per line of `text`.
+    pub fn with_comment(mut self, text: &str) -> Self {
+        self.comment_lines
+            .extend(text.lines().map(|line| MetadataLine::new(line.to_string())));
+        self
+    }
+
+    /// Append one line per type signature, keeping its line number if set.
+    /// Context lines follow their signature, dimmed and indented one level deeper.
+    pub fn with_types(mut self, signatures: Vec<crate::types::TypeSignature>) -> Self {
+        for sig in signatures {
+            let context_indent = sig.indent + 4;
+            self.type_lines.push(MetadataLine {
+                line_number: sig.line_number,
+                ..MetadataLine::with_symbol(
+                    sig.signature,
+                    LineStyle::TypeSignature,
+                    sig.symbol,
+                    sig.indent,
+                )
+            });
+            self.type_lines.extend(sig.context.into_iter().map(|line| {
+                let indent = crate::types::calculate_indent(&line).max(context_indent);
+                MetadataLine {
+                    indent,
+                    ..MetadataLine::new(line.trim())
+                }
+            }));
+        }
+        self
+    }
+
+    /// Append one line per TODO/FIXME marker. Rust annotations such as
+    /// `SAFETY` get their own style.
+    pub fn with_todos(mut self, todos: &[crate::todos::TodoItem]) -> Self {
+        self.todo_lines.extend(todos.iter().map(|todo| {
+            let style = if crate::todos::is_annotation_marker(&todo.marker_type) {
+                LineStyle::Annotation
+            } else {
+                LineStyle::Todo
+            };
+            match &todo.assignee {
+                Some(assignee) => {
+                    let assignee = format!("@{}", assignee);
+                    let content = format!(
+                        "{}({}): {} (line {})",
+                        todo.marker_type, assignee, todo.text, todo.line
+                    );
+                    MetadataLine::with_symbol(content, style, assignee, 0)
+                }
+                None => {
+                    let content =
+                        format!("{}: {} (line {})", todo.marker_type, todo.text, todo.line);
+                    MetadataLine::with_style(content, style)
+                }
+            }
+        }));
+        self
+    }
+
+    /// Append an import summary line (see `FileImports::summary`).
+    /// An empty summary adds nothing.
+    pub fn with_imports(mut self, summary: &str) -> Self {
+        if !summary.is_empty() {
+            self.import_lines.push(MetadataLine::with_style(
+                format!("imports: {}", summary),
+                LineStyle::Import,
+            ));
+        }
+        self
+    }
+
+    /// Add a line counting imports left out of the summary (`--max-imports`).
+    pub fn with_more_imports(mut self, count: usize) -> Self {
+        if count > 0 {
+            self.import_lines.push(MetadataLine::with_style(
+                format!("... and {} more", count),
+                LineStyle::Import,
+            ));
         }
+        self
+    }
+
+    /// Add a line for imports reached through internal imports (`--imports-depth`).
+    pub fn with_transitive_imports(mut self, imports: &[String]) -> Self {
+        if !imports.is_empty() {
+            self.import_lines.push(MetadataLine::with_style(
+                format!("→ transitive: {}", imports.join(", ")),
+                LineStyle::Import,
+            ));
+        }
+        self
+    }
+
+    /// Append lines produced by the plugin called `name`, styled as
+    /// `LineStyle::Custom(name)`.
+    pub fn with_custom(mut self, name: &str, lines: Vec<MetadataLine>) -> Self {
+        self.custom_lines
+            .extend(lines.into_iter().map(|line| MetadataLine {
+                style: LineStyle::Custom(name.to_string()),
+                ..line
+            }));
+        self
+    }
+
+    /// Combine two blocks, appending each group of lines from `other` after
+    /// the corresponding group of `self`.
+    ///
+    /// Useful for composing the results of several `MetadataExtractor`s.
+    pub fn merge(mut self, other: MetadataBlock) -> Self {
+        self.comment_lines.extend(other.comment_lines);
+        self.type_lines.extend(other.type_lines);
+        self.todo_lines.extend(other.todo_lines);
+        self.import_lines.extend(other.import_lines);
+        self.custom_lines.extend(other.custom_lines);
+        self
     }
 
     /// Check if this block has any content.
@@ -160,6 +282,7 @@ impl MetadataBlock {
             && self.type_lines.is_empty()
             && self.todo_lines.is_empty()
             && self.import_lines.is_empty()
+            && self.custom_lines.is_empty()
     }
 
     /// Check if only comments are present (no types, todos, or imports).
@@ -168,6 +291,7 @@ impl MetadataBlock {
             && self.type_lines.is_empty()
             && self.todo_lines.is_empty()
             && self.import_lines.is_empty()
+            && self.custom_lines.is_empty()
     }
 
     /// Check if only types are present (no comments, todos, or imports).
@@ -176,6 +300,7 @@ impl MetadataBlock {
             && !self.type_lines.is_empty()
             && self.todo_lines.is_empty()
             && self.import_lines.is_empty()
+            && self.custom_lines.is_empty()
     }
 
     /// Check if only imports are present (no comments, types, or todos).
@@ -184,6 +309,7 @@ impl MetadataBlock {
             && self.type_lines.is_empty()
             && self.todo_lines.is_empty()
             && !self.import_lines.is_empty()
+            && self.custom_lines.is_empty()
     }
 
     /// Check if only todos are present (no comments, types, or imports).
@@ -192,6 +318,7 @@ impl MetadataBlock {
             && self.type_lines.is_empty()
             && !self.todo_lines.is_empty()
             && self.import_lines.is_empty()
+            && self.custom_lines.is_empty()
     }
 
     /// Check if both comments and types are present.
@@ -209,76 +336,93 @@ impl MetadataBlock {
         !self.import_lines.is_empty()
     }
 
-    /// Get lines in the specified order, with an empty line between groups if both exist.
-    /// Order: comments/types (per order), then imports, then TODOs.
-    pub fn lines_in_order(&self, order: MetadataOrder) -> Vec<MetadataLine> {
-        let mut result = Vec::new();
-
-        let (first, second) = match order {
-            MetadataOrder::CommentsFirst => (&self.comment_lines, &self.type_lines),
-            MetadataOrder::TypesFirst => (&self.type_lines, &self.comment_lines),
-        };
-
-        result.extend(first.iter().cloned());
+    /// Check if plugin lines are present.
+    pub fn has_custom(&self) -> bool {
+        !self.custom_lines.is_empty()
+    }
 
-        // Add separator if both groups have content
-        if !first.is_empty() && !second.is_empty() {
-            result.push(MetadataLine::new(String::new())); // empty line separator
+    /// Line groups in display order. The group selected by `order` leads, and
+    /// the others follow in their default order: comments, types, imports,
+    /// plugin lines, TODOs.
+    fn groups_in_order(&self, order: MetadataOrder) -> [&Vec<MetadataLine>; 5] {
+        let comments = &self.comment_lines;
+        let types = &self.type_lines;
+        let imports = &self.import_lines;
+        let custom = &self.custom_lines;
+        let todos = &self.todo_lines;
+        match order {
+            MetadataOrder::CommentsFirst => [comments, types, imports, custom, todos],
+            MetadataOrder::TypesFirst => [types, comments, imports, custom, todos],
+            MetadataOrder::ImportFirst => [imports, comments, types, custom, todos],
+            MetadataOrder::TodoFirst => [todos, comments, types, imports, custom],
         }
+    }
+
+    /// Get lines in the specified order, with an empty line between non-empty groups.
+    pub fn lines_in_order(&self, order: MetadataOrder) -> Vec<MetadataLine> {
+        self.lines_in_order_capped(order, None)
+    }
 
-        result.extend(second.iter().cloned());
+    /// Like `lines_in_order`, keeping at most `max_lines` non-empty lines
+    /// (at least one) and ending with a `... (N more)` line if any were cut.
+    pub fn lines_in_order_capped(
+        &self,
+        order: MetadataOrder,
+        max_lines: Option<usize>,
+    ) -> Vec<MetadataLine> {
+        let mut result = Vec::new();
 
-        // Add imports with separator
-        if !self.import_lines.is_empty() && !result.is_empty() {
-            result.push(MetadataLine::new(String::new())); // empty line separator
+        for group in self.groups_in_order(order) {
+            if !group.is_empty() && !result.is_empty() {
+                result.push(MetadataLine::new(String::new())); // empty line separator
+            }
+            result.extend(group.iter().cloned());
         }
-        result.extend(self.import_lines.iter().cloned());
 
-        // Add TODOs at the end with separator
-        if !self.todo_lines.is_empty() && !result.is_empty() {
-            result.push(MetadataLine::new(String::new())); // empty line separator
+        let Some(max_lines) = max_lines.map(|n| n.max(1)) else {
+            return result;
+        };
+        let is_content = |line: &MetadataLine| !line.content.is_empty();
+        let total = result.iter().filter(|line| is_content(line)).count();
+        if total <= max_lines {
+            return result;
         }
-        result.extend(self.todo_lines.iter().cloned());
-
+        let cut = result
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| is_content(line))
+            .nth(max_lines)
+            .map_or(result.len(), |(i, _)| i);
+        result.truncate(cut);
+        // Drop a group separator left dangling by the cut
+        while result.last().is_some_and(|line| !is_content(line)) {
+            result.pop();
+        }
+        result.push(MetadataLine::with_style(
+            format!("... ({} more)", total - max_lines),
+            LineStyle::Truncation,
+        ));
         result
     }
 
+    /// The first non-empty group of lines in `order`.
+    fn first_group(&self, order: MetadataOrder) -> Option<&Vec<MetadataLine>> {
+        self.groups_in_order(order)
+            .into_iter()
+            .find(|group| !group.is_empty())
+    }
+
     /// Get the first line of metadata (for inline display).
     /// Returns the first line from the first non-empty group based on order.
     pub fn first_line(&self, order: MetadataOrder) -> Option<&MetadataLine> {
-        let (first, second) = match order {
-            MetadataOrder::CommentsFirst => (&self.comment_lines, &self.type_lines),
-            MetadataOrder::TypesFirst => (&self.type_lines, &self.comment_lines),
-        };
-
-        first
-            .first()
-            .or_else(|| second.first())
-            .or_else(|| self.todo_lines.first())
+        self.first_group(order)?.first()
     }
 
     /// Check if the first metadata section (based on order) has only one line.
     /// This is used to determine if it should be displayed inline.
     pub fn first_section_is_single_line(&self, order: MetadataOrder) -> bool {
-        let first = match order {
-            MetadataOrder::CommentsFirst => &self.comment_lines,
-            MetadataOrder::TypesFirst => &self.type_lines,
-        };
-
-        // If the first section is empty, check the second
-        if first.is_empty() {
-            let second = match order {
-                MetadataOrder::CommentsFirst => &self.type_lines,
-                MetadataOrder::TypesFirst => &self.comment_lines,
-            };
-            if second.is_empty() {
-                // Only todos present
-                return self.todo_lines.len() == 1;
-            }
-            return second.len() == 1;
-        }
-
-        first.len() == 1
+        self.first_group(order)
+            .is_some_and(|group| group.len() == 1)
     }
 
     /// Total number of lines (not counting separator).
@@ -287,6 +431,7 @@ impl MetadataBlock {
             + self.type_lines.len()
             + self.todo_lines.len()
             + self.import_lines.len()
+            + self.custom_lines.len()
     }
 }
 
@@ -301,10 +446,106 @@ pub trait MetadataExtractor: Send + Sync {
     /// no relevant content, etc.).
     fn extract(&self, path: &Path) -> Option<MetadataBlock>;
 
+    /// Extract metadata from several files, returning one result per path in
+    /// the same order.
+    ///
+    /// The default calls `extract` for each path. Override it to share setup
+    /// work, like building a regex set, across a batch.
+    fn extract_batch(&self, paths: &[&Path]) -> Vec<Option<MetadataBlock>> {
+        paths.iter().map(|path| self.extract(path)).collect()
+    }
+
     /// The name of this extractor (e.g., "comments", "types", "structure").
     fn name(&self) -> &'static str;
 }
 
+/// An optional extractor: `None` never extracts anything, `Some` delegates.
+///
+/// ```
+/// use fruit::{CommentExtractor, MetadataExtractor};
+///
+/// let show_comments = false;
+/// let extractor = show_comments.then_some(CommentExtractor);
+/// assert!(extractor.extract("src/main.rs".as_ref()).is_none());
+/// ```
+impl<E: MetadataExtractor> MetadataExtractor for Option<E> {
+    fn extract(&self, path: &Path) -> Option<MetadataBlock> {
+        self.as_ref()?.extract(path)
+    }
+
+    fn extract_batch(&self, paths: &[&Path]) -> Vec<Option<MetadataBlock>> {
+        match self {
+            Some(extractor) => extractor.extract_batch(paths),
+            None => vec![None; paths.len()],
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        self.as_ref().map_or("none", |extractor| extractor.name())
+    }
+}
+
+/// Runs several extractors in order and merges their output into one block.
+#[derive(Default)]
+pub struct ExtractorChain {
+    extractors: Vec<Box<dyn MetadataExtractor>>,
+}
+
+impl ExtractorChain {
+    /// Create an empty chain.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an extractor; it runs after those already in the chain.
+    pub fn with(mut self, extractor: impl MetadataExtractor + 'static) -> Self {
+        self.extractors.push(Box::new(extractor));
+        self
+    }
+
+    /// Number of extractors in the chain.
+    pub fn len(&self) -> usize {
+        self.extractors.len()
+    }
+
+    /// Check if the chain has no extractors.
+    pub fn is_empty(&self) -> bool {
+        self.extractors.is_empty()
+    }
+}
+
+impl MetadataExtractor for ExtractorChain {
+    fn extract(&self, path: &Path) -> Option<MetadataBlock> {
+        let block = self
+            .extractors
+            .iter()
+            .filter_map(|extractor| extractor.extract(path))
+            .fold(MetadataBlock::new(), MetadataBlock::merge);
+        if block.is_empty() { None } else { Some(block) }
+    }
+
+    /// Hands the whole batch to each extractor in turn, so their own
+    /// `extract_batch` overrides apply.
+    fn extract_batch(&self, paths: &[&Path]) -> Vec<Option<MetadataBlock>> {
+        let mut blocks = vec![MetadataBlock::new(); paths.len()];
+        for extractor in &self.extractors {
+            for (block, extracted) in blocks.iter_mut().zip(extractor.extract_batch(paths)) {
+                if let Some(extracted) = extracted {
+                    *block = std::mem::take(block).merge(extracted);
+                }
+            }
+        }
+        blocks
+            .into_iter()
+            .map(|block| if block.is_empty() { None } else { Some(block) })
+            .collect()
+    }
+
+    fn name(&self) -> &'static str {
+        "chain"
+    }
+}
+
 /// Built-in comment extractor that wraps the existing comment extraction logic.
 pub struct CommentExtractor;
 
@@ -318,7 +559,7 @@ impl MetadataExtractor for CommentExtractor {
     }
 }
 
-/// Order in which to display metadata types when both are enabled.
+/// Order in which to display metadata types when several are enabled.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum MetadataOrder {
     /// Comments first, then types (default)
@@ -326,6 +567,10 @@ pub enum MetadataOrder {
     CommentsFirst,
     /// Types first, then comments
     TypesFirst,
+    /// Imports first, then comments and types
+    ImportFirst,
+    /// TODOs first, then comments, types and imports
+    TodoFirst,
 }
 
 /// Configuration for which metadata extractors to use.
@@ -343,6 +588,8 @@ pub struct MetadataConfig {
     pub prefix: Option<String>,
     /// Order to display metadata when both comments and types are enabled
     pub order: MetadataOrder,
+    /// Most metadata lines shown per file in full mode (`None` = unlimited)
+    pub max_lines: Option<usize>,
 }
 
 impl MetadataConfig {
@@ -355,6 +602,7 @@ impl MetadataConfig {
             full,
             prefix: None,
             order: MetadataOrder::CommentsFirst,
+            max_lines: None,
         }
     }
 
@@ -367,6 +615,7 @@ impl MetadataConfig {
             full,
             prefix: None,
             order: MetadataOrder::TypesFirst,
+            max_lines: None,
         }
     }
 
@@ -379,6 +628,7 @@ impl MetadataConfig {
             full,
             prefix: None,
             order,
+            max_lines: None,
         }
     }
 
@@ -391,6 +641,7 @@ impl MetadataConfig {
             full: false,
             prefix: None,
             order: MetadataOrder::CommentsFirst,
+            max_lines: None,
         }
     }
 
@@ -400,6 +651,13 @@ impl MetadataConfig {
         self
     }
 
+    /// Show at most `max_lines` metadata lines per file, replacing the rest
+    /// with a `... (N more)` line.
+    pub fn with_max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines);
+        self
+    }
+
     /// Get the prefix string, or empty string if none set.
     pub fn prefix_str(&self) -> &str {
         self.prefix.as_deref().unwrap_or("")
@@ -409,6 +667,7 @@ impl MetadataConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::TypeSignature;
 
     #[test]
     fn test_metadata_line_creation() {
@@ -434,11 +693,19 @@ mod tests {
         assert!(block.type_lines.is_empty());
     }
 
+    #[test]
+    fn test_metadata_block_from_markdown_summary() {
+        let block = MetadataBlock::from_markdown_summary("# User Guide\n\nHow to use it.\n");
+        assert_eq!(block.comment_lines.len(), 1);
+        assert_eq!(block.comment_lines[0].content, "User Guide");
+        assert!(MetadataBlock::from_markdown_summary("```\ncode\n```\n").is_empty());
+    }
+
     #[test]
     fn test_metadata_block_from_types() {
         let block = MetadataBlock::from_types(vec![
-            ("pub fn foo()".to_string(), "foo".to_string(), 0),
-            ("pub struct Bar".to_string(), "Bar".to_string(), 4),
+            TypeSignature::new("pub fn foo()", "foo", 0, 1),
+            TypeSignature::new("pub struct Bar", "Bar", 4, 2),
         ]);
         assert!(block.comment_lines.is_empty());
         assert_eq!(block.type_lines.len(), 2);
@@ -450,6 +717,50 @@ mod tests {
         assert_eq!(block.type_lines[1].indent, 4);
     }
 
+    #[test]
+    fn test_metadata_block_merge() {
+        let comments = MetadataBlock::from_comments("Entry point");
+        let types = MetadataBlock::from_types(vec![TypeSignature::new("fn main()", "main", 0, 1)])
+            .with_comment("More docs");
+
+        let merged = comments.merge(types);
+        assert_eq!(merged.comment_lines.len(), 2);
+        assert_eq!(merged.comment_lines[0].content, "Entry point");
+        assert_eq!(merged.comment_lines[1].content, "More docs");
+        assert_eq!(merged.type_lines.len(), 1);
+        assert_eq!(merged.type_lines[0].symbol_name.as_deref(), Some("main"));
+
+        assert!(MetadataBlock::new().merge(MetadataBlock::new()).is_empty());
+    }
+
+    #[test]
+    fn test_metadata_block_builders() {
+        let todo = crate::todos::TodoItem {
+            marker_type: "TODO".to_string(),
+            text: "finish".to_string(),
+            line: 3,
+            assignee: None,
+        };
+        let assigned = crate::todos::TodoItem {
+            assignee: Some("alice".to_string()),
+            ..todo.clone()
+        };
+        let block = MetadataBlock::new()
+            .with_comment("Docs")
+            .with_todos(&[todo, assigned])
+            .with_imports("std: io")
+            .with_imports("");
+
+        assert_eq!(block.comment_lines[0].content, "Docs");
+        assert_eq!(block.todo_lines[0].content, "TODO: finish (line 3)");
+        assert_eq!(block.todo_lines[0].style, LineStyle::Todo);
+        assert_eq!(block.todo_lines[1].content, "TODO(@alice): finish (line 3)");
+        assert_eq!(block.todo_lines[1].symbol_name.as_deref(), Some("@alice"));
+        assert_eq!(block.import_lines.len(), 1);
+        assert_eq!(block.import_lines[0].content, "imports: std: io");
+        assert_eq!(block.import_lines[0].style, LineStyle::Import);
+    }
+
     #[test]
     fn test_metadata_block_is_empty() {
         let empty = MetadataBlock::new();
@@ -459,7 +770,7 @@ mod tests {
         assert!(!with_comments.is_empty());
 
         let with_types =
-            MetadataBlock::from_types(vec![("fn foo()".to_string(), "foo".to_string(), 0)]);
+            MetadataBlock::from_types(vec![TypeSignature::new("fn foo()", "foo", 0, 1)]);
         assert!(!with_types.is_empty());
     }
 
@@ -487,6 +798,33 @@ mod tests {
         assert_eq!(lines[2].content, "comment");
     }
 
+    #[test]
+    fn test_metadata_block_lines_in_order_capped() {
+        let mut block = MetadataBlock::new();
+        block.comment_lines = vec![MetadataLine::new("one"), MetadataLine::new("two")];
+        block.type_lines = vec![
+            MetadataLine::with_style("fn a()", LineStyle::TypeSignature),
+            MetadataLine::with_style("fn b()", LineStyle::TypeSignature),
+        ];
+
+        let lines = block.lines_in_order_capped(MetadataOrder::CommentsFirst, Some(2));
+        let contents: Vec<_> = lines.iter().map(|line| line.content.as_str()).collect();
+        assert_eq!(contents, ["one", "two", "... (2 more)"]);
+        assert_eq!(lines[2].style, LineStyle::Truncation);
+
+        let lines = block.lines_in_order_capped(MetadataOrder::CommentsFirst, Some(3));
+        let contents: Vec<_> = lines.iter().map(|line| line.content.as_str()).collect();
+        assert_eq!(contents, ["one", "two", "", "fn a()", "... (1 more)"]);
+
+        // Within the cap, and a cap of zero still shows one line
+        let lines = block.lines_in_order_capped(MetadataOrder::CommentsFirst, Some(4));
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[4].content, "fn b()");
+        let lines = block.lines_in_order_capped(MetadataOrder::TypesFirst, Some(0));
+        assert_eq!(lines[0].content, "fn a()");
+        assert_eq!(lines[1].content, "... (3 more)");
+    }
+
     #[test]
     fn test_metadata_block_first_section_single_line() {
         let mut block = MetadataBlock::new();
@@ -500,6 +838,48 @@ mod tests {
         assert!(!block.first_section_is_single_line(MetadataOrder::TypesFirst));
     }
 
+    #[test]
+    fn test_metadata_block_import_and_todo_first() {
+        let block = MetadataBlock::from_comments("comment")
+            .with_imports("std: io")
+            .with_todos(&[crate::todos::TodoItem {
+                marker_type: "TODO".to_string(),
+                text: "fix".to_string(),
+                line: 2,
+                assignee: None,
+            }]);
+
+        let contents = |order| {
+            block
+                .lines_in_order(order)
+                .into_iter()
+                .map(|line| line.content)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            contents(MetadataOrder::ImportFirst),
+            ["imports: std: io", "", "comment", "", "TODO: fix (line 2)"]
+        );
+        assert_eq!(
+            contents(MetadataOrder::TodoFirst),
+            ["TODO: fix (line 2)", "", "comment", "", "imports: std: io"]
+        );
+
+        let first = block.first_line(MetadataOrder::TodoFirst).unwrap();
+        assert_eq!(first.content, "TODO: fix (line 2)");
+        assert!(block.first_section_is_single_line(MetadataOrder::ImportFirst));
+
+        // An empty leading group falls back to comments
+        let comments = MetadataBlock::from_comments("only");
+        assert_eq!(
+            comments
+                .first_line(MetadataOrder::ImportFirst)
+                .unwrap()
+                .content,
+            "only"
+        );
+    }
+
     #[test]
     fn test_line_style_colors() {
         assert_eq!(LineStyle::Comment.color(), Color::Black);
@@ -551,6 +931,16 @@ mod tests {
         assert_eq!(no_prefix.prefix_str(), "");
     }
 
+    #[test]
+    fn test_metadata_config_with_max_lines() {
+        let config = MetadataConfig::comments_only(true)
+            .with_max_lines(5)
+            .with_prefix("# ");
+        assert_eq!(config.max_lines, Some(5));
+        assert_eq!(config.prefix_str(), "# ");
+        assert_eq!(MetadataConfig::none().max_lines, None);
+    }
+
     #[test]
     fn test_has_only_comments_excludes_imports() {
         let mut block = MetadataBlock::new();
@@ -565,7 +955,10 @@ mod tests {
     #[test]
     fn test_has_only_types_excludes_imports() {
         let mut block = MetadataBlock::new();
-        block.type_lines = vec![MetadataLine::with_style("fn foo()", LineStyle::TypeSignature)];
+        block.type_lines = vec![MetadataLine::with_style(
+            "fn foo()",
+            LineStyle::TypeSignature,
+        )];
         assert!(block.has_only_types());
 
         // Adding imports should make has_only_types return false
@@ -604,4 +997,68 @@ mod tests {
         block.import_lines = vec![MetadataLine::with_style("use foo", LineStyle::Import)];
         assert!(!block.has_only_todos());
     }
+
+    #[test]
+    fn test_optional_extractor() {
+        let repo = crate::test_utils::TestRepo::new();
+        let path = repo.add_file("main.rs", "//! Entry point\nfn main() {}\n");
+
+        let disabled: Option<CommentExtractor> = None;
+        assert!(disabled.extract(&path).is_none());
+        assert_eq!(disabled.name(), "none");
+
+        let enabled = Some(CommentExtractor);
+        assert_eq!(enabled.extract(&path).unwrap().comment_lines.len(), 1);
+        assert_eq!(enabled.name(), "comments");
+    }
+
+    #[test]
+    fn test_extractor_chain_merges_blocks() {
+        let repo = crate::test_utils::TestRepo::new();
+        let path = repo.add_file("lib.rs", "//! Library\npub fn run() {}\n");
+
+        let chain = ExtractorChain::new()
+            .with(CommentExtractor)
+            .with(None::<crate::types::TypeExtractor>)
+            .with(Some(crate::types::TypeExtractor));
+        assert_eq!(chain.len(), 3);
+
+        let block = chain.extract(&path).unwrap();
+        assert_eq!(block.comment_lines[0].content, "Library");
+        assert_eq!(block.type_lines[0].content, "pub fn run()");
+
+        let empty = repo.add_file("notes.txt", "nothing here");
+        assert!(ExtractorChain::new().extract(&path).is_none());
+        assert!(chain.extract(&empty).is_none());
+    }
+
+    #[test]
+    fn test_extract_batch_matches_extract() {
+        let repo = crate::test_utils::TestRepo::new();
+        let lib = repo.add_file("lib.rs", "//! Library\npub fn run() {}\n");
+        let empty = repo.add_file("notes.txt", "nothing here");
+        let main = repo.add_file("main.rs", "//! Entry point\n");
+        let paths = [lib.as_path(), empty.as_path(), main.as_path()];
+
+        let chain = ExtractorChain::new()
+            .with(CommentExtractor)
+            .with(Some(crate::types::TypeExtractor));
+        let batch = chain.extract_batch(&paths);
+        assert_eq!(batch.len(), 3);
+        for (path, block) in paths.iter().zip(&batch) {
+            let single = chain.extract(path);
+            assert_eq!(
+                block.as_ref().map(MetadataBlock::total_lines),
+                single.as_ref().map(MetadataBlock::total_lines)
+            );
+        }
+        assert_eq!(
+            batch[0].as_ref().unwrap().type_lines[0].content,
+            "pub fn run()"
+        );
+        assert!(batch[1].is_none());
+
+        assert_eq!(None::<CommentExtractor>.extract_batch(&paths).len(), 3);
+        assert!(ExtractorChain::new().extract_batch(&[]).is_empty());
+    }
 }
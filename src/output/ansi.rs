@@ -0,0 +1,30 @@
+//! How colored console output is written
+//!
+//! termcolor decides for itself whether a terminal can show colors, looking
+//! at `TERM` and, on Windows, the console mode. Some terminals and CI logs
+//! understand ANSI escape codes without passing that check, so
+//! `--ansi-only` skips it and always writes the escape codes.
+
+use termcolor::ColorChoice;
+
+/// The termcolor setting for output that is colored when `use_color` is set,
+/// as raw ANSI escape codes when `ansi_only` is.
+pub fn color_choice(use_color: bool, ansi_only: bool) -> ColorChoice {
+    match (use_color, ansi_only) {
+        (false, _) => ColorChoice::Never,
+        (true, true) => ColorChoice::AlwaysAnsi,
+        (true, false) => ColorChoice::Auto,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_choice() {
+        assert_eq!(color_choice(false, true), ColorChoice::Never);
+        assert_eq!(color_choice(true, false), ColorChoice::Auto);
+        assert_eq!(color_choice(true, true), ColorChoice::AlwaysAnsi);
+    }
+}
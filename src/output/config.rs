@@ -1,6 +1,15 @@
 //! Output configuration types
 
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use termcolor::{Color, ColorChoice};
+
 use crate::metadata::MetadataConfig;
+use crate::tree::SizeStyle;
+
+use super::ansi::color_choice;
+use super::theme::{DEPTH_COLORS, Theme};
 
 const DEFAULT_WRAP_WIDTH: usize = 100;
 
@@ -8,9 +17,31 @@ const DEFAULT_WRAP_WIDTH: usize = 100;
 #[derive(Debug, Clone)]
 pub struct OutputConfig {
     pub use_color: bool,
+    /// Write colors as ANSI escape codes without checking that the terminal
+    /// supports them
+    pub ansi_only: bool,
     /// Metadata display configuration
     pub metadata: MetadataConfig,
     pub wrap_width: Option<usize>,
+    /// Prefix prepended to displayed paths (replaces the walked root)
+    pub path_prefix: Option<String>,
+    /// Absolute walked root, set to display absolute paths; `path_prefix` takes precedence
+    pub root_path: Option<PathBuf>,
+    /// Show the number of direct children next to each directory
+    pub show_dir_counts: bool,
+    /// Colors for console output
+    pub theme: Theme,
+    /// Color directory names by nesting depth instead of the theme's directory color
+    pub depth_colors: bool,
+    /// In Markdown, fold a file's type signatures into a `<details>` section
+    /// when there are more than this many
+    pub collapse_types: Option<usize>,
+    /// Print the final `N directories, M files` line
+    pub show_summary: bool,
+    /// Pack runs of sibling files without metadata onto shared lines
+    pub compact: bool,
+    /// How file sizes are written
+    pub size_style: SizeStyle,
 }
 
 impl OutputConfig {
@@ -18,14 +49,77 @@ impl OutputConfig {
     pub fn show_full(&self) -> bool {
         self.metadata.full
     }
+
+    /// The termcolor setting for console output.
+    pub fn color_choice(&self) -> ColorChoice {
+        color_choice(self.use_color, self.ansi_only)
+    }
+
+    /// Width of the screen lines that `compact` packs file names into:
+    /// `$COLUMNS` when set, otherwise the wrap width.
+    pub fn line_width(&self) -> usize {
+        std::env::var("COLUMNS")
+            .ok()
+            .and_then(|columns| columns.trim().parse().ok())
+            .filter(|&columns: &usize| columns > 0)
+            .or(self.wrap_width)
+            .unwrap_or(DEFAULT_WRAP_WIDTH)
+    }
+
+    /// Color for a directory name at the given depth (root is depth 0).
+    pub fn dir_color(&self, depth: usize) -> Color {
+        if self.depth_colors {
+            DEPTH_COLORS[depth % DEPTH_COLORS.len()]
+        } else {
+            self.theme.dir_color
+        }
+    }
+
+    /// The string that replaces the walked root in displayed paths: the path
+    /// prefix if set, otherwise the absolute root path.
+    pub(super) fn display_root(&self) -> Option<Cow<'_, str>> {
+        match (&self.path_prefix, &self.root_path) {
+            (Some(prefix), _) => Some(Cow::Borrowed(prefix)),
+            (None, Some(root)) => Some(root.to_string_lossy()),
+            (None, None) => None,
+        }
+    }
+
+    /// Apply the configured path prefix (or absolute root) to a path relative
+    /// to the walked root.
+    ///
+    /// Returns `None` when neither is configured. An empty relative path
+    /// yields the prefix itself.
+    pub fn prefixed_path(&self, relative: &str) -> Option<String> {
+        let display_root = self.display_root()?;
+        let prefix = display_root.trim_end_matches('/');
+        let relative = relative.trim_start_matches("./").trim_start_matches('/');
+        if relative.is_empty() || relative == "." {
+            Some(prefix.to_string())
+        } else if prefix.is_empty() {
+            Some(relative.to_string())
+        } else {
+            Some(format!("{}/{}", prefix, relative))
+        }
+    }
 }
 
 impl Default for OutputConfig {
     fn default() -> Self {
         Self {
             use_color: true,
+            ansi_only: false,
             metadata: MetadataConfig::comments_only(false),
             wrap_width: Some(DEFAULT_WRAP_WIDTH),
+            path_prefix: None,
+            root_path: None,
+            show_dir_counts: false,
+            theme: Theme::default(),
+            depth_colors: false,
+            collapse_types: None,
+            show_summary: true,
+            compact: false,
+            size_style: SizeStyle::default(),
         }
     }
 }
@@ -0,0 +1,178 @@
+//! GraphViz output formatting
+//!
+//! This module provides `DotFormatter` which renders a complete tree as a
+//! GraphViz `digraph`, with one node per file and an edge for each internal
+//! import. The output can be piped straight into `dot -Tsvg`.
+
+use std::io;
+use std::path::Path;
+
+use crate::imports::{FileImports, module_key, resolve_import};
+use crate::tree::TreeNode;
+
+use super::config::OutputConfig;
+use super::utils::first_line;
+
+/// A file node collected from the tree.
+struct DotFile<'a> {
+    /// Path relative to the root node, using `/` separators
+    path: String,
+    comment: Option<&'a str>,
+    imports: Option<&'a FileImports>,
+}
+
+/// GraphViz formatter - works on a full `TreeNode`, since edges can point
+/// anywhere in the tree.
+pub struct DotFormatter {
+    config: OutputConfig,
+}
+
+impl DotFormatter {
+    pub fn new(config: OutputConfig) -> Self {
+        Self { config }
+    }
+
+    /// Render the tree as a `digraph` block.
+    ///
+    /// Node labels show the file path and, when comments are enabled, the
+    /// first line of the file's comment.
+    pub fn format(&self, tree: &TreeNode) -> String {
+        let root = match tree {
+            TreeNode::File { path, .. } => path.parent().unwrap_or(path),
+            TreeNode::Dir { path, .. } => path.as_path(),
+        };
+        let mut files = Vec::new();
+        collect_files(tree, root, &mut files);
+
+        let module_keys: Vec<String> = files.iter().map(|f| module_key(&f.path)).collect();
+
+        let mut output = String::from("digraph dependencies {\n");
+        output.push_str("    rankdir=LR;\n");
+        output.push_str("    node [shape=box];\n");
+
+        for (i, file) in files.iter().enumerate() {
+            let mut label = escape_label(&file.path);
+            if self.config.metadata.comments
+                && let Some(comment) = file.comment
+            {
+                label.push_str("\\n");
+                label.push_str(&escape_label(first_line(comment)));
+            }
+            output.push_str(&format!("    f{} [label=\"{}\"];\n", i, label));
+        }
+
+        let mut edges = Vec::new();
+        for (i, file) in files.iter().enumerate() {
+            let Some(imports) = file.imports else {
+                continue;
+            };
+            for import in &imports.internal {
+                if let Some(target) = resolve_import(import, &file.path, &module_keys)
+                    && target != i
+                    && !edges.contains(&(i, target))
+                {
+                    edges.push((i, target));
+                }
+            }
+        }
+        for (from, to) in edges {
+            output.push_str(&format!("    f{} -> f{};\n", from, to));
+        }
+
+        output.push_str("}\n");
+        output
+    }
+}
+
+/// Print tree node as a GraphViz digraph to stdout.
+pub fn print_dot(node: &TreeNode, config: &OutputConfig) -> io::Result<()> {
+    print!("{}", DotFormatter::new(config.clone()).format(node));
+    Ok(())
+}
+
+/// Recursively collect file nodes in tree order.
+fn collect_files<'a>(node: &'a TreeNode, root: &Path, files: &mut Vec<DotFile<'a>>) {
+    match node {
+        TreeNode::File {
+            path,
+            comment,
+            imports,
+            ..
+        } => {
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            files.push(DotFile {
+                path: relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+                comment: comment.as_deref(),
+                imports: imports.as_ref(),
+            });
+        }
+        TreeNode::Dir { children, .. } => {
+            for child in children {
+                collect_files(child, root, files);
+            }
+        }
+    }
+}
+
+/// Escape text for use inside a quoted DOT string.
+fn escape_label(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{dir_node, file_node};
+
+    fn file(path: &str, comment: Option<&str>, internal: &[&str]) -> TreeNode {
+        let mut node = file_node(path);
+        if let TreeNode::File {
+            comment: node_comment,
+            imports,
+            ..
+        } = &mut node
+        {
+            *node_comment = comment.map(String::from);
+            *imports = Some(FileImports {
+                internal: internal.iter().map(|s| s.to_string()).collect(),
+                ..FileImports::default()
+            });
+        }
+        node
+    }
+
+    fn tree() -> TreeNode {
+        dir_node(
+            "/proj",
+            vec![
+                file("/proj/config.rs", Some("Config \"loading\"\nMore"), &[]),
+                file("/proj/main.rs", None, &["config::Config", "main"]),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_dot_nodes_and_edges() {
+        let output = DotFormatter::new(OutputConfig::default()).format(&tree());
+
+        assert_eq!(
+            output,
+            "digraph dependencies {\n    rankdir=LR;\n    node [shape=box];\n    \
+             f0 [label=\"config.rs\\nConfig \\\"loading\\\"\"];\n    \
+             f1 [label=\"main.rs\"];\n    f1 -> f0;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_dot_without_comments() {
+        let mut config = OutputConfig::default();
+        config.metadata.comments = false;
+        let output = DotFormatter::new(config).format(&tree());
+
+        assert!(output.contains("f0 [label=\"config.rs\"];"), "{}", output);
+    }
+}
@@ -1,13 +1,593 @@
 //! JSON output formatting
 
-use std::io;
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
-use crate::tree::TreeNode;
+use crate::tree::{LevelEntry, SizeStyle, TreeNode, TreeWalker, format_size};
+
+use super::config::OutputConfig;
+
+/// JSON Schema (draft-07) of the `--json` output, printed by `--json-schema`.
+///
+/// Kept by hand in step with the serde types in `tree::json_types`; a test
+/// checks that every field they serialize is described here.
+pub const JSON_SCHEMA: &str = r##"{
+  "$schema": "http://json-schema.org/draft-07/schema#",
+  "title": "fruit --json output",
+  "description": "A file tree node: the walked directory, or a file when a single file is given.",
+  "oneOf": [
+    { "$ref": "#/definitions/dir" },
+    { "$ref": "#/definitions/file" }
+  ],
+  "definitions": {
+    "node": {
+      "oneOf": [
+        { "$ref": "#/definitions/dir" },
+        { "$ref": "#/definitions/file" }
+      ]
+    },
+    "dir": {
+      "type": "object",
+      "required": ["type", "name", "path", "children"],
+      "properties": {
+        "type": { "const": "dir" },
+        "name": { "type": "string" },
+        "path": {
+          "type": "string",
+          "description": "Path as walked, or relative to the root after --prefix-path or --absolute"
+        },
+        "child_count": {
+          "type": "integer",
+          "minimum": 0,
+          "description": "Number of direct children, only with --count"
+        },
+        "children": {
+          "type": "array",
+          "items": { "$ref": "#/definitions/node" }
+        }
+      },
+      "additionalProperties": false
+    },
+    "file": {
+      "type": "object",
+      "required": ["type", "name", "path"],
+      "properties": {
+        "type": { "const": "file" },
+        "name": { "type": "string" },
+        "path": { "type": "string" },
+        "comment": {
+          "type": "string",
+          "description": "First documentation comment, lines joined with newlines"
+        },
+        "types": {
+          "type": "array",
+          "items": { "$ref": "#/definitions/type_signature" }
+        },
+        "todos": {
+          "type": "array",
+          "items": { "$ref": "#/definitions/todo" }
+        },
+        "imports": { "$ref": "#/definitions/imports" },
+        "size_bytes": { "type": "integer", "minimum": 0 },
+        "size_human": { "type": "string" }
+      },
+      "additionalProperties": false
+    },
+    "type_signature": {
+      "description": "A signature, or an object when line numbers or context lines are shown",
+      "oneOf": [
+        { "type": "string" },
+        {
+          "type": "object",
+          "required": ["signature"],
+          "properties": {
+            "signature": { "type": "string" },
+            "line": { "type": "integer", "minimum": 1 },
+            "context": {
+              "type": "array",
+              "items": { "type": "string" }
+            }
+          },
+          "additionalProperties": false
+        }
+      ]
+    },
+    "todo": {
+      "type": "object",
+      "required": ["type", "text", "line"],
+      "properties": {
+        "type": { "type": "string", "description": "Marker, e.g. TODO or FIXME" },
+        "text": { "type": "string" },
+        "line": { "type": "integer", "minimum": 1 },
+        "assignee": { "type": "string" }
+      },
+      "additionalProperties": false
+    },
+    "imports": {
+      "type": "object",
+      "properties": {
+        "external": { "$ref": "#/definitions/import_list" },
+        "std": { "$ref": "#/definitions/import_list" },
+        "internal": { "$ref": "#/definitions/import_list" },
+        "filtered": {
+          "const": true,
+          "description": "Set with --relative-imports-only, which hides external and std imports from the tree but not from JSON"
+        }
+      },
+      "additionalProperties": false
+    },
+    "import_list": {
+      "type": "array",
+      "items": { "type": "string" }
+    }
+  }
+}"##;
 
 /// Print tree node as pretty-printed JSON to stdout.
-pub fn print_json(node: &TreeNode) -> io::Result<()> {
-    let json =
-        serde_json::to_string_pretty(node).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-    println!("{}", json);
+///
+/// If `config.path_prefix` (or `config.root_path`) is set, every `path` field
+/// is rewritten as `<prefix>/<path relative to the root node>`. If `config.show_dir_counts`
+/// is set, directories include a `child_count` field.
+pub fn print_json(node: &TreeNode, config: &OutputConfig) -> io::Result<()> {
+    let root = match node {
+        TreeNode::File { path, .. } | TreeNode::Dir { path, .. } => path,
+    };
+    println!("{}", node_to_json(node, root, config)?);
     Ok(())
 }
+
+/// Walk `root` with `walker` and print it as JSON to stdout as it goes.
+///
+/// Only one directory level is held in memory at a time instead of the whole
+/// tree, which matters for very large repositories. The output is identical
+/// to `print_json` of `walker.walk(root)`. Returns `Ok(false)` if `root`
+/// cannot be walked.
+pub fn print_json_streaming(
+    walker: &TreeWalker,
+    root: &Path,
+    config: &OutputConfig,
+) -> io::Result<bool> {
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    let found = write_json_streaming(walker, root, config, &mut out)?;
+    out.flush()?;
+    Ok(found)
+}
+
+fn write_json_streaming<W: Write>(
+    walker: &TreeWalker,
+    root: &Path,
+    config: &OutputConfig,
+    out: &mut W,
+) -> io::Result<bool> {
+    // A single file (or a symlinked root, which is not walked) has nothing to stream
+    if !root.is_dir() || root.is_symlink() {
+        let Some(node) = walker.walk(root) else {
+            return Ok(false);
+        };
+        writeln!(out, "{}", node_to_json(&node, root, config)?)?;
+        return Ok(true);
+    }
+
+    let Some(entries) = walker.walk_level(root, 0) else {
+        return Ok(false);
+    };
+    let mut writer = JsonStreamWriter {
+        walker,
+        root,
+        config,
+        out,
+    };
+    writer.write_dir(root, 0, entries, "")?;
+    writeln!(writer.out)?;
+    walker.save_cache();
+    Ok(true)
+}
+
+/// Writes directories as pretty-printed JSON one level at a time, matching
+/// the layout of `serde_json::to_string_pretty`.
+struct JsonStreamWriter<'a, W: Write> {
+    walker: &'a TreeWalker,
+    root: &'a Path,
+    config: &'a OutputConfig,
+    out: &'a mut W,
+}
+
+impl<W: Write> JsonStreamWriter<'_, W> {
+    /// Write the directory at `path` with its already-walked `entries`,
+    /// with `indent` being the indentation of its opening brace.
+    fn write_dir(
+        &mut self,
+        path: &Path,
+        depth: usize,
+        entries: Vec<LevelEntry>,
+        indent: &str,
+    ) -> io::Result<()> {
+        let inner = format!("{}  ", indent);
+        let name = path
+            .file_name()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| ".".to_string());
+        let display_path = display_path(path, self.root, self.config);
+
+        writeln!(self.out, "{{")?;
+        writeln!(self.out, "{}\"type\": \"dir\",", inner)?;
+        writeln!(self.out, "{}\"name\": {},", inner, to_json_string(&name)?)?;
+        writeln!(
+            self.out,
+            "{}\"path\": {},",
+            inner,
+            to_json_string(&display_path)?
+        )?;
+        if self.config.show_dir_counts {
+            // Directories that turn out to be unreadable are left out below
+            let count = if self.walker.at_depth_limit(depth) {
+                self.walker.count_unwalked_children(path, depth)
+            } else {
+                entries
+                    .iter()
+                    .filter(|entry| match entry {
+                        LevelEntry::Node(_) => true,
+                        LevelEntry::Dir(dir) => std::fs::read_dir(dir).is_ok(),
+                    })
+                    .count()
+            };
+            writeln!(self.out, "{}\"child_count\": {},", inner, count)?;
+        }
+        write!(self.out, "{}\"children\": ", inner)?;
+
+        let child_indent = format!("{}  ", inner);
+        let mut first = true;
+        for entry in entries {
+            match entry {
+                LevelEntry::Node(node) => {
+                    let json = node_to_json(&node, self.root, self.config)?;
+                    self.begin_child(&mut first, &child_indent)?;
+                    // Pretty JSON has no raw newlines inside strings, so every
+                    // line break can be re-indented
+                    write!(
+                        self.out,
+                        "{}",
+                        json.replace('\n', &format!("\n{}", child_indent))
+                    )?;
+                }
+                LevelEntry::Dir(dir) => {
+                    // Skip unreadable directories, like `TreeWalker::walk`
+                    let Some(entries) = self.walker.walk_level(&dir, depth + 1) else {
+                        continue;
+                    };
+                    self.begin_child(&mut first, &child_indent)?;
+                    self.write_dir(&dir, depth + 1, entries, &child_indent)?;
+                }
+            }
+        }
+        if first {
+            writeln!(self.out, "[]")?;
+        } else {
+            writeln!(self.out, "\n{}]", inner)?;
+        }
+        write!(self.out, "{}}}", indent)
+    }
+
+    /// Open the children array or separate from the previous child.
+    fn begin_child(&mut self, first: &mut bool, child_indent: &str) -> io::Result<()> {
+        let separator = if *first { "[" } else { "," };
+        *first = false;
+        write!(self.out, "{}\n{}", separator, child_indent)
+    }
+}
+
+/// Serialize `node` as pretty JSON, applying the configured path prefix
+/// (relative to `root`) and directory counts.
+fn node_to_json(node: &TreeNode, root: &Path, config: &OutputConfig) -> io::Result<String> {
+    serde_json::to_string_pretty(&node_for_output(node, root, config)).map_err(io::Error::other)
+}
+
+/// `node` as JSON output shows it: with the configured path prefix (relative
+/// to `root`), directory counts and size style applied.
+pub(super) fn node_for_output<'a>(
+    node: &'a TreeNode,
+    root: &Path,
+    config: &OutputConfig,
+) -> Cow<'a, TreeNode> {
+    if config.display_root().is_none()
+        && !config.show_dir_counts
+        && config.size_style == SizeStyle::Binary
+    {
+        return Cow::Borrowed(node);
+    }
+    let mut node = node.clone();
+    if config.display_root().is_some() {
+        apply_path_prefix(&mut node, root, config);
+    }
+    if config.show_dir_counts {
+        apply_child_counts(&mut node);
+    }
+    if config.size_style != SizeStyle::Binary {
+        apply_size_style(&mut node, config.size_style);
+    }
+    Cow::Owned(node)
+}
+
+/// Serialize a single value as a JSON string literal.
+fn to_json_string<T: serde::Serialize + ?Sized>(value: &T) -> io::Result<String> {
+    serde_json::to_string(value).map_err(io::Error::other)
+}
+
+/// `path` as shown in the output: relative to `root` with the configured prefix, if any.
+fn display_path(path: &Path, root: &Path, config: &OutputConfig) -> PathBuf {
+    let relative = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned();
+    match config.prefixed_path(&relative) {
+        Some(prefixed) => PathBuf::from(prefixed),
+        None => path.to_path_buf(),
+    }
+}
+
+/// Recursively rewrite node paths relative to `root` with the configured prefix.
+fn apply_path_prefix(node: &mut TreeNode, root: &Path, config: &OutputConfig) {
+    let (TreeNode::File { path, .. } | TreeNode::Dir { path, .. }) = node;
+    *path = display_path(path, root, config);
+
+    if let TreeNode::Dir { children, .. } = node {
+        for child in children {
+            apply_path_prefix(child, root, config);
+        }
+    }
+}
+
+/// Recursively set `child_count` on every directory node the walker left
+/// uncounted. Counts from the walker are kept, since only it can count the
+/// children of directories cut off by the depth limit.
+fn apply_child_counts(node: &mut TreeNode) {
+    if let TreeNode::Dir {
+        child_count,
+        children,
+        ..
+    } = node
+    {
+        child_count.get_or_insert(children.len());
+        for child in children {
+            apply_child_counts(child);
+        }
+    }
+}
+
+/// Recursively rewrite `size_human` of files in `style`.
+fn apply_size_style(node: &mut TreeNode, style: SizeStyle) {
+    match node {
+        TreeNode::File {
+            size_bytes,
+            size_human,
+            ..
+        } => {
+            if let Some(bytes) = size_bytes {
+                *size_human = Some(format_size(*bytes, style));
+            }
+        }
+        TreeNode::Dir { children, .. } => {
+            for child in children {
+                apply_size_style(child, style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::WalkerConfig;
+
+    #[test]
+    fn test_apply_path_prefix() {
+        let mut tree = TreeNode::Dir {
+            name: "project".to_string(),
+            path: PathBuf::from("/tmp/project"),
+            child_count: None,
+            children: vec![TreeNode::File {
+                name: "main.rs".to_string(),
+                path: PathBuf::from("/tmp/project/src/main.rs"),
+                comment: None,
+                types: None,
+                todos: None,
+                imports: None,
+                size_bytes: None,
+                size_human: None,
+            }],
+        };
+        let config = OutputConfig {
+            path_prefix: Some("repo".to_string()),
+            ..OutputConfig::default()
+        };
+        apply_path_prefix(&mut tree, Path::new("/tmp/project"), &config);
+
+        let TreeNode::Dir { path, children, .. } = &tree else {
+            panic!("expected dir");
+        };
+        assert_eq!(path, &PathBuf::from("repo"));
+        let TreeNode::File { path, .. } = &children[0] else {
+            panic!("expected file");
+        };
+        assert_eq!(path, &PathBuf::from("repo/src/main.rs"));
+    }
+
+    #[test]
+    fn test_apply_absolute_root_path() {
+        let mut tree = TreeNode::Dir {
+            name: ".".to_string(),
+            path: PathBuf::from("/home/me/./project"),
+            child_count: None,
+            children: vec![TreeNode::File {
+                name: "lib.rs".to_string(),
+                path: PathBuf::from("/home/me/./project/lib.rs"),
+                comment: None,
+                types: None,
+                todos: None,
+                imports: None,
+                size_bytes: None,
+                size_human: None,
+            }],
+        };
+        let config = OutputConfig {
+            root_path: Some(PathBuf::from("/home/me/project")),
+            ..OutputConfig::default()
+        };
+        apply_path_prefix(&mut tree, Path::new("/home/me/./project"), &config);
+
+        let TreeNode::Dir { path, children, .. } = &tree else {
+            panic!("expected dir");
+        };
+        assert_eq!(path, &PathBuf::from("/home/me/project"));
+        let TreeNode::File { path, .. } = &children[0] else {
+            panic!("expected file");
+        };
+        assert_eq!(path, &PathBuf::from("/home/me/project/lib.rs"));
+    }
+
+    /// Stream `root` and check the output matches the buffered JSON.
+    fn assert_streaming_matches(root: &Path, walker_config: WalkerConfig, config: &OutputConfig) {
+        let walker = TreeWalker::new(walker_config);
+        let mut streamed = Vec::new();
+        assert!(write_json_streaming(&walker, root, config, &mut streamed).unwrap());
+
+        let tree = walker.walk(root).unwrap();
+        let buffered = format!("{}\n", node_to_json(&tree, root, config).unwrap());
+        assert_eq!(String::from_utf8(streamed).unwrap(), buffered);
+    }
+
+    /// Assert that every key of the JSON object `value` is a property of
+    /// the schema definition `definition`.
+    fn assert_described(schema: &serde_json::Value, definition: &str, value: &serde_json::Value) {
+        let properties = &schema["definitions"][definition]["properties"];
+        for key in value.as_object().unwrap().keys() {
+            assert!(
+                properties.get(key).is_some(),
+                "{} is missing from {}",
+                key,
+                definition
+            );
+        }
+    }
+
+    #[test]
+    fn test_json_schema_describes_output() {
+        let schema: serde_json::Value = serde_json::from_str(JSON_SCHEMA).unwrap();
+        let file = TreeNode::File {
+            name: "main.rs".to_string(),
+            path: PathBuf::from("main.rs"),
+            comment: Some("Entry point".to_string()),
+            types: Some(vec![crate::tree::JsonTypeSignature::Detailed {
+                signature: "fn main()".to_string(),
+                line: Some(1),
+                context: vec!["// context".to_string()],
+            }]),
+            todos: Some(vec![crate::tree::JsonTodoItem {
+                marker_type: "TODO".to_string(),
+                text: "finish".to_string(),
+                line: 2,
+                assignee: Some("alice".to_string()),
+            }]),
+            imports: Some(crate::imports::FileImports {
+                external: vec!["serde".to_string()],
+                std: vec!["io".to_string()],
+                internal: vec!["crate::tree".to_string()],
+                filtered: true,
+            }),
+            size_bytes: Some(10),
+            size_human: Some("10 B".to_string()),
+        };
+        let dir = TreeNode::Dir {
+            name: "project".to_string(),
+            path: PathBuf::from("."),
+            child_count: Some(1),
+            children: vec![file],
+        };
+
+        let json = serde_json::to_value(&dir).unwrap();
+        assert_described(&schema, "dir", &json);
+        let file = &json["children"][0];
+        assert_described(&schema, "file", file);
+        assert_described(&schema, "todo", &file["todos"][0]);
+        assert_described(&schema, "imports", &file["imports"]);
+        let detailed = &schema["definitions"]["type_signature"]["oneOf"][1]["properties"];
+        for key in file["types"][0].as_object().unwrap().keys() {
+            assert!(detailed.get(key).is_some(), "{} is missing", key);
+        }
+    }
+
+    #[test]
+    fn test_streaming_json_matches_buffered() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("main.rs"), "//! Entry \"point\"\nfn main() {}\n").unwrap();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn lib() {}\n").unwrap();
+        std::fs::write(root.join("src/nested/deep.rs"), "// Deep\n").unwrap();
+        std::fs::create_dir(root.join("empty")).unwrap();
+
+        let walker_config = || {
+            WalkerConfig::builder()
+                .extract_comments(true)
+                .extract_types(true)
+                .build()
+                .unwrap()
+        };
+        assert_streaming_matches(root, walker_config(), &OutputConfig::default());
+
+        let config = OutputConfig {
+            path_prefix: Some("repo".to_string()),
+            show_dir_counts: true,
+            ..OutputConfig::default()
+        };
+        assert_streaming_matches(root, walker_config(), &config);
+
+        let shallow = || {
+            WalkerConfig::builder()
+                .max_depth(1)
+                .count_children(true)
+                .build()
+                .unwrap()
+        };
+        assert_streaming_matches(root, shallow(), &config);
+        assert_streaming_matches(&root.join("main.rs"), walker_config(), &config);
+    }
+
+    #[test]
+    fn test_child_count_of_dir_at_depth_limit() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn lib() {}\n").unwrap();
+        std::fs::write(root.join("src/nested/deep.rs"), "// Deep\n").unwrap();
+
+        let walker = TreeWalker::new(
+            WalkerConfig::builder()
+                .max_depth(1)
+                .count_children(true)
+                .build()
+                .unwrap(),
+        );
+        let tree = walker.walk(root).unwrap();
+        let config = OutputConfig {
+            show_dir_counts: true,
+            ..OutputConfig::default()
+        };
+        let TreeNode::Dir { children, .. } = node_for_output(&tree, root, &config).into_owned()
+        else {
+            panic!("expected dir");
+        };
+        let TreeNode::Dir {
+            child_count,
+            children,
+            ..
+        } = &children[0]
+        else {
+            panic!("expected dir");
+        };
+        assert!(children.is_empty());
+        assert_eq!(*child_count, Some(2));
+    }
+}
@@ -5,17 +5,20 @@
 
 use std::io;
 
-use crate::metadata::MetadataBlock;
-use crate::tree::StreamingOutput;
+use crate::metadata::{LineStyle, MetadataBlock, MetadataLine};
+use crate::tree::{ChildCount, NodeFlags, StreamingOutput};
 
 use super::config::OutputConfig;
-use super::utils::first_line;
+use super::utils::{first_line, push_line_number};
 
 /// Markdown output formatter - outputs tree as nested markdown list.
 /// Implements the StreamingOutput trait for use with StreamingWalker.
 pub struct MarkdownFormatter {
     config: OutputConfig,
     output: String,
+    /// Names of the directories enclosing the current node (excluding root),
+    /// used to build prefixed file labels.
+    dir_stack: Vec<String>,
 }
 
 impl MarkdownFormatter {
@@ -23,6 +26,7 @@ impl MarkdownFormatter {
         Self {
             config,
             output: String::new(),
+            dir_stack: Vec::new(),
         }
     }
 
@@ -35,6 +39,28 @@ impl MarkdownFormatter {
     pub fn into_output(self) -> String {
         self.output
     }
+
+    /// Append type signatures as a collapsed `<details>` section, indented to
+    /// stay inside the current list item.
+    fn push_collapsed_types(&mut self, types: &[MetadataLine], indent: &str) {
+        let signatures = types
+            .iter()
+            .filter(|line| line.style == LineStyle::TypeSignature)
+            .count();
+        self.output.push_str(&format!(
+            "{}<details><summary>Types ({})</summary>\n\n{}```\n",
+            indent, signatures, indent
+        ));
+        for line in types {
+            self.output.push_str(indent);
+            self.output.push_str(&" ".repeat(line.indent));
+            self.output.push_str(&line.content);
+            push_line_number(&mut self.output, line.line_number);
+            self.output.push('\n');
+        }
+        self.output
+            .push_str(&format!("{}```\n\n{}</details>\n", indent, indent));
+    }
 }
 
 impl StreamingOutput for MarkdownFormatter {
@@ -47,86 +73,151 @@ impl StreamingOutput for MarkdownFormatter {
         prefix: &str,
         is_root: bool,
         size: Option<u64>,
+        child_count: Option<ChildCount>,
+        flags: NodeFlags,
     ) -> io::Result<()> {
         // Calculate indentation level from prefix length
         // Each level is 2 spaces in markdown list format
         let indent_level = if is_root { 0 } else { (prefix.len() / 4) + 1 };
         let indent = "  ".repeat(indent_level);
 
+        // Each tree level adds one 4-character segment to the prefix
+        let depth = if is_root {
+            0
+        } else {
+            prefix.chars().count() / 4 + 1
+        };
+        self.dir_stack.truncate(depth.saturating_sub(1));
+
         if is_dir {
+            if !is_root {
+                self.dir_stack.push(name.to_string());
+            }
+
             // Directories in bold
             self.output.push_str(&indent);
             self.output.push_str("- **");
             self.output.push_str(name);
-            self.output.push_str("/**\n");
+            self.output.push_str("/**");
+            if flags.ignored {
+                self.output.push_str(" [ignored]");
+            }
+            if let Some(count) = child_count {
+                self.output.push(' ');
+                self.output.push_str(&count.to_string());
+            }
+            self.output.push('\n');
         } else {
             // Files with optional metadata
             self.output.push_str(&indent);
             self.output.push_str("- `");
-            self.output.push_str(name);
+            let relative = self
+                .dir_stack
+                .iter()
+                .map(String::as_str)
+                .chain(std::iter::once(name))
+                .collect::<Vec<_>>()
+                .join("/");
+            match self.config.prefixed_path(&relative) {
+                Some(label) => self.output.push_str(&label),
+                None => self.output.push_str(name),
+            }
             self.output.push('`');
+            if flags.ignored {
+                self.output.push_str(" [ignored]");
+            }
 
             // Show file size if provided
             if let Some(bytes) = size {
                 self.output.push_str(" (");
-                self.output.push_str(&crate::tree::format_size(bytes));
+                self.output
+                    .push_str(&crate::tree::format_size(bytes, self.config.size_style));
                 self.output.push(')');
             }
 
+            // Long type lists are folded into a <details> section after the item
+            let mut metadata = metadata;
+            let collapsed_types = match (&mut metadata, self.config.collapse_types) {
+                (Some(block), Some(threshold)) if block.type_lines.len() > threshold => {
+                    Some(std::mem::take(&mut block.type_lines))
+                }
+                _ => None,
+            };
+
             // Add metadata if present
-            if let Some(ref block) = metadata {
-                if !block.is_empty() {
-                    let order = self.config.metadata.order;
-
-                    if !self.config.show_full() {
-                        // Inline mode: show first line after filename
-                        if let Some(first) = block.first_line(order) {
-                            self.output.push_str(" - ");
-                            self.output.push_str(first_line(&first.content));
-                        }
-                    } else {
-                        // Full mode: show first line inline, rest as nested content
-                        if let Some(first) = block.first_line(order) {
-                            self.output.push_str(" - ");
-                            self.output.push_str(first_line(&first.content));
-                        }
+            if let Some(ref block) = metadata
+                && !block.is_empty()
+            {
+                let order = self.config.metadata.order;
+
+                if !self.config.show_full() {
+                    // Inline mode: show first line after filename
+                    if let Some(first) = block.first_line(order) {
+                        self.output.push_str(" - ");
+                        self.output.push_str(first_line(&first.content));
+                        push_line_number(&mut self.output, first.line_number);
+                    }
+                } else {
+                    // Full mode: show first line inline, rest as nested content
+                    if let Some(first) = block.first_line(order) {
+                        self.output.push_str(" - ");
+                        self.output.push_str(first_line(&first.content));
+                        push_line_number(&mut self.output, first.line_number);
+                    }
 
-                        // If there's more than one line, show the rest as a nested block
-                        let lines = block.lines_in_order(order);
-                        if lines.len() > 1 {
-                            self.output.push('\n');
-                            let nested_indent = "  ".repeat(indent_level + 1);
-                            self.output.push_str(&nested_indent);
-                            self.output.push('\n');
-                            self.output.push_str(&nested_indent);
-                            self.output.push_str("> ");
-
-                            // Skip the first line (already shown inline) and format the rest
-                            let remaining: Vec<_> = lines
-                                .iter()
-                                .skip(1)
-                                .filter(|l| !l.content.trim().is_empty())
-                                .collect();
-
-                            for (i, line) in remaining.iter().enumerate() {
-                                if i > 0 {
-                                    self.output.push('\n');
-                                    self.output.push_str(&nested_indent);
-                                    self.output.push_str("> ");
-                                }
-                                self.output.push_str(line.content.trim());
+                    // If there's more than one line, show the rest as a nested block
+                    let lines = block.lines_in_order_capped(order, self.config.metadata.max_lines);
+                    if lines.len() > 1 {
+                        self.output.push('\n');
+                        let nested_indent = "  ".repeat(indent_level + 1);
+                        self.output.push_str(&nested_indent);
+                        self.output.push('\n');
+                        self.output.push_str(&nested_indent);
+                        self.output.push_str("> ");
+
+                        // Skip the first line (already shown inline) and format the rest
+                        let remaining: Vec<_> = lines
+                            .iter()
+                            .skip(1)
+                            .filter(|l| !l.content.trim().is_empty())
+                            .collect();
+
+                        for (i, line) in remaining.iter().enumerate() {
+                            if i > 0 {
+                                self.output.push('\n');
+                                self.output.push_str(&nested_indent);
+                                self.output.push_str("> ");
                             }
-                            self.output.push('\n');
+                            self.output.push_str(line.content.trim());
+                            push_line_number(&mut self.output, line.line_number);
                         }
+                        self.output.push('\n');
                     }
                 }
             }
             self.output.push('\n');
+
+            if let Some(types) = collapsed_types {
+                self.push_collapsed_types(&types, &"  ".repeat(indent_level + 1));
+            }
         }
         Ok(())
     }
 
+    fn wants_child_counts(&self) -> bool {
+        self.config.show_dir_counts
+    }
+
+    fn output_separator(&mut self, _depth: usize) -> io::Result<()> {
+        // The blank line keeps the rule from turning the previous item into a heading
+        self.output.push_str("\n---\n\n");
+        Ok(())
+    }
+
     fn finish(&mut self, dir_count: usize, file_count: usize) -> io::Result<()> {
+        if !self.config.show_summary {
+            return Ok(());
+        }
         self.output.push('\n');
         self.output.push_str(&format!(
             "*{} directories, {} files*\n",
@@ -145,11 +236,14 @@ pub fn print_markdown(formatter: &MarkdownFormatter) -> io::Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::metadata::{LineStyle, MetadataConfig, MetadataLine, MetadataOrder};
+    use crate::metadata::{MetadataConfig, MetadataOrder};
+    use crate::output::Theme;
+    use crate::tree::SizeStyle;
 
     fn make_config(full: bool) -> OutputConfig {
         OutputConfig {
             use_color: false,
+            ansi_only: false,
             metadata: MetadataConfig {
                 comments: true,
                 types: false,
@@ -157,8 +251,18 @@ mod tests {
                 full,
                 prefix: None,
                 order: MetadataOrder::CommentsFirst,
+                max_lines: None,
             },
             wrap_width: None,
+            path_prefix: None,
+            root_path: None,
+            show_dir_counts: false,
+            theme: Theme::default(),
+            depth_colors: false,
+            collapse_types: None,
+            show_summary: true,
+            compact: false,
+            size_style: SizeStyle::Binary,
         }
     }
 
@@ -169,7 +273,17 @@ mod tests {
 
         // Output root directory
         formatter
-            .output_node("my_project", None, true, true, "", true, None)
+            .output_node(
+                "my_project",
+                None,
+                true,
+                true,
+                "",
+                true,
+                None,
+                None,
+                NodeFlags::default(),
+            )
             .unwrap();
 
         let output = formatter.output();
@@ -187,7 +301,17 @@ mod tests {
 
         // Output a file (not root)
         formatter
-            .output_node("main.rs", None, false, true, "    ", false, None)
+            .output_node(
+                "main.rs",
+                None,
+                false,
+                true,
+                "    ",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
             .unwrap();
 
         let output = formatter.output();
@@ -198,6 +322,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_markdown_path_prefix_in_file_labels() {
+        let mut config = make_config(false);
+        config.path_prefix = Some("repo/".to_string());
+        let mut formatter = MarkdownFormatter::new(config);
+
+        formatter
+            .output_node(
+                ".",
+                None,
+                true,
+                true,
+                "",
+                true,
+                None,
+                None,
+                NodeFlags::default(),
+            )
+            .unwrap();
+        formatter
+            .output_node(
+                "README.md",
+                None,
+                false,
+                false,
+                "",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
+            .unwrap();
+        formatter
+            .output_node(
+                "src",
+                None,
+                true,
+                true,
+                "",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
+            .unwrap();
+        formatter
+            .output_node(
+                "main.rs",
+                None,
+                false,
+                true,
+                "    ",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
+            .unwrap();
+
+        let output = formatter.output();
+        assert!(output.contains("`repo/README.md`"), "{}", output);
+        assert!(output.contains("**src/**"), "{}", output);
+        assert!(output.contains("`repo/src/main.rs`"), "{}", output);
+    }
+
     #[test]
     fn test_markdown_file_with_size() {
         let config = make_config(false);
@@ -205,7 +394,17 @@ mod tests {
 
         // Output a file with size
         formatter
-            .output_node("main.rs", None, false, true, "    ", false, Some(1024))
+            .output_node(
+                "main.rs",
+                None,
+                false,
+                true,
+                "    ",
+                false,
+                Some(1024),
+                None,
+                NodeFlags::default(),
+            )
             .unwrap();
 
         let output = formatter.output();
@@ -227,7 +426,17 @@ mod tests {
         block.comment_lines = vec![MetadataLine::new("This is a module comment")];
 
         formatter
-            .output_node("lib.rs", Some(block), false, true, "    ", false, None)
+            .output_node(
+                "lib.rs",
+                Some(block),
+                false,
+                true,
+                "    ",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
             .unwrap();
 
         let output = formatter.output();
@@ -256,13 +465,43 @@ mod tests {
         // Simulate nested structure
         // Prefix represents tree prefix characters (4 chars per level: "    " or "│   ")
         formatter
-            .output_node("project", None, true, true, "", true, None)
+            .output_node(
+                "project",
+                None,
+                true,
+                true,
+                "",
+                true,
+                None,
+                None,
+                NodeFlags::default(),
+            )
             .unwrap();
         formatter
-            .output_node("src", None, true, false, "    ", false, None)
+            .output_node(
+                "src",
+                None,
+                true,
+                false,
+                "    ",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
             .unwrap();
         formatter
-            .output_node("main.rs", None, false, true, "        ", false, None)
+            .output_node(
+                "main.rs",
+                None,
+                false,
+                true,
+                "        ",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
             .unwrap();
 
         let output = formatter.output();
@@ -301,7 +540,17 @@ mod tests {
         ];
 
         formatter
-            .output_node("lib.rs", Some(block), false, true, "    ", false, None)
+            .output_node(
+                "lib.rs",
+                Some(block),
+                false,
+                true,
+                "    ",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
             .unwrap();
 
         let output = formatter.output();
@@ -343,6 +592,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_markdown_finish_without_summary() {
+        let config = OutputConfig {
+            show_summary: false,
+            compact: false,
+            size_style: SizeStyle::Binary,
+            ..make_config(false)
+        };
+        let mut formatter = MarkdownFormatter::new(config);
+
+        formatter.finish(5, 23).unwrap();
+
+        assert!(formatter.output().is_empty());
+    }
+
     #[test]
     fn test_markdown_special_filename_chars() {
         let config = make_config(false);
@@ -350,7 +614,17 @@ mod tests {
 
         // Test filename with underscores and dots (common in Rust/Python)
         formatter
-            .output_node("my_module.test.rs", None, false, true, "    ", false, None)
+            .output_node(
+                "my_module.test.rs",
+                None,
+                false,
+                true,
+                "    ",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
             .unwrap();
 
         let output = formatter.output();
@@ -365,6 +639,7 @@ mod tests {
     fn test_markdown_type_signatures() {
         let config = OutputConfig {
             use_color: false,
+            ansi_only: false,
             metadata: MetadataConfig {
                 comments: false,
                 types: true,
@@ -372,8 +647,18 @@ mod tests {
                 full: false,
                 prefix: None,
                 order: MetadataOrder::TypesFirst,
+                max_lines: None,
             },
             wrap_width: None,
+            path_prefix: None,
+            root_path: None,
+            show_dir_counts: false,
+            theme: Theme::default(),
+            depth_colors: false,
+            collapse_types: None,
+            show_summary: true,
+            compact: false,
+            size_style: SizeStyle::Binary,
         };
         let mut formatter = MarkdownFormatter::new(config);
 
@@ -384,7 +669,17 @@ mod tests {
         )];
 
         formatter
-            .output_node("main.rs", Some(block), false, true, "    ", false, None)
+            .output_node(
+                "main.rs",
+                Some(block),
+                false,
+                true,
+                "    ",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
             .unwrap();
 
         let output = formatter.output();
@@ -399,6 +694,7 @@ mod tests {
     fn test_markdown_todo_markers() {
         let config = OutputConfig {
             use_color: false,
+            ansi_only: false,
             metadata: MetadataConfig {
                 comments: false,
                 types: false,
@@ -406,8 +702,18 @@ mod tests {
                 full: false,
                 prefix: None,
                 order: MetadataOrder::CommentsFirst,
+                max_lines: None,
             },
             wrap_width: None,
+            path_prefix: None,
+            root_path: None,
+            show_dir_counts: false,
+            theme: Theme::default(),
+            depth_colors: false,
+            collapse_types: None,
+            show_summary: true,
+            compact: false,
+            size_style: SizeStyle::Binary,
         };
         let mut formatter = MarkdownFormatter::new(config);
 
@@ -415,7 +721,17 @@ mod tests {
         block.todo_lines = vec![MetadataLine::with_style("TODO: fix this", LineStyle::Todo)];
 
         formatter
-            .output_node("main.rs", Some(block), false, true, "    ", false, None)
+            .output_node(
+                "main.rs",
+                Some(block),
+                false,
+                true,
+                "    ",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
             .unwrap();
 
         let output = formatter.output();
@@ -425,4 +741,60 @@ mod tests {
             output
         );
     }
+
+    #[test]
+    fn test_markdown_collapse_types() {
+        let mut config = make_config(false);
+        config.metadata.types = true;
+        config.collapse_types = Some(2);
+        let mut formatter = MarkdownFormatter::new(config);
+
+        let block = |count: usize| {
+            let mut block = MetadataBlock::from_comments("Widgets");
+            block.type_lines = (0..count)
+                .map(|i| {
+                    MetadataLine::with_style(format!("pub fn f{}()", i), LineStyle::TypeSignature)
+                })
+                .collect();
+            block
+        };
+        formatter
+            .output_node(
+                "few.rs",
+                Some(block(2)),
+                false,
+                false,
+                "",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
+            .unwrap();
+        formatter
+            .output_node(
+                "many.rs",
+                Some(block(3)),
+                false,
+                true,
+                "",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
+            .unwrap();
+
+        let output = formatter.output();
+        assert!(output.contains("- `few.rs` - Widgets\n"), "{}", output);
+        assert!(
+            output.contains(
+                "- `many.rs` - Widgets\n    <details><summary>Types (3)</summary>\n\n    ```\n    pub fn f0()\n"
+            ),
+            "{}",
+            output
+        );
+        assert!(output.contains("    ```\n\n    </details>\n"), "{}", output);
+        assert_eq!(output.matches("<details>").count(), 1, "{}", output);
+    }
 }
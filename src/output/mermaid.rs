@@ -0,0 +1,209 @@
+//! Mermaid dependency graph output
+//!
+//! This module provides `MermaidFormatter` which collects every file in the
+//! walk along with its imports, then renders a Mermaid `graph TD` diagram
+//! where edges point from a file to the project files it imports.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+
+use crate::imports::{FileImports, extract_imports, module_key, resolve_import};
+use crate::metadata::MetadataBlock;
+use crate::tree::{ChildCount, NodeFlags, StreamingOutput};
+
+/// A file collected during the walk.
+struct MermaidFile {
+    /// Path relative to the walked root, using `/` separators
+    path: String,
+    imports: FileImports,
+}
+
+/// Mermaid output formatter - buffers files and import edges, then renders
+/// a dependency graph when the walk finishes.
+/// Implements the StreamingOutput trait for use with StreamingWalker.
+pub struct MermaidFormatter {
+    root: PathBuf,
+    all_imports: bool,
+    files: Vec<MermaidFile>,
+    /// Names of the directories enclosing the current node (excluding root)
+    dir_stack: Vec<String>,
+    output: String,
+}
+
+impl MermaidFormatter {
+    /// Create a formatter for a walk rooted at `root`.
+    ///
+    /// With `all_imports`, external and standard library imports are drawn as
+    /// separate nodes; otherwise only imports between project files appear.
+    pub fn new(root: impl Into<PathBuf>, all_imports: bool) -> Self {
+        Self {
+            root: root.into(),
+            all_imports,
+            files: Vec::new(),
+            dir_stack: Vec::new(),
+            output: String::new(),
+        }
+    }
+
+    /// Get the formatted output string (empty until the walk finishes).
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Take ownership of the output string.
+    pub fn into_output(self) -> String {
+        self.output
+    }
+
+    /// Render the buffered files and edges as a Mermaid diagram.
+    fn render(&self) -> String {
+        let mut lines = vec!["graph TD".to_string()];
+        let mut edges = Vec::new();
+        let mut external_ids: HashMap<&str, String> = HashMap::new();
+
+        let module_keys: Vec<String> = self.files.iter().map(|f| module_key(&f.path)).collect();
+
+        for (i, file) in self.files.iter().enumerate() {
+            lines.push(format!("    f{}[\"{}\"]", i, escape_label(&file.path)));
+        }
+
+        for (i, file) in self.files.iter().enumerate() {
+            for import in &file.imports.internal {
+                if let Some(target) = resolve_import(import, &file.path, &module_keys)
+                    && target != i
+                {
+                    let edge = format!("    f{} --> f{}", i, target);
+                    if !edges.contains(&edge) {
+                        edges.push(edge);
+                    }
+                }
+            }
+
+            if !self.all_imports {
+                continue;
+            }
+            for import in file.imports.external.iter().chain(&file.imports.std) {
+                let next_id = format!("x{}", external_ids.len());
+                let id = external_ids.entry(import.as_str()).or_insert_with(|| {
+                    lines.push(format!("    {}([\"{}\"])", next_id, escape_label(import)));
+                    next_id
+                });
+                let edge = format!("    f{} -.-> {}", i, id);
+                if !edges.contains(&edge) {
+                    edges.push(edge);
+                }
+            }
+        }
+
+        lines.extend(edges);
+        let mut output = lines.join("\n");
+        output.push('\n');
+        output
+    }
+}
+
+impl StreamingOutput for MermaidFormatter {
+    fn output_node(
+        &mut self,
+        name: &str,
+        _metadata: Option<MetadataBlock>,
+        is_dir: bool,
+        _is_last: bool,
+        prefix: &str,
+        is_root: bool,
+        _size: Option<u64>,
+        _child_count: Option<ChildCount>,
+        _flags: NodeFlags,
+    ) -> io::Result<()> {
+        // Each tree level adds one 4-character segment to the prefix
+        let depth = if is_root {
+            0
+        } else {
+            prefix.chars().count() / 4 + 1
+        };
+        self.dir_stack.truncate(depth.saturating_sub(1));
+
+        if is_dir {
+            if !is_root {
+                self.dir_stack.push(name.to_string());
+            }
+            return Ok(());
+        }
+
+        let mut path = self.root.clone();
+        path.extend(&self.dir_stack);
+        path.push(name);
+
+        let relative = self
+            .dir_stack
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(name))
+            .collect::<Vec<_>>()
+            .join("/");
+
+        self.files.push(MermaidFile {
+            path: relative,
+            imports: extract_imports(&path).unwrap_or_default(),
+        });
+        Ok(())
+    }
+
+    fn output_separator(&mut self, _depth: usize) -> io::Result<()> {
+        // The graph has no notion of sections
+        Ok(())
+    }
+
+    fn finish(&mut self, _dir_count: usize, _file_count: usize) -> io::Result<()> {
+        self.output = self.render();
+        Ok(())
+    }
+}
+
+/// Print mermaid output to stdout.
+pub fn print_mermaid(formatter: &MermaidFormatter) -> io::Result<()> {
+    print!("{}", formatter.output());
+    Ok(())
+}
+
+/// Escape a node label for use inside a quoted Mermaid string.
+fn escape_label(label: &str) -> String {
+    label.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_graph() {
+        let mut formatter = MermaidFormatter::new("/nonexistent", false);
+        formatter.files = vec![
+            MermaidFile {
+                path: "src/main.rs".to_string(),
+                imports: FileImports {
+                    external: vec!["serde".to_string()],
+                    std: Vec::new(),
+                    internal: vec!["output::Config".to_string(), "output::Theme".to_string()],
+                    filtered: false,
+                },
+            },
+            MermaidFile {
+                path: "src/output.rs".to_string(),
+                imports: FileImports::default(),
+            },
+        ];
+        formatter.finish(1, 2).unwrap();
+
+        assert_eq!(
+            formatter.output(),
+            "graph TD\n    f0[\"src/main.rs\"]\n    f1[\"src/output.rs\"]\n    f0 --> f1\n"
+        );
+
+        formatter.all_imports = true;
+        formatter.finish(1, 2).unwrap();
+        assert!(formatter.output().contains("    x0([\"serde\"])\n"));
+        assert!(formatter.output().contains("    f0 -.-> x0\n"));
+    }
+}
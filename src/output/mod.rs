@@ -4,34 +4,74 @@
 //! - Console output with colors (streaming or buffered)
 //! - JSON output
 //! - Markdown output
+//! - Emacs org-mode output
+//! - Mermaid and GraphViz dependency graphs
+//! - Plain file lists
+//! - Plain ASCII trees
+//! - Summary lines
+//! - Custom Tera templates (with the `templates` feature)
 //!
 //! # Module Structure
 //!
 //! - `config` - Output configuration types
+//! - `ansi` - Choosing between termcolor's terminal detection and raw ANSI codes
 //! - `utils` - Shared utility functions (text wrapping, prefix calculation)
 //! - `tree` - Buffered tree formatter for complete tree structures
 //! - `streaming` - Streaming formatter for console output
 //! - `markdown` - Markdown output formatter
+//! - `orgmode` - Emacs org-mode output formatter
+//! - `mermaid` - Mermaid dependency graph formatter
+//! - `dot` - GraphViz dependency graph formatter
+//! - `graph` - Import resolution shared by the graph formatters
+//! - `plainlist` - One-path-per-line file list formatter
+//! - `plaintext` - ASCII-only tree formatter without colors
+//! - `summary` - Totals-only formatter for `--summary-only`
+//! - `timing` - Walk timing for `FRUIT_DEBUG_TIMING`
 //! - `json` - JSON output
+//! - `template` - Tera template output
+//! - `theme` - Color themes for console output
 
+mod ansi;
 mod config;
+mod dot;
 mod json;
 mod markdown;
+mod mermaid;
+mod orgmode;
+mod plainlist;
+mod plaintext;
 mod streaming;
+mod summary;
+#[cfg(feature = "templates")]
+mod template;
+mod theme;
+mod timing;
 mod tree;
 mod utils;
 
 // Re-export public types and functions
+pub use ansi::color_choice;
 pub use config::OutputConfig;
-pub use json::print_json;
-pub use markdown::{print_markdown, MarkdownFormatter};
+pub use dot::{DotFormatter, print_dot};
+pub use json::{JSON_SCHEMA, print_json, print_json_streaming};
+pub use markdown::{MarkdownFormatter, print_markdown};
+pub use mermaid::{MermaidFormatter, print_mermaid};
+pub use orgmode::{OrgFormatter, print_org};
+pub use plainlist::PlainListFormatter;
+pub use plaintext::PlainTextFormatter;
 pub use streaming::StreamingFormatter;
+pub use summary::SummaryOnlyFormatter;
+#[cfg(feature = "templates")]
+pub use template::TemplateFormatter;
+pub use theme::Theme;
+pub use timing::OutputStats;
+pub(crate) use timing::TimedOutput;
 pub use tree::TreeFormatter;
 
 // Re-export utility functions used by tests
 pub use utils::{
     calculate_wrap_width, continuation_prefix, first_line, has_indented_children,
-    should_insert_group_separator, wrap_text,
+    should_insert_group_separator, visible_length, wrap_text,
 };
 
 #[cfg(test)]
@@ -39,7 +79,7 @@ mod tests {
     use std::path::PathBuf;
 
     use crate::metadata::MetadataConfig;
-    use crate::tree::TreeNode;
+    use crate::tree::{SizeStyle, TreeNode};
 
     use super::*;
 
@@ -61,12 +101,23 @@ mod tests {
         let root = TreeNode::Dir {
             name: ".".to_string(),
             path: PathBuf::from("."),
+            child_count: None,
             children: vec![tree],
         };
         let formatter = TreeFormatter::new(OutputConfig {
             use_color: false,
+            ansi_only: false,
             metadata: MetadataConfig::comments_only(false), // Not full mode
             wrap_width: None,
+            path_prefix: None,
+            root_path: None,
+            show_dir_counts: false,
+            theme: Theme::default(),
+            depth_colors: false,
+            collapse_types: None,
+            show_summary: true,
+            compact: false,
+            size_style: SizeStyle::Binary,
         });
         let output = formatter.format(&root);
 
@@ -91,12 +142,23 @@ mod tests {
         let root = TreeNode::Dir {
             name: ".".to_string(),
             path: PathBuf::from("."),
+            child_count: None,
             children: vec![tree],
         };
         let formatter = TreeFormatter::new(OutputConfig {
             use_color: false,
+            ansi_only: false,
             metadata: MetadataConfig::comments_only(false), // Not full mode
             wrap_width: None,
+            path_prefix: None,
+            root_path: None,
+            show_dir_counts: false,
+            theme: Theme::default(),
+            depth_colors: false,
+            collapse_types: None,
+            show_summary: true,
+            compact: false,
+            size_style: SizeStyle::Binary,
         });
         let output = formatter.format(&root);
 
@@ -124,12 +186,23 @@ mod tests {
         let root = TreeNode::Dir {
             name: ".".to_string(),
             path: PathBuf::from("."),
+            child_count: None,
             children: vec![tree],
         };
         let formatter = TreeFormatter::new(OutputConfig {
             use_color: false,
+            ansi_only: false,
             metadata: MetadataConfig::comments_only(true), // Full mode
             wrap_width: None,
+            path_prefix: None,
+            root_path: None,
+            show_dir_counts: false,
+            theme: Theme::default(),
+            depth_colors: false,
+            collapse_types: None,
+            show_summary: true,
+            compact: false,
+            size_style: SizeStyle::Binary,
         });
         let output = formatter.format(&root);
 
@@ -154,12 +227,23 @@ mod tests {
         let root = TreeNode::Dir {
             name: ".".to_string(),
             path: PathBuf::from("."),
+            child_count: None,
             children: vec![tree],
         };
         let formatter = TreeFormatter::new(OutputConfig {
             use_color: false,
+            ansi_only: false,
             metadata: MetadataConfig::comments_only(false),
             wrap_width: None,
+            path_prefix: None,
+            root_path: None,
+            show_dir_counts: false,
+            theme: Theme::default(),
+            depth_colors: false,
+            collapse_types: None,
+            show_summary: true,
+            compact: false,
+            size_style: SizeStyle::Binary,
         });
         let output = formatter.format(&root);
 
@@ -193,13 +277,24 @@ mod tests {
         let root = TreeNode::Dir {
             name: ".".to_string(),
             path: PathBuf::from("."),
+            child_count: None,
             children: vec![tree],
         };
         let config = MetadataConfig::comments_only(false).with_prefix("// ".to_string());
         let formatter = TreeFormatter::new(OutputConfig {
             use_color: false,
+            ansi_only: false,
             metadata: config,
             wrap_width: None,
+            path_prefix: None,
+            root_path: None,
+            show_dir_counts: false,
+            theme: Theme::default(),
+            depth_colors: false,
+            collapse_types: None,
+            show_summary: true,
+            compact: false,
+            size_style: SizeStyle::Binary,
         });
         let output = formatter.format(&root);
 
@@ -0,0 +1,281 @@
+//! Emacs org-mode output formatting
+//!
+//! This module provides `OrgFormatter`, which outputs the tree as an org-mode
+//! outline: directories are headlines nested by depth, and files are list
+//! items under their directory's headline.
+
+use std::io;
+
+use crate::metadata::{LineStyle, MetadataBlock, MetadataLine};
+use crate::tree::{ChildCount, NodeFlags, StreamingOutput};
+
+use super::config::OutputConfig;
+use super::utils::{first_line, push_line_number, tree_depth};
+
+/// Org-mode output formatter - outputs the tree as an outline of headlines.
+/// Implements the StreamingOutput trait for use with StreamingWalker.
+pub struct OrgFormatter {
+    config: OutputConfig,
+    output: String,
+    /// For each directory enclosing the current node (root first), the offset
+    /// in `output` where its next file item goes. In org-mode everything after
+    /// a headline belongs to it, so files are placed before the headlines of
+    /// their sibling directories.
+    file_offsets: Vec<usize>,
+}
+
+impl OrgFormatter {
+    pub fn new(config: OutputConfig) -> Self {
+        Self {
+            config,
+            output: String::new(),
+            file_offsets: Vec::new(),
+        }
+    }
+
+    /// Get the formatted output string.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Take ownership of the output string.
+    pub fn into_output(self) -> String {
+        self.output
+    }
+
+    /// Format a file's list item, with the first metadata line as its
+    /// description and, in full mode, the remaining lines nested below.
+    fn file_item(
+        &self,
+        name: &str,
+        metadata: Option<MetadataBlock>,
+        size: Option<u64>,
+        flags: NodeFlags,
+    ) -> String {
+        let mut item = format!("- ={}=", name);
+        if flags.ignored {
+            item.push_str(" [ignored]");
+        }
+        if let Some(bytes) = size {
+            item.push_str(&format!(
+                " ({})",
+                crate::tree::format_size(bytes, self.config.size_style)
+            ));
+        }
+
+        if let Some(block) = metadata.filter(|block| !block.is_empty()) {
+            let order = self.config.metadata.order;
+            if let Some(first) = block.first_line(order) {
+                item.push_str(" :: ");
+                push_org_text(&mut item, first_line(&first.content), &first.style);
+                push_line_number(&mut item, first.line_number);
+            }
+            item.push('\n');
+            if self.config.show_full() {
+                let lines = block.lines_in_order_capped(order, self.config.metadata.max_lines);
+                push_nested_lines(&mut item, lines.iter().skip(1));
+            }
+        } else {
+            item.push('\n');
+        }
+        item
+    }
+}
+
+/// Append metadata `text`, using `=verbatim=` markup for type signatures.
+fn push_org_text(output: &mut String, text: &str, style: &LineStyle) {
+    if *style == LineStyle::TypeSignature {
+        output.push('=');
+        output.push_str(text.trim());
+        output.push('=');
+    } else {
+        output.push_str(text.trim());
+    }
+}
+
+/// Append the metadata lines after a file's description: type signatures as
+/// nested `=verbatim=` items, everything else in `#+BEGIN_COMMENT` blocks.
+fn push_nested_lines<'a>(output: &mut String, lines: impl Iterator<Item = &'a MetadataLine>) {
+    let mut in_comment = false;
+    for line in lines.filter(|line| !line.content.trim().is_empty()) {
+        let is_type = line.style == LineStyle::TypeSignature;
+        if in_comment && is_type {
+            output.push_str("  #+END_COMMENT\n");
+            in_comment = false;
+        } else if !in_comment && !is_type {
+            output.push_str("  #+BEGIN_COMMENT\n");
+            in_comment = true;
+        }
+
+        if is_type {
+            output.push_str("  ");
+            output.push_str(&" ".repeat(line.indent));
+            output.push_str("- ");
+        } else {
+            output.push_str("  ");
+        }
+        push_org_text(output, &line.content, &line.style);
+        push_line_number(output, line.line_number);
+        output.push('\n');
+    }
+    if in_comment {
+        output.push_str("  #+END_COMMENT\n");
+    }
+}
+
+impl StreamingOutput for OrgFormatter {
+    fn output_node(
+        &mut self,
+        name: &str,
+        metadata: Option<MetadataBlock>,
+        is_dir: bool,
+        _is_last: bool,
+        prefix: &str,
+        is_root: bool,
+        size: Option<u64>,
+        child_count: Option<ChildCount>,
+        flags: NodeFlags,
+    ) -> io::Result<()> {
+        let depth = if is_root { 0 } else { tree_depth(prefix) + 1 };
+        // Directories at this depth or deeper are finished
+        self.file_offsets.truncate(depth);
+
+        if is_dir {
+            let mut headline = format!("{} ={}/=", "*".repeat(depth + 1), name);
+            if let Some(count) = child_count {
+                headline.push(' ');
+                headline.push_str(&count.to_string());
+            }
+            if flags.ignored {
+                headline.push_str(" :ignored:");
+            }
+            headline.push('\n');
+            self.output.push_str(&headline);
+            self.file_offsets.push(self.output.len());
+        } else {
+            let item = self.file_item(name, metadata, size, flags);
+            match self.file_offsets.last_mut() {
+                Some(offset) => {
+                    self.output.insert_str(*offset, &item);
+                    *offset += item.len();
+                }
+                None => self.output.push_str(&item),
+            }
+        }
+        Ok(())
+    }
+
+    fn wants_child_counts(&self) -> bool {
+        self.config.show_dir_counts
+    }
+
+    fn output_separator(&mut self, _depth: usize) -> io::Result<()> {
+        // Files still to come go before the next headline, so the blank line
+        // stays between the two sections
+        self.output.push('\n');
+        Ok(())
+    }
+
+    fn finish(&mut self, dir_count: usize, file_count: usize) -> io::Result<()> {
+        if !self.config.show_summary {
+            return Ok(());
+        }
+        self.output.push('\n');
+        self.output.push_str(&format!(
+            "{} directories, {} files\n",
+            dir_count, file_count
+        ));
+        Ok(())
+    }
+}
+
+/// Print org-mode output to stdout.
+pub fn print_org(formatter: &OrgFormatter) -> io::Result<()> {
+    print!("{}", formatter.output());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::MetadataConfig;
+
+    fn make_config(full: bool) -> OutputConfig {
+        OutputConfig {
+            use_color: false,
+            metadata: MetadataConfig::comments_only(full),
+            wrap_width: None,
+            ..OutputConfig::default()
+        }
+    }
+
+    fn node(formatter: &mut OrgFormatter, name: &str, is_dir: bool, prefix: &str, is_root: bool) {
+        formatter
+            .output_node(
+                name,
+                None,
+                is_dir,
+                false,
+                prefix,
+                is_root,
+                None,
+                None,
+                NodeFlags::default(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_org_files_precede_subdirectory_headlines() {
+        let mut formatter = OrgFormatter::new(make_config(false));
+        node(&mut formatter, "project", true, "", true);
+        node(&mut formatter, "Cargo.toml", false, "", false);
+        node(&mut formatter, "src", true, "", false);
+        node(&mut formatter, "lib.rs", false, "│   ", false);
+        node(&mut formatter, "README.md", false, "", false);
+        formatter.finish(1, 3).unwrap();
+
+        assert_eq!(
+            formatter.output(),
+            "* =project/=\n- =Cargo.toml=\n- =README.md=\n** =src/=\n- =lib.rs=\n\n1 directories, 3 files\n"
+        );
+    }
+
+    #[test]
+    fn test_org_metadata_description_and_blocks() {
+        let formatter = OrgFormatter::new(make_config(true));
+        let mut block = MetadataBlock::from_comments("Entry point\nParses arguments");
+        block.type_lines = vec![MetadataLine::with_style(
+            "pub fn main()",
+            LineStyle::TypeSignature,
+        )];
+
+        let item = formatter.file_item("main.rs", Some(block), None, NodeFlags::default());
+        assert_eq!(
+            item,
+            "- =main.rs= :: Entry point\n  #+BEGIN_COMMENT\n  Parses arguments\n  #+END_COMMENT\n  - =pub fn main()=\n"
+        );
+    }
+
+    #[test]
+    fn test_org_ignored_directory_tag() {
+        let mut formatter = OrgFormatter::new(make_config(false));
+        formatter
+            .output_node(
+                "target",
+                None,
+                true,
+                true,
+                "",
+                false,
+                None,
+                None,
+                NodeFlags {
+                    ignored: true,
+                    ..NodeFlags::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(formatter.output(), "** =target/= :ignored:\n");
+    }
+}
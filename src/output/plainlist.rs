@@ -0,0 +1,143 @@
+//! Plain file list output
+//!
+//! This module provides `PlainListFormatter` which prints the path of each
+//! file on its own line, with no tree drawing, metadata or summary. Useful
+//! for piping a gitignore-aware file list into other tools.
+
+use std::io::{self, Stdout, Write};
+
+use crate::metadata::MetadataBlock;
+use crate::tree::{ChildCount, NodeFlags, StreamingOutput};
+
+use super::config::OutputConfig;
+
+/// Plain list formatter - writes one file path per line as files are walked.
+/// Implements the StreamingOutput trait for use with StreamingWalker.
+pub struct PlainListFormatter<W: Write = Stdout> {
+    config: OutputConfig,
+    writer: W,
+    /// Names of the directories enclosing the current node (excluding root)
+    dir_stack: Vec<String>,
+}
+
+impl PlainListFormatter {
+    /// Create a formatter that writes to stdout.
+    pub fn new(config: OutputConfig) -> Self {
+        Self::with_writer(config, io::stdout())
+    }
+}
+
+impl<W: Write> PlainListFormatter<W> {
+    /// Create a formatter that writes to `writer`.
+    pub fn with_writer(config: OutputConfig, writer: W) -> Self {
+        Self {
+            config,
+            writer,
+            dir_stack: Vec::new(),
+        }
+    }
+
+    /// Take ownership of the writer.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> StreamingOutput for PlainListFormatter<W> {
+    fn output_node(
+        &mut self,
+        name: &str,
+        _metadata: Option<MetadataBlock>,
+        is_dir: bool,
+        _is_last: bool,
+        prefix: &str,
+        is_root: bool,
+        _size: Option<u64>,
+        _child_count: Option<ChildCount>,
+        _flags: NodeFlags,
+    ) -> io::Result<()> {
+        // Each tree level adds one 4-character segment to the prefix
+        let depth = if is_root {
+            0
+        } else {
+            prefix.chars().count() / 4 + 1
+        };
+        self.dir_stack.truncate(depth.saturating_sub(1));
+
+        if is_dir {
+            if !is_root {
+                self.dir_stack.push(name.to_string());
+            }
+            return Ok(());
+        }
+
+        let relative = self
+            .dir_stack
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(name))
+            .collect::<Vec<_>>()
+            .join("/");
+        match self.config.prefixed_path(&relative) {
+            Some(path) => writeln!(self.writer, "{}", path),
+            None => writeln!(self.writer, "{}", relative),
+        }
+    }
+
+    fn finish(&mut self, _dir_count: usize, _file_count: usize) -> io::Result<()> {
+        // No summary line, so the output stays a clean list of paths
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(config: OutputConfig) -> String {
+        let mut formatter = PlainListFormatter::with_writer(config, Vec::new());
+        let nodes = [
+            ("root", true, "", true),
+            ("Cargo.toml", false, "", false),
+            ("src", true, "", false),
+            ("main.rs", false, "│   ", false),
+            ("util", true, "│   ", false),
+            ("mod.rs", false, "│   │   ", false),
+            ("README.md", false, "", false),
+        ];
+        for (name, is_dir, prefix, is_root) in nodes {
+            formatter
+                .output_node(
+                    name,
+                    None,
+                    is_dir,
+                    false,
+                    prefix,
+                    is_root,
+                    None,
+                    None,
+                    NodeFlags::default(),
+                )
+                .unwrap();
+        }
+        formatter.finish(2, 4).unwrap();
+        String::from_utf8(formatter.into_writer()).unwrap()
+    }
+
+    #[test]
+    fn test_plain_list_relative_paths() {
+        assert_eq!(
+            list(OutputConfig::default()),
+            "Cargo.toml\nsrc/main.rs\nsrc/util/mod.rs\nREADME.md\n"
+        );
+    }
+
+    #[test]
+    fn test_plain_list_path_prefix() {
+        let config = OutputConfig {
+            path_prefix: Some("repo".to_string()),
+            ..OutputConfig::default()
+        };
+        assert!(list(config).starts_with("repo/Cargo.toml\nrepo/src/main.rs\n"));
+    }
+}
@@ -0,0 +1,330 @@
+//! Plain ASCII tree output
+//!
+//! This module provides `PlainTextFormatter` which draws the tree with ASCII
+//! connectors (`|-- `, `` `-- ``, `|   `) and never writes ANSI escape codes.
+//! Unlike `--color never`, which keeps the Unicode box-drawing characters,
+//! the output is safe for terminals and tools that only handle ASCII.
+
+use std::io::{self, Stdout, Write};
+
+use crate::metadata::MetadataBlock;
+use crate::tree::{ChildCount, NodeFlags, StreamingOutput};
+
+use super::config::OutputConfig;
+use super::utils::{
+    MetadataRenderResult, RenderedLine, calculate_wrap_width, continuation_prefix,
+    push_line_number, render_metadata_block, tree_depth,
+};
+
+/// Plain text formatter - draws the tree with ASCII characters only.
+/// Implements the StreamingOutput trait for use with StreamingWalker.
+pub struct PlainTextFormatter<W: Write = Stdout> {
+    config: OutputConfig,
+    writer: W,
+    /// Size column layout per tree depth: (widest file name, widest size)
+    size_columns: Vec<(usize, usize)>,
+    /// Token estimate for the next file, set by `file_tokens`
+    pending_tokens: Option<usize>,
+    /// Sum of the token estimates shown, if any were
+    total_tokens: Option<usize>,
+    /// Token budget that ended the walk early, if any
+    exceeded_budget: Option<usize>,
+}
+
+/// Replace the box-drawing characters the walker puts in prefixes.
+fn ascii_prefix(prefix: &str) -> String {
+    prefix.replace('│', "|")
+}
+
+impl PlainTextFormatter {
+    /// Create a formatter that writes to stdout.
+    pub fn new(config: OutputConfig) -> Self {
+        Self::with_writer(config, io::stdout())
+    }
+}
+
+impl<W: Write> PlainTextFormatter<W> {
+    /// Create a formatter that writes to `writer`.
+    pub fn with_writer(config: OutputConfig, writer: W) -> Self {
+        Self {
+            config,
+            writer,
+            size_columns: Vec::new(),
+            pending_tokens: None,
+            total_tokens: None,
+            exceeded_budget: None,
+        }
+    }
+
+    /// Take ownership of the writer.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    /// Write a rendered metadata line, without a trailing newline.
+    fn write_content(&mut self, line: &RenderedLine, meta_prefix: &str) -> io::Result<()> {
+        if let RenderedLine::Content {
+            text,
+            indent,
+            line_number,
+            ..
+        } = line
+        {
+            let mut output = format!("{}{:indent$}{}", meta_prefix, "", text, indent = indent);
+            push_line_number(&mut output, *line_number);
+            write!(self.writer, "{}", output)?;
+        }
+        Ok(())
+    }
+
+    /// Write a metadata line below the file name.
+    fn write_block_line(
+        &mut self,
+        line: &RenderedLine,
+        cont_prefix: &str,
+        meta_prefix: &str,
+    ) -> io::Result<()> {
+        match line {
+            RenderedLine::Separator => writeln!(self.writer, "{}", cont_prefix.trim_end()),
+            RenderedLine::Content { .. } => {
+                write!(self.writer, "{}", cont_prefix)?;
+                self.write_content(line, meta_prefix)?;
+                writeln!(self.writer)
+            }
+        }
+    }
+
+    /// Finish the file name line with its metadata, inline or as a block.
+    fn write_metadata_block(
+        &mut self,
+        block: &MetadataBlock,
+        prefix: &str,
+        is_last: bool,
+    ) -> io::Result<()> {
+        let meta_prefix = self.config.metadata.prefix_str().to_string();
+        let cont_prefix = ascii_prefix(&continuation_prefix(prefix, is_last));
+        let wrap_width = calculate_wrap_width(
+            self.config.wrap_width,
+            cont_prefix.chars().count(),
+            meta_prefix.chars().count(),
+        );
+
+        match render_metadata_block(
+            block,
+            self.config.metadata.order,
+            self.config.metadata.max_lines,
+            self.config.show_full(),
+            wrap_width,
+        ) {
+            MetadataRenderResult::Empty => writeln!(self.writer),
+            MetadataRenderResult::Inline { first } => {
+                write!(self.writer, "  ")?;
+                self.write_content(&first, &meta_prefix)?;
+                writeln!(self.writer)
+            }
+            MetadataRenderResult::InlineWithBlock { first, block_lines } => {
+                write!(self.writer, "  ")?;
+                self.write_content(&first, &meta_prefix)?;
+                writeln!(self.writer)?;
+                for line in &block_lines {
+                    self.write_block_line(line, &cont_prefix, &meta_prefix)?;
+                }
+                Ok(())
+            }
+            MetadataRenderResult::Block { lines } => {
+                writeln!(self.writer)?;
+                for line in &lines {
+                    self.write_block_line(line, &cont_prefix, &meta_prefix)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<W: Write> StreamingOutput for PlainTextFormatter<W> {
+    fn output_node(
+        &mut self,
+        name: &str,
+        metadata: Option<MetadataBlock>,
+        is_dir: bool,
+        is_last: bool,
+        prefix: &str,
+        is_root: bool,
+        size: Option<u64>,
+        child_count: Option<ChildCount>,
+        flags: NodeFlags,
+    ) -> io::Result<()> {
+        let connector = if is_last { "`-- " } else { "|-- " };
+        let ignored = if flags.ignored { " [ignored]" } else { "" };
+
+        if is_dir {
+            if is_root {
+                // A path prefix stands in for the walked root
+                let label = self
+                    .config
+                    .prefixed_path("")
+                    .unwrap_or_else(|| name.to_string());
+                write!(self.writer, "{}", label)?;
+            } else {
+                write!(
+                    self.writer,
+                    "{}{}{}{}",
+                    ascii_prefix(prefix),
+                    connector,
+                    name,
+                    ignored
+                )?;
+            }
+            if let Some(count) = child_count {
+                write!(self.writer, " {}", count)?;
+            }
+            return writeln!(self.writer);
+        }
+
+        write!(self.writer, "{}{}{}", ascii_prefix(prefix), connector, name)?;
+
+        // Show file size if provided, aligned with sibling sizes
+        if let Some(bytes) = size {
+            let (name_width, size_width) = self
+                .size_columns
+                .get(tree_depth(prefix))
+                .copied()
+                .unwrap_or_default();
+            let padding = name_width.saturating_sub(name.chars().count());
+            write!(
+                self.writer,
+                "  {}[{:>width$}]",
+                " ".repeat(padding),
+                crate::tree::format_size(bytes, self.config.size_style),
+                width = size_width
+            )?;
+        }
+        if let Some(tokens) = self.pending_tokens.take() {
+            write!(self.writer, "  ~{} tokens", tokens)?;
+        }
+        write!(self.writer, "{}", ignored)?;
+
+        match metadata {
+            Some(block) => self.write_metadata_block(&block, prefix, is_last),
+            None => writeln!(self.writer),
+        }
+    }
+
+    fn wants_child_counts(&self) -> bool {
+        self.config.show_dir_counts
+    }
+
+    fn begin_children(&mut self, prefix: &str, files: &[(String, Option<u64>)]) -> io::Result<()> {
+        let sized = files
+            .iter()
+            .filter_map(|(name, size)| Some((name, (*size)?)));
+        let layout = sized.fold((0, 0), |(name_width, size_width), (name, bytes)| {
+            (
+                name_width.max(name.chars().count()),
+                size_width.max(crate::tree::format_size(bytes, self.config.size_style).len()),
+            )
+        });
+
+        let depth = tree_depth(prefix);
+        self.size_columns.resize(depth, (0, 0));
+        self.size_columns.push(layout);
+        Ok(())
+    }
+
+    fn output_separator(&mut self, _depth: usize) -> io::Result<()> {
+        writeln!(self.writer)
+    }
+
+    fn file_tokens(&mut self, tokens: usize) -> io::Result<()> {
+        self.pending_tokens = Some(tokens);
+        *self.total_tokens.get_or_insert(0) += tokens;
+        Ok(())
+    }
+
+    fn token_budget_exceeded(&mut self, max_tokens: usize) -> io::Result<()> {
+        self.exceeded_budget = Some(max_tokens);
+        Ok(())
+    }
+
+    fn finish(&mut self, dir_count: usize, file_count: usize) -> io::Result<()> {
+        if self.exceeded_budget.is_some() || self.config.show_summary {
+            writeln!(self.writer)?;
+        }
+        if let Some(max_tokens) = self.exceeded_budget {
+            writeln!(
+                self.writer,
+                "(truncated: remaining files would exceed the budget of {} tokens)",
+                max_tokens
+            )?;
+        }
+        if self.config.show_summary {
+            write!(
+                self.writer,
+                "{} directories, {} files",
+                dir_count, file_count
+            )?;
+            if let Some(tokens) = self.total_tokens {
+                write!(self.writer, ", ~{} tokens", tokens)?;
+            }
+            writeln!(self.writer)?;
+        }
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::MetadataLine;
+
+    fn render(config: OutputConfig) -> String {
+        let mut formatter = PlainTextFormatter::with_writer(config, Vec::new());
+        let comment = MetadataBlock {
+            comment_lines: vec![MetadataLine::new("Entry point")],
+            ..Default::default()
+        };
+        let nodes = [
+            ("root", None, true, false, "", true),
+            ("src", None, true, false, "", false),
+            ("main.rs", Some(comment), false, true, "│   ", false),
+            ("README.md", None, false, true, "", false),
+        ];
+        for (name, metadata, is_dir, is_last, prefix, is_root) in nodes {
+            formatter
+                .output_node(
+                    name,
+                    metadata,
+                    is_dir,
+                    is_last,
+                    prefix,
+                    is_root,
+                    None,
+                    None,
+                    NodeFlags::default(),
+                )
+                .unwrap();
+        }
+        formatter.finish(1, 2).unwrap();
+        String::from_utf8(formatter.into_writer()).unwrap()
+    }
+
+    #[test]
+    fn test_plaintext_uses_ascii_connectors() {
+        let output = render(OutputConfig::default());
+        assert_eq!(
+            output,
+            "root\n|-- src\n|   `-- main.rs  Entry point\n`-- README.md\n\n1 directories, 2 files\n"
+        );
+        assert!(output.is_ascii());
+    }
+
+    #[test]
+    fn test_plaintext_without_summary() {
+        let config = OutputConfig {
+            show_summary: false,
+            ..OutputConfig::default()
+        };
+        assert!(render(config).ends_with("`-- README.md\n"));
+    }
+}
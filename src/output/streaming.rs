@@ -4,15 +4,18 @@
 //! directly to stdout without buffering, for use with `StreamingWalker`.
 
 use std::io::{self, Write};
-use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+use std::path::Path;
+use termcolor::{ColorSpec, StandardStream, WriteColor};
 
+use crate::file_utils::has_binary_extension;
 use crate::metadata::MetadataBlock;
-use crate::tree::StreamingOutput;
+use crate::tree::{ChildCount, NodeFlags, StreamingOutput};
 
 use super::config::OutputConfig;
 use super::utils::{
-    calculate_wrap_width, continuation_prefix, render_metadata_block, write_metadata_line_with_symbol,
-    MetadataRenderResult, RenderedLine,
+    calculate_wrap_width, continuation_prefix, render_metadata_block, tree_depth,
+    write_line_number, write_metadata_line_with_symbol, write_with_link, MetadataRenderResult,
+    RenderedLine,
 };
 
 /// Streaming output formatter - outputs directly to stdout without buffering.
@@ -20,21 +23,105 @@ use super::utils::{
 pub struct StreamingFormatter {
     config: OutputConfig,
     stdout: StandardStream,
+    /// Size column layout per tree depth: (widest file name, widest size)
+    size_columns: Vec<(usize, usize)>,
+    /// Token estimate for the next file, set by `file_tokens`
+    pending_tokens: Option<usize>,
+    /// Sum of the token estimates shown, if any were
+    total_tokens: Option<usize>,
+    /// Token budget that ended the walk early, if any
+    exceeded_budget: Option<usize>,
+    /// Sibling files waiting to be packed onto shared lines in compact mode
+    compact_group: Option<CompactGroup>,
+}
+
+/// A run of sibling files without metadata, buffered by compact mode.
+struct CompactGroup {
+    prefix: String,
+    names: Vec<String>,
+    /// Whether the last buffered file is the last entry of its directory
+    is_last: bool,
+}
+
+/// Separator between file names that share a line in compact mode.
+const COMPACT_SEPARATOR: &str = " · ";
+
+/// Split `names` into runs that fit within `width` columns when joined by
+/// the compact separator. A name wider than `width` gets a line of its own.
+fn pack_names(names: &[String], width: usize) -> Vec<&[String]> {
+    let separator_width = COMPACT_SEPARATOR.chars().count();
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut line_width = 0;
+    for (i, name) in names.iter().enumerate() {
+        let name_width = name.chars().count();
+        if i > start && line_width + separator_width + name_width > width {
+            lines.push(&names[start..i]);
+            start = i;
+            line_width = name_width;
+        } else if i == start {
+            line_width = name_width;
+        } else {
+            line_width += separator_width + name_width;
+        }
+    }
+    if start < names.len() {
+        lines.push(&names[start..]);
+    }
+    lines
 }
 
 impl StreamingFormatter {
     pub fn new(config: OutputConfig) -> Self {
-        let choice = if config.use_color {
-            ColorChoice::Auto
-        } else {
-            ColorChoice::Never
-        };
         Self {
+            stdout: StandardStream::stdout(config.color_choice()),
             config,
-            stdout: StandardStream::stdout(choice),
+            size_columns: Vec::new(),
+            pending_tokens: None,
+            total_tokens: None,
+            exceeded_budget: None,
+            compact_group: None,
         }
     }
 
+    /// Write a file name in the file color, dimmed if `dimmed` or for binary files.
+    fn write_file_name(&mut self, name: &str, dimmed: bool) -> io::Result<()> {
+        self.stdout.set_color(
+            ColorSpec::new()
+                .set_fg(Some(self.config.theme.file_color))
+                .set_dimmed(dimmed || has_binary_extension(Path::new(name))),
+        )?;
+        write!(self.stdout, "{}", name)?;
+        self.stdout.reset()
+    }
+
+    /// Print the buffered compact group, packing as many names onto each
+    /// line as fit the line width.
+    fn flush_compact_group(&mut self) -> io::Result<()> {
+        let Some(group) = self.compact_group.take() else {
+            return Ok(());
+        };
+        let prefix_width = group.prefix.chars().count() + "├── ".chars().count();
+        let width = self.config.line_width().saturating_sub(prefix_width);
+        let lines = pack_names(&group.names, width);
+        for (i, names) in lines.iter().enumerate() {
+            let connector = if group.is_last && i + 1 == lines.len() {
+                "└── "
+            } else {
+                "├── "
+            };
+            write!(self.stdout, "{}{}", group.prefix, connector)?;
+            for (j, name) in names.iter().enumerate() {
+                if j > 0 {
+                    write!(self.stdout, "{}", COMPACT_SEPARATOR)?;
+                }
+                self.write_file_name(name, false)?;
+            }
+            writeln!(self.stdout)?;
+        }
+        Ok(())
+    }
+
     /// Write a rendered line with colors.
     fn write_rendered_line(
         &mut self,
@@ -47,17 +134,28 @@ impl StreamingFormatter {
                 self.stdout.reset()?;
                 writeln!(self.stdout, "{}", cont_prefix)?;
             }
-            RenderedLine::Content { text, symbol_name, style, indent } => {
+            RenderedLine::Content {
+                text,
+                symbol_name,
+                style,
+                indent,
+                line_number,
+                link,
+            } => {
                 self.stdout.reset()?;
                 write!(self.stdout, "{}{}", cont_prefix, meta_prefix)?;
-                write_metadata_line_with_symbol(
-                    &mut self.stdout,
-                    text,
-                    symbol_name.as_deref(),
-                    style.color(),
-                    style.is_intense(),
-                    *indent,
-                )?;
+                write_with_link(&mut self.stdout, link.as_deref(), |stdout| {
+                    write_metadata_line_with_symbol(
+                        stdout,
+                        text,
+                        symbol_name.as_deref(),
+                        self.config.theme.line_color(style),
+                        style.is_intense(),
+                        self.config.theme.symbol_color_for(style),
+                        *indent,
+                    )
+                })?;
+                write_line_number(&mut self.stdout, *line_number)?;
                 writeln!(self.stdout)?;
             }
         }
@@ -66,22 +164,44 @@ impl StreamingFormatter {
 
     /// Write inline content (first line on same line as filename).
     fn write_inline_content(&mut self, line: &RenderedLine, meta_prefix: &str) -> io::Result<()> {
-        if let RenderedLine::Content { text, symbol_name, style, indent } = line {
+        if let RenderedLine::Content {
+            text,
+            symbol_name,
+            style,
+            indent,
+            line_number,
+            link,
+        } = line
+        {
             write!(self.stdout, "  {}", meta_prefix)?;
-            write_metadata_line_with_symbol(
-                &mut self.stdout,
-                text,
-                symbol_name.as_deref(),
-                style.color(),
-                style.is_intense(),
-                *indent,
-            )?;
+            write_with_link(&mut self.stdout, link.as_deref(), |stdout| {
+                write_metadata_line_with_symbol(
+                    stdout,
+                    text,
+                    symbol_name.as_deref(),
+                    self.config.theme.line_color(style),
+                    style.is_intense(),
+                    self.config.theme.symbol_color_for(style),
+                    *indent,
+                )
+            })?;
+            write_line_number(&mut self.stdout, *line_number)?;
             writeln!(self.stdout)?;
             self.stdout.reset()?;
         }
         Ok(())
     }
 
+    /// Print the dimmed ` [ignored]` marker of entries ignored by .gitignore.
+    fn write_ignored_marker(&mut self, flags: NodeFlags) -> io::Result<()> {
+        if flags.ignored {
+            self.stdout.set_color(ColorSpec::new().set_dimmed(true))?;
+            write!(self.stdout, " [ignored]")?;
+            self.stdout.reset()?;
+        }
+        Ok(())
+    }
+
     /// Print a metadata block with colors to stdout.
     fn print_metadata_block(
         &mut self,
@@ -100,7 +220,13 @@ impl StreamingFormatter {
             meta_prefix.chars().count(),
         );
 
-        let result = render_metadata_block(block, order, show_full, wrap_width);
+        let result = render_metadata_block(
+            block,
+            order,
+            self.config.metadata.max_lines,
+            show_full,
+            wrap_width,
+        );
 
         match result {
             MetadataRenderResult::Empty => {
@@ -138,38 +264,99 @@ impl StreamingOutput for StreamingFormatter {
         prefix: &str,
         is_root: bool,
         size: Option<u64>,
+        child_count: Option<ChildCount>,
+        flags: NodeFlags,
     ) -> io::Result<()> {
+        let compactable = self.config.compact
+            && !is_dir
+            && size.is_none()
+            && self.pending_tokens.is_none()
+            && !flags.ignored
+            && !flags.generated
+            && metadata.as_ref().is_none_or(MetadataBlock::is_empty);
+        if compactable {
+            if self
+                .compact_group
+                .as_ref()
+                .is_some_and(|group| group.prefix != prefix)
+            {
+                self.flush_compact_group()?;
+            }
+            let group = self.compact_group.get_or_insert_with(|| CompactGroup {
+                prefix: prefix.to_string(),
+                names: Vec::new(),
+                is_last,
+            });
+            group.names.push(name.to_string());
+            group.is_last = is_last;
+            if is_last {
+                self.flush_compact_group()?;
+            }
+            return Ok(());
+        }
+        self.flush_compact_group()?;
+
         let connector = if is_last { "└── " } else { "├── " };
 
         if is_dir {
+            let depth = if is_root { 0 } else { tree_depth(prefix) + 1 };
+            let mut dir_spec = ColorSpec::new();
+            dir_spec
+                .set_fg(Some(self.config.dir_color(depth)))
+                .set_bold(true);
+
             if is_root {
-                self.stdout
-                    .set_color(ColorSpec::new().set_fg(Some(Color::Blue)).set_bold(true))?;
-                writeln!(self.stdout, "{}", name)?;
+                // A path prefix stands in for the walked root
+                let label = self
+                    .config
+                    .prefixed_path("")
+                    .unwrap_or_else(|| name.to_string());
+                self.stdout.set_color(&dir_spec)?;
+                write!(self.stdout, "{}", label)?;
                 self.stdout.reset()?;
             } else {
                 write!(self.stdout, "{}{}", prefix, connector)?;
-                self.stdout
-                    .set_color(ColorSpec::new().set_fg(Some(Color::Blue)).set_bold(true))?;
-                writeln!(self.stdout, "{}", name)?;
+                self.stdout.set_color(dir_spec.set_dimmed(flags.ignored))?;
+                write!(self.stdout, "{}", name)?;
                 self.stdout.reset()?;
+                self.write_ignored_marker(flags)?;
+            }
+            if let Some(count) = child_count {
+                write!(self.stdout, " {}", count)?;
             }
+            writeln!(self.stdout)?;
         } else {
             // File
             write!(self.stdout, "{}{}", prefix, connector)?;
-            self.stdout
-                .set_color(ColorSpec::new().set_fg(Some(Color::White)))?;
-            write!(self.stdout, "{}", name)?;
-            self.stdout.reset()?;
+            self.write_file_name(name, flags.ignored || flags.generated)?;
 
-            // Show file size if provided
+            // Show file size if provided, aligned with sibling sizes
             if let Some(bytes) = size {
-                write!(self.stdout, "  ")?;
+                let (name_width, size_width) = self
+                    .size_columns
+                    .get(tree_depth(prefix))
+                    .copied()
+                    .unwrap_or_default();
+                let padding = name_width.saturating_sub(name.chars().count());
+                write!(self.stdout, "  {}", " ".repeat(padding))?;
+                self.stdout
+                    .set_color(ColorSpec::new().set_fg(Some(self.config.theme.size_color)))?;
+                write!(
+                    self.stdout,
+                    "[{:>width$}]",
+                    crate::tree::format_size(bytes, self.config.size_style),
+                    width = size_width
+                )?;
+                self.stdout.reset()?;
+            }
+
+            if let Some(tokens) = self.pending_tokens.take() {
                 self.stdout
-                    .set_color(ColorSpec::new().set_fg(Some(Color::Green)))?;
-                write!(self.stdout, "[{}]", crate::tree::format_size(bytes))?;
+                    .set_color(ColorSpec::new().set_fg(Some(self.config.theme.size_color)))?;
+                write!(self.stdout, "  ~{} tokens", tokens)?;
                 self.stdout.reset()?;
             }
+            self.write_ignored_marker(flags)?;
 
             if let Some(block) = metadata {
                 self.print_metadata_block(&block, prefix, is_last)?;
@@ -180,13 +367,68 @@ impl StreamingOutput for StreamingFormatter {
         Ok(())
     }
 
+    fn wants_child_counts(&self) -> bool {
+        self.config.show_dir_counts
+    }
+
+    fn begin_children(&mut self, prefix: &str, files: &[(String, Option<u64>)]) -> io::Result<()> {
+        let sized = files
+            .iter()
+            .filter_map(|(name, size)| Some((name, (*size)?)));
+        let layout = sized.fold((0, 0), |(name_width, size_width), (name, bytes)| {
+            (
+                name_width.max(name.chars().count()),
+                size_width.max(crate::tree::format_size(bytes, self.config.size_style).len()),
+            )
+        });
+
+        let depth = tree_depth(prefix);
+        self.size_columns.resize(depth, (0, 0));
+        self.size_columns.push(layout);
+        Ok(())
+    }
+
+    fn output_separator(&mut self, _depth: usize) -> io::Result<()> {
+        self.flush_compact_group()?;
+        writeln!(self.stdout)
+    }
+
+    fn file_tokens(&mut self, tokens: usize) -> io::Result<()> {
+        self.pending_tokens = Some(tokens);
+        *self.total_tokens.get_or_insert(0) += tokens;
+        Ok(())
+    }
+
+    fn token_budget_exceeded(&mut self, max_tokens: usize) -> io::Result<()> {
+        self.exceeded_budget = Some(max_tokens);
+        Ok(())
+    }
+
     fn finish(&mut self, dir_count: usize, file_count: usize) -> io::Result<()> {
+        self.flush_compact_group()?;
+        if self.exceeded_budget.is_none() && !self.config.show_summary {
+            return Ok(());
+        }
         writeln!(self.stdout)?;
-        writeln!(
+        if let Some(max_tokens) = self.exceeded_budget {
+            writeln!(
+                self.stdout,
+                "(truncated: remaining files would exceed the budget of {} tokens)",
+                max_tokens
+            )?;
+        }
+        if !self.config.show_summary {
+            return Ok(());
+        }
+        write!(
             self.stdout,
             "{} directories, {} files",
             dir_count, file_count
         )?;
+        if let Some(tokens) = self.total_tokens {
+            write!(self.stdout, ", ~{} tokens", tokens)?;
+        }
+        writeln!(self.stdout)?;
         Ok(())
     }
 }
@@ -0,0 +1,99 @@
+//! Summary line output
+//!
+//! This module provides `SummaryOnlyFormatter` which prints nothing while the
+//! tree is walked and only the final `N directories, M files` line at the end.
+
+use std::io::{self, Stdout, Write};
+
+use crate::metadata::MetadataBlock;
+use crate::tree::{ChildCount, NodeFlags, StreamingOutput};
+
+/// Summary formatter - discards every node and prints just the totals.
+/// Implements the StreamingOutput trait for use with StreamingWalker.
+pub struct SummaryOnlyFormatter<W: Write = Stdout> {
+    writer: W,
+}
+
+impl SummaryOnlyFormatter {
+    /// Create a formatter that writes to stdout.
+    pub fn new() -> Self {
+        Self::with_writer(io::stdout())
+    }
+}
+
+impl Default for SummaryOnlyFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Write> SummaryOnlyFormatter<W> {
+    /// Create a formatter that writes to `writer`.
+    pub fn with_writer(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Take ownership of the writer.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> StreamingOutput for SummaryOnlyFormatter<W> {
+    fn output_node(
+        &mut self,
+        _name: &str,
+        _metadata: Option<MetadataBlock>,
+        _is_dir: bool,
+        _is_last: bool,
+        _prefix: &str,
+        _is_root: bool,
+        _size: Option<u64>,
+        _child_count: Option<ChildCount>,
+        _flags: NodeFlags,
+    ) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn output_separator(&mut self, _depth: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn finish(&mut self, dir_count: usize, file_count: usize) -> io::Result<()> {
+        writeln!(
+            self.writer,
+            "{} directories, {} files",
+            dir_count, file_count
+        )?;
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_only_prints_just_the_totals() {
+        let mut formatter = SummaryOnlyFormatter::with_writer(Vec::new());
+        formatter
+            .output_node(
+                "main.rs",
+                None,
+                false,
+                true,
+                "",
+                false,
+                None,
+                None,
+                NodeFlags::default(),
+            )
+            .unwrap();
+        formatter.output_separator(0).unwrap();
+        formatter.finish(3, 7).unwrap();
+        assert_eq!(
+            String::from_utf8(formatter.into_writer()).unwrap(),
+            "3 directories, 7 files\n"
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! Custom output templates for `--template` (requires the `templates` feature)
+//!
+//! A template is a [Tera](https://keats.github.io/tera/) file rendered with a
+//! single variable, `tree`: the tree as `--json` prints it. Directories have
+//! `name`, `path` and `children`; files have `name`, `path` and, when
+//! extracted, `comment`, `types`, `todos`, `imports` and `size_human`.
+//!
+//! ```text
+//! # {{ tree.name }}
+//! {% for child in tree.children %}- {{ child.name }}{% if child.comment %}: {{ child.comment }}{% endif %}
+//! {% endfor %}
+//! ```
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use tera::{Context, Tera};
+
+use crate::tree::TreeNode;
+
+use super::config::OutputConfig;
+use super::json::node_for_output;
+
+/// Renders the tree through a user-supplied Tera template.
+pub struct TemplateFormatter {
+    tera: Tera,
+    name: String,
+    config: OutputConfig,
+}
+
+impl TemplateFormatter {
+    /// Parse `template`, reporting errors against `name`.
+    pub fn new(name: &str, template: &str, config: OutputConfig) -> Result<Self, String> {
+        let mut tera = Tera::default();
+        tera.add_raw_template(name, template)
+            .map_err(|e| error_chain(&e))?;
+        Ok(Self {
+            tera,
+            name: name.to_string(),
+            config,
+        })
+    }
+
+    /// Read and parse the template file at `path`.
+    pub fn from_file(path: &Path, config: OutputConfig) -> Result<Self, String> {
+        let template = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "template".to_string());
+        Self::new(&name, &template, config)
+    }
+
+    /// Render the template with `tree` set to the JSON form of `node`.
+    pub fn format(&self, node: &TreeNode) -> Result<String, String> {
+        let root = match node {
+            TreeNode::File { path, .. } | TreeNode::Dir { path, .. } => path,
+        };
+        let mut context = Context::new();
+        context.insert("tree", &node_for_output(node, root, &self.config));
+        self.tera
+            .render(&self.name, &context)
+            .map_err(|e| error_chain(&e))
+    }
+}
+
+/// Tera reports the cause of an error in its source chain, so join them all.
+fn error_chain(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{dir_node, file_node};
+
+    fn tree() -> TreeNode {
+        let mut main = file_node("/project/main.rs");
+        if let TreeNode::File { comment, .. } = &mut main {
+            *comment = Some("Entry point".to_string());
+        }
+        dir_node("/project", vec![main])
+    }
+
+    #[test]
+    fn test_template_renders_tree() {
+        let template = "{{ tree.name }}\n{% for child in tree.children %}{{ child.name }}: {{ child.comment }}\n{% endfor %}";
+        let formatter = TemplateFormatter::new("test", template, OutputConfig::default()).unwrap();
+        assert_eq!(
+            formatter.format(&tree()).unwrap(),
+            "project\nmain.rs: Entry point\n"
+        );
+    }
+
+    #[test]
+    fn test_template_errors_include_cause() {
+        assert!(TemplateFormatter::new("bad", "{% for %}", OutputConfig::default()).is_err());
+
+        let formatter =
+            TemplateFormatter::new("missing", "{{ tree.nope }}", OutputConfig::default()).unwrap();
+        let error = formatter.format(&tree()).unwrap_err();
+        assert!(error.contains("tree.nope"), "{}", error);
+    }
+}
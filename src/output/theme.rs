@@ -0,0 +1,234 @@
+//! Color themes for console output
+//!
+//! A `Theme` maps each kind of output element (directories, files, metadata
+//! lines, highlighted symbols, sizes) to a terminal color. Themes are selected
+//! by name with the `--theme` flag.
+
+use termcolor::Color;
+
+use crate::metadata::LineStyle;
+
+/// Directory colors cycled through by nesting depth with `--depth-colors`.
+pub const DEPTH_COLORS: [Color; 4] = [Color::Blue, Color::Cyan, Color::Green, Color::Yellow];
+
+/// Colors used for console output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Directory names (always rendered bold)
+    pub dir_color: Color,
+    /// File names
+    pub file_color: Color,
+    /// Comment lines
+    pub comment_color: Color,
+    /// Type signature lines
+    pub type_color: Color,
+    /// TODO/FIXME marker lines
+    pub todo_color: Color,
+    /// Rust annotation lines (SAFETY, SOUNDNESS, ...)
+    pub annotation_color: Color,
+    /// Import summary lines
+    pub import_color: Color,
+    /// Plugin output lines
+    pub custom_color: Color,
+    /// Highlighted symbol names within type signatures (always rendered bold)
+    pub symbol_color: Color,
+    /// Highlighted `@username` assignees within TODO lines (always rendered bold)
+    pub assignee_color: Color,
+    /// File sizes
+    pub size_color: Color,
+}
+
+impl Theme {
+    /// Names accepted by `Theme::from_name`, in display order.
+    pub const NAMES: [&'static str; 6] = [
+        "default",
+        "dark",
+        "light",
+        "solarized",
+        "gruvbox",
+        "monochrome",
+    ];
+
+    /// Look up a theme by name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "default" => Some(Self::default()),
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "solarized" => Some(Self::solarized()),
+            "gruvbox" => Some(Self::gruvbox()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// Brighter 256-color palette for dark terminal backgrounds.
+    pub fn dark() -> Self {
+        Self {
+            dir_color: Color::Ansi256(75),
+            file_color: Color::Ansi256(255),
+            comment_color: Color::Ansi256(245),
+            type_color: Color::Ansi256(117),
+            todo_color: Color::Ansi256(221),
+            annotation_color: Color::Ansi256(111),
+            import_color: Color::Ansi256(176),
+            custom_color: Color::Ansi256(150),
+            symbol_color: Color::Ansi256(210),
+            assignee_color: Color::Ansi256(87),
+            size_color: Color::Ansi256(114),
+        }
+    }
+
+    /// Darker 256-color palette for light terminal backgrounds.
+    pub fn light() -> Self {
+        Self {
+            dir_color: Color::Ansi256(25),
+            file_color: Color::Ansi256(235),
+            comment_color: Color::Ansi256(242),
+            type_color: Color::Ansi256(30),
+            todo_color: Color::Ansi256(130),
+            annotation_color: Color::Ansi256(26),
+            import_color: Color::Ansi256(90),
+            custom_color: Color::Ansi256(64),
+            symbol_color: Color::Ansi256(160),
+            assignee_color: Color::Ansi256(31),
+            size_color: Color::Ansi256(28),
+        }
+    }
+
+    /// Solarized palette (https://ethanschoonover.com/solarized/).
+    pub fn solarized() -> Self {
+        Self {
+            dir_color: Color::Rgb(0x26, 0x8b, 0xd2),
+            file_color: Color::Rgb(0x93, 0xa1, 0xa1),
+            comment_color: Color::Rgb(0x58, 0x6e, 0x75),
+            type_color: Color::Rgb(0x2a, 0xa1, 0x98),
+            todo_color: Color::Rgb(0xb5, 0x89, 0x00),
+            annotation_color: Color::Rgb(0x26, 0x8b, 0xd2),
+            import_color: Color::Rgb(0xd3, 0x36, 0x82),
+            custom_color: Color::Rgb(0x6c, 0x71, 0xc4),
+            symbol_color: Color::Rgb(0xdc, 0x32, 0x2f),
+            assignee_color: Color::Rgb(0x2a, 0xa1, 0x98),
+            size_color: Color::Rgb(0x85, 0x99, 0x00),
+        }
+    }
+
+    /// Gruvbox dark palette (https://github.com/morhetz/gruvbox).
+    pub fn gruvbox() -> Self {
+        Self {
+            dir_color: Color::Rgb(0x83, 0xa5, 0x98),
+            file_color: Color::Rgb(0xeb, 0xdb, 0xb2),
+            comment_color: Color::Rgb(0x92, 0x83, 0x74),
+            type_color: Color::Rgb(0x8e, 0xc0, 0x7c),
+            todo_color: Color::Rgb(0xfa, 0xbd, 0x2f),
+            annotation_color: Color::Rgb(0x83, 0xa5, 0x98),
+            import_color: Color::Rgb(0xd3, 0x86, 0x9b),
+            custom_color: Color::Rgb(0xfe, 0x80, 0x19),
+            symbol_color: Color::Rgb(0xfb, 0x49, 0x34),
+            assignee_color: Color::Rgb(0x8e, 0xc0, 0x7c),
+            size_color: Color::Rgb(0xb8, 0xbb, 0x26),
+        }
+    }
+
+    /// Single color; structure is conveyed by bold directories and symbols only.
+    pub fn monochrome() -> Self {
+        Self {
+            dir_color: Color::White,
+            file_color: Color::White,
+            comment_color: Color::White,
+            type_color: Color::White,
+            todo_color: Color::White,
+            annotation_color: Color::White,
+            import_color: Color::White,
+            custom_color: Color::White,
+            symbol_color: Color::White,
+            assignee_color: Color::White,
+            size_color: Color::White,
+        }
+    }
+
+    /// Get the color for a metadata line style.
+    pub fn line_color(&self, style: &LineStyle) -> Color {
+        match style {
+            LineStyle::Comment | LineStyle::Truncation => self.comment_color,
+            LineStyle::TypeSignature => self.type_color,
+            LineStyle::Todo => self.todo_color,
+            LineStyle::Annotation => self.annotation_color,
+            LineStyle::Import => self.import_color,
+            LineStyle::Custom(_) => self.custom_color,
+        }
+    }
+
+    /// Get the color for highlighted symbols within a metadata line style.
+    pub fn symbol_color_for(&self, style: &LineStyle) -> Color {
+        match style {
+            LineStyle::Todo | LineStyle::Annotation => self.assignee_color,
+            _ => self.symbol_color,
+        }
+    }
+}
+
+impl Default for Theme {
+    /// The original fruit colors, using the terminal's basic palette.
+    fn default() -> Self {
+        Self {
+            dir_color: Color::Blue,
+            file_color: Color::White,
+            comment_color: LineStyle::Comment.color(),
+            type_color: LineStyle::TypeSignature.color(),
+            todo_color: LineStyle::Todo.color(),
+            annotation_color: LineStyle::Annotation.color(),
+            import_color: LineStyle::Import.color(),
+            custom_color: LineStyle::Custom(String::new()).color(),
+            symbol_color: Color::Red,
+            assignee_color: Color::Cyan,
+            size_color: Color::Green,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_depth_colors_cycle() {
+        use crate::output::OutputConfig;
+
+        let config = OutputConfig {
+            depth_colors: true,
+            ..OutputConfig::default()
+        };
+        assert_eq!(config.dir_color(0), Color::Blue);
+        assert_eq!(config.dir_color(1), Color::Cyan);
+        assert_eq!(config.dir_color(3), Color::Yellow);
+        assert_eq!(config.dir_color(4), Color::Blue);
+
+        let config = OutputConfig::default();
+        assert_eq!(config.dir_color(2), Theme::default().dir_color);
+    }
+
+    #[test]
+    fn test_all_names_resolve() {
+        for name in Theme::NAMES {
+            assert!(Theme::from_name(name).is_some(), "theme {} missing", name);
+        }
+        assert_eq!(Theme::from_name("Solarized"), Some(Theme::solarized()));
+        assert_eq!(Theme::from_name("nope"), None);
+    }
+
+    #[test]
+    fn test_default_matches_line_styles() {
+        let theme = Theme::default();
+        for style in [
+            LineStyle::Comment,
+            LineStyle::TypeSignature,
+            LineStyle::Todo,
+            LineStyle::Annotation,
+            LineStyle::Import,
+            LineStyle::Custom("plugin".to_string()),
+        ] {
+            assert_eq!(theme.line_color(&style), style.color());
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Timing of a streaming walk for `FRUIT_DEBUG_TIMING=1`
+//!
+//! This module provides `OutputStats`, which splits the time of a walk into
+//! file walking, metadata extraction and output formatting, and
+//! `TimedOutput`, which times every call into a `StreamingOutput`.
+
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::metadata::MetadataBlock;
+use crate::tree::{ChildCount, NodeFlags, StreamingOutput};
+
+/// Where the time of a walk went, from `StreamingWalker::output_stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputStats {
+    /// Time spent reading directories and filtering entries
+    pub walk_duration: Duration,
+    /// Time spent extracting metadata (wall time when extracting in parallel)
+    pub extraction_duration: Duration,
+    /// Time spent in the output formatter
+    pub output_duration: Duration,
+    /// Number of files metadata was extracted from
+    pub files_extracted: usize,
+    /// Total size of those files
+    pub bytes_read: u64,
+}
+
+impl fmt::Display for OutputStats {
+    /// Formats as `# Timing: walk=Xms extract=Xms output=Xms`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "# Timing: walk={}ms extract={}ms output={}ms",
+            self.walk_duration.as_millis(),
+            self.extraction_duration.as_millis(),
+            self.output_duration.as_millis()
+        )
+    }
+}
+
+/// Forwards to another `StreamingOutput`, adding the time of each call to
+/// `output_duration`.
+pub(crate) struct TimedOutput<'a, O> {
+    output: &'a mut O,
+    stats: &'a Mutex<OutputStats>,
+}
+
+impl<'a, O: StreamingOutput> TimedOutput<'a, O> {
+    pub(crate) fn new(output: &'a mut O, stats: &'a Mutex<OutputStats>) -> Self {
+        Self { output, stats }
+    }
+
+    fn timed<T>(&mut self, f: impl FnOnce(&mut O) -> T) -> T {
+        let start = Instant::now();
+        let result = f(self.output);
+        let mut stats = self.stats.lock().unwrap_or_else(PoisonError::into_inner);
+        stats.output_duration += start.elapsed();
+        result
+    }
+}
+
+impl<O: StreamingOutput> StreamingOutput for TimedOutput<'_, O> {
+    fn output_node(
+        &mut self,
+        name: &str,
+        metadata: Option<MetadataBlock>,
+        is_dir: bool,
+        is_last: bool,
+        prefix: &str,
+        is_root: bool,
+        size: Option<u64>,
+        child_count: Option<ChildCount>,
+        flags: NodeFlags,
+    ) -> std::io::Result<()> {
+        self.timed(|output| {
+            output.output_node(
+                name,
+                metadata,
+                is_dir,
+                is_last,
+                prefix,
+                is_root,
+                size,
+                child_count,
+                flags,
+            )
+        })
+    }
+
+    fn finish(&mut self, dir_count: usize, file_count: usize) -> std::io::Result<()> {
+        self.timed(|output| output.finish(dir_count, file_count))
+    }
+
+    fn wants_child_counts(&self) -> bool {
+        self.output.wants_child_counts()
+    }
+
+    fn begin_children(
+        &mut self,
+        prefix: &str,
+        files: &[(String, Option<u64>)],
+    ) -> std::io::Result<()> {
+        self.timed(|output| output.begin_children(prefix, files))
+    }
+
+    fn file_tokens(&mut self, tokens: usize) -> std::io::Result<()> {
+        self.timed(|output| output.file_tokens(tokens))
+    }
+
+    fn token_budget_exceeded(&mut self, max_tokens: usize) -> std::io::Result<()> {
+        self.timed(|output| output.token_budget_exceeded(max_tokens))
+    }
+
+    fn output_separator(&mut self, depth: usize) -> std::io::Result<()> {
+        self.timed(|output| output.output_separator(depth))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_stats_display() {
+        let stats = OutputStats {
+            walk_duration: Duration::from_millis(12),
+            extraction_duration: Duration::from_micros(3400),
+            output_duration: Duration::ZERO,
+            files_extracted: 2,
+            bytes_read: 100,
+        };
+        assert_eq!(
+            stats.to_string(),
+            "# Timing: walk=12ms extract=3ms output=0ms"
+        );
+    }
+}
@@ -4,18 +4,31 @@
 //! tree structure into a string or prints it with colors.
 
 use std::io::{self, Write};
-use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+use std::path::Path;
+use termcolor::{ColorSpec, StandardStream, WriteColor};
 
+use crate::file_utils::has_binary_extension;
 use crate::metadata::MetadataBlock;
-use crate::tree::TreeNode;
+use crate::tree::{ChildCount, TreeNode, TreeVisitor};
 
 use super::config::OutputConfig;
 use super::utils::{
-    calculate_wrap_width, continuation_prefix, render_metadata_block,
-    write_metadata_line_with_symbol, MetadataRenderResult, RenderedLine,
+    calculate_wrap_width, continuation_prefix, render_metadata_block, tree_depth,
+    push_line_number, write_line_number, write_metadata_line_with_symbol, write_with_link,
+    MetadataRenderResult, RenderedLine,
 };
 
+/// A directory whose children are being formatted.
+struct DirFrame {
+    /// Prefix for the directory's children
+    child_prefix: String,
+    /// Children not yet visited
+    remaining: usize,
+}
+
 /// Formatter for buffered tree output.
+///
+/// Plain-text formatting is done by visiting the tree (see `TreeVisitor`).
 pub struct TreeFormatter {
     config: OutputConfig,
 }
@@ -26,25 +39,25 @@ impl TreeFormatter {
     }
 
     pub fn format(&self, node: &TreeNode) -> String {
-        let mut output = String::new();
-        let (dir_count, file_count) = self.format_node(node, &mut output, "", true, true);
-        output.push_str(&format!(
-            "\n{} directories, {} files\n",
-            dir_count, file_count
-        ));
+        let mut visitor = TextVisitor::new(&self.config);
+        node.accept(&mut visitor);
+        let mut output = visitor.output;
+        if self.config.show_summary {
+            output.push_str(&format!(
+                "\n{} directories, {} files\n",
+                visitor.dir_count, visitor.file_count
+            ));
+        }
         output
     }
 
     pub fn print(&self, node: &TreeNode) -> io::Result<()> {
-        let choice = if self.config.use_color {
-            ColorChoice::Auto
-        } else {
-            ColorChoice::Never
-        };
-        let mut stdout = StandardStream::stdout(choice);
+        let mut stdout = StandardStream::stdout(self.config.color_choice());
         let (dir_count, file_count) = self.print_node(node, &mut stdout, "", true, true)?;
-        writeln!(stdout)?;
-        writeln!(stdout, "{} directories, {} files", dir_count, file_count)?;
+        if self.config.show_summary {
+            writeln!(stdout)?;
+            writeln!(stdout, "{} directories, {} files", dir_count, file_count)?;
+        }
         Ok(())
     }
 
@@ -61,17 +74,28 @@ impl TreeFormatter {
                 stdout.reset()?;
                 writeln!(stdout, "{}", cont_prefix)?;
             }
-            RenderedLine::Content { text, symbol_name, style, indent } => {
+            RenderedLine::Content {
+                text,
+                symbol_name,
+                style,
+                indent,
+                line_number,
+                link,
+            } => {
                 stdout.reset()?;
                 write!(stdout, "{}{}", cont_prefix, meta_prefix)?;
-                write_metadata_line_with_symbol(
-                    stdout,
-                    text,
-                    symbol_name.as_deref(),
-                    style.color(),
-                    style.is_intense(),
-                    *indent,
-                )?;
+                write_with_link(stdout, link.as_deref(), |stdout| {
+                    write_metadata_line_with_symbol(
+                        stdout,
+                        text,
+                        symbol_name.as_deref(),
+                        self.config.theme.line_color(style),
+                        style.is_intense(),
+                        self.config.theme.symbol_color_for(style),
+                        *indent,
+                    )
+                })?;
+                write_line_number(stdout, *line_number)?;
                 writeln!(stdout)?;
             }
         }
@@ -85,16 +109,28 @@ impl TreeFormatter {
         line: &RenderedLine,
         meta_prefix: &str,
     ) -> io::Result<()> {
-        if let RenderedLine::Content { text, symbol_name, style, indent } = line {
+        if let RenderedLine::Content {
+            text,
+            symbol_name,
+            style,
+            indent,
+            line_number,
+            link,
+        } = line
+        {
             write!(stdout, "  {}", meta_prefix)?;
-            write_metadata_line_with_symbol(
-                stdout,
-                text,
-                symbol_name.as_deref(),
-                style.color(),
-                style.is_intense(),
-                *indent,
-            )?;
+            write_with_link(stdout, link.as_deref(), |stdout| {
+                write_metadata_line_with_symbol(
+                    stdout,
+                    text,
+                    symbol_name.as_deref(),
+                    self.config.theme.line_color(style),
+                    style.is_intense(),
+                    self.config.theme.symbol_color_for(style),
+                    *indent,
+                )
+            })?;
+            write_line_number(stdout, *line_number)?;
         }
         writeln!(stdout)?;
         stdout.reset()?;
@@ -120,7 +156,13 @@ impl TreeFormatter {
             meta_prefix.chars().count(),
         );
 
-        let result = render_metadata_block(block, order, show_full, wrap_width);
+        let result = render_metadata_block(
+            block,
+            order,
+            self.config.metadata.max_lines,
+            show_full,
+            wrap_width,
+        );
 
         match result {
             MetadataRenderResult::Empty => {
@@ -147,143 +189,6 @@ impl TreeFormatter {
         Ok(())
     }
 
-    /// Format a rendered line to plain text.
-    fn format_rendered_line(
-        &self,
-        output: &mut String,
-        line: &RenderedLine,
-        cont_prefix: &str,
-        meta_prefix: &str,
-    ) {
-        match line {
-            RenderedLine::Separator => {
-                output.push_str(cont_prefix);
-                output.push('\n');
-            }
-            RenderedLine::Content { text, .. } => {
-                output.push_str(cont_prefix);
-                output.push_str(meta_prefix);
-                output.push_str(text);
-                output.push('\n');
-            }
-        }
-    }
-
-    /// Format inline content to plain text.
-    fn format_inline_content(&self, output: &mut String, line: &RenderedLine, meta_prefix: &str) {
-        if let RenderedLine::Content { text, .. } = line {
-            output.push_str("  ");
-            output.push_str(meta_prefix);
-            output.push_str(text);
-        }
-        output.push('\n');
-    }
-
-    /// Format a metadata block to plain text output.
-    fn format_metadata_block_plain(
-        &self,
-        output: &mut String,
-        block: &MetadataBlock,
-        prefix: &str,
-        is_last: bool,
-    ) {
-        let meta_prefix = self.config.metadata.prefix_str();
-        let order = self.config.metadata.order;
-        let show_full = self.config.show_full();
-
-        let cont_prefix = continuation_prefix(prefix, is_last);
-        let wrap_width = calculate_wrap_width(
-            self.config.wrap_width,
-            cont_prefix.chars().count(),
-            meta_prefix.chars().count(),
-        );
-
-        let result = render_metadata_block(block, order, show_full, wrap_width);
-
-        match result {
-            MetadataRenderResult::Empty => {
-                output.push('\n');
-            }
-            MetadataRenderResult::Inline { first } => {
-                self.format_inline_content(output, &first, meta_prefix);
-            }
-            MetadataRenderResult::InlineWithBlock { first, block_lines } => {
-                self.format_inline_content(output, &first, meta_prefix);
-                for line in &block_lines {
-                    self.format_rendered_line(output, line, &cont_prefix, meta_prefix);
-                }
-            }
-            MetadataRenderResult::Block { lines } => {
-                output.push('\n'); // End the filename line
-                for line in &lines {
-                    self.format_rendered_line(output, line, &cont_prefix, meta_prefix);
-                }
-            }
-        }
-    }
-
-    fn format_node(
-        &self,
-        node: &TreeNode,
-        output: &mut String,
-        prefix: &str,
-        is_last: bool,
-        is_root: bool,
-    ) -> (usize, usize) {
-        let connector = if is_last { "└── " } else { "├── " };
-
-        match node {
-            TreeNode::File { name, comment, .. } => {
-                output.push_str(prefix);
-                output.push_str(connector);
-                output.push_str(name);
-                if let Some(c) = comment {
-                    // Convert comment to metadata block for unified handling
-                    let block = MetadataBlock::from_comments(c);
-                    self.format_metadata_block_plain(output, &block, prefix, is_last);
-                } else {
-                    output.push('\n');
-                }
-                (0, 1)
-            }
-            TreeNode::Dir { name, children, .. } => {
-                if is_root {
-                    // Root node - print without connector
-                    output.push_str(name);
-                    output.push('\n');
-                } else {
-                    output.push_str(prefix);
-                    output.push_str(connector);
-                    output.push_str(name);
-                    output.push('\n');
-                }
-
-                let new_prefix = if is_root {
-                    String::new()
-                } else if is_last {
-                    format!("{}    ", prefix)
-                } else {
-                    format!("{}│   ", prefix)
-                };
-
-                let mut dir_count = 0;
-                let mut file_count = 0;
-
-                for (i, child) in children.iter().enumerate() {
-                    let child_is_last = i == children.len() - 1;
-                    let (d, f) = self.format_node(child, output, &new_prefix, child_is_last, false);
-                    dir_count += d;
-                    file_count += f;
-                    if child.is_dir() {
-                        dir_count += 1;
-                    }
-                }
-
-                (dir_count, file_count)
-            }
-        }
-    }
-
     fn print_node(
         &self,
         node: &TreeNode,
@@ -297,7 +202,11 @@ impl TreeFormatter {
         match node {
             TreeNode::File { name, comment, .. } => {
                 write!(stdout, "{}{}", prefix, connector)?;
-                stdout.set_color(ColorSpec::new().set_fg(Some(Color::White)))?;
+                stdout.set_color(
+                    ColorSpec::new()
+                        .set_fg(Some(self.config.theme.file_color))
+                        .set_dimmed(has_binary_extension(Path::new(name))),
+                )?;
                 write!(stdout, "{}", name)?;
                 stdout.reset()?;
 
@@ -311,17 +220,31 @@ impl TreeFormatter {
                 Ok((0, 1))
             }
             TreeNode::Dir { name, children, .. } => {
+                let depth = if is_root { 0 } else { tree_depth(prefix) + 1 };
+                let mut dir_spec = ColorSpec::new();
+                dir_spec
+                    .set_fg(Some(self.config.dir_color(depth)))
+                    .set_bold(true);
+
                 if is_root {
-                    // Root node - print without connector
-                    stdout.set_color(ColorSpec::new().set_fg(Some(Color::Blue)).set_bold(true))?;
-                    writeln!(stdout, "{}", name)?;
+                    // Root node - print without connector, using the path prefix if set
+                    let label = self
+                        .config
+                        .prefixed_path("")
+                        .unwrap_or_else(|| name.to_string());
+                    stdout.set_color(&dir_spec)?;
+                    write!(stdout, "{}", label)?;
                     stdout.reset()?;
                 } else {
                     write!(stdout, "{}{}", prefix, connector)?;
-                    stdout.set_color(ColorSpec::new().set_fg(Some(Color::Blue)).set_bold(true))?;
-                    writeln!(stdout, "{}", name)?;
+                    stdout.set_color(&dir_spec)?;
+                    write!(stdout, "{}", name)?;
                     stdout.reset()?;
                 }
+                if let Some(count) = child_count(&self.config, children) {
+                    write!(stdout, " {}", count)?;
+                }
+                writeln!(stdout)?;
 
                 let new_prefix = if is_root {
                     String::new()
@@ -351,11 +274,207 @@ impl TreeFormatter {
     }
 }
 
+/// Count the direct children of a directory if counts are enabled.
+fn child_count(config: &OutputConfig, children: &[TreeNode]) -> Option<ChildCount> {
+    if !config.show_dir_counts {
+        return None;
+    }
+    let dirs = children.iter().filter(|c| c.is_dir()).count();
+    Some(ChildCount {
+        dirs,
+        files: children.len() - dirs,
+        truncated: false,
+    })
+}
+
+/// Plain-text output accumulated while visiting a tree for
+/// `TreeFormatter::format`.
+struct TextVisitor<'a> {
+    config: &'a OutputConfig,
+    output: String,
+    frames: Vec<DirFrame>,
+    dir_count: usize,
+    file_count: usize,
+}
+
+impl<'a> TextVisitor<'a> {
+    fn new(config: &'a OutputConfig) -> Self {
+        Self {
+            config,
+            output: String::new(),
+            frames: Vec::new(),
+            dir_count: 0,
+            file_count: 0,
+        }
+    }
+
+    /// Prefix, is-last and is-root flags for the next visited node.
+    fn next_position(&mut self) -> (String, bool, bool) {
+        match self.frames.last_mut() {
+            Some(frame) => {
+                frame.remaining = frame.remaining.saturating_sub(1);
+                (frame.child_prefix.clone(), frame.remaining == 0, false)
+            }
+            None => (String::new(), true, true),
+        }
+    }
+
+    /// Format a metadata block to plain text output.
+    fn format_metadata_block_plain(&mut self, block: &MetadataBlock, prefix: &str, is_last: bool) {
+        let meta_prefix = self.config.metadata.prefix_str();
+        let order = self.config.metadata.order;
+        let show_full = self.config.show_full();
+
+        let cont_prefix = continuation_prefix(prefix, is_last);
+        let wrap_width = calculate_wrap_width(
+            self.config.wrap_width,
+            cont_prefix.chars().count(),
+            meta_prefix.chars().count(),
+        );
+
+        let result = render_metadata_block(
+            block,
+            order,
+            self.config.metadata.max_lines,
+            show_full,
+            wrap_width,
+        );
+
+        let output = &mut self.output;
+        match result {
+            MetadataRenderResult::Empty => {
+                output.push('\n');
+            }
+            MetadataRenderResult::Inline { first } => {
+                format_inline_content(output, &first, meta_prefix);
+            }
+            MetadataRenderResult::InlineWithBlock { first, block_lines } => {
+                format_inline_content(output, &first, meta_prefix);
+                for line in &block_lines {
+                    format_rendered_line(output, line, &cont_prefix, meta_prefix);
+                }
+            }
+            MetadataRenderResult::Block { lines } => {
+                output.push('\n'); // End the filename line
+                for line in &lines {
+                    format_rendered_line(output, line, &cont_prefix, meta_prefix);
+                }
+            }
+        }
+    }
+}
+
+impl TreeVisitor for TextVisitor<'_> {
+    fn visit_file(&mut self, node: &TreeNode) {
+        let TreeNode::File { name, comment, .. } = node else {
+            return;
+        };
+        let (prefix, is_last, _) = self.next_position();
+        let connector = if is_last { "└── " } else { "├── " };
+
+        self.output.push_str(&prefix);
+        self.output.push_str(connector);
+        self.output.push_str(name);
+        if let Some(c) = comment {
+            // Convert comment to metadata block for unified handling
+            let block = MetadataBlock::from_comments(c);
+            self.format_metadata_block_plain(&block, &prefix, is_last);
+        } else {
+            self.output.push('\n');
+        }
+        self.file_count += 1;
+    }
+
+    fn visit_dir_enter(&mut self, node: &TreeNode) {
+        let TreeNode::Dir { name, children, .. } = node else {
+            return;
+        };
+        let (prefix, is_last, is_root) = self.next_position();
+        let connector = if is_last { "└── " } else { "├── " };
+
+        let mut line = String::new();
+        if is_root {
+            // Root node - print without connector, using the path prefix if set
+            match self.config.prefixed_path("") {
+                Some(label) => line.push_str(&label),
+                None => line.push_str(name),
+            }
+        } else {
+            line.push_str(&prefix);
+            line.push_str(connector);
+            line.push_str(name);
+            self.dir_count += 1;
+        }
+        if let Some(count) = child_count(self.config, children) {
+            line.push(' ');
+            line.push_str(&count.to_string());
+        }
+        line.push('\n');
+        self.output.push_str(&line);
+
+        let child_prefix = if is_root {
+            String::new()
+        } else if is_last {
+            format!("{}    ", prefix)
+        } else {
+            format!("{}│   ", prefix)
+        };
+        self.frames.push(DirFrame {
+            child_prefix,
+            remaining: children.len(),
+        });
+    }
+
+    fn visit_dir_exit(&mut self, _node: &TreeNode) {
+        self.frames.pop();
+    }
+}
+
+/// Format a rendered line to plain text.
+fn format_rendered_line(
+    output: &mut String,
+    line: &RenderedLine,
+    cont_prefix: &str,
+    meta_prefix: &str,
+) {
+    match line {
+        RenderedLine::Separator => {
+            output.push_str(cont_prefix);
+            output.push('\n');
+        }
+        RenderedLine::Content {
+            text, line_number, ..
+        } => {
+            output.push_str(cont_prefix);
+            output.push_str(meta_prefix);
+            output.push_str(text);
+            push_line_number(output, *line_number);
+            output.push('\n');
+        }
+    }
+}
+
+/// Format inline content to plain text.
+fn format_inline_content(output: &mut String, line: &RenderedLine, meta_prefix: &str) {
+    if let RenderedLine::Content {
+        text, line_number, ..
+    } = line
+    {
+        output.push_str("  ");
+        output.push_str(meta_prefix);
+        output.push_str(text);
+        push_line_number(output, *line_number);
+    }
+    output.push('\n');
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
 
     use crate::metadata::MetadataConfig;
+    use crate::output::Theme;
+    use crate::tree::SizeStyle;
 
     use super::*;
 
@@ -363,6 +482,7 @@ mod tests {
         TreeNode::Dir {
             name: ".".to_string(),
             path: PathBuf::from("."),
+            child_count: None,
             children: vec![
                 TreeNode::File {
                     name: "Cargo.toml".to_string(),
@@ -377,6 +497,7 @@ mod tests {
                 TreeNode::Dir {
                     name: "src".to_string(),
                     path: PathBuf::from("src"),
+                    child_count: None,
                     children: vec![
                         TreeNode::File {
                             name: "main.rs".to_string(),
@@ -409,8 +530,18 @@ mod tests {
         let tree = sample_tree();
         let formatter = TreeFormatter::new(OutputConfig {
             use_color: false,
+            ansi_only: false,
             metadata: MetadataConfig::comments_only(false),
             wrap_width: None,
+            path_prefix: None,
+            root_path: None,
+            show_dir_counts: false,
+            theme: Theme::default(),
+            depth_colors: false,
+            collapse_types: None,
+            show_summary: true,
+            compact: false,
+            size_style: SizeStyle::Binary,
         });
         let output = formatter.format(&tree);
 
@@ -424,6 +555,53 @@ mod tests {
         assert!(output.contains("files"));
     }
 
+    #[test]
+    fn test_display_matches_default_format() {
+        let tree = sample_tree();
+        let formatted = TreeFormatter::new(OutputConfig::default()).format(&tree);
+
+        assert_eq!(tree.to_string(), formatted.trim_end());
+        assert!(format!("{}", tree).ends_with("1 directories, 3 files"));
+        assert_eq!(tree.to_tree_string(OutputConfig::default()), formatted);
+    }
+
+    #[test]
+    fn test_path_prefix_replaces_root_label() {
+        let tree = sample_tree();
+        let formatter = TreeFormatter::new(OutputConfig {
+            use_color: false,
+            ansi_only: false,
+            metadata: MetadataConfig::comments_only(false),
+            wrap_width: None,
+            path_prefix: Some("github.com/org/repo".to_string()),
+            root_path: None,
+            show_dir_counts: false,
+            theme: Theme::default(),
+            depth_colors: false,
+            collapse_types: None,
+            show_summary: true,
+            compact: false,
+            size_style: SizeStyle::Binary,
+        });
+        let output = formatter.format(&tree);
+
+        assert!(output.starts_with("github.com/org/repo\n"));
+        assert!(output.contains("├── Cargo.toml"));
+    }
+
+    #[test]
+    fn test_show_dir_counts() {
+        let tree = sample_tree();
+        let formatter = TreeFormatter::new(OutputConfig {
+            show_dir_counts: true,
+            ..OutputConfig::default()
+        });
+        let output = formatter.format(&tree);
+
+        assert!(output.starts_with(". (1 dir, 1 file)\n"), "{}", output);
+        assert!(output.contains("└── src (2 files)"), "{}", output);
+    }
+
     #[test]
     fn test_dir_count() {
         let tree = sample_tree();
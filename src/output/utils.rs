@@ -1,9 +1,11 @@
 //! Shared utility functions for output formatting
 
 use std::io::{self, Write};
+use std::sync::LazyLock;
 use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
 
 use crate::metadata::{LineStyle, MetadataBlock, MetadataLine, MetadataOrder};
+use crate::string_utils::{DEFAULT_TAB_WIDTH, expand_tabs};
 
 /// Calculate the continuation prefix for lines below the filename.
 /// Used by both TreeFormatter and StreamingFormatter.
@@ -27,6 +29,11 @@ pub fn calculate_wrap_width(
         .filter(|&w| w > 10)
 }
 
+/// Number of tree levels encoded in a node's prefix (one 4-character segment per level).
+pub fn tree_depth(prefix: &str) -> usize {
+    prefix.chars().count() / 4
+}
+
 /// Check if the next non-empty line in a slice is indented relative to current indent.
 pub fn has_indented_children(lines: &[&crate::metadata::MetadataLine], current_indent: usize) -> bool {
     lines
@@ -55,7 +62,7 @@ pub fn first_line(s: &str) -> &str {
     s.lines().next().unwrap_or(s)
 }
 
-/// Write a metadata line, highlighting the symbol name in bold red if present.
+/// Write a metadata line, highlighting the symbol name in bold `symbol_color` if present.
 /// The `indent` parameter specifies the number of spaces to prepend for hierarchy display.
 pub fn write_metadata_line_with_symbol(
     stdout: &mut StandardStream,
@@ -63,6 +70,7 @@ pub fn write_metadata_line_with_symbol(
     symbol_name: Option<&str>,
     base_color: Color,
     is_intense: bool,
+    symbol_color: Color,
     indent: usize,
 ) -> io::Result<()> {
     // Write indentation spaces
@@ -84,8 +92,8 @@ pub fn write_metadata_line_with_symbol(
                 write!(stdout, "{}", before)?;
             }
 
-            // Write symbol in bold red
-            stdout.set_color(ColorSpec::new().set_fg(Some(Color::Red)).set_bold(true))?;
+            // Write symbol in bold
+            stdout.set_color(ColorSpec::new().set_fg(Some(symbol_color)).set_bold(true))?;
             write!(stdout, "{}", sym)?;
 
             // Write part after symbol
@@ -119,65 +127,206 @@ pub fn write_metadata_line_with_symbol(
     Ok(())
 }
 
+/// Length in bytes of the ANSI escape sequence at the start of `s`, if any.
+/// Recognizes CSI sequences (`\x1b[32m`), OSC sequences terminated by BEL or
+/// `\x1b\\`, and two-character escapes.
+fn ansi_escape_len(s: &str) -> Option<usize> {
+    let rest = s.strip_prefix('\x1b')?;
+    let mut chars = rest.char_indices();
+    match chars.next()? {
+        (_, '[') => {
+            // Parameter and intermediate bytes, then one final byte in @..=~
+            chars
+                .find(|&(_, c)| ('@'..='~').contains(&c))
+                .map(|(i, c)| 1 + i + c.len_utf8())
+        }
+        (_, ']') => {
+            let end = rest.find('\x07').map(|i| i + 1);
+            let st = rest.find("\x1b\\").map(|i| i + 2);
+            let len = match (end, st) {
+                (Some(a), Some(b)) => a.min(b),
+                (a, b) => a.or(b)?,
+            };
+            Some(1 + len)
+        }
+        (_, c) => Some(1 + c.len_utf8()),
+    }
+}
+
+/// Split `s` into (text, is_escape) segments, keeping ANSI escape sequences whole.
+fn ansi_segments(s: &str) -> Vec<(&str, bool)> {
+    let mut segments = Vec::new();
+    let mut rest = s;
+    while !rest.is_empty() {
+        if let Some(len) = ansi_escape_len(rest) {
+            segments.push((&rest[..len], true));
+            rest = &rest[len..];
+        } else {
+            // Always consume the first char, which may be a lone ESC
+            let first = rest.chars().next().map_or(1, char::len_utf8);
+            let len = rest[first..].find('\x1b').map_or(rest.len(), |i| i + first);
+            segments.push((&rest[..len], false));
+            rest = &rest[len..];
+        }
+    }
+    segments
+}
+
+/// Number of characters `s` occupies on screen, ignoring ANSI escape sequences.
+pub fn visible_length(s: &str) -> usize {
+    ansi_segments(s)
+        .into_iter()
+        .filter(|(_, is_escape)| !is_escape)
+        .map(|(text, _)| text.chars().count())
+        .sum()
+}
+
+/// Split a word into chunks of at most `max_width` visible characters.
+/// Escape sequences stay attached to the text that follows them.
+fn split_visible(word: &str, max_width: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    let mut chunk_len = 0;
+    for (text, is_escape) in ansi_segments(word) {
+        if is_escape {
+            chunk.push_str(text);
+            continue;
+        }
+        for c in text.chars() {
+            if chunk_len == max_width {
+                chunks.push(std::mem::take(&mut chunk));
+                chunk_len = 0;
+            }
+            chunk.push(c);
+            chunk_len += 1;
+        }
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// Carry SGR color codes across line breaks: each line that ends with a color
+/// still active is reset, and the next line re-applies it.
+fn carry_ansi_colors(lines: Vec<String>) -> Vec<String> {
+    let mut active = String::new();
+    let count = lines.len();
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let mut out = active.clone();
+            out.push_str(&line);
+            for (seq, is_escape) in ansi_segments(&line) {
+                if !is_escape || !seq.starts_with("\x1b[") || !seq.ends_with('m') {
+                    continue;
+                }
+                if seq == "\x1b[0m" || seq == "\x1b[m" {
+                    active.clear();
+                } else {
+                    active.push_str(seq);
+                }
+            }
+            if !active.is_empty() && i + 1 < count {
+                out.push_str("\x1b[0m");
+            }
+            out
+        })
+        .collect()
+}
+
+/// Write a dimmed `:N` line number after a metadata line, if there is one.
+pub fn write_line_number(
+    stdout: &mut StandardStream,
+    line_number: Option<usize>,
+) -> io::Result<()> {
+    if let Some(line) = line_number {
+        stdout.set_color(ColorSpec::new().set_dimmed(true))?;
+        write!(stdout, " :{}", line)?;
+        stdout.reset()?;
+    }
+    Ok(())
+}
+
+/// Terminals known to render OSC 8 hyperlinks, by `$TERM_PROGRAM`.
+const HYPERLINK_TERM_PROGRAMS: &[&str] = &["iTerm.app", "WezTerm", "vscode", "Hyper", "ghostty"];
+
+/// Whether the current terminal renders OSC 8 hyperlinks (checked once per run).
+static TERMINAL_HYPERLINKS: LazyLock<bool> = LazyLock::new(|| {
+    terminal_supports_hyperlinks(
+        std::env::var("TERM_PROGRAM").ok().as_deref(),
+        std::env::var("COLORTERM").ok().as_deref(),
+    )
+});
+
+/// Decide hyperlink support from `$TERM_PROGRAM` and `$COLORTERM`.
+/// Truecolor terminals (Kitty, GNOME Terminal, ...) all handle OSC 8.
+fn terminal_supports_hyperlinks(term_program: Option<&str>, colorterm: Option<&str>) -> bool {
+    term_program.is_some_and(|program| HYPERLINK_TERM_PROGRAMS.contains(&program))
+        || matches!(colorterm, Some("truecolor" | "24bit"))
+}
+
+/// Run `write`, wrapping its output in an OSC 8 hyperlink to `link` when one
+/// is set and the terminal supports it. Colorless output never gets links.
+pub fn write_with_link(
+    stdout: &mut StandardStream,
+    link: Option<&str>,
+    write: impl FnOnce(&mut StandardStream) -> io::Result<()>,
+) -> io::Result<()> {
+    let Some(url) = link.filter(|_| stdout.supports_color() && *TERMINAL_HYPERLINKS) else {
+        return write(stdout);
+    };
+    write!(stdout, "\x1b]8;;{}\x1b\\", url)?;
+    write(stdout)?;
+    write!(stdout, "\x1b]8;;\x1b\\")
+}
+
+/// Append the plain-text form of `write_line_number` to `output`.
+pub fn push_line_number(output: &mut String, line_number: Option<usize>) {
+    if let Some(line) = line_number {
+        output.push_str(&format!(" :{}", line));
+    }
+}
+
 /// Wrap text to fit within max_width, preferring word boundaries.
-/// Uses character count (not byte count) to properly handle UTF-8.
+/// Uses visible character count (not byte count, ignoring ANSI escape codes)
+/// to properly handle UTF-8 and pre-colored text. Tabs are expanded to
+/// `DEFAULT_TAB_WIDTH` spaces first so they are measured like spaces.
 pub fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
+    let text = expand_tabs(text, DEFAULT_TAB_WIDTH);
     if max_width == 0 {
-        return vec![text.to_string()];
+        return vec![text];
     }
 
     let mut lines = Vec::new();
     let mut current_line = String::new();
-    let mut current_len = 0; // Character count of current_line
+    let mut current_len = 0; // Visible character count of current_line
 
     for word in text.split_whitespace() {
-        let word_len = word.chars().count();
-
-        if current_line.is_empty() {
-            // First word on line - may need character wrap if too long
-            if word_len > max_width {
-                // Character wrap for very long words
-                let mut chars = word.chars().peekable();
-                while chars.peek().is_some() {
-                    let chunk: String = chars.by_ref().take(max_width).collect();
-                    let chunk_len = chunk.chars().count();
-                    if chars.peek().is_some() {
-                        lines.push(chunk);
-                    } else {
-                        current_line = chunk;
-                        current_len = chunk_len;
-                    }
-                }
-            } else {
-                current_line = word.to_string();
-                current_len = word_len;
-            }
-        } else if current_len + 1 + word_len <= max_width {
+        let word_len = visible_length(word);
+
+        if !current_line.is_empty() && current_len + 1 + word_len <= max_width {
             // Word fits on current line
             current_line.push(' ');
             current_line.push_str(word);
             current_len += 1 + word_len;
-        } else {
-            // Start new line
+            continue;
+        }
+
+        // Start new line
+        if !current_line.is_empty() {
             lines.push(std::mem::take(&mut current_line));
-            current_len = 0;
-            // Handle long words
-            if word_len > max_width {
-                let mut chars = word.chars().peekable();
-                while chars.peek().is_some() {
-                    let chunk: String = chars.by_ref().take(max_width).collect();
-                    let chunk_len = chunk.chars().count();
-                    if chars.peek().is_some() {
-                        lines.push(chunk);
-                    } else {
-                        current_line = chunk;
-                        current_len = chunk_len;
-                    }
-                }
-            } else {
-                current_line = word.to_string();
-                current_len = word_len;
-            }
+        }
+        if word_len > max_width {
+            // Character wrap for very long words
+            let mut chunks = split_visible(word, max_width);
+            current_line = chunks.pop().unwrap_or_default();
+            current_len = visible_length(&current_line);
+            lines.extend(chunks);
+        } else {
+            current_line = word.to_string();
+            current_len = word_len;
         }
     }
 
@@ -189,7 +338,11 @@ pub fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
         lines.push(String::new());
     }
 
-    lines
+    if text.contains('\x1b') {
+        carry_ansi_colors(lines)
+    } else {
+        lines
+    }
 }
 
 /// A rendered line from a metadata block, ready for output.
@@ -205,6 +358,8 @@ pub enum RenderedLine {
         symbol_name: Option<String>,
         style: LineStyle,
         indent: usize,
+        line_number: Option<usize>,
+        link: Option<String>,
     },
 }
 
@@ -230,9 +385,12 @@ pub enum MetadataRenderResult {
 
 /// Render a metadata block into a structured result that formatters can write.
 /// This centralizes the logic for determining inline vs block display and group separators.
+/// In full mode, at most `max_lines` lines are shown (see
+/// `MetadataBlock::lines_in_order_capped`).
 pub fn render_metadata_block(
     block: &MetadataBlock,
     order: MetadataOrder,
+    max_lines: Option<usize>,
     show_full: bool,
     wrap_width: Option<usize>,
 ) -> MetadataRenderResult {
@@ -240,7 +398,7 @@ pub fn render_metadata_block(
         return MetadataRenderResult::Empty;
     }
 
-    let lines = block.lines_in_order(order);
+    let lines = block.lines_in_order_capped(order, max_lines);
 
     // Not in full mode: show first line inline only
     if !show_full {
@@ -249,8 +407,10 @@ pub fn render_metadata_block(
                 first: RenderedLine::Content {
                     text: first_line(&first.content).to_string(),
                     symbol_name: first.symbol_name.clone(),
-                    style: first.style,
+                    style: first.style.clone(),
                     indent: first.indent,
+                    line_number: first.line_number,
+                    link: first.link.clone(),
                 },
             };
         }
@@ -268,8 +428,10 @@ pub fn render_metadata_block(
                 first: RenderedLine::Content {
                     text: first_line(&first.content).to_string(),
                     symbol_name: first.symbol_name.clone(),
-                    style: first.style,
+                    style: first.style.clone(),
                     indent: first.indent,
+                    line_number: first.line_number,
+                    link: first.link.clone(),
                 },
             };
         }
@@ -308,12 +470,16 @@ pub fn render_metadata_block(
                 vec![content.to_string()]
             };
 
-            for wrapped_line in wrapped {
+            let last = wrapped.len() - 1;
+            for (j, wrapped_line) in wrapped.into_iter().enumerate() {
                 result.push(RenderedLine::Content {
                     text: wrapped_line,
                     symbol_name: meta_line.symbol_name.clone(),
-                    style: meta_line.style,
+                    style: meta_line.style.clone(),
                     indent: meta_line.indent,
+                    // The line number follows the end of the entry
+                    line_number: meta_line.line_number.filter(|_| j == last),
+                    link: meta_line.link.clone(),
                 });
             }
         }
@@ -325,23 +491,27 @@ pub fn render_metadata_block(
     };
 
     // If first section is single line and there's more content, show first inline then rest below
-    if first_is_single {
-        if let Some(first) = block.first_line(order) {
-            // Skip the first line (already shown inline) and the separator after it
-            let skip_count = if block.has_both() { 2 } else { 1 };
-            let remaining: Vec<_> = lines.iter().skip(skip_count).collect();
-            let block_lines = render_lines(&remaining);
-
-            return MetadataRenderResult::InlineWithBlock {
-                first: RenderedLine::Content {
-                    text: first_line(&first.content).to_string(),
-                    symbol_name: first.symbol_name.clone(),
-                    style: first.style,
-                    indent: first.indent,
-                },
-                block_lines,
-            };
-        }
+    if first_is_single && let Some(first) = block.first_line(order) {
+        // Skip the first line (already shown inline) and the separator after it
+        let skip_count = if lines.get(1).is_some_and(|l| l.content.is_empty()) {
+            2
+        } else {
+            1
+        };
+        let remaining: Vec<_> = lines.iter().skip(skip_count).collect();
+        let block_lines = render_lines(&remaining);
+
+        return MetadataRenderResult::InlineWithBlock {
+            first: RenderedLine::Content {
+                text: first_line(&first.content).to_string(),
+                symbol_name: first.symbol_name.clone(),
+                style: first.style.clone(),
+                indent: first.indent,
+                line_number: first.line_number,
+                link: first.link.clone(),
+            },
+            block_lines,
+        };
     }
 
     // First section has multiple lines, show everything below
@@ -427,6 +597,12 @@ mod tests {
         assert_eq!(wrapped, vec!["hello world"]);
     }
 
+    #[test]
+    fn test_wrap_text_tabs() {
+        assert_eq!(wrap_text("a\tb", 0), vec!["a    b"]);
+        assert_eq!(wrap_text("hello\tworld", 8), vec!["hello", "world"]);
+    }
+
     #[test]
     fn test_wrap_text_utf8() {
         // Test that emoji don't cause panics (they're 4 bytes each)
@@ -445,16 +621,47 @@ mod tests {
         assert_eq!(wrapped, vec!["Hello 世界", "🎉"]);
     }
 
+    #[test]
+    fn test_visible_length_ignores_ansi() {
+        assert_eq!(visible_length("plain"), 5);
+        assert_eq!(visible_length("\x1b[32mgreen\x1b[0m"), 5);
+        assert_eq!(visible_length("\x1b[1;38;5;208m世界\x1b[m"), 2);
+        assert_eq!(
+            visible_length("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07"),
+            4
+        );
+    }
+
+    #[test]
+    fn test_wrap_text_ansi() {
+        // Escape codes don't count towards the width
+        let wrapped = wrap_text("\x1b[32mhello\x1b[0m world", 11);
+        assert_eq!(wrapped, vec!["\x1b[32mhello\x1b[0m world"]);
+
+        // An active color is reset at the break and re-applied on the next line
+        let wrapped = wrap_text("\x1b[32mhello world\x1b[0m", 5);
+        assert_eq!(
+            wrapped,
+            vec!["\x1b[32mhello\x1b[0m", "\x1b[32mworld\x1b[0m"]
+        );
+
+        // Long words split on visible characters
+        let wrapped = wrap_text("\x1b[31mabcdef\x1b[0m", 3);
+        assert_eq!(wrapped, vec!["\x1b[31mabc\x1b[0m", "\x1b[31mdef\x1b[0m"]);
+    }
+
     #[test]
     fn test_has_indented_children_with_children() {
         use crate::metadata::{LineStyle, MetadataLine};
 
-        let lines = vec![
+        let lines = [
             MetadataLine {
                 content: "child".to_string(),
                 style: LineStyle::TypeSignature,
                 symbol_name: None,
                 indent: 4,
+                line_number: None,
+                link: None,
             },
         ];
         let line_refs: Vec<&MetadataLine> = lines.iter().collect();
@@ -465,12 +672,14 @@ mod tests {
     fn test_has_indented_children_no_children() {
         use crate::metadata::{LineStyle, MetadataLine};
 
-        let lines = vec![
+        let lines = [
             MetadataLine {
                 content: "sibling".to_string(),
                 style: LineStyle::TypeSignature,
                 symbol_name: None,
                 indent: 0,
+                line_number: None,
+                link: None,
             },
         ];
         let line_refs: Vec<&MetadataLine> = lines.iter().collect();
@@ -487,18 +696,22 @@ mod tests {
     fn test_has_indented_children_skips_empty_lines() {
         use crate::metadata::{LineStyle, MetadataLine};
 
-        let lines = vec![
+        let lines = [
             MetadataLine {
                 content: "   ".to_string(), // Empty/whitespace only
                 style: LineStyle::TypeSignature,
                 symbol_name: None,
                 indent: 0,
+                line_number: None,
+                link: None,
             },
             MetadataLine {
                 content: "child".to_string(),
                 style: LineStyle::TypeSignature,
                 symbol_name: None,
                 indent: 4,
+                line_number: None,
+                link: None,
             },
         ];
         let line_refs: Vec<&MetadataLine> = lines.iter().collect();
@@ -528,4 +741,27 @@ mod tests {
         assert!(!should_insert_group_separator(4, Some(0), false));
         assert!(!should_insert_group_separator(4, Some(4), true));
     }
+
+    #[test]
+    fn test_terminal_supports_hyperlinks() {
+        assert!(terminal_supports_hyperlinks(Some("iTerm.app"), None));
+        assert!(terminal_supports_hyperlinks(Some("WezTerm"), None));
+        assert!(terminal_supports_hyperlinks(None, Some("truecolor")));
+        assert!(!terminal_supports_hyperlinks(Some("Apple_Terminal"), None));
+        assert!(!terminal_supports_hyperlinks(None, None));
+    }
+
+    #[test]
+    fn test_render_keeps_link() {
+        let mut block = MetadataBlock::new();
+        block.comment_lines =
+            vec![MetadataLine::new("serde").with_link("https://crates.io/crates/serde")];
+        let MetadataRenderResult::Inline {
+            first: RenderedLine::Content { link, .. },
+        } = render_metadata_block(&block, MetadataOrder::default(), None, true, None)
+        else {
+            panic!("expected an inline line");
+        };
+        assert_eq!(link.as_deref(), Some("https://crates.io/crates/serde"));
+    }
 }
@@ -0,0 +1,249 @@
+//! User-defined metadata extractors
+//!
+//! A `Plugin` adds lines of its own beneath matching files without forking
+//! the crate. Plugins are registered at compile time through
+//! `WalkerConfig::plugins`; their output is shown after the built-in
+//! metadata with `LineStyle::Custom` carrying the plugin name.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::file_utils::{ExtractionConfig, read_source_file};
+use crate::language::Language;
+use crate::metadata::{MetadataBlock, MetadataLine};
+
+/// A custom metadata extractor.
+///
+/// ```
+/// use std::path::Path;
+/// use fruit::{Language, Plugin};
+///
+/// struct LineCount;
+///
+/// impl Plugin for LineCount {
+///     fn name(&self) -> &str {
+///         "lines"
+///     }
+///
+///     fn extract(&self, _path: &Path, content: &str) -> Option<Vec<String>> {
+///         let count = content.lines().count();
+///         Some(vec![format!("{} lines", count)])
+///     }
+///
+///     fn supports(&self, lang: Language) -> bool {
+///         lang == Language::Rust
+///     }
+/// }
+/// ```
+pub trait Plugin: Send + Sync {
+    /// Name shown in `LineStyle::Custom`, e.g. "license" or "owners".
+    fn name(&self) -> &str;
+
+    /// Extract lines to display for a file, given its contents.
+    ///
+    /// Returns `None` (or an empty list) when there is nothing to show.
+    fn extract(&self, path: &Path, content: &str) -> Option<Vec<String>>;
+
+    /// Like `extract`, but for plugins whose lines carry more than text, such
+    /// as a URL set with `MetadataLine::with_link`. Lines are shown with
+    /// `LineStyle::Custom` whatever their style.
+    ///
+    /// Defaults to the lines returned by `extract`.
+    fn extract_lines(&self, path: &Path, content: &str) -> Option<Vec<MetadataLine>> {
+        let lines = self.extract(path, content)?;
+        Some(lines.into_iter().map(MetadataLine::new).collect())
+    }
+
+    /// Whether this plugin should run on files of `lang`.
+    fn supports(&self, lang: Language) -> bool;
+}
+
+impl fmt::Debug for dyn Plugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Plugin")
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+/// Run every plugin that supports the file's language and collect their lines.
+/// The file is only read if at least one plugin is registered.
+pub(crate) fn extract_plugin_metadata(
+    path: &Path,
+    plugins: &[Arc<dyn Plugin>],
+    config: &ExtractionConfig,
+) -> Option<MetadataBlock> {
+    if plugins.is_empty() {
+        return None;
+    }
+    let (content, ext) = read_source_file(path, config.max_file_size)?;
+    let language = Language::from_extension(ext)?;
+
+    let block = plugins
+        .iter()
+        .filter(|plugin| plugin.supports(language))
+        .filter_map(|plugin| Some((plugin.name(), plugin.extract_lines(path, &content)?)))
+        .fold(MetadataBlock::new(), |block, (name, lines)| {
+            block.with_custom(name, lines)
+        });
+    if block.is_empty() { None } else { Some(block) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::LineStyle;
+    use std::fs;
+    use tempfile::TempDir;
+
+    struct Marker(&'static str, Language);
+
+    impl Plugin for Marker {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn extract(&self, _path: &Path, content: &str) -> Option<Vec<String>> {
+            let first = content.lines().next()?;
+            Some(vec![format!("{}: {}", self.0, first)])
+        }
+
+        fn supports(&self, lang: Language) -> bool {
+            lang == self.1
+        }
+    }
+
+    /// Links each line of `Marker` to a page named after the plugin.
+    struct Linked(Marker);
+
+    impl Plugin for Linked {
+        fn name(&self) -> &str {
+            self.0.name()
+        }
+
+        fn extract(&self, path: &Path, content: &str) -> Option<Vec<String>> {
+            self.0.extract(path, content)
+        }
+
+        fn extract_lines(&self, path: &Path, content: &str) -> Option<Vec<MetadataLine>> {
+            let url = format!("https://example.com/{}", self.name());
+            let lines = self.extract(path, content)?;
+            Some(
+                lines
+                    .into_iter()
+                    .map(|line| MetadataLine::new(line).with_link(url.clone()))
+                    .collect(),
+            )
+        }
+
+        fn supports(&self, lang: Language) -> bool {
+            self.0.supports(lang)
+        }
+    }
+
+    #[test]
+    fn test_plugins_filtered_by_language() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("main.rs");
+        fs::write(&file, "fn main() {}\n").unwrap();
+
+        let plugins: Vec<Arc<dyn Plugin>> = vec![
+            Arc::new(Marker("rust", Language::Rust)),
+            Arc::new(Marker("python", Language::Python)),
+        ];
+        let block = extract_plugin_metadata(&file, &plugins, &ExtractionConfig::default()).unwrap();
+
+        assert_eq!(block.custom_lines.len(), 1);
+        assert_eq!(block.custom_lines[0].content, "rust: fn main() {}");
+        assert_eq!(
+            block.custom_lines[0].style,
+            LineStyle::Custom("rust".to_string())
+        );
+        assert_eq!(block.custom_lines[0].link, None);
+    }
+
+    #[test]
+    fn test_plugin_lines_keep_links() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("main.rs");
+        fs::write(&file, "fn main() {}\n").unwrap();
+
+        let plugins: Vec<Arc<dyn Plugin>> = vec![Arc::new(Linked(Marker("rust", Language::Rust)))];
+        let block = extract_plugin_metadata(&file, &plugins, &ExtractionConfig::default()).unwrap();
+
+        assert_eq!(block.custom_lines[0].content, "rust: fn main() {}");
+        assert_eq!(
+            block.custom_lines[0].style,
+            LineStyle::Custom("rust".to_string())
+        );
+        assert_eq!(
+            block.custom_lines[0].link.as_deref(),
+            Some("https://example.com/rust")
+        );
+    }
+
+    #[test]
+    fn test_streaming_walker_runs_plugins() {
+        use crate::tree::{ChildCount, NodeFlags, StreamingOutput, StreamingWalker, WalkerConfig};
+
+        #[derive(Default)]
+        struct Collect(Vec<(String, MetadataBlock)>);
+
+        impl StreamingOutput for Collect {
+            fn output_node(
+                &mut self,
+                name: &str,
+                metadata: Option<MetadataBlock>,
+                _is_dir: bool,
+                _is_last: bool,
+                _prefix: &str,
+                _is_root: bool,
+                _size: Option<u64>,
+                _child_count: Option<ChildCount>,
+                _flags: NodeFlags,
+            ) -> std::io::Result<()> {
+                if let Some(block) = metadata {
+                    self.0.push((name.to_string(), block));
+                }
+                Ok(())
+            }
+
+            fn finish(&mut self, _dirs: usize, _files: usize) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("main.rs"), "//! Entry\nfn main() {}\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "notes\n").unwrap();
+
+        let config = WalkerConfig::builder()
+            .show_all(true)
+            .extract_comments(true)
+            .plugin(Marker("rust", Language::Rust))
+            .build()
+            .unwrap();
+        let mut output = Collect::default();
+        StreamingWalker::new(config)
+            .walk_streaming(dir.path(), &mut output)
+            .unwrap();
+
+        assert_eq!(output.0.len(), 1);
+        let (name, block) = &output.0[0];
+        assert_eq!(name, "main.rs");
+        assert_eq!(block.comment_lines[0].content, "Entry");
+        assert_eq!(block.custom_lines[0].content, "rust: //! Entry");
+    }
+
+    #[test]
+    fn test_no_plugins_or_unknown_language() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("data.bin");
+        fs::write(&file, "binary").unwrap();
+
+        assert!(extract_plugin_metadata(&file, &[], &ExtractionConfig::default()).is_none());
+        let plugins: Vec<Arc<dyn Plugin>> = vec![Arc::new(Marker("rust", Language::Rust))];
+        assert!(extract_plugin_metadata(&file, &plugins, &ExtractionConfig::default()).is_none());
+    }
+}
@@ -0,0 +1,319 @@
+//! Named presets of command-line flags for `--profile`
+//!
+//! Profiles are stored in `$XDG_CONFIG_HOME/fruit/profiles.toml`, falling
+//! back to `~/.config/fruit/profiles.toml`, with one table per profile:
+//!
+//! ```toml
+//! [llm-context]
+//! markdown = true
+//! full_comment = true
+//! ignore = ["*.lock", "dist"]
+//! ```
+//!
+//! Keys are flag names, spelled with `-` or `_`, and values are booleans,
+//! integers, strings or arrays of them. A dotted table such as `[work.docs]`
+//! holds the profile `work.docs`.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+/// File name of the profiles file within the fruit config directory.
+pub const PROFILES_FILE_NAME: &str = "profiles.toml";
+
+/// Value of a profile setting.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ProfileValue {
+    Bool(bool),
+    Integer(i64),
+    String(String),
+    Array(Vec<ProfileValue>),
+}
+
+impl ProfileValue {
+    /// The setting as command-line arguments for the flag `--{long}`: `true`
+    /// is the bare flag, `false` adds nothing, and arrays repeat the flag.
+    pub fn to_args(&self, long: &str) -> Vec<String> {
+        match self {
+            ProfileValue::Bool(true) => vec![format!("--{}", long)],
+            ProfileValue::Bool(false) => Vec::new(),
+            ProfileValue::Integer(n) => vec![format!("--{}={}", long, n)],
+            ProfileValue::String(s) => vec![format!("--{}={}", long, s)],
+            ProfileValue::Array(values) => values.iter().flat_map(|v| v.to_args(long)).collect(),
+        }
+    }
+}
+
+/// A named set of flag settings, in file order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    pub settings: Vec<(String, ProfileValue)>,
+}
+
+impl Profile {
+    /// The profile's settings as a TOML table.
+    fn to_table(&self) -> Result<Table, toml::ser::Error> {
+        self.settings
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), Value::try_from(value)?)))
+            .collect()
+    }
+}
+
+/// Default profiles file location: `$XDG_CONFIG_HOME/fruit/profiles.toml`,
+/// falling back to `~/.config/fruit/profiles.toml`.
+pub fn default_path() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("fruit").join(PROFILES_FILE_NAME))
+}
+
+/// Load the profiles stored at `path`. A missing file has no profiles.
+pub fn load_profiles(path: &Path) -> Result<Vec<Profile>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => parse_profiles(&content),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Parse the profiles of a profiles file.
+pub fn parse_profiles(content: &str) -> Result<Vec<Profile>, String> {
+    let table: Table = content
+        .parse()
+        .map_err(|e: toml::de::Error| e.to_string())?;
+    let mut profiles = Vec::new();
+    collect_profiles("", table, &mut profiles)?;
+    Ok(profiles)
+}
+
+/// Add the profiles in `table`, whose dotted name is `prefix`, to `profiles`.
+/// A table is a profile when it has settings or no nested tables.
+fn collect_profiles(prefix: &str, table: Table, profiles: &mut Vec<Profile>) -> Result<(), String> {
+    let mut settings = Vec::new();
+    let mut nested = Vec::new();
+    for (key, value) in table {
+        match value {
+            Value::Table(table) => nested.push((key, table)),
+            _ if prefix.is_empty() => {
+                return Err(format!("'{}' is not inside a [profile] table", key));
+            }
+            value => {
+                let value = value.try_into().map_err(|_| {
+                    format!("unsupported value for '{}' in profile '{}'", key, prefix)
+                })?;
+                settings.push((key, value));
+            }
+        }
+    }
+
+    if !prefix.is_empty() && (!settings.is_empty() || nested.is_empty()) {
+        profiles.push(Profile {
+            name: prefix.to_string(),
+            settings,
+        });
+    }
+    for (key, table) in nested {
+        let name = if prefix.is_empty() {
+            key
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        collect_profiles(&name, table, profiles)?;
+    }
+    Ok(())
+}
+
+/// Store `profile` in the profiles file at `path`, replacing the settings of
+/// any profile of the same name. Other profiles are kept, but the file is
+/// rewritten, so comments and formatting are not.
+pub fn save_profile(path: &Path, profile: &Profile) -> io::Result<()> {
+    let mut table: Table = match fs::read_to_string(path) {
+        Ok(content) => content
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Table::new(),
+        Err(e) => return Err(e),
+    };
+
+    // Walk down to the table holding the profile, creating tables as needed
+    let mut segments: Vec<&str> = profile.name.split('.').collect();
+    let last = segments.pop().unwrap_or_default();
+    let mut parent = &mut table;
+    for segment in segments {
+        parent = parent
+            .entry(segment)
+            .or_insert_with(|| Value::Table(Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("'{}' is not a table", segment),
+                )
+            })?;
+    }
+
+    // Profiles nested inside the replaced one stay
+    let mut settings = profile.to_table().map_err(io::Error::other)?;
+    if let Some(Value::Table(old)) = parent.get(last) {
+        settings.extend(
+            old.iter()
+                .filter(|(_, value)| value.is_table())
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+    }
+    parent.insert(last.to_string(), Value::Table(settings));
+
+    let content = toml::to_string(&table).map_err(io::Error::other)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profiles() {
+        let content = "# My presets\n[llm-context]\nmarkdown = true\nlevel = 3  # shallow\ntheme = \"dark\"\nignore = ['*.lock', \"dist\"]\n\n[quick]\nno_lines = false\n";
+        let profiles = parse_profiles(content).unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "llm-context");
+        assert_eq!(
+            profiles[0].settings,
+            vec![
+                ("markdown".to_string(), ProfileValue::Bool(true)),
+                ("level".to_string(), ProfileValue::Integer(3)),
+                (
+                    "theme".to_string(),
+                    ProfileValue::String("dark".to_string())
+                ),
+                (
+                    "ignore".to_string(),
+                    ProfileValue::Array(vec![
+                        ProfileValue::String("*.lock".to_string()),
+                        ProfileValue::String("dist".to_string()),
+                    ])
+                ),
+            ]
+        );
+        assert_eq!(
+            profiles[1].settings,
+            vec![("no_lines".to_string(), ProfileValue::Bool(false))]
+        );
+    }
+
+    #[test]
+    fn test_parse_profiles_toml_syntax() {
+        let content = "[\"llm context\"]\nignore = [\n  \"*.lock\",  # generated\n  \"dist\",\n]\n\"no-lines\" = true\n\n[work.docs]\nlevel = 2\n";
+        let profiles = parse_profiles(content).unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "llm context");
+        assert_eq!(
+            profiles[0].settings,
+            vec![
+                (
+                    "ignore".to_string(),
+                    ProfileValue::Array(vec![
+                        ProfileValue::String("*.lock".to_string()),
+                        ProfileValue::String("dist".to_string()),
+                    ])
+                ),
+                ("no-lines".to_string(), ProfileValue::Bool(true)),
+            ]
+        );
+        assert_eq!(profiles[1].name, "work.docs");
+        assert_eq!(
+            profiles[1].settings,
+            vec![("level".to_string(), ProfileValue::Integer(2))]
+        );
+    }
+
+    #[test]
+    fn test_parse_profiles_errors() {
+        assert!(
+            parse_profiles("markdown = true\n")
+                .unwrap_err()
+                .contains("not inside a [profile] table")
+        );
+        assert!(parse_profiles("[a]\nlevel = soon\n").is_err());
+        assert!(
+            parse_profiles("[a]\nratio = 0.5\n")
+                .unwrap_err()
+                .contains("unsupported value for 'ratio'")
+        );
+        assert!(parse_profiles("[a]\ntheme = \"dark\" x\n").is_err());
+        assert!(parse_profiles("[a]\n[a]\n").is_err());
+    }
+
+    #[test]
+    fn test_profile_value_to_args() {
+        assert_eq!(ProfileValue::Bool(true).to_args("types"), vec!["--types"]);
+        assert!(ProfileValue::Bool(false).to_args("types").is_empty());
+        assert_eq!(ProfileValue::Integer(2).to_args("level"), vec!["--level=2"]);
+        assert_eq!(
+            ProfileValue::Array(vec![
+                ProfileValue::String("a".to_string()),
+                ProfileValue::String("b".to_string()),
+            ])
+            .to_args("ignore"),
+            vec!["--ignore=a", "--ignore=b"]
+        );
+    }
+
+    #[test]
+    fn test_save_profile_replaces_table_and_round_trips() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("fruit").join(PROFILES_FILE_NAME);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            "# Presets\n[old]\ntypes = true\n\n[old.nested]\nlevel = 2\n\n[other]\nignore = [\n  \"dist\",\n]\n",
+        )
+        .unwrap();
+
+        let profile = Profile {
+            name: "old".to_string(),
+            settings: vec![(
+                "prefix".to_string(),
+                ProfileValue::String("say \"hi\"".to_string()),
+            )],
+        };
+        save_profile(&path, &profile).unwrap();
+
+        let profiles = load_profiles(&path).unwrap();
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["old", "old.nested", "other"]);
+        assert_eq!(profiles[0], profile);
+        assert_eq!(
+            profiles[2].settings,
+            vec![(
+                "ignore".to_string(),
+                ProfileValue::Array(vec![ProfileValue::String("dist".to_string())])
+            )]
+        );
+    }
+
+    #[test]
+    fn test_save_profile_creates_dotted_table() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("fruit").join(PROFILES_FILE_NAME);
+
+        let profile = Profile {
+            name: "work.docs".to_string(),
+            settings: vec![("level".to_string(), ProfileValue::Integer(1))],
+        };
+        save_profile(&path, &profile).unwrap();
+
+        assert_eq!(load_profiles(&path).unwrap(), vec![profile]);
+    }
+}
@@ -0,0 +1,436 @@
+//! Dependency graph of internal imports for `--stats --import-graph`
+//!
+//! Each file's internal imports are resolved to the project files they name,
+//! giving a directed graph from importers to the files they depend on.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::imports::{FileImports, module_key, resolve_import};
+
+/// Error from `ImportGraph::topological_sort`: the files import each other
+/// in a cycle, so no file can come before all of its dependencies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+    /// The files of one cycle, each importing the next and the last
+    /// importing the first
+    pub cycle: Vec<PathBuf>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = self
+            .cycle
+            .iter()
+            .chain(self.cycle.first())
+            .map(|path| path.display().to_string())
+            .collect();
+        write!(f, "circular import: {}", names.join(" → "))
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+/// Internal import dependencies between the files of a project.
+#[derive(Debug, Clone, Default)]
+pub struct ImportGraph {
+    /// Each file mapped to the files it imports, sorted
+    dependencies: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl ImportGraph {
+    /// Wrap a map from each file to the files it imports.
+    pub fn new(dependencies: HashMap<PathBuf, Vec<PathBuf>>) -> Self {
+        Self { dependencies }
+    }
+
+    /// Resolve the internal imports of `files` to the files they refer to.
+    /// Imports that match no file in `files` are dropped.
+    pub fn from_imports(files: &[(PathBuf, Vec<String>)]) -> Self {
+        let relative: Vec<String> = files
+            .iter()
+            .map(|(path, _)| path.to_string_lossy().replace('\\', "/"))
+            .collect();
+        let module_keys: Vec<String> = relative.iter().map(|path| module_key(path)).collect();
+
+        let dependencies = files
+            .iter()
+            .enumerate()
+            .map(|(i, (path, imports))| {
+                let mut targets: Vec<PathBuf> = imports
+                    .iter()
+                    .filter_map(|import| resolve_import(import, &relative[i], &module_keys))
+                    .filter(|&target| target != i)
+                    .map(|target| files[target].0.clone())
+                    .collect();
+                targets.sort();
+                targets.dedup();
+                (path.clone(), targets)
+            })
+            .collect();
+        Self { dependencies }
+    }
+
+    /// Resolve the internal imports of each file, as extracted by
+    /// `extract_imports`. External and standard library imports are ignored.
+    pub fn from_file_imports(files: &[(&Path, &FileImports)]) -> Self {
+        let files: Vec<(PathBuf, Vec<String>)> = files
+            .iter()
+            .map(|(path, imports)| (path.to_path_buf(), imports.internal.clone()))
+            .collect();
+        Self::from_imports(&files)
+    }
+
+    /// The underlying map from each file to the files it imports.
+    pub fn dependencies(&self) -> &HashMap<PathBuf, Vec<PathBuf>> {
+        &self.dependencies
+    }
+
+    /// Circular dependencies, each listed from the file where the cycle was
+    /// entered. Found by a depth-first topological sort: an import of a file
+    /// that is still being visited closes a cycle.
+    pub fn find_cycles(&self) -> Vec<Vec<PathBuf>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            Visiting,
+            Done,
+        }
+
+        fn visit<'a>(
+            graph: &'a ImportGraph,
+            file: &'a PathBuf,
+            state: &mut HashMap<&'a PathBuf, State>,
+            stack: &mut Vec<&'a PathBuf>,
+            cycles: &mut Vec<Vec<PathBuf>>,
+        ) {
+            state.insert(file, State::Visiting);
+            stack.push(file);
+            for dependency in graph.dependencies.get(file).into_iter().flatten() {
+                match state.get(dependency) {
+                    Some(State::Visiting) => {
+                        let start = stack.iter().position(|f| *f == dependency).unwrap_or(0);
+                        cycles.push(stack[start..].iter().map(|f| (*f).clone()).collect());
+                    }
+                    Some(State::Done) => {}
+                    None => visit(graph, dependency, state, stack, cycles),
+                }
+            }
+            stack.pop();
+            state.insert(file, State::Done);
+        }
+
+        let mut state = HashMap::new();
+        let mut cycles = Vec::new();
+        for file in self.sorted_files() {
+            if !state.contains_key(file) {
+                visit(self, file, &mut state, &mut Vec::new(), &mut cycles);
+            }
+        }
+        cycles
+    }
+
+    /// Files that `path` imports directly, sorted.
+    pub fn dependencies_of(&self, path: &Path) -> Vec<PathBuf> {
+        self.dependencies.get(path).cloned().unwrap_or_default()
+    }
+
+    /// Files that import `path` directly, sorted.
+    pub fn dependents_of(&self, path: &Path) -> Vec<PathBuf> {
+        self.sorted_files()
+            .into_iter()
+            .filter(|file| {
+                self.dependencies[*file]
+                    .iter()
+                    .any(|dependency| dependency == path)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Every file ordered so that each comes after the files it imports, or
+    /// the first cycle found if there is no such order.
+    pub fn topological_sort(&self) -> Result<Vec<PathBuf>, CycleError> {
+        if let Some(cycle) = self.find_cycles().into_iter().next() {
+            return Err(CycleError { cycle });
+        }
+        // Without cycles every component is a single file, already in order
+        Ok(self
+            .strongly_connected_components()
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    /// Groups of files that all depend on each other, found with Tarjan's
+    /// algorithm. Every file is in exactly one group; files outside any cycle
+    /// are groups of their own. Groups are listed after the groups they
+    /// import, and the files in each are sorted.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<PathBuf>> {
+        struct Tarjan<'a> {
+            graph: &'a ImportGraph,
+            next_index: usize,
+            /// Visit order of each visited file
+            indices: HashMap<&'a PathBuf, usize>,
+            stack: Vec<&'a PathBuf>,
+            on_stack: HashSet<&'a PathBuf>,
+            components: Vec<Vec<PathBuf>>,
+        }
+
+        impl<'a> Tarjan<'a> {
+            fn visit(&mut self, file: &'a PathBuf) -> usize {
+                let index = self.next_index;
+                self.next_index += 1;
+                // Lowest index of a file still on the stack reachable from here
+                let mut low = index;
+                self.indices.insert(file, index);
+                self.stack.push(file);
+                self.on_stack.insert(file);
+
+                for dependency in self.graph.dependencies.get(file).into_iter().flatten() {
+                    match self.indices.get(dependency) {
+                        None => low = low.min(self.visit(dependency)),
+                        Some(&dependency_index) if self.on_stack.contains(dependency) => {
+                            low = low.min(dependency_index);
+                        }
+                        Some(_) => {}
+                    }
+                }
+
+                if low == index {
+                    let mut component = Vec::new();
+                    while let Some(member) = self.stack.pop() {
+                        self.on_stack.remove(member);
+                        component.push(member.clone());
+                        if member == file {
+                            break;
+                        }
+                    }
+                    component.sort();
+                    self.components.push(component);
+                }
+                low
+            }
+        }
+
+        let mut tarjan = Tarjan {
+            graph: self,
+            next_index: 0,
+            indices: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: HashSet::new(),
+            components: Vec::new(),
+        };
+        for file in self.sorted_files() {
+            if !tarjan.indices.contains_key(file) {
+                tarjan.visit(file);
+            }
+        }
+        tarjan.components
+    }
+
+    /// Files that depend on `path`, directly or through other files, sorted.
+    pub fn transitive_dependents(&self, path: &Path) -> Vec<PathBuf> {
+        let mut importers: HashMap<&Path, Vec<&Path>> = HashMap::new();
+        for (file, dependencies) in &self.dependencies {
+            for dependency in dependencies {
+                importers.entry(dependency).or_default().push(file);
+            }
+        }
+
+        let mut seen: HashSet<&Path> = HashSet::from([path]);
+        let mut queue = VecDeque::from([path]);
+        while let Some(file) = queue.pop_front() {
+            for &importer in importers.get(file).into_iter().flatten() {
+                if seen.insert(importer) {
+                    queue.push_back(importer);
+                }
+            }
+        }
+
+        let mut dependents: Vec<PathBuf> = seen
+            .into_iter()
+            .filter(|file| *file != path)
+            .map(Path::to_path_buf)
+            .collect();
+        dependents.sort();
+        dependents
+    }
+
+    /// The number of `transitive_dependents` of every file, computed in one
+    /// pass over the strongly connected components instead of a search per
+    /// file. Files in a cycle are dependents of each other.
+    pub fn transitive_dependent_counts(&self) -> HashMap<PathBuf, usize> {
+        let components = self.strongly_connected_components();
+        let component_of: HashMap<&PathBuf, usize> = components
+            .iter()
+            .enumerate()
+            .flat_map(|(index, component)| component.iter().map(move |file| (file, index)))
+            .collect();
+
+        // Other components that import each component directly
+        let mut importers: Vec<HashSet<usize>> = vec![HashSet::new(); components.len()];
+        for (file, dependencies) in &self.dependencies {
+            let importer = component_of[file];
+            for dependency in dependencies {
+                let imported = component_of[dependency];
+                if imported != importer {
+                    importers[imported].insert(importer);
+                }
+            }
+        }
+
+        // Components come after the ones they import, so walking them in
+        // reverse finishes every importer before the components it imports.
+        // Each component's dependents are kept as a bitset of components.
+        let words = components.len().div_ceil(64);
+        let mut dependents: Vec<Vec<u64>> = vec![Vec::new(); components.len()];
+        for index in (0..components.len()).rev() {
+            let mut bits = vec![0u64; words];
+            for &importer in &importers[index] {
+                bits[importer / 64] |= 1 << (importer % 64);
+                for (word, importer_word) in bits.iter_mut().zip(&dependents[importer]) {
+                    *word |= importer_word;
+                }
+            }
+            dependents[index] = bits;
+        }
+
+        let mut counts = HashMap::new();
+        for (index, component) in components.iter().enumerate() {
+            let bits = &dependents[index];
+            let outside: usize = (0..components.len())
+                .filter(|&other| bits[other / 64] & (1 << (other % 64)) != 0)
+                .map(|other| components[other].len())
+                .sum();
+            for file in component {
+                counts.insert(file.clone(), outside + component.len() - 1);
+            }
+        }
+        counts
+    }
+
+    /// Files that no other file imports, sorted.
+    pub fn orphans(&self) -> Vec<PathBuf> {
+        let imported: HashSet<&PathBuf> = self.dependencies.values().flatten().collect();
+        self.sorted_files()
+            .into_iter()
+            .filter(|file| !imported.contains(file))
+            .cloned()
+            .collect()
+    }
+
+    /// Every file in the graph, sorted so results are deterministic.
+    fn sorted_files(&self) -> Vec<&PathBuf> {
+        let mut files: Vec<&PathBuf> = self.dependencies.keys().collect();
+        files.sort();
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(files: &[(&str, &[&str])]) -> ImportGraph {
+        let files: Vec<(PathBuf, Vec<String>)> = files
+            .iter()
+            .map(|(path, imports)| {
+                (
+                    PathBuf::from(path),
+                    imports.iter().map(|s| s.to_string()).collect(),
+                )
+            })
+            .collect();
+        ImportGraph::from_imports(&files)
+    }
+
+    fn paths(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn test_from_imports_resolves_internal_imports() {
+        let graph = graph(&[
+            ("src/app.ts", &["./api", "./missing"]),
+            ("src/api.ts", &["./http"]),
+            ("src/http.ts", &[]),
+        ]);
+        assert_eq!(
+            graph.dependencies()[Path::new("src/app.ts")],
+            paths(&["src/api.ts"])
+        );
+        assert!(graph.dependencies()[Path::new("src/http.ts")].is_empty());
+        assert_eq!(graph.orphans(), paths(&["src/app.ts"]));
+        assert_eq!(
+            graph.transitive_dependents(Path::new("src/http.ts")),
+            paths(&["src/api.ts", "src/app.ts"])
+        );
+        let counts = graph.transitive_dependent_counts();
+        assert_eq!(counts[Path::new("src/http.ts")], 2);
+        assert_eq!(counts[Path::new("src/api.ts")], 1);
+        assert_eq!(counts[Path::new("src/app.ts")], 0);
+        assert!(graph.find_cycles().is_empty());
+        assert_eq!(
+            graph.topological_sort().unwrap(),
+            paths(&["src/http.ts", "src/api.ts", "src/app.ts"])
+        );
+        assert_eq!(
+            graph.dependents_of(Path::new("src/api.ts")),
+            paths(&["src/app.ts"])
+        );
+        assert_eq!(
+            graph.dependencies_of(Path::new("src/api.ts")),
+            paths(&["src/http.ts"])
+        );
+    }
+
+    #[test]
+    fn test_find_cycles() {
+        let graph = graph(&[
+            ("a.py", &[".b"]),
+            ("b.py", &[".c"]),
+            ("c.py", &[".a"]),
+            ("d.py", &[".a"]),
+        ]);
+        assert_eq!(graph.find_cycles(), vec![paths(&["a.py", "b.py", "c.py"])]);
+        assert_eq!(graph.orphans(), paths(&["d.py"]));
+        assert_eq!(
+            graph.transitive_dependents(Path::new("b.py")),
+            paths(&["a.py", "c.py", "d.py"])
+        );
+        let counts = graph.transitive_dependent_counts();
+        assert_eq!(counts[Path::new("b.py")], 3);
+        assert_eq!(counts[Path::new("d.py")], 0);
+        assert_eq!(
+            graph.strongly_connected_components(),
+            vec![paths(&["a.py", "b.py", "c.py"]), paths(&["d.py"])]
+        );
+        let error = graph.topological_sort().unwrap_err();
+        assert_eq!(error.cycle, paths(&["a.py", "b.py", "c.py"]));
+        assert_eq!(
+            error.to_string(),
+            "circular import: a.py → b.py → c.py → a.py"
+        );
+    }
+
+    #[test]
+    fn test_from_file_imports_ignores_external_imports() {
+        let app = FileImports {
+            external: vec!["requests".to_string()],
+            std: vec!["os".to_string()],
+            internal: vec![".models".to_string()],
+            filtered: false,
+        };
+        let models = FileImports::default();
+        let graph = ImportGraph::from_file_imports(&[
+            (Path::new("app.py"), &app),
+            (Path::new("models.py"), &models),
+        ]);
+        assert_eq!(
+            graph.dependencies_of(Path::new("app.py")),
+            paths(&["models.py"])
+        );
+        assert!(graph.dependencies_of(Path::new("models.py")).is_empty());
+    }
+}
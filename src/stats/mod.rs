@@ -0,0 +1,950 @@
+//! Codebase statistics collection and display
+//!
+//! This module collects and formats aggregate statistics about a codebase:
+//! file counts by type, line counts, and language breakdown, plus per-file
+//! rankings (largest files, most TODOs, most imports).
+//!
+//! - `import_graph`: Dependency graph of internal imports (`--import-graph`)
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+use crate::comments::extract_first_comment_with_config;
+use crate::file_utils::ExtractionConfig;
+use crate::imports::{extract_imports_with_config, supports_imports};
+use crate::language::Language;
+use crate::todos::{TodoItem, extract_todos_with_config, is_annotation_marker};
+
+mod import_graph;
+
+pub use import_graph::{CycleError, ImportGraph};
+
+/// Maximum file size for line counting (5MB).
+const MAX_FILE_SIZE_FOR_LINES: u64 = 5_000_000;
+
+/// Number of files shown in per-file rankings.
+const TOP_FILES_COUNT: usize = 10;
+
+/// Version of the `--stats --json` format, bumped on incompatible changes.
+pub const STATS_JSON_VERSION: u32 = 1;
+
+/// Collected statistics about a codebase.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CodebaseStats {
+    /// Total number of files
+    pub files: usize,
+    /// Total number of directories
+    pub directories: usize,
+    /// Total lines of code (if counted)
+    pub total_lines: Option<usize>,
+    /// Total TODO/FIXME markers (if counted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_todos: Option<usize>,
+    /// Statistics by language
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub by_language: Vec<LanguageStats>,
+    /// Per-file measurements, used for the `top_files_by_*` rankings
+    #[serde(skip)]
+    pub file_stats: Vec<FileStats>,
+    /// Absolute path of the scanned directory (if the collector had a root)
+    #[serde(skip)]
+    pub scan_root: Option<PathBuf>,
+    /// Dependencies between files through internal imports (if built)
+    #[serde(skip)]
+    pub import_graph: Option<ImportGraph>,
+    /// Whether reports include the load order and strongly connected
+    /// components of the import graph
+    #[serde(skip)]
+    pub analyze_import_graph: bool,
+}
+
+impl CodebaseStats {
+    /// The stable JSON document printed by `--stats --json`:
+    ///
+    /// ```json
+    /// {
+    ///   "version": 1,
+    ///   "generated_at": "2024-01-01T12:00:00Z",
+    ///   "scan_root": "/path/to/project",
+    ///   "total_files": 42,
+    ///   "total_dirs": 7,
+    ///   "total_lines": 3141,
+    ///   "languages": [{ "name": "Rust", "file_count": 42, "line_count": 3141, "comment_coverage": 0.78 }]
+    /// }
+    /// ```
+    ///
+    /// Counts that were not collected are `null`. When the import graph was
+    /// built, an `import_graph` object lists its `cycles` and `orphans`, and
+    /// with `analyze_import_graph` its `load_order` (`null` when there are
+    /// cycles) and the `components` of more than one file.
+    pub fn to_json_value(&self) -> serde_json::Value {
+        let mut value = serde_json::json!({
+            "version": STATS_JSON_VERSION,
+            "generated_at": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "scan_root": self.scan_root.as_ref().map(|root| root.to_string_lossy()),
+            "total_files": self.files,
+            "total_dirs": self.directories,
+            "total_lines": self.total_lines,
+            "languages": self.language_breakdown_json(),
+        });
+        if let Some(graph) = &self.import_graph {
+            value["import_graph"] = serde_json::json!({
+                "cycles": graph.find_cycles(),
+                "orphans": graph.orphans(),
+            });
+            if self.analyze_import_graph {
+                value["import_graph"]["load_order"] =
+                    serde_json::json!(graph.topological_sort().ok());
+                value["import_graph"]["components"] = serde_json::json!(graph_components(graph));
+            }
+        }
+        value
+    }
+
+    /// The `languages` array of `to_json_value`, most files first.
+    pub fn language_breakdown_json(&self) -> serde_json::Value {
+        self.by_language
+            .iter()
+            .map(LanguageStats::to_json_value)
+            .collect()
+    }
+
+    /// The `n` files with the most TODO/FIXME markers (requires `count_todos`).
+    pub fn top_files_by_todos(&self, n: usize) -> Vec<(PathBuf, usize)> {
+        self.top_files(n, |f| f.todos)
+    }
+
+    /// The `n` largest files by size in bytes.
+    pub fn top_files_by_size(&self, n: usize) -> Vec<(PathBuf, u64)> {
+        self.top_files(n, |f| Some(f.size))
+    }
+
+    /// The `n` files with the most import statements (requires `count_imports`).
+    pub fn top_files_by_imports(&self, n: usize) -> Vec<(PathBuf, usize)> {
+        self.top_files(n, |f| f.imports)
+    }
+
+    /// The `n` most complex files, using line count as a proxy (requires `count_lines`).
+    pub fn top_files_by_complexity(&self, n: usize) -> Vec<(PathBuf, usize)> {
+        self.top_files(n, |f| f.lines)
+    }
+
+    /// The `n` files with the most transitive dependents (requires `build_import_graph`).
+    pub fn top_files_by_dependents(&self, n: usize) -> Vec<(PathBuf, usize)> {
+        let Some(graph) = &self.import_graph else {
+            return Vec::new();
+        };
+        let mut ranked: Vec<(PathBuf, usize)> = graph
+            .transitive_dependent_counts()
+            .into_iter()
+            .filter(|(_, dependents)| *dependents > 0)
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked.truncate(n);
+        ranked
+    }
+
+    /// Rank files by `key`, largest first, skipping files where it is missing or zero.
+    /// Ties are broken by path so the ranking is stable.
+    fn top_files<T: Ord + Copy + Default>(
+        &self,
+        n: usize,
+        key: impl Fn(&FileStats) -> Option<T>,
+    ) -> Vec<(PathBuf, T)> {
+        let mut ranked: Vec<(PathBuf, T)> = self
+            .file_stats
+            .iter()
+            .filter_map(|f| Some((f.path.clone(), key(f)?)))
+            .filter(|(_, value)| *value != T::default())
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked.truncate(n);
+        ranked
+    }
+}
+
+/// Measurements for a single file.
+#[derive(Debug, Clone, Default)]
+pub struct FileStats {
+    /// Path relative to the collector's root (if one was set)
+    pub path: PathBuf,
+    /// Size in bytes
+    pub size: u64,
+    /// Number of lines (if counted)
+    pub lines: Option<usize>,
+    /// Number of TODO/FIXME markers (if counted)
+    pub todos: Option<usize>,
+    /// Number of import statements (if counted)
+    pub imports: Option<usize>,
+}
+
+/// Statistics for a single language.
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageStats {
+    /// Language name
+    pub language: String,
+    /// Number of files
+    pub files: usize,
+    /// Number of lines (if counted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<usize>,
+    /// File extensions for this language
+    pub extensions: Vec<String>,
+    /// Number of files with a file-level comment (if checked)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files_with_comments: Option<usize>,
+    /// Number of TODO/FIXME markers of any kind (if counted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub todo_count: Option<usize>,
+    /// Number of those markers that are FIXMEs (if counted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixme_count: Option<usize>,
+}
+
+impl LanguageStats {
+    /// Fraction of files with a file-level comment, from 0.0 to 1.0 (if checked).
+    pub fn comment_coverage(&self) -> Option<f64> {
+        let commented = self.files_with_comments?;
+        Some(if self.files == 0 {
+            0.0
+        } else {
+            commented as f64 / self.files as f64
+        })
+    }
+
+    /// TODO/FIXME markers per 100 lines (if both were counted).
+    pub fn todo_density(&self) -> Option<f64> {
+        let (todos, lines) = (self.todo_count?, self.lines?);
+        Some(if lines == 0 {
+            0.0
+        } else {
+            todos as f64 * 100.0 / lines as f64
+        })
+    }
+
+    /// This language as an entry of the `--stats --json` `languages` array.
+    /// Comment coverage is rounded to two decimal places.
+    pub fn to_json_value(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.language,
+            "file_count": self.files,
+            "line_count": self.lines,
+            "comment_coverage": self
+                .comment_coverage()
+                .map(|coverage| (coverage * 100.0).round() / 100.0),
+        })
+    }
+}
+
+/// Configuration for statistics collection.
+#[derive(Debug, Clone, Default)]
+pub struct StatsConfig {
+    /// Whether to count lines of code
+    pub count_lines: bool,
+    /// Whether to count TODO/FIXME markers per file
+    pub count_todos: bool,
+    /// Whether to count import statements per file
+    pub count_imports: bool,
+    /// Whether to check each file for a file-level comment (for comment coverage)
+    pub count_comments: bool,
+    /// Whether to build a dependency graph from internal imports
+    pub build_import_graph: bool,
+    /// Whether to report the load order and strongly connected components
+    /// of the import graph (requires `build_import_graph`)
+    pub analyze_import_graph: bool,
+    /// Settings passed to the extractors, such as the maximum file size
+    pub extraction: ExtractionConfig,
+}
+
+/// Statistics collector that accumulates data during tree traversal.
+#[derive(Debug, Default)]
+pub struct StatsCollector {
+    config: StatsConfig,
+    files: usize,
+    directories: usize,
+    /// Maps extension -> (file_count, line_count, commented_file_count)
+    by_extension: HashMap<String, (usize, usize, usize)>,
+    /// Maps extension -> (todo_count, fixme_count)
+    todos_by_extension: HashMap<String, (usize, usize)>,
+    /// Recorded paths are made relative to this directory
+    root: Option<PathBuf>,
+    file_stats: Vec<FileStats>,
+    /// Internal imports of each source file, for the import graph
+    internal_imports: Vec<(PathBuf, Vec<String>)>,
+}
+
+impl StatsCollector {
+    pub fn new(config: StatsConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Report per-file paths relative to `root` instead of as recorded.
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Record a file in the statistics.
+    pub fn record_file(&mut self, path: &Path) {
+        self.files += 1;
+
+        let mut file_stats = FileStats {
+            path: self
+                .root
+                .as_deref()
+                .and_then(|root| path.strip_prefix(root).ok())
+                .unwrap_or(path)
+                .to_path_buf(),
+            size: path.metadata().map(|m| m.len()).unwrap_or(0),
+            ..FileStats::default()
+        };
+        if self.config.count_todos {
+            // Annotations such as SAFETY document code rather than ask for work
+            let mut todos =
+                extract_todos_with_config(path, &self.config.extraction).unwrap_or_default();
+            todos.retain(|todo| !is_annotation_marker(&todo.marker_type));
+            for todo in &todos {
+                self.record_todo(path, todo);
+            }
+            file_stats.todos = Some(todos.len());
+        }
+        if self.config.count_imports || self.config.build_import_graph {
+            let imports = extract_imports_with_config(path, &self.config.extraction);
+            if self.config.count_imports {
+                file_stats.imports = Some(imports.as_ref().map_or(0, |imports| {
+                    imports.external.len() + imports.std.len() + imports.internal.len()
+                }));
+            }
+            // Files without import support are left out of the graph, but
+            // supported files without imports are in it as dependencies
+            let internal = match imports {
+                Some(imports) => Some(imports.internal),
+                None => Language::from_path(path)
+                    .filter(|language| supports_imports(*language))
+                    .map(|_| Vec::new()),
+            };
+            if self.config.build_import_graph
+                && let Some(internal) = internal
+            {
+                self.internal_imports
+                    .push((file_stats.path.clone(), internal));
+            }
+        }
+
+        let entry = self
+            .by_extension
+            .entry(extension_key(path))
+            .or_insert((0, 0, 0));
+        entry.0 += 1;
+
+        if self.config.count_comments
+            && extract_first_comment_with_config(path, &self.config.extraction).is_some()
+        {
+            entry.2 += 1;
+        }
+
+        if self.config.count_lines
+            && let Some(lines) = count_lines(path)
+        {
+            entry.1 += lines;
+            file_stats.lines = Some(lines);
+        }
+        self.file_stats.push(file_stats);
+    }
+
+    /// Record a TODO/FIXME marker found in the file at `path`, counting it
+    /// towards the file's language. `record_file` records the markers of
+    /// each file itself when `count_todos` is set.
+    pub fn record_todo(&mut self, path: &Path, todo: &TodoItem) {
+        let entry = self
+            .todos_by_extension
+            .entry(extension_key(path))
+            .or_insert((0, 0));
+        entry.0 += 1;
+        if todo.marker_type == "FIXME" {
+            entry.1 += 1;
+        }
+    }
+
+    /// Record a directory in the statistics.
+    pub fn record_directory(&mut self) {
+        self.directories += 1;
+    }
+
+    /// Finalize and return the collected statistics.
+    pub fn finalize(self) -> CodebaseStats {
+        // Group extensions by language
+        let mut by_language: HashMap<&str, (Vec<String>, usize, usize, usize)> = HashMap::new();
+
+        for (ext, (file_count, line_count, commented)) in &self.by_extension {
+            let lang = extension_to_language(ext);
+            let entry = by_language.entry(lang).or_insert((Vec::new(), 0, 0, 0));
+            if !ext.is_empty() && !entry.0.contains(&format!(".{}", ext)) {
+                entry.0.push(format!(".{}", ext));
+            }
+            entry.1 += file_count;
+            entry.2 += line_count;
+            entry.3 += commented;
+        }
+
+        let mut todos_by_language: HashMap<&str, (usize, usize)> = HashMap::new();
+        for (ext, (todos, fixmes)) in &self.todos_by_extension {
+            let entry = todos_by_language
+                .entry(extension_to_language(ext))
+                .or_default();
+            entry.0 += todos;
+            entry.1 += fixmes;
+        }
+
+        // Convert to sorted vector
+        let mut languages: Vec<LanguageStats> = by_language
+            .into_iter()
+            .map(|(lang, (mut exts, files, lines, commented))| {
+                exts.sort();
+                let (todos, fixmes) = todos_by_language.get(lang).copied().unwrap_or_default();
+                LanguageStats {
+                    language: lang.to_string(),
+                    files,
+                    lines: if self.config.count_lines {
+                        Some(lines)
+                    } else {
+                        None
+                    },
+                    extensions: exts,
+                    files_with_comments: self.config.count_comments.then_some(commented),
+                    todo_count: self.config.count_todos.then_some(todos),
+                    fixme_count: self.config.count_todos.then_some(fixmes),
+                }
+            })
+            .collect();
+
+        // Sort by file count descending
+        languages.sort_by_key(|l| std::cmp::Reverse(l.files));
+
+        let total_lines = if self.config.count_lines {
+            Some(languages.iter().filter_map(|l| l.lines).sum())
+        } else {
+            None
+        };
+
+        let total_todos = self
+            .config
+            .count_todos
+            .then(|| languages.iter().filter_map(|l| l.todo_count).sum());
+
+        CodebaseStats {
+            files: self.files,
+            directories: self.directories,
+            total_lines,
+            total_todos,
+            by_language: languages,
+            file_stats: self.file_stats,
+            import_graph: self
+                .config
+                .build_import_graph
+                .then(|| ImportGraph::from_imports(&self.internal_imports)),
+            analyze_import_graph: self.config.analyze_import_graph,
+            scan_root: self.root.map(|root| root.canonicalize().unwrap_or(root)),
+        }
+    }
+}
+
+/// Count lines in a file using efficient byte scanning.
+fn count_lines(path: &Path) -> Option<usize> {
+    // Skip large files
+    if let Ok(metadata) = path.metadata()
+        && metadata.len() > MAX_FILE_SIZE_FOR_LINES
+    {
+        return None;
+    }
+
+    // Read file and count newlines
+    let content = std::fs::read(path).ok()?;
+    let newlines = content.iter().filter(|&&b| b == b'\n').count();
+
+    // Add 1 if file doesn't end with newline and has content
+    Some(if content.is_empty() || content.last() == Some(&b'\n') {
+        newlines
+    } else {
+        newlines + 1
+    })
+}
+
+/// Lowercased extension of `path`, the key of the per-extension counts.
+fn extension_key(path: &Path) -> String {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Map file extension to language name: the `Language` display name for
+/// source languages, otherwise a name for common data and markup formats.
+fn extension_to_language(ext: &str) -> &'static str {
+    if let Some(language) = Language::from_extension(ext) {
+        return language.display_name();
+    }
+    match ext {
+        "erb" => "Ruby",
+        // Web
+        "html" | "htm" => "HTML",
+        "css" => "CSS",
+        "scss" | "sass" => "Sass",
+        "less" => "Less",
+        // Data/Config
+        "json" => "JSON",
+        "xml" => "XML",
+        "ini" | "cfg" => "Config",
+        // Documentation
+        "txt" => "Text",
+        "rst" => "reStructuredText",
+        // Other
+        "sql" => "SQL",
+        "graphql" | "gql" => "GraphQL",
+        "proto" => "Protocol Buffers",
+        "nim" => "Nim",
+        "" => "No Extension",
+        _ => "Other",
+    }
+}
+
+/// Print statistics to stdout, colored as `color` says (see
+/// `output::color_choice`).
+pub fn print_stats(stats: &CodebaseStats, color: ColorChoice) -> io::Result<()> {
+    let mut stdout = StandardStream::stdout(color);
+
+    // Header
+    let mut bold = ColorSpec::new();
+    bold.set_bold(true);
+    stdout.set_color(&bold)?;
+    writeln!(stdout, "Codebase Statistics")?;
+    stdout.reset()?;
+    writeln!(stdout, "───────────────────")?;
+
+    // Summary
+    writeln!(stdout, "Files:        {} total", stats.files)?;
+    writeln!(stdout, "Directories:  {}", stats.directories)?;
+    writeln!(stdout)?;
+
+    // By language
+    if !stats.by_language.is_empty() {
+        stdout.set_color(&bold)?;
+        writeln!(stdout, "By Language:")?;
+        stdout.reset()?;
+
+        let mut lang_color = ColorSpec::new();
+        lang_color.set_fg(Some(Color::Cyan));
+
+        for lang in &stats.by_language {
+            write!(stdout, "  ")?;
+            stdout.set_color(&lang_color)?;
+            write!(stdout, "{:<14}", lang.language)?;
+            stdout.reset()?;
+
+            write!(stdout, "{:>4} files", lang.files)?;
+            if let Some(lines) = lang.lines {
+                write!(stdout, "  {:>8} lines", format_number(lines))?;
+            }
+            writeln!(stdout)?;
+        }
+
+        writeln!(stdout)?;
+    }
+
+    // Total lines
+    if let Some(total) = stats.total_lines {
+        stdout.set_color(&bold)?;
+        write!(stdout, "Total:       ")?;
+        stdout.reset()?;
+        writeln!(stdout, "{} lines of code", format_number(total))?;
+    }
+
+    if let Some(total) = stats.total_todos {
+        print_todo_hotspots(&mut stdout, stats, total, &bold)?;
+    }
+
+    if let Some(graph) = &stats.import_graph {
+        print_import_graph(&mut stdout, stats, graph, &bold)?;
+    }
+
+    Ok(())
+}
+
+/// Print the TODO/FIXME counts by language and the files with the most markers.
+fn print_todo_hotspots(
+    stdout: &mut StandardStream,
+    stats: &CodebaseStats,
+    total: usize,
+    bold: &ColorSpec,
+) -> io::Result<()> {
+    let mut lang_color = ColorSpec::new();
+    lang_color.set_fg(Some(Color::Cyan));
+    let mut count_color = ColorSpec::new();
+    count_color.set_fg(Some(Color::Yellow));
+
+    writeln!(stdout)?;
+    stdout.set_color(bold)?;
+    writeln!(stdout, "TODO Hotspots:")?;
+    stdout.reset()?;
+
+    if total == 0 {
+        writeln!(stdout, "  No TODO markers")?;
+        return Ok(());
+    }
+    let fixmes: usize = stats.by_language.iter().filter_map(|l| l.fixme_count).sum();
+    writeln!(
+        stdout,
+        "  {} markers ({} FIXME)",
+        format_number(total),
+        fixmes
+    )?;
+
+    for lang in &stats.by_language {
+        let (Some(todos), Some(fixmes)) = (lang.todo_count, lang.fixme_count) else {
+            continue;
+        };
+        if todos == 0 {
+            continue;
+        }
+        write!(stdout, "  ")?;
+        stdout.set_color(&lang_color)?;
+        write!(stdout, "{:<14}", lang.language)?;
+        stdout.reset()?;
+        write!(stdout, "{:>4} markers  ({} FIXME)", todos, fixmes)?;
+        if let Some(density) = lang.todo_density() {
+            write!(stdout, "  {:>5.1} per 100 lines", density)?;
+        }
+        writeln!(stdout)?;
+    }
+
+    writeln!(stdout, "  Top {} files with most TODOs:", TOP_FILES_COUNT)?;
+    for (path, todos) in &stats.top_files_by_todos(TOP_FILES_COUNT) {
+        write!(stdout, "    ")?;
+        stdout.set_color(&count_color)?;
+        write!(stdout, "{:>4}", todos)?;
+        stdout.reset()?;
+        writeln!(stdout, "  {}", path.display())?;
+    }
+
+    Ok(())
+}
+
+/// Print the cycles, most depended-on files and orphans of the import graph.
+fn print_import_graph(
+    stdout: &mut StandardStream,
+    stats: &CodebaseStats,
+    graph: &ImportGraph,
+    bold: &ColorSpec,
+) -> io::Result<()> {
+    let mut warn_color = ColorSpec::new();
+    warn_color.set_fg(Some(Color::Red));
+    let mut count_color = ColorSpec::new();
+    count_color.set_fg(Some(Color::Yellow));
+
+    writeln!(stdout)?;
+    stdout.set_color(bold)?;
+    writeln!(stdout, "Import Graph:")?;
+    stdout.reset()?;
+
+    let cycles = graph.find_cycles();
+    if cycles.is_empty() {
+        writeln!(stdout, "  No circular imports")?;
+    } else {
+        writeln!(stdout, "  Circular imports:")?;
+        for cycle in &cycles {
+            let names: Vec<String> = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|path| path.display().to_string())
+                .collect();
+            write!(stdout, "    ")?;
+            stdout.set_color(&warn_color)?;
+            writeln!(stdout, "{}", names.join(" → "))?;
+            stdout.reset()?;
+        }
+    }
+
+    let top_dependents = stats.top_files_by_dependents(TOP_FILES_COUNT);
+    if !top_dependents.is_empty() {
+        writeln!(stdout, "  Most depended on:")?;
+        for (path, dependents) in &top_dependents {
+            write!(stdout, "    ")?;
+            stdout.set_color(&count_color)?;
+            write!(stdout, "{:>4}", dependents)?;
+            stdout.reset()?;
+            writeln!(stdout, "  {}", path.display())?;
+        }
+    }
+
+    let orphans = graph.orphans();
+    if !orphans.is_empty() {
+        writeln!(stdout, "  Imported by nothing:")?;
+        for path in &orphans {
+            writeln!(stdout, "    {}", path.display())?;
+        }
+    }
+
+    if stats.analyze_import_graph {
+        match graph.topological_sort() {
+            Ok(order) => {
+                writeln!(stdout, "  Load order:")?;
+                for (i, path) in order.iter().enumerate() {
+                    write!(stdout, "    ")?;
+                    stdout.set_color(&count_color)?;
+                    write!(stdout, "{:>4}", i + 1)?;
+                    stdout.reset()?;
+                    writeln!(stdout, "  {}", path.display())?;
+                }
+            }
+            Err(_) => writeln!(stdout, "  Load order: none, imports are circular")?,
+        }
+        let components = graph_components(graph);
+        if !components.is_empty() {
+            writeln!(stdout, "  Strongly connected components:")?;
+            for component in &components {
+                let names: Vec<String> = component
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect();
+                write!(stdout, "    ")?;
+                stdout.set_color(&warn_color)?;
+                writeln!(stdout, "{}", names.join(", "))?;
+                stdout.reset()?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The strongly connected components of more than one file: groups of files
+/// that import each other.
+fn graph_components(graph: &ImportGraph) -> Vec<Vec<PathBuf>> {
+    graph
+        .strongly_connected_components()
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .collect()
+}
+
+/// Format a number with thousand separators.
+fn format_number(n: usize) -> String {
+    let s = n.to_string();
+    let chars: Vec<char> = s.chars().collect();
+    let mut result = String::new();
+
+    for (i, c) in chars.iter().rev().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            result.insert(0, ',');
+        }
+        result.insert(0, *c);
+    }
+
+    result
+}
+
+/// Print statistics as JSON (see `CodebaseStats::to_json_value` for the format).
+pub fn print_stats_json(stats: &CodebaseStats) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(&stats.to_json_value()).map_err(io::Error::other)?;
+    println!("{}", json);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension_to_language() {
+        assert_eq!(extension_to_language("rs"), "Rust");
+        assert_eq!(extension_to_language("js"), "JavaScript");
+        assert_eq!(extension_to_language("ts"), "TypeScript");
+        assert_eq!(extension_to_language("py"), "Python");
+        assert_eq!(extension_to_language("tsx"), "TypeScript");
+        assert_eq!(extension_to_language("json"), "JSON");
+        assert_eq!(extension_to_language("unknown"), "Other");
+        assert_eq!(extension_to_language(""), "No Extension");
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(0), "0");
+        assert_eq!(format_number(999), "999");
+        assert_eq!(format_number(1000), "1,000");
+        assert_eq!(format_number(1234567), "1,234,567");
+    }
+
+    #[test]
+    fn test_stats_collector() {
+        let mut collector = StatsCollector::new(StatsConfig::default());
+        collector.record_directory();
+        collector.record_directory();
+
+        let stats = collector.finalize();
+        assert_eq!(stats.files, 0);
+        assert_eq!(stats.directories, 2);
+        assert!(stats.total_lines.is_none());
+    }
+
+    #[test]
+    fn test_top_files_rankings() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            path
+        };
+        let a = write("a.rs", "// TODO: one\n// FIXME: two\nuse std::io;\n");
+        let b = write("b.rs", "// TODO: one\nfn b() {}\nfn c() {}\nfn d() {}\n");
+        let c = write("c.rs", "fn main() {}\n");
+
+        let config = StatsConfig {
+            count_lines: true,
+            count_todos: true,
+            count_imports: true,
+            ..StatsConfig::default()
+        };
+        let mut collector = StatsCollector::new(config).with_root(dir.path());
+        for path in [&a, &b, &c] {
+            collector.record_file(path);
+        }
+        let stats = collector.finalize();
+
+        assert_eq!(
+            stats.top_files_by_todos(10),
+            [(PathBuf::from("a.rs"), 2), (PathBuf::from("b.rs"), 1)]
+        );
+        assert_eq!(stats.top_files_by_todos(1).len(), 1);
+        assert_eq!(
+            stats.top_files_by_complexity(1),
+            [(PathBuf::from("b.rs"), 4)]
+        );
+        assert_eq!(stats.top_files_by_imports(10), [(PathBuf::from("a.rs"), 1)]);
+        assert_eq!(stats.top_files_by_size(3)[0].0, PathBuf::from("b.rs"));
+    }
+
+    #[test]
+    fn test_todo_counts_by_language() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            path
+        };
+        let a = write(
+            "a.rs",
+            "// TODO: one\n// FIXME: two\nfn a() {}\nfn b() {}\n",
+        );
+        let b = write("b.py", "# TODO: three\n");
+
+        let config = StatsConfig {
+            count_lines: true,
+            count_todos: true,
+            ..StatsConfig::default()
+        };
+        let mut collector = StatsCollector::new(config);
+        collector.record_file(&a);
+        collector.record_file(&b);
+        let stats = collector.finalize();
+
+        assert_eq!(stats.total_todos, Some(3));
+        let rust = stats
+            .by_language
+            .iter()
+            .find(|l| l.language == "Rust")
+            .unwrap();
+        assert_eq!((rust.todo_count, rust.fixme_count), (Some(2), Some(1)));
+        assert_eq!(rust.todo_density(), Some(50.0));
+    }
+
+    #[test]
+    fn test_top_files_empty_when_not_counted() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("a.rs");
+        std::fs::write(&path, "// TODO: one\n").unwrap();
+
+        let mut collector = StatsCollector::new(StatsConfig::default());
+        collector.record_file(&path);
+        let stats = collector.finalize();
+
+        assert!(stats.top_files_by_todos(10).is_empty());
+        assert!(stats.top_files_by_complexity(10).is_empty());
+        assert!(stats.total_todos.is_none());
+        assert!(stats.by_language[0].todo_density().is_none());
+        assert_eq!(stats.top_files_by_size(10), [(path, 13)]);
+    }
+
+    #[test]
+    fn test_stats_json_format() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            path
+        };
+        let documented = write("a.rs", "//! Documented\nfn a() {}\n");
+        let bare = write("b.rs", "fn b() {}\n");
+        let bare2 = write("c.rs", "fn c() {}\n");
+
+        let config = StatsConfig {
+            count_lines: true,
+            count_comments: true,
+            ..StatsConfig::default()
+        };
+        let mut collector = StatsCollector::new(config).with_root(dir.path());
+        collector.record_directory();
+        for path in [&documented, &bare, &bare2] {
+            collector.record_file(path);
+        }
+        let json = collector.finalize().to_json_value();
+
+        assert_eq!(json["version"], 1);
+        assert!(json["generated_at"].as_str().unwrap().ends_with('Z'));
+        assert_eq!(
+            json["scan_root"],
+            dir.path()
+                .canonicalize()
+                .unwrap()
+                .to_string_lossy()
+                .as_ref()
+        );
+        assert_eq!(json["total_files"], 3);
+        assert_eq!(json["total_dirs"], 1);
+        assert_eq!(json["total_lines"], 4);
+        assert_eq!(
+            json["languages"],
+            serde_json::json!([{
+                "name": "Rust",
+                "file_count": 3,
+                "line_count": 4,
+                "comment_coverage": 0.33,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_stats_json_uncounted_fields_are_null() {
+        let stats = CodebaseStats {
+            by_language: vec![LanguageStats {
+                language: "Go".to_string(),
+                files: 2,
+                lines: None,
+                extensions: vec![".go".to_string()],
+                files_with_comments: None,
+                todo_count: None,
+                fixme_count: None,
+            }],
+            ..CodebaseStats::default()
+        };
+        let json = stats.to_json_value();
+        assert!(json["scan_root"].is_null());
+        assert!(json["total_lines"].is_null());
+        assert!(json["languages"][0]["line_count"].is_null());
+        assert!(json["languages"][0]["comment_coverage"].is_null());
+    }
+}
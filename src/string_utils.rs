@@ -0,0 +1,296 @@
+//! Shared string helpers for extraction and output formatting
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Number of spaces a tab stands for when measuring indentation and width.
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+/// Replace each tab in `s` with `tab_width` spaces.
+///
+/// ```
+/// use fruit::string_utils::expand_tabs;
+///
+/// assert_eq!(expand_tabs("\tfn main()", 4), "    fn main()");
+/// ```
+pub fn expand_tabs(s: &str, tab_width: usize) -> String {
+    if !s.contains('\t') {
+        return s.to_string();
+    }
+    s.replace('\t', &" ".repeat(tab_width))
+}
+
+/// Shorten `s` to at most `max_width` terminal columns, ending it with `…`
+/// when anything was cut.
+///
+/// Grapheme clusters are kept whole, so emoji and combining characters are
+/// never split, and East Asian wide characters count as two columns.
+///
+/// ```
+/// use fruit::string_utils::truncate_to_width;
+///
+/// assert_eq!(truncate_to_width("main.rs", 10), "main.rs");
+/// assert_eq!(truncate_to_width("configuration.rs", 10), "configura…");
+/// assert_eq!(truncate_to_width("日本語のファイル", 7), "日本語…");
+/// ```
+pub fn truncate_to_width(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    // Leave a column for the ellipsis
+    let mut truncated = String::new();
+    let mut width = 0;
+    for grapheme in s.graphemes(true) {
+        let grapheme_width = grapheme.width();
+        if width + grapheme_width > max_width - 1 {
+            break;
+        }
+        truncated.push_str(grapheme);
+        width += grapheme_width;
+    }
+    truncated.push('…');
+    truncated
+}
+
+/// Strip the comment marker `prefix` from `line`, returning the comment
+/// text with surrounding whitespace trimmed, or `None` if the line (after
+/// any indentation) does not start with `prefix`.
+///
+/// ```
+/// use fruit::string_utils::strip_comment_prefix;
+///
+/// assert_eq!(strip_comment_prefix("  // Entry point ", "//"), Some("Entry point"));
+/// assert_eq!(strip_comment_prefix("#", "#"), Some(""));
+/// assert_eq!(strip_comment_prefix("fn main() {}", "//"), None);
+/// ```
+pub fn strip_comment_prefix<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    line.trim_start().strip_prefix(prefix).map(str::trim)
+}
+
+/// Like [`strip_comment_prefix`], trying each of `prefixes` in turn and
+/// returning the index of the first that matches along with the comment
+/// text. List longer markers first, since `//` also matches a `///` line.
+///
+/// ```
+/// use fruit::string_utils::strip_any_prefix_and_trim;
+///
+/// let prefixes = ["///", "//"];
+/// assert_eq!(strip_any_prefix_and_trim("/// Docs", &prefixes), Some((0, "Docs")));
+/// assert_eq!(strip_any_prefix_and_trim("// Note", &prefixes), Some((1, "Note")));
+/// assert_eq!(strip_any_prefix_and_trim("# Other", &prefixes), None);
+/// ```
+pub fn strip_any_prefix_and_trim<'a>(line: &'a str, prefixes: &[&str]) -> Option<(usize, &'a str)> {
+    prefixes
+        .iter()
+        .enumerate()
+        .find_map(|(i, prefix)| Some((i, strip_comment_prefix(line, prefix)?)))
+}
+
+/// Parse a file size like `"5M"`, `"100K"` or `"1.5G"` into bytes.
+///
+/// Suffixes are case-insensitive: `K`/`KB` (1024), `M`/`MB` (1024^2),
+/// `G`/`GB` (1024^3) and `B` for bytes; a bare number is bytes. Fractional
+/// sizes round to the nearest byte, so the output of
+/// [`format_size`](crate::tree::format_size) parses back.
+///
+/// ```
+/// use fruit::string_utils::parse_file_size;
+///
+/// assert_eq!(parse_file_size("5M"), Ok(5 * 1024 * 1024));
+/// assert_eq!(parse_file_size(" 1.5 kb "), Ok(1536));
+/// assert!(parse_file_size("K").is_err());
+/// ```
+pub fn parse_file_size(s: &str) -> Result<u64, String> {
+    let upper = s.trim().to_uppercase();
+    let (num_str, multiplier) = if let Some(n) = upper.strip_suffix("GB") {
+        (n, 1024 * 1024 * 1024)
+    } else if let Some(n) = upper.strip_suffix('G') {
+        (n, 1024 * 1024 * 1024)
+    } else if let Some(n) = upper.strip_suffix("MB") {
+        (n, 1024 * 1024)
+    } else if let Some(n) = upper.strip_suffix('M') {
+        (n, 1024 * 1024)
+    } else if let Some(n) = upper.strip_suffix("KB") {
+        (n, 1024)
+    } else if let Some(n) = upper.strip_suffix('K') {
+        (n, 1024)
+    } else if let Some(n) = upper.strip_suffix('B') {
+        (n, 1)
+    } else {
+        (upper.as_str(), 1)
+    };
+
+    let num_str = num_str.trim();
+    let invalid = || format!("invalid number: {}", num_str);
+    if !num_str.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let too_large = || format!("size too large: {}", s.trim());
+
+    if num_str.contains('.') {
+        if !num_str.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            return Err(invalid());
+        }
+        let num: f64 = num_str.parse().map_err(|_| invalid())?;
+        let bytes = (num * multiplier as f64).round();
+        // u64::MAX as f64 rounds up to 2^64, which is already out of range
+        if bytes >= u64::MAX as f64 {
+            return Err(too_large());
+        }
+        return Ok(bytes as u64);
+    }
+
+    let num: u64 = num_str.parse().map_err(|e: std::num::ParseIntError| {
+        if *e.kind() == std::num::IntErrorKind::PosOverflow {
+            too_large()
+        } else {
+            invalid()
+        }
+    })?;
+    num.checked_mul(multiplier).ok_or_else(too_large)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::{SizeStyle, format_size};
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_expand_tabs() {
+        assert_eq!(expand_tabs("no tabs", 4), "no tabs");
+        assert_eq!(expand_tabs("\t\tx", 4), "        x");
+        assert_eq!(expand_tabs("a\tb", 2), "a  b");
+        assert_eq!(expand_tabs("\t", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("", 3), "");
+        assert_eq!(truncate_to_width("abc", 3), "abc");
+        assert_eq!(truncate_to_width("abcd", 3), "ab…");
+        assert_eq!(truncate_to_width("abcd", 1), "…");
+        assert_eq!(truncate_to_width("abcd", 0), "");
+
+        // Combining accents and emoji sequences stay whole
+        assert_eq!(
+            truncate_to_width("e\u{301}e\u{301}e\u{301}", 2),
+            "e\u{301}…"
+        );
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        assert_eq!(
+            truncate_to_width(&format!("{}{}", family, family), 3),
+            format!("{}…", family)
+        );
+
+        // Wide characters take two columns and are not split
+        assert_eq!(truncate_to_width("日本語", 6), "日本語");
+        assert_eq!(truncate_to_width("日本語", 5), "日本…");
+        assert_eq!(truncate_to_width("日本語", 4), "日…");
+    }
+
+    #[test]
+    fn test_strip_comment_prefix() {
+        assert_eq!(
+            strip_comment_prefix("//! Crate docs", "//!"),
+            Some("Crate docs")
+        );
+        assert_eq!(
+            strip_comment_prefix("\t#   indented\t", "#"),
+            Some("indented")
+        );
+        assert_eq!(strip_comment_prefix("--", "--"), Some(""));
+        assert_eq!(strip_comment_prefix("x // trailing", "//"), None);
+        assert_eq!(strip_comment_prefix("", "#"), None);
+
+        assert_eq!(
+            strip_any_prefix_and_trim("#[attr]", &["//", "#"]),
+            Some((1, "[attr]"))
+        );
+        assert_eq!(
+            strip_any_prefix_and_trim("// a", &["//", "///"]),
+            Some((0, "a"))
+        );
+        assert_eq!(strip_any_prefix_and_trim("// a", &[]), None);
+    }
+
+    #[test]
+    fn test_parse_file_size() {
+        assert_eq!(parse_file_size("0"), Ok(0));
+        assert_eq!(parse_file_size("512"), Ok(512));
+        assert_eq!(parse_file_size("512B"), Ok(512));
+        assert_eq!(parse_file_size("100K"), Ok(100 * 1024));
+        assert_eq!(parse_file_size("1kB"), Ok(1024));
+        assert_eq!(parse_file_size("5m"), Ok(5 * 1024 * 1024));
+        assert_eq!(parse_file_size(" 5 M "), Ok(5 * 1024 * 1024));
+        assert_eq!(parse_file_size("2GB"), Ok(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_file_size("1.5K"), Ok(1536));
+        assert_eq!(parse_file_size("0.5"), Ok(1));
+    }
+
+    #[test]
+    fn test_parse_file_size_rejects_malformed_input() {
+        for input in [
+            "", " ", "K", "MB", ".5K", "-5", "+5", "5KK", "1.2.3M", "1e3", "five",
+        ] {
+            assert!(parse_file_size(input).is_err(), "{:?}", input);
+        }
+        assert_eq!(parse_file_size("9999999999G"), Ok(9999999999 << 30));
+        assert!(
+            parse_file_size("99999999999G")
+                .unwrap_err()
+                .contains("too large")
+        );
+        assert!(parse_file_size("99999999999999999999").is_err());
+        assert!(parse_file_size("99999999999999999999.0G").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_file_size_never_panics(s in "\\PC*") {
+            let _ = parse_file_size(&s);
+        }
+
+        #[test]
+        fn prop_parse_file_size_rejects_non_sizes(s in "[^0-9]*") {
+            prop_assert!(parse_file_size(&s).is_err());
+        }
+
+        #[test]
+        fn prop_parse_file_size_is_monotone(
+            a in 0u64..1 << 33,
+            b in 0u64..1 << 33,
+            suffix in prop::sample::select(vec!["", "B", "K", "KB", "M", "MB", "G", "GB"]),
+        ) {
+            let (small, large) = (a.min(b), a.max(b));
+            let small_bytes = parse_file_size(&format!("{}{}", small, suffix)).unwrap();
+            let large_bytes = parse_file_size(&format!("{}{}", large, suffix)).unwrap();
+            prop_assert!(small_bytes <= large_bytes);
+            if small < large {
+                prop_assert!(small_bytes < large_bytes);
+            }
+        }
+
+        #[test]
+        fn prop_format_size_round_trips(bytes in 0u64..1 << 50) {
+            let formatted = format_size(bytes, SizeStyle::Binary);
+            let parsed = parse_file_size(&formatted).unwrap();
+            // One decimal place of the displayed unit is lost: within 5% of
+            // sizes of a unit or more, and within 1% from ten units upwards
+            let unit = [1u64 << 30, 1 << 20, 1 << 10, 1]
+                .into_iter()
+                .find(|&unit| bytes >= unit)
+                .unwrap_or(1);
+            prop_assert!(parsed.abs_diff(bytes) * 20 <= unit);
+            if bytes >= unit * 10 {
+                prop_assert!(parsed.abs_diff(bytes) * 100 <= bytes);
+            }
+            // Formatting the parsed size gives back the same text
+            prop_assert_eq!(format_size(parsed, SizeStyle::Binary), formatted);
+        }
+    }
+}
@@ -1,4 +1,5 @@
-//! Test utilities for creating temporary git repositories.
+//! Test utilities for creating temporary git repositories and `TreeNode`
+//! fixtures.
 //!
 //! This module is only compiled for tests and benchmarks.
 
@@ -7,6 +8,8 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use tempfile::TempDir;
 
+use crate::tree::TreeNode;
+
 /// A temporary git repository for testing.
 ///
 /// Provides methods for creating files, git initialization, and staging files.
@@ -131,3 +134,31 @@ impl Default for TestRepo {
         Self::new()
     }
 }
+
+/// A file node at `path` without metadata, named after the last path segment.
+pub fn file_node(path: &str) -> TreeNode {
+    TreeNode::File {
+        name: node_name(path),
+        path: PathBuf::from(path),
+        comment: None,
+        types: None,
+        todos: None,
+        imports: None,
+        size_bytes: None,
+        size_human: None,
+    }
+}
+
+/// A directory node at `path` holding `children`, named after the last path segment.
+pub fn dir_node(path: &str, children: Vec<TreeNode>) -> TreeNode {
+    TreeNode::Dir {
+        name: node_name(path),
+        path: PathBuf::from(path),
+        child_count: None,
+        children,
+    }
+}
+
+fn node_name(path: &str) -> String {
+    path.rsplit('/').next().unwrap_or(path).to_string()
+}
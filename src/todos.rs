@@ -1,33 +1,253 @@
 //! TODO/FIXME/HACK comment extraction
 //!
 //! This module extracts task markers from comments across source files.
-//! Supported markers: TODO, FIXME, HACK, XXX, BUG, NOTE
+//! Default markers: TODO, FIXME, HACK, XXX, BUG, NOTE, plus the audit
+//! annotations SAFETY, SOUNDNESS, PANICS, ERRORS and INVARIANT in Rust files;
+//! `TodoMatcher` accepts a custom set.
 
 use std::path::Path;
 use std::sync::LazyLock;
 
 use regex::Regex;
 
-use crate::file_utils::read_source_file;
+use crate::file_utils::{ExtractionConfig, read_source_file};
 
-/// Pattern matches TODO, FIXME, HACK, XXX, BUG, NOTE at the start of comment text
-/// followed by colon and the actual message.
-static TODO_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(
-        r"(?i)^\s*(?://+|/?\*+|#+|--+|;+)\s*!?\s*(TODO|FIXME|HACK|XXX|BUG|NOTE)\s*:\s*(.+)",
-    )
-    .expect("TODO_PATTERN regex is invalid")
-});
+/// Markers matched when no custom markers are configured.
+pub const DEFAULT_TODO_MARKERS: [&str; 6] = ["TODO", "FIXME", "HACK", "XXX", "BUG", "NOTE"];
+
+/// Annotations that document unsafe code and API contracts in Rust, matched
+/// in `.rs` files alongside the default markers.
+pub const RUST_ANNOTATION_MARKERS: [&str; 5] =
+    ["SAFETY", "SOUNDNESS", "PANICS", "ERRORS", "INVARIANT"];
+
+/// Whether `marker_type` is one of the `RUST_ANNOTATION_MARKERS`, which
+/// document code rather than ask for work on it.
+pub fn is_annotation_marker(marker_type: &str) -> bool {
+    RUST_ANNOTATION_MARKERS.contains(&marker_type)
+}
+
+/// Optional `(@username)` right after the marker; captured as the assignee.
+const ASSIGNEE_PATTERN: &str = r"(?:\(\s*@([\w.-]+)\s*\))?";
+
+/// Matcher for the default markers, shared by `extract_todos`.
+static DEFAULT_MATCHER: LazyLock<TodoMatcher> =
+    LazyLock::new(|| TodoMatcher::new(&DEFAULT_TODO_MARKERS));
+
+/// Compiled patterns for a set of task markers (case-insensitive).
+#[derive(Debug, Clone)]
+pub struct TodoMatcher {
+    /// Marker at the start of comment text, followed by colon and the message
+    comment: Regex,
+    /// Like `comment`, also matching the Rust annotations (default markers only)
+    rust_comment: Option<Regex>,
+    /// Markdown markers inside HTML comments: `<!-- TODO: text -->`
+    markdown_html: Regex,
+    /// Markdown bold markers: `**TODO:** text` or `**TODO**: text`
+    markdown_bold: Regex,
+    is_default: bool,
+    /// Only keep TODOs assigned to this user (stored without the leading `@`)
+    assignee: Option<String>,
+    /// Only keep TODOs of these marker types (uppercase; empty = all)
+    types: Vec<String>,
+}
+
+/// Pattern for a marker from `alternation` at the start of comment text.
+fn comment_regex(alternation: &str) -> Regex {
+    Regex::new(&format!(
+        r"(?i)^\s*(?://+|/?\*+|#+|--+|;+)\s*!?\s*({}){}\s*:\s*(.+)",
+        alternation, ASSIGNEE_PATTERN
+    ))
+    .expect("TODO comment regex is invalid")
+}
+
+impl TodoMatcher {
+    /// Build a matcher for `markers` (e.g. `["TODO", "OPTIMIZE"]`).
+    /// An empty list selects `DEFAULT_TODO_MARKERS`.
+    pub fn new<S: AsRef<str>>(markers: &[S]) -> Self {
+        let mut names: Vec<String> = markers
+            .iter()
+            .map(|m| m.as_ref().trim())
+            .filter(|m| !m.is_empty())
+            .map(regex::escape)
+            .collect();
+        if names.is_empty() {
+            names = DEFAULT_TODO_MARKERS.iter().map(|m| m.to_string()).collect();
+        }
+        let is_default = names.len() == DEFAULT_TODO_MARKERS.len()
+            && names
+                .iter()
+                .zip(DEFAULT_TODO_MARKERS)
+                .all(|(name, default)| name.eq_ignore_ascii_case(default));
+        let alternation = names.join("|");
+
+        Self {
+            comment: comment_regex(&alternation),
+            rust_comment: is_default.then(|| {
+                comment_regex(&format!(
+                    "{}|{}",
+                    alternation,
+                    RUST_ANNOTATION_MARKERS.join("|")
+                ))
+            }),
+            markdown_html: Regex::new(&format!(
+                r"(?i)<!--\s*({}){}\s*:\s*(.+?)\s*(?:-->|$)",
+                alternation, ASSIGNEE_PATTERN
+            ))
+            .expect("TODO markdown comment regex is invalid"),
+            markdown_bold: Regex::new(&format!(
+                r"(?i)\*\*({}){}(?::\*\*|\*\*:)\s*(.+)",
+                alternation, ASSIGNEE_PATTERN
+            ))
+            .expect("TODO markdown bold regex is invalid"),
+            is_default,
+            assignee: None,
+            types: Vec::new(),
+        }
+    }
+
+    /// Only keep TODOs assigned to `assignee` (with or without the leading `@`).
+    pub fn with_assignee(mut self, assignee: impl Into<Option<String>>) -> Self {
+        self.assignee = assignee
+            .into()
+            .map(|name| name.trim_start_matches('@').to_string());
+        self
+    }
+
+    /// Only keep TODOs of these marker types (case-insensitive, e.g.
+    /// `["SAFETY", "TODO"]`). An empty list keeps every type.
+    pub fn with_types<S: AsRef<str>>(mut self, types: &[S]) -> Self {
+        self.types = types
+            .iter()
+            .map(|t| t.as_ref().trim().to_uppercase())
+            .filter(|t| !t.is_empty())
+            .collect();
+        self
+    }
+
+    /// Drop TODOs not of the configured types or not assigned to the
+    /// configured assignee. Returns `None` when no TODOs remain.
+    pub fn retain_selected(&self, mut todos: Vec<TodoItem>) -> Option<Vec<TodoItem>> {
+        if !self.types.is_empty() {
+            todos.retain(|todo| self.types.contains(&todo.marker_type));
+        }
+        self.retain_assigned(todos)
+    }
+
+    /// Drop TODOs not assigned to the configured assignee, if any.
+    /// Returns `None` when no TODOs remain.
+    pub fn retain_assigned(&self, mut todos: Vec<TodoItem>) -> Option<Vec<TodoItem>> {
+        if let Some(assignee) = &self.assignee {
+            todos.retain(|todo| {
+                todo.assignee
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(assignee))
+            });
+        }
+        if todos.is_empty() { None } else { Some(todos) }
+    }
+
+    /// Whether this matcher uses the default markers.
+    pub fn is_default(&self) -> bool {
+        self.is_default
+    }
+
+    /// Extract all matching markers from a source file (see `extract_todos`).
+    pub fn extract(&self, path: &Path) -> Option<Vec<TodoItem>> {
+        self.extract_with_config(path, &ExtractionConfig::global())
+    }
+
+    /// Like `extract`, skipping files over `config.max_file_size`.
+    pub fn extract_with_config(
+        &self,
+        path: &Path,
+        config: &ExtractionConfig,
+    ) -> Option<Vec<TodoItem>> {
+        // read_source_file handles extension filtering and case-normalization
+        let (content, extension) = read_source_file(path, config.max_file_size)?;
+
+        let todos = match (extension, &self.rust_comment) {
+            ("md", _) => self.markdown_todos(&content),
+            ("rs", Some(rust_comment)) => comment_todos(rust_comment, &content),
+            _ => self.source_todos(&content),
+        };
+
+        self.retain_selected(todos)
+    }
+
+    /// Extract TODO items from file content.
+    fn source_todos(&self, content: &str) -> Vec<TodoItem> {
+        comment_todos(&self.comment, content)
+    }
+
+    /// Extract TODO items from Markdown, where markers live in HTML comments or bold text.
+    fn markdown_todos(&self, content: &str) -> Vec<TodoItem> {
+        let mut todos = Vec::new();
+        let mut in_code_block = false;
+
+        for (line_idx, line) in content.lines().enumerate() {
+            // Fenced code blocks hold examples, not tasks for this document
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+                continue;
+            }
+            if in_code_block {
+                continue;
+            }
+
+            let caps = self
+                .markdown_html
+                .captures(line)
+                .or_else(|| self.markdown_bold.captures(line));
+            if let Some(caps) = caps
+                && let Some(todo) = todo_from_captures(&caps, line_idx)
+            {
+                todos.push(todo);
+            }
+        }
+
+        todos
+    }
+}
+
+/// Extract the comment markers matched by `pattern` from file content.
+fn comment_todos(pattern: &Regex, content: &str) -> Vec<TodoItem> {
+    let mut todos = Vec::new();
+
+    for (line_idx, line) in content.lines().enumerate() {
+        // Skip lines that don't look like comments
+        let trimmed = line.trim();
+        if !looks_like_comment(trimmed) {
+            continue;
+        }
+
+        if let Some(caps) = pattern.captures(line)
+            && let Some(todo) = todo_from_captures(&caps, line_idx)
+        {
+            todos.push(todo);
+        }
+    }
+
+    todos
+}
+
+impl Default for TodoMatcher {
+    fn default() -> Self {
+        DEFAULT_MATCHER.clone()
+    }
+}
 
 /// A single TODO/FIXME marker extracted from a file.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TodoItem {
-    /// The type of marker (TODO, FIXME, HACK, XXX, BUG, NOTE)
+    /// The type of marker, uppercased (TODO, FIXME, HACK, XXX, BUG, NOTE, or
+    /// a Rust annotation such as SAFETY)
     pub marker_type: String,
     /// The text content after the marker
     pub text: String,
     /// The line number where this TODO was found (1-indexed)
     pub line: usize,
+    /// Username from `TODO(@username): ...`, without the `@`
+    pub assignee: Option<String>,
 }
 
 /// Extract all TODO/FIXME markers from a source file.
@@ -37,6 +257,8 @@ pub struct TodoItem {
 ///
 /// # Supported Markers
 ///
+/// Use a `TodoMatcher` to match a different set of markers. The defaults are:
+///
 /// - `TODO`: Tasks to be done
 /// - `FIXME`: Code that needs fixing
 /// - `HACK`: Temporary workarounds
@@ -44,64 +266,56 @@ pub struct TodoItem {
 /// - `BUG`: Known bugs
 /// - `NOTE`: Important notes
 ///
+/// Rust files also match the annotations `SAFETY`, `SOUNDNESS`, `PANICS`,
+/// `ERRORS` and `INVARIANT` (see `RUST_ANNOTATION_MARKERS`).
+///
 /// # Pattern Matching
 ///
 /// Markers are matched with optional colon and surrounding text:
 /// - `TODO: fix this` → type="TODO", text="fix this"
 /// - `FIXME - memory leak` → type="FIXME", text="memory leak"
 /// - `// TODO: implement` → type="TODO", text="implement"
+/// - `// TODO(@alice): fix this` → type="TODO", assignee="alice", text="fix this"
+///
+/// YAML and TOML use `#` comments. In Markdown, where `#` starts a heading,
+/// only `<!-- TODO: ... -->` comments and `**TODO:**` bold text are matched.
 pub fn extract_todos(path: &Path) -> Option<Vec<TodoItem>> {
-    // read_source_file handles extension filtering and case-normalization
-    let (content, _extension) = read_source_file(path)?;
-
-    let todos = extract_todos_from_content(&content);
-
-    if todos.is_empty() { None } else { Some(todos) }
+    DEFAULT_MATCHER.extract(path)
 }
 
-/// Extract TODO items from file content.
-fn extract_todos_from_content(content: &str) -> Vec<TodoItem> {
-    let mut todos = Vec::new();
-
-    for (line_idx, line) in content.lines().enumerate() {
-        // Skip lines that don't look like comments
-        let trimmed = line.trim();
-        if !looks_like_comment(trimmed) {
-            continue;
-        }
-
-        if let Some(caps) = TODO_PATTERN.captures(line) {
-            // Group 1 contains the marker type (TODO, FIXME, etc.)
-            // Group 2 contains the text after the colon
-            let marker_type = caps
-                .get(1)
-                .map(|m| m.as_str().to_uppercase())
-                .unwrap_or_else(|| "TODO".to_string());
-            let text = caps
-                .get(2)
-                .map(|m| m.as_str().trim().to_string())
-                .unwrap_or_default();
-
-            // Skip if the text is empty or just contains closing comment markers
-            let cleaned_text = clean_comment_text(&text);
-
-            // Skip lines that look like documentation examples or descriptions
-            // These typically start with backticks, mention "markers", or have special patterns
-            if is_documentation_example(&cleaned_text) {
-                continue;
-            }
+/// Like `extract_todos`, skipping files over `config.max_file_size`.
+pub fn extract_todos_with_config(path: &Path, config: &ExtractionConfig) -> Option<Vec<TodoItem>> {
+    DEFAULT_MATCHER.extract_with_config(path, config)
+}
 
-            if !cleaned_text.is_empty() {
-                todos.push(TodoItem {
-                    marker_type,
-                    text: cleaned_text,
-                    line: line_idx + 1, // 1-indexed
-                });
-            }
-        }
+/// Build a `TodoItem` from a match whose group 1 is the marker type, group 2
+/// the optional assignee and group 3 the text after the colon. Returns `None` for empty text or documentation examples.
+fn todo_from_captures(caps: &regex::Captures, line_idx: usize) -> Option<TodoItem> {
+    let marker_type = caps
+        .get(1)
+        .map(|m| m.as_str().to_uppercase())
+        .unwrap_or_else(|| "TODO".to_string());
+    let assignee = caps.get(2).map(|m| m.as_str().to_string());
+    let text = caps
+        .get(3)
+        .map(|m| m.as_str().trim().to_string())
+        .unwrap_or_default();
+
+    // Skip if the text is empty or just contains closing comment markers
+    let cleaned_text = clean_comment_text(&text);
+
+    // Skip lines that look like documentation examples or descriptions
+    // These typically start with backticks, mention "markers", or have special patterns
+    if cleaned_text.is_empty() || is_documentation_example(&cleaned_text) {
+        return None;
     }
 
-    todos
+    Some(TodoItem {
+        marker_type,
+        text: cleaned_text,
+        line: line_idx + 1, // 1-indexed
+        assignee,
+    })
 }
 
 /// Check if text looks like a documentation example rather than an actual TODO.
@@ -175,7 +389,7 @@ fn foo() {}
 // FIXME: memory leak here
 fn bar() {}
 "#;
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert_eq!(todos.len(), 2);
         assert_eq!(todos[0].marker_type, "TODO");
         assert_eq!(todos[0].text, "implement this function");
@@ -195,7 +409,7 @@ fn bar() {}
 # BUG: known issue
 # NOTE: important note
 "#;
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert_eq!(todos.len(), 6);
         assert_eq!(todos[0].marker_type, "TODO");
         assert_eq!(todos[1].marker_type, "FIXME");
@@ -212,7 +426,7 @@ fn bar() {}
 // Todo: mixed case
 // TODO: uppercase
 "#;
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert_eq!(todos.len(), 3);
         // All should be normalized to uppercase
         assert!(todos.iter().all(|t| t.marker_type == "TODO"));
@@ -225,7 +439,7 @@ fn bar() {}
 // TODO implement without colon
 // FIXME - with dash
 "#;
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert!(todos.is_empty());
     }
 
@@ -235,7 +449,7 @@ fn bar() {}
 // TODO: implement with colon
 // FIXME: fix with colon
 "#;
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert_eq!(todos.len(), 2);
         assert_eq!(todos[0].text, "implement with colon");
         assert_eq!(todos[1].text, "fix with colon");
@@ -249,7 +463,7 @@ fn bar() {}
  * FIXME: multi-line block
  */
 "#;
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert_eq!(todos.len(), 2);
         assert_eq!(todos[0].text, "in block comment");
         assert_eq!(todos[1].text, "multi-line block");
@@ -261,7 +475,7 @@ fn bar() {}
 # TODO: task in hash comment
 # FIXME: another task
 "#;
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert_eq!(todos.len(), 2);
         assert_eq!(todos[0].text, "task in hash comment");
         assert_eq!(todos[1].text, "another task");
@@ -273,7 +487,7 @@ fn bar() {}
 // TODO:
 // TODO
 "#;
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert!(todos.is_empty());
     }
 
@@ -299,7 +513,7 @@ fn bar() {}
     #[test]
     fn test_todo_with_trailing_punctuation() {
         let content = "// TODO: fix this bug!!!\n";
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert_eq!(todos.len(), 1);
         assert_eq!(todos[0].text, "fix this bug!!!");
     }
@@ -308,7 +522,7 @@ fn bar() {}
     fn test_todo_inside_doc_comment() {
         // Doc comments with TODOs should still be captured
         let content = "/// TODO: document this function\nfn foo() {}";
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert_eq!(todos.len(), 1);
         assert_eq!(todos[0].text, "document this function");
     }
@@ -316,7 +530,7 @@ fn bar() {}
     #[test]
     fn test_multiple_todos_same_line_type() {
         let content = "// TODO: first\n// TODO: second\n// TODO: third";
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert_eq!(todos.len(), 3);
         assert_eq!(todos[0].line, 1);
         assert_eq!(todos[1].line, 2);
@@ -326,7 +540,7 @@ fn bar() {}
     #[test]
     fn test_todo_preserves_line_numbers() {
         let content = "\n\n\n// TODO: on line 4\n\n// FIXME: on line 6";
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert_eq!(todos.len(), 2);
         assert_eq!(todos[0].line, 4);
         assert_eq!(todos[1].line, 6);
@@ -335,21 +549,153 @@ fn bar() {}
     #[test]
     fn test_note_marker() {
         let content = "# NOTE: important observation\n";
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert_eq!(todos.len(), 1);
         assert_eq!(todos[0].marker_type, "NOTE");
     }
 
     #[test]
     fn test_empty_content() {
-        let todos = extract_todos_from_content("");
+        let todos = DEFAULT_MATCHER.source_todos("");
         assert!(todos.is_empty());
     }
 
     #[test]
     fn test_content_without_todos() {
         let content = "// Regular comment\nfn main() {}\n// Another comment";
-        let todos = extract_todos_from_content(content);
+        let todos = DEFAULT_MATCHER.source_todos(content);
         assert!(todos.is_empty());
     }
+
+    #[test]
+    fn test_yaml_and_toml_todos() {
+        let yaml = "# CI workflow\non: push\njobs:\n  build:\n    # TODO: add caching\n    runs-on: ubuntu-latest\n";
+        let todos = DEFAULT_MATCHER.source_todos(yaml);
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].text, "add caching");
+        assert_eq!(todos[0].line, 5);
+
+        let toml = "[package]\nname = \"x\" # FIXME: rename\n# FIXME: pin versions\n";
+        let todos = DEFAULT_MATCHER.source_todos(toml);
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].marker_type, "FIXME");
+        assert_eq!(todos[0].text, "pin versions");
+    }
+
+    #[test]
+    fn test_markdown_todos() {
+        let content = "\
+# TODO: not a task, just a heading
+Intro text.
+<!-- TODO: expand the intro -->
+**FIXME:** broken link below
+**Important**: plain bold text
+**TODO**: add screenshots
+```
+<!-- TODO: example inside a code block -->
+```
+";
+        let todos = DEFAULT_MATCHER.markdown_todos(content);
+        assert_eq!(todos.len(), 3);
+        assert_eq!(todos[0].text, "expand the intro");
+        assert_eq!(todos[0].line, 3);
+        assert_eq!(todos[1].marker_type, "FIXME");
+        assert_eq!(todos[1].text, "broken link below");
+        assert_eq!(todos[2].text, "add screenshots");
+    }
+
+    #[test]
+    fn test_extract_todos_config_and_docs_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cases = [
+            ("ci.yml", "# TODO: matrix builds\n"),
+            ("Cargo.toml", "# TODO: trim features\n"),
+            ("README.md", "<!-- TODO: badges -->\n"),
+        ];
+        for (name, content) in cases {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            let todos = extract_todos(&path).unwrap_or_else(|| panic!("no TODOs in {}", name));
+            assert_eq!(todos.len(), 1, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_custom_markers() {
+        let matcher = TodoMatcher::new(&["OPTIMIZE", "warn"]);
+        assert!(!matcher.is_default());
+        let content = "// TODO: default marker\n// OPTIMIZE: cache this\n# WARN: slow path\n";
+        let todos = matcher.source_todos(content);
+        assert_eq!(todos.len(), 2);
+        assert_eq!(todos[0].marker_type, "OPTIMIZE");
+        assert_eq!(todos[1].marker_type, "WARN");
+        assert_eq!(todos[1].text, "slow path");
+
+        let todos = matcher.markdown_todos("<!-- optimize: images -->\n");
+        assert_eq!(todos[0].text, "images");
+    }
+
+    #[test]
+    fn test_empty_markers_use_defaults() {
+        let empty: [&str; 0] = [];
+        assert!(TodoMatcher::new(&empty).is_default());
+        assert!(TodoMatcher::new(&["todo", "FIXME", "HACK", "XXX", "BUG", "NOTE"]).is_default());
+        assert!(TodoMatcher::default().is_default());
+        // Regex metacharacters in marker names are matched literally
+        let matcher = TodoMatcher::new(&["C++"]);
+        assert_eq!(matcher.source_todos("// C++: port this").len(), 1);
+        assert!(matcher.source_todos("// CCC: no").is_empty());
+    }
+
+    #[test]
+    fn test_todo_assignee() {
+        let content =
+            "// TODO(@alice): fix this\n# FIXME( @bob.smith ): leak\n// TODO: unassigned\n";
+        let todos = DEFAULT_MATCHER.source_todos(content);
+        assert_eq!(todos.len(), 3);
+        assert_eq!(todos[0].assignee.as_deref(), Some("alice"));
+        assert_eq!(todos[0].text, "fix this");
+        assert_eq!(todos[1].assignee.as_deref(), Some("bob.smith"));
+        assert_eq!(todos[2].assignee, None);
+
+        let todos = DEFAULT_MATCHER.markdown_todos("**TODO(@carol):** docs\n");
+        assert_eq!(todos[0].assignee.as_deref(), Some("carol"));
+        assert_eq!(todos[0].text, "docs");
+
+        let matcher = TodoMatcher::default().with_assignee("@Alice".to_string());
+        assert!(matcher.is_default());
+        let todos = matcher
+            .retain_assigned(DEFAULT_MATCHER.source_todos(content))
+            .unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].text, "fix this");
+        assert!(matcher.retain_assigned(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_rust_annotation_markers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let content = "// SAFETY: the pointer is non-null\nunsafe { *ptr }\n// TODO: drop unsafe\n/// Panics: when empty\n";
+        let rust = dir.path().join("lib.rs");
+        std::fs::write(&rust, content).unwrap();
+        let python = dir.path().join("lib.py");
+        std::fs::write(&python, content.replace("//", "#")).unwrap();
+
+        let todos = extract_todos(&rust).unwrap();
+        let types: Vec<&str> = todos.iter().map(|t| t.marker_type.as_str()).collect();
+        assert_eq!(types, ["SAFETY", "TODO", "PANICS"]);
+        assert!(is_annotation_marker(&todos[0].marker_type));
+        assert!(!is_annotation_marker(&todos[1].marker_type));
+
+        // Other languages and custom markers only match their own markers
+        assert_eq!(extract_todos(&python).unwrap().len(), 1);
+        let custom = TodoMatcher::new(&["TODO"]).extract(&rust).unwrap();
+        assert_eq!(custom.len(), 1);
+
+        let safety_only = TodoMatcher::default().with_types(&["safety"]);
+        let todos = safety_only.extract(&rust).unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].text, "the pointer is non-null");
+        assert!(safety_only.extract(&python).is_none());
+    }
 }
@@ -1,20 +1,72 @@
 //! Configuration types for tree walkers
 
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::SystemTime;
 
+use crate::file_utils::ExtractionConfig;
+use crate::git::GitCommitInfo;
+use crate::language::Language;
+use crate::plugin::Plugin;
+
 /// Configuration for tree walking behavior.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct WalkerConfig {
     pub show_all: bool,
     pub max_depth: Option<usize>,
+    /// Hide entries shallower than this depth; directories above it are
+    /// opened and their entries shown in their place, named by relative path
+    pub min_depth: Option<usize>,
     pub dirs_only: bool,
+    /// Hide directories that contain no included files (on by default).
+    /// "Empty" means nothing under the directory passes the gitignore (or
+    /// tracked-files) filter, not that it has no files on disk. Directories
+    /// left without files by `imports_only` or `imports_from` are pruned too;
+    /// time and TODO filters are applied to files only.
+    pub prune_empty_dirs: bool,
+    /// Show entries ignored by .gitignore, marked as ignored, instead of hiding them
+    pub show_git_ignored: bool,
     pub extract_comments: bool,
     pub extract_types: bool,
     pub extract_todos: bool,
     /// Only show files that contain TODO/FIXME markers (requires extract_todos = true)
     pub todos_only: bool,
+    /// Only show files whose imports contain this module name
+    pub imports_from: Option<String>,
+    /// Only show files with at least one extracted import
+    pub imports_only: bool,
+    /// Match pattern filters like `imports_from` case-insensitively
+    pub ignore_case: bool,
     pub extract_imports: bool,
+    /// Levels of internal imports to follow for transitive external imports (0 = off)
+    pub imports_depth: usize,
+    /// Show at most this many imports per file, counting every category
+    pub max_imports: Option<usize>,
+    /// Only show internal imports, hiding external and std ones (JSON keeps
+    /// them all and marks the imports `filtered`)
+    pub imports_relative_only: bool,
     pub show_size: bool,
+    /// Set `child_count` on directories, counting the children of directories
+    /// cut off by `max_depth` as if they had been walked
+    pub count_children: bool,
+    /// Output a separator between top-level directories
+    pub separator: bool,
+    /// Report the line of each type signature
+    pub show_line_numbers: bool,
+    /// Lines of code to show after each type signature
+    pub context_lines: usize,
+    /// Report the approximate LLM token count of each file
+    pub show_tokens: bool,
+    /// Stop the walk before the estimated tokens of shown files exceed this budget
+    pub max_tokens: Option<usize>,
+    /// Reuse extraction results cached on disk from previous runs
+    pub use_cache: bool,
+    /// Show files marked as generated (`// Code generated ... DO NOT EDIT`)
+    /// dimmed and without metadata
+    pub skip_generated: bool,
+    /// Settings passed to every extractor, such as the maximum file size
+    pub extraction: ExtractionConfig,
     pub ignore_patterns: Vec<String>,
     /// Number of parallel workers for metadata extraction.
     /// 0 = auto-detect (use all available cores)
@@ -25,4 +77,405 @@ pub struct WalkerConfig {
     pub newer_than: Option<SystemTime>,
     /// Only include files modified before this time
     pub older_than: Option<SystemTime>,
+    /// Only include these files (absolute, canonical paths), e.g. those changed since a commit
+    pub changed_files: Option<HashSet<PathBuf>>,
+    /// Last commit of each file (absolute, canonical paths), shown beneath it
+    pub last_commits: Option<HashMap<PathBuf, GitCommitInfo>>,
+    /// User-defined extractors run on each file (shared so the config stays `Clone`)
+    pub plugins: Vec<Arc<dyn Plugin>>,
+    /// Only extract metadata from files in these languages (`None` = all languages)
+    pub language_filter: Option<Vec<Language>>,
+    /// TODO markers to match instead of the defaults (empty = TODO, FIXME, HACK, XXX, BUG, NOTE)
+    pub custom_markers: Vec<String>,
+    /// Only keep TODOs assigned to this user via `TODO(@user): ...`
+    pub todo_assignee: Option<String>,
+    /// Only keep TODOs of these marker types (empty = all types)
+    pub todo_types: Vec<String>,
+}
+
+impl Default for WalkerConfig {
+    fn default() -> Self {
+        Self {
+            show_all: false,
+            max_depth: None,
+            min_depth: None,
+            dirs_only: false,
+            prune_empty_dirs: true,
+            show_git_ignored: false,
+            extract_comments: false,
+            extract_types: false,
+            extract_todos: false,
+            todos_only: false,
+            imports_from: None,
+            imports_only: false,
+            ignore_case: false,
+            extract_imports: false,
+            imports_depth: 0,
+            max_imports: None,
+            imports_relative_only: false,
+            show_size: false,
+            count_children: false,
+            separator: false,
+            show_line_numbers: false,
+            context_lines: 0,
+            show_tokens: false,
+            max_tokens: None,
+            use_cache: false,
+            skip_generated: false,
+            extraction: ExtractionConfig::default(),
+            ignore_patterns: Vec::new(),
+            parallel_workers: 0,
+            newer_than: None,
+            older_than: None,
+            changed_files: None,
+            last_commits: None,
+            plugins: Vec::new(),
+            language_filter: None,
+            custom_markers: Vec::new(),
+            todo_assignee: None,
+            todo_types: Vec::new(),
+        }
+    }
+}
+
+impl WalkerConfig {
+    /// Start building a configuration. Unset options keep their defaults.
+    ///
+    /// ```
+    /// use fruit::WalkerConfig;
+    ///
+    /// let config = WalkerConfig::builder()
+    ///     .max_depth(3)
+    ///     .extract_comments(true)
+    ///     .parallel_workers(4)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(config.max_depth, Some(3));
+    /// ```
+    pub fn builder() -> WalkerConfigBuilder {
+        WalkerConfigBuilder::default()
+    }
+
+    /// Whether metadata should be extracted from `path` under `language_filter`.
+    /// Decided from the file name alone, so filtered-out files are never read.
+    pub fn extracts_language(&self, path: &Path) -> bool {
+        match &self.language_filter {
+            None => true,
+            Some(languages) => Language::from_path(path).is_some_and(|l| languages.contains(&l)),
+        }
+    }
+}
+
+/// Builder for `WalkerConfig`, validating option combinations in `build()`.
+#[derive(Debug, Clone, Default)]
+pub struct WalkerConfigBuilder {
+    config: WalkerConfig,
+}
+
+impl WalkerConfigBuilder {
+    /// Include files ignored by .gitignore.
+    pub fn show_all(mut self, show_all: bool) -> Self {
+        self.config.show_all = show_all;
+        self
+    }
+
+    /// Limit how many levels deep the walk descends.
+    pub fn max_depth(mut self, max_depth: impl Into<Option<usize>>) -> Self {
+        self.config.max_depth = max_depth.into();
+        self
+    }
+
+    /// Hide entries shallower than `min_depth` (the root's children are at depth 1).
+    pub fn min_depth(mut self, min_depth: impl Into<Option<usize>>) -> Self {
+        self.config.min_depth = min_depth.into();
+        self
+    }
+
+    /// Show directories only.
+    pub fn dirs_only(mut self, dirs_only: bool) -> Self {
+        self.config.dirs_only = dirs_only;
+        self
+    }
+
+    /// Hide directories that contain no included files (the default).
+    pub fn prune_empty_dirs(mut self, prune_empty_dirs: bool) -> Self {
+        self.config.prune_empty_dirs = prune_empty_dirs;
+        self
+    }
+
+    /// Show entries ignored by .gitignore, marked as ignored, instead of hiding them.
+    pub fn show_git_ignored(mut self, show_git_ignored: bool) -> Self {
+        self.config.show_git_ignored = show_git_ignored;
+        self
+    }
+
+    /// Extract the first comment of each file.
+    pub fn extract_comments(mut self, extract_comments: bool) -> Self {
+        self.config.extract_comments = extract_comments;
+        self
+    }
+
+    /// Extract exported type signatures.
+    pub fn extract_types(mut self, extract_types: bool) -> Self {
+        self.config.extract_types = extract_types;
+        self
+    }
+
+    /// Extract TODO/FIXME markers.
+    pub fn extract_todos(mut self, extract_todos: bool) -> Self {
+        self.config.extract_todos = extract_todos;
+        self
+    }
+
+    /// Only show files that contain TODO/FIXME markers (requires `extract_todos`).
+    pub fn todos_only(mut self, todos_only: bool) -> Self {
+        self.config.todos_only = todos_only;
+        self
+    }
+
+    /// Only show files that import `module` (matched as a substring).
+    pub fn imports_from(mut self, module: impl Into<Option<String>>) -> Self {
+        self.config.imports_from = module.into();
+        self
+    }
+
+    /// Only show files that have at least one import.
+    pub fn imports_only(mut self, imports_only: bool) -> Self {
+        self.config.imports_only = imports_only;
+        self
+    }
+
+    /// Match pattern filters like `imports_from` case-insensitively.
+    pub fn ignore_case(mut self, ignore_case: bool) -> Self {
+        self.config.ignore_case = ignore_case;
+        self
+    }
+
+    /// Extract import statements.
+    pub fn extract_imports(mut self, extract_imports: bool) -> Self {
+        self.config.extract_imports = extract_imports;
+        self
+    }
+
+    /// Follow internal imports this many levels deep, reporting the external
+    /// imports of the files they lead to (0 = off).
+    pub fn imports_depth(mut self, imports_depth: usize) -> Self {
+        self.config.imports_depth = imports_depth;
+        self
+    }
+
+    /// Show at most `max_imports` imports per file, with a count of the rest.
+    pub fn max_imports(mut self, max_imports: impl Into<Option<usize>>) -> Self {
+        self.config.max_imports = max_imports.into();
+        self
+    }
+
+    /// Only show internal imports, hiding external and std ones.
+    pub fn imports_relative_only(mut self, imports_relative_only: bool) -> Self {
+        self.config.imports_relative_only = imports_relative_only;
+        self
+    }
+
+    /// Show generated files dimmed and without metadata.
+    pub fn skip_generated(mut self, skip_generated: bool) -> Self {
+        self.config.skip_generated = skip_generated;
+        self
+    }
+
+    /// Report file sizes.
+    pub fn show_size(mut self, show_size: bool) -> Self {
+        self.config.show_size = show_size;
+        self
+    }
+
+    /// Report the number of children of each directory.
+    pub fn count_children(mut self, count_children: bool) -> Self {
+        self.config.count_children = count_children;
+        self
+    }
+
+    /// Output a separator between top-level directories.
+    pub fn separator(mut self, separator: bool) -> Self {
+        self.config.separator = separator;
+        self
+    }
+
+    /// Report the line of each type signature.
+    pub fn show_line_numbers(mut self, show_line_numbers: bool) -> Self {
+        self.config.show_line_numbers = show_line_numbers;
+        self
+    }
+
+    /// Show up to `context_lines` lines of code after each type signature.
+    pub fn context_lines(mut self, context_lines: usize) -> Self {
+        self.config.context_lines = context_lines;
+        self
+    }
+
+    /// Report the approximate LLM token count of each file.
+    pub fn show_tokens(mut self, show_tokens: bool) -> Self {
+        self.config.show_tokens = show_tokens;
+        self
+    }
+
+    /// Stop the walk before the estimated tokens of shown files exceed `max_tokens`.
+    pub fn max_tokens(mut self, max_tokens: impl Into<Option<usize>>) -> Self {
+        self.config.max_tokens = max_tokens.into();
+        self
+    }
+
+    /// Reuse extraction results cached on disk from previous runs.
+    pub fn use_cache(mut self, use_cache: bool) -> Self {
+        self.config.use_cache = use_cache;
+        self
+    }
+
+    /// Skip extraction for files larger than `max_file_size` bytes.
+    pub fn max_file_size(mut self, max_file_size: u64) -> Self {
+        self.config.extraction.max_file_size = max_file_size;
+        self
+    }
+
+    /// Guess the leading comment of files without a comment extractor from
+    /// common `#`, `//` and `/* */` conventions.
+    pub fn generic_comments(mut self, generic_comments: bool) -> Self {
+        self.config.extraction.generic_comments = generic_comments;
+        self
+    }
+
+    /// Include private methods in type signatures.
+    pub fn show_private(mut self, show_private: bool) -> Self {
+        self.config.extraction.show_private = show_private;
+        self
+    }
+
+    /// Names or glob patterns of entries to skip.
+    pub fn ignore_patterns(mut self, ignore_patterns: Vec<String>) -> Self {
+        self.config.ignore_patterns = ignore_patterns;
+        self
+    }
+
+    /// Number of parallel workers (0 = auto-detect, 1 = sequential).
+    pub fn parallel_workers(mut self, parallel_workers: usize) -> Self {
+        self.config.parallel_workers = parallel_workers;
+        self
+    }
+
+    /// Only include files modified after this time.
+    pub fn newer_than(mut self, newer_than: impl Into<Option<SystemTime>>) -> Self {
+        self.config.newer_than = newer_than.into();
+        self
+    }
+
+    /// Only include files modified before this time.
+    pub fn older_than(mut self, older_than: impl Into<Option<SystemTime>>) -> Self {
+        self.config.older_than = older_than.into();
+        self
+    }
+
+    /// Only include the given files (absolute, canonical paths).
+    pub fn changed_files(mut self, changed_files: impl Into<Option<HashSet<PathBuf>>>) -> Self {
+        self.config.changed_files = changed_files.into();
+        self
+    }
+
+    /// Show each file's last commit, as returned by `git::last_commits`.
+    pub fn last_commits(
+        mut self,
+        last_commits: impl Into<Option<HashMap<PathBuf, GitCommitInfo>>>,
+    ) -> Self {
+        self.config.last_commits = last_commits.into();
+        self
+    }
+
+    /// Only extract metadata from files in these languages.
+    pub fn language_filter(mut self, language_filter: impl Into<Option<Vec<Language>>>) -> Self {
+        self.config.language_filter = language_filter.into();
+        self
+    }
+
+    /// Match only these TODO markers. An empty list keeps the default markers.
+    pub fn custom_markers(mut self, custom_markers: Vec<String>) -> Self {
+        self.config.custom_markers = custom_markers;
+        self
+    }
+
+    /// Only keep TODOs assigned to this user (with or without the leading `@`).
+    pub fn todo_assignee(mut self, todo_assignee: impl Into<Option<String>>) -> Self {
+        self.config.todo_assignee = todo_assignee.into();
+        self
+    }
+
+    /// Only keep TODOs of these marker types, e.g. `SAFETY` or `FIXME`.
+    /// An empty list keeps every type.
+    pub fn todo_types(mut self, todo_types: Vec<String>) -> Self {
+        self.config.todo_types = todo_types;
+        self
+    }
+
+    /// Register a user-defined extractor. Plugins run in registration order.
+    pub fn plugin(mut self, plugin: impl Plugin + 'static) -> Self {
+        self.config.plugins.push(Arc::new(plugin));
+        self
+    }
+
+    /// Finish building, checking that the options are consistent.
+    pub fn build(self) -> Result<WalkerConfig, String> {
+        if self.config.todos_only && !self.config.extract_todos {
+            return Err("todos_only requires extract_todos".to_string());
+        }
+        Ok(self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_sets_fields() {
+        let config = WalkerConfig::builder()
+            .max_depth(3)
+            .extract_comments(true)
+            .parallel_workers(4)
+            .ignore_patterns(vec!["target".to_string()])
+            .imports_only(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.max_depth, Some(3));
+        assert!(config.extract_comments);
+        assert_eq!(config.parallel_workers, 4);
+        assert_eq!(config.ignore_patterns, ["target"]);
+        assert!(config.imports_only);
+        assert!(!config.show_all);
+        assert!(config.prune_empty_dirs);
+    }
+
+    #[test]
+    fn test_extracts_language() {
+        let config = WalkerConfig::default();
+        assert!(config.extracts_language(Path::new("notes.txt")));
+
+        let config = WalkerConfig::builder()
+            .language_filter(vec![Language::Rust, Language::Go])
+            .build()
+            .unwrap();
+        assert!(config.extracts_language(Path::new("src/main.rs")));
+        assert!(config.extracts_language(Path::new("cmd/main.go")));
+        assert!(!config.extracts_language(Path::new("app.py")));
+        assert!(!config.extracts_language(Path::new("README")));
+    }
+
+    #[test]
+    fn test_builder_rejects_todos_only_without_todos() {
+        assert!(WalkerConfig::builder().todos_only(true).build().is_err());
+        assert!(
+            WalkerConfig::builder()
+                .extract_todos(true)
+                .todos_only(true)
+                .build()
+                .is_ok()
+        );
+    }
 }
@@ -20,4 +20,22 @@ impl FileFilter {
             FileFilter::GitTracked(f) => f.is_tracked(path),
         }
     }
+
+    /// Check if a path is excluded by .gitignore. Under the tracked-files
+    /// filter nothing counts as ignored, since untracked files are not ignored.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        match self {
+            FileFilter::Gitignore(f) => f.is_ignored(path),
+            FileFilter::GitTracked(_) => false,
+        }
+    }
+
+    /// Check if a path is a directory the gitignore filter rejects by name,
+    /// without consulting any rules.
+    pub fn is_definitely_ignored(&self, path: &Path) -> bool {
+        match self {
+            FileFilter::Gitignore(f) => f.is_definitely_ignored(path),
+            FileFilter::GitTracked(_) => false,
+        }
+    }
 }
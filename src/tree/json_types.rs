@@ -1,10 +1,12 @@
 //! JSON serialization types for tree output
 
+use std::fmt;
 use std::path::PathBuf;
 
 use serde::Serialize;
 
 use crate::imports::FileImports;
+use crate::output::{OutputConfig, TreeFormatter};
 
 /// Serializable TODO item for JSON output.
 #[derive(Debug, Clone, Serialize)]
@@ -13,6 +15,36 @@ pub struct JsonTodoItem {
     pub marker_type: String,
     pub text: String,
     pub line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+}
+
+/// Serializable type signature for JSON output: a plain string, or an object
+/// with the declaration's line and context lines when either is requested.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum JsonTypeSignature {
+    Signature(String),
+    Detailed {
+        signature: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        line: Option<usize>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        context: Vec<String>,
+    },
+}
+
+impl From<crate::types::TypeSignature> for JsonTypeSignature {
+    fn from(sig: crate::types::TypeSignature) -> Self {
+        if sig.line_number.is_none() && sig.context.is_empty() {
+            return Self::Signature(sig.signature);
+        }
+        Self::Detailed {
+            signature: sig.signature,
+            line: sig.line_number,
+            context: sig.context,
+        }
+    }
 }
 
 impl From<&crate::todos::TodoItem> for JsonTodoItem {
@@ -21,6 +53,7 @@ impl From<&crate::todos::TodoItem> for JsonTodoItem {
             marker_type: item.marker_type.clone(),
             text: item.text.clone(),
             line: item.line,
+            assignee: item.assignee.clone(),
         }
     }
 }
@@ -36,7 +69,7 @@ pub enum TreeNode {
         #[serde(skip_serializing_if = "Option::is_none")]
         comment: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
-        types: Option<Vec<String>>,
+        types: Option<Vec<JsonTypeSignature>>,
         #[serde(skip_serializing_if = "Option::is_none")]
         todos: Option<Vec<JsonTodoItem>>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -49,6 +82,9 @@ pub enum TreeNode {
     Dir {
         name: String,
         path: PathBuf,
+        /// Number of direct children, only set when directory counts are requested
+        #[serde(skip_serializing_if = "Option::is_none")]
+        child_count: Option<usize>,
         children: Vec<TreeNode>,
     },
 }
@@ -64,4 +100,121 @@ impl TreeNode {
     pub fn is_dir(&self) -> bool {
         matches!(self, TreeNode::Dir { .. })
     }
+
+    /// Render this tree as plain text, like `TreeFormatter::format`.
+    pub fn to_tree_string(&self, config: OutputConfig) -> String {
+        TreeFormatter::new(config).format(self)
+    }
+
+    /// All file nodes of this tree, in tree order.
+    pub fn flatten(&self) -> Vec<&TreeNode> {
+        self.files().collect()
+    }
+
+    /// All nodes of this tree, directories included, in tree order
+    /// (each directory before its children, starting with this node).
+    pub fn flatten_all(&self) -> Vec<&TreeNode> {
+        self.nodes().collect()
+    }
+
+    /// Lazily iterate over the file nodes of this tree, in tree order.
+    pub fn files(&self) -> impl Iterator<Item = &TreeNode> {
+        self.nodes().filter(|node| !node.is_dir())
+    }
+
+    /// The first node in tree order, this one included, named `name`.
+    pub fn find_by_name(&self, name: &str) -> Option<&TreeNode> {
+        self.nodes().find(|node| node.name() == name)
+    }
+
+    /// Number of levels below this node: 0 for a file or an empty directory,
+    /// otherwise one more than its deepest child. Nodes do not know their
+    /// parent, so depth is measured down from the node it is called on;
+    /// for a walked root this is the `-L` level needed to show everything.
+    pub fn depth(&self) -> usize {
+        match self {
+            TreeNode::File { .. } => 0,
+            TreeNode::Dir { children, .. } => children
+                .iter()
+                .map(|child| child.depth() + 1)
+                .max()
+                .unwrap_or(0),
+        }
+    }
+
+    /// Depth-first iterator over this node and everything below it.
+    fn nodes(&self) -> impl Iterator<Item = &TreeNode> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            if let TreeNode::Dir { children, .. } = node {
+                stack.extend(children.iter().rev());
+            }
+            Some(node)
+        })
+    }
+}
+
+/// Formats the tree with the default `OutputConfig`, without the trailing newline.
+impl fmt::Display for TreeNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = self.to_tree_string(OutputConfig::default());
+        f.write_str(text.trim_end_matches('\n'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{dir_node, file_node};
+
+    fn names(nodes: &[&TreeNode]) -> Vec<String> {
+        nodes.iter().map(|node| node.name().to_string()).collect()
+    }
+
+    #[test]
+    fn test_flatten_and_lookup() {
+        let tree = dir_node(
+            ".",
+            vec![
+                file_node("Cargo.toml"),
+                dir_node(
+                    "src",
+                    vec![
+                        dir_node("tree", vec![file_node("walker.rs")]),
+                        file_node("lib.rs"),
+                    ],
+                ),
+                dir_node("empty", vec![]),
+            ],
+        );
+
+        assert_eq!(
+            names(&tree.flatten()),
+            ["Cargo.toml", "walker.rs", "lib.rs"]
+        );
+        assert_eq!(
+            names(&tree.flatten_all()),
+            [
+                ".",
+                "Cargo.toml",
+                "src",
+                "tree",
+                "walker.rs",
+                "lib.rs",
+                "empty"
+            ]
+        );
+        assert_eq!(tree.files().count(), 3);
+
+        let src = tree.find_by_name("src").unwrap();
+        assert!(src.is_dir());
+        assert_eq!(names(&src.flatten()), ["walker.rs", "lib.rs"]);
+        assert!(tree.find_by_name("missing.rs").is_none());
+
+        assert_eq!(tree.depth(), 3);
+        assert_eq!(src.depth(), 2);
+        assert_eq!(tree.find_by_name("empty").unwrap().depth(), 0);
+        assert_eq!(file_node("main.rs").depth(), 0);
+    }
 }
@@ -5,20 +5,26 @@
 //!
 //! - `TreeWalker`: Builds full tree in memory, required for JSON output
 //! - `StreamingWalker`: Streams output directly, uses O(depth) memory for console output
+//!
+//! Trees built by `TreeWalker` can be traversed again with a `TreeVisitor`.
 
 mod config;
 mod filter;
 mod json_types;
 mod streaming;
+mod transitive;
 mod utils;
+mod visitor;
 mod walker;
 
 // Re-export public types
-pub use config::WalkerConfig;
+pub use config::{WalkerConfig, WalkerConfigBuilder};
 pub use filter::FileFilter;
-pub use json_types::{JsonTodoItem, TreeNode};
-pub use streaming::{StreamingOutput, StreamingWalker};
-pub use utils::format_size;
+pub use json_types::{JsonTodoItem, JsonTypeSignature, TreeNode};
+pub use streaming::{ChildCount, NodeFlags, StreamingOutput, StreamingWalker};
+pub use utils::{SizeStyle, format_size};
+pub use visitor::{FlattenVisitor, TreeVisitor};
+pub(crate) use walker::LevelEntry;
 pub use walker::TreeWalker;
 
 // Re-export MetadataOrder for convenience
@@ -1,19 +1,29 @@
 //! StreamingWalker - streams output without building full tree in memory
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError, RwLock, RwLockReadGuard};
+use std::time::{Duration, Instant};
 
 use rayon::prelude::*;
 
-use crate::comments::extract_first_comment;
+use crate::cache::ExtractionCache;
+use crate::file_utils::{estimate_tokens, is_generated_file};
 use crate::git::{GitFilter, GitignoreFilter};
-use crate::imports::extract_imports;
+use crate::imports::FileImports;
 use crate::metadata::{LineStyle, MetadataBlock, MetadataLine};
-use crate::todos::extract_todos;
-use crate::types::extract_type_signatures;
+use crate::output::{OutputStats, TimedOutput};
+use crate::plugin::extract_plugin_metadata;
+use crate::todos::TodoMatcher;
 
 use super::config::WalkerConfig;
 use super::filter::FileFilter;
-use super::utils::{has_included_files, should_ignore_path, should_include_path};
+use super::transitive::ImportIndex;
+use super::utils::{
+    has_files_passing_imports_filter, has_included_files, passes_changed_filter,
+    passes_imports_filter, passes_time_filter, should_ignore_path, should_include_path,
+};
 
 /// Entry collected during tree traversal for parallel metadata extraction.
 #[derive(Debug)]
@@ -24,10 +34,55 @@ struct CollectedEntry {
     is_last: bool,
     prefix: String,
     is_root: bool,
+    depth: usize,
+    flags: NodeFlags,
+}
+
+/// Directories with more entries than this are filtered in parallel while
+/// collecting entries.
+const PARALLEL_FILTER_THRESHOLD: usize = 100;
+
+/// Name and size of each child file of a directory, used to align sizes.
+type ChildSizes = Vec<(String, Option<u64>)>;
+
+/// Number of direct children shown under a directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChildCount {
+    pub dirs: usize,
+    pub files: usize,
+    /// True if the directory was not descended into (max depth reached),
+    /// so nested contents are not reflected in the count.
+    pub truncated: bool,
+}
+
+impl std::fmt::Display for ChildCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let plus = if self.truncated { "+" } else { "" };
+        let files = if self.files == 1 { "file" } else { "files" };
+        if self.dirs > 0 && !self.truncated {
+            let dirs = if self.dirs == 1 { "dir" } else { "dirs" };
+            write!(f, "({} {}, {} {})", self.dirs, dirs, self.files, files)
+        } else {
+            write!(f, "({}{} {})", self.files, plus, files)
+        }
+    }
+}
+
+/// Extra facts about a node passed to `StreamingOutput::output_node`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NodeFlags {
+    /// The entry is ignored by .gitignore and only shown because of `show_git_ignored`.
+    /// Ignored directories are shown but not descended into.
+    pub ignored: bool,
+    /// The file is marked as generated and `skip_generated` is set, so its
+    /// metadata was not extracted.
+    pub generated: bool,
 }
 
 /// Callback for streaming output - receives node information for display.
 pub trait StreamingOutput {
+    /// Output a single node. `child_count` is only provided for directories,
+    /// and only when `wants_child_counts` returns true.
     #[allow(clippy::too_many_arguments)]
     fn output_node(
         &mut self,
@@ -38,9 +93,49 @@ pub trait StreamingOutput {
         prefix: &str,
         is_root: bool,
         size: Option<u64>,
+        child_count: Option<ChildCount>,
+        flags: NodeFlags,
     ) -> std::io::Result<()>;
 
     fn finish(&mut self, dir_count: usize, file_count: usize) -> std::io::Result<()>;
+
+    /// Whether this output displays per-directory child counts. Counting
+    /// requires an extra pass over each directory, so walkers skip it otherwise.
+    fn wants_child_counts(&self) -> bool {
+        false
+    }
+
+    /// Called before the children of a directory are output (only when sizes
+    /// are shown), with the prefix they will use and the name and size of each
+    /// child file. Formatters can use this to align the size column.
+    fn begin_children(
+        &mut self,
+        _prefix: &str,
+        _files: &[(String, Option<u64>)],
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    /// Called just before a file's `output_node` (only when token counts are
+    /// shown) with the file's approximate LLM token count.
+    fn file_tokens(&mut self, _tokens: usize) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    /// Called once when the walk stops early because the next file would take
+    /// the estimated tokens past `max_tokens`. `finish` is still called afterwards.
+    fn token_budget_exceeded(&mut self, _max_tokens: usize) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    /// Called between sections (only when separators are enabled), after the
+    /// contents of a directory at `depth` and before its next sibling.
+    /// Walkers currently only separate top-level directories (`depth` 1).
+    /// Outputs without a notion of sections keep the default, which writes
+    /// nothing.
+    fn output_separator(&mut self, _depth: usize) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 /// Streaming tree walker that outputs directly without building tree in memory.
@@ -49,13 +144,38 @@ pub trait StreamingOutput {
 pub struct StreamingWalker {
     config: WalkerConfig,
     filter: Option<FileFilter>,
+    cache: ExtractionCache,
+    /// Estimated tokens of the files output so far in the current walk
+    tokens_used: AtomicUsize,
+    /// Set once a file was skipped for exceeding `max_tokens`; ends the walk
+    budget_exceeded: AtomicBool,
+    /// Imports of the whole tree, collected before the walk when `imports_depth > 0`
+    import_index: RwLock<Option<ImportIndex>>,
+    /// Where the time of the last walk went, when enabled with `with_timing`
+    timing: Option<Mutex<OutputStats>>,
 }
 
 impl StreamingWalker {
     pub fn new(config: WalkerConfig) -> Self {
+        let cache = if config.use_cache {
+            ExtractionCache::load_default()
+        } else {
+            ExtractionCache::disabled()
+        }
+        .with_todo_matcher(
+            TodoMatcher::new(&config.custom_markers)
+                .with_assignee(config.todo_assignee.clone())
+                .with_types(&config.todo_types),
+        )
+        .with_extraction_config(config.extraction);
         Self {
             config,
             filter: None,
+            cache,
+            tokens_used: AtomicUsize::new(0),
+            budget_exceeded: AtomicBool::new(false),
+            import_index: RwLock::new(None),
+            timing: None,
         }
     }
 
@@ -74,16 +194,66 @@ impl StreamingWalker {
         self.with_filter(FileFilter::Gitignore(filter))
     }
 
+    /// Measure where the time of each walk goes, for `output_stats`.
+    pub fn with_timing(mut self) -> Self {
+        self.timing = Some(Mutex::new(OutputStats::default()));
+        self
+    }
+
+    /// Timing of the last walk, if enabled with `with_timing`.
+    pub fn output_stats(&self) -> Option<OutputStats> {
+        self.timing
+            .as_ref()
+            .map(|timing| *timing.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
     /// Walk and stream output - returns (dir_count, file_count)
     pub fn walk_streaming<O: StreamingOutput>(
         &self,
         root: &Path,
         output: &mut O,
+    ) -> std::io::Result<Option<(usize, usize)>> {
+        let start = Instant::now();
+        if let Some(timing) = &self.timing {
+            *timing.lock().unwrap_or_else(PoisonError::into_inner) = OutputStats::default();
+        }
+        self.tokens_used.store(0, Ordering::Relaxed);
+        self.budget_exceeded.store(false, Ordering::Relaxed);
+        let import_index = (self.config.extract_imports && self.config.imports_depth > 0)
+            .then(|| self.build_import_index(root));
+        *self
+            .import_index
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = import_index;
+
+        let result = match &self.timing {
+            Some(timing) => self.walk_streaming_with(root, &mut TimedOutput::new(output, timing)),
+            None => self.walk_streaming_with(root, output),
+        };
+
+        // Whatever was not spent extracting or formatting was spent walking
+        if let Some(timing) = &self.timing {
+            let mut stats = timing.lock().unwrap_or_else(PoisonError::into_inner);
+            stats.walk_duration = start
+                .elapsed()
+                .saturating_sub(stats.extraction_duration + stats.output_duration);
+        }
+
+        // The cache is only an optimization, so failing to persist it is not fatal
+        if let Err(e) = self.cache.save() {
+            eprintln!("fruit: warning: failed to write extraction cache: {}", e);
+        }
+        result
+    }
+
+    fn walk_streaming_with<O: StreamingOutput>(
+        &self,
+        root: &Path,
+        output: &mut O,
     ) -> std::io::Result<Option<(usize, usize)>> {
         // Use parallel extraction if workers != 1
         let use_parallel = self.config.parallel_workers != 1
             && (self.config.extract_comments || self.config.extract_types);
-
         if use_parallel {
             self.walk_streaming_parallel(root, output)
         } else {
@@ -127,87 +297,59 @@ impl StreamingWalker {
         let file_indices: Vec<usize> = entries
             .iter()
             .enumerate()
-            .filter_map(|(i, e)| if !e.is_dir { Some(i) } else { None })
+            .filter_map(|(i, e)| (!e.is_dir && !e.flags.generated).then_some(i))
             .collect();
 
         // Extract metadata in parallel
-        // Note: We use a standalone function to avoid capturing &self (which contains
-        // non-Sync FileFilter/GitFilter) in the parallel closure.
-        let extract_comments = self.config.extract_comments;
-        let extract_types = self.config.extract_types;
-        let extract_todo_markers = self.config.extract_todos;
-        let extract_import_statements = self.config.extract_imports;
-
-        let metadata_results: Vec<(usize, Option<MetadataBlock>)> =
-            if self.config.parallel_workers == 0 {
-                // Auto-detect: use rayon's default thread pool
-                file_indices
-                    .par_iter()
-                    .map(|&i| {
-                        let path = &entries[i].path;
-                        let metadata = extract_metadata_from_path(
-                            path,
-                            extract_comments,
-                            extract_types,
-                            extract_todo_markers,
-                            extract_import_statements,
-                        );
-                        (i, metadata)
-                    })
-                    .collect()
-            } else {
-                // Use custom thread pool with specified worker count
-                match rayon::ThreadPoolBuilder::new()
-                    .num_threads(self.config.parallel_workers)
-                    .build()
-                {
-                    Ok(pool) => pool.install(|| {
-                        file_indices
-                            .par_iter()
-                            .map(|&i| {
-                                let path = &entries[i].path;
-                                let metadata = extract_metadata_from_path(
-                                    path,
-                                    extract_comments,
-                                    extract_types,
-                                    extract_todo_markers,
-                                    extract_import_statements,
-                                );
-                                (i, metadata)
-                            })
-                            .collect()
-                    }),
-                    Err(e) => {
-                        // Warn user and fall back to rayon's global pool
-                        eprintln!(
-                            "fruit: warning: failed to create thread pool with {} workers ({}), using default pool",
-                            self.config.parallel_workers, e
-                        );
-                        file_indices
-                            .par_iter()
-                            .map(|&i| {
-                                let path = &entries[i].path;
-                                let metadata = extract_metadata_from_path(
-                                    path,
-                                    extract_comments,
-                                    extract_types,
-                                    extract_todo_markers,
-                                    extract_import_statements,
-                                );
-                                (i, metadata)
-                            })
-                            .collect()
-                    }
+        // Note: We use a standalone function so the parallel closure only captures
+        // the config and cache it needs.
+        let config = &self.config;
+        let cache = &self.cache;
+        let index_guard = self.import_index();
+        let import_index = index_guard.as_ref();
+        let extraction_start = Instant::now();
+
+        let extract = |&i: &usize| {
+            let metadata =
+                extract_metadata_from_path(&entries[i].path, config, cache, import_index);
+            (i, metadata)
+        };
+
+        let metadata_results: Vec<_> = if config.parallel_workers == 0 {
+            // Auto-detect: use rayon's default thread pool
+            file_indices.par_iter().map(extract).collect()
+        } else {
+            // Use custom thread pool with specified worker count
+            match rayon::ThreadPoolBuilder::new()
+                .num_threads(config.parallel_workers)
+                .build()
+            {
+                Ok(pool) => pool.install(|| file_indices.par_iter().map(extract).collect()),
+                Err(e) => {
+                    // Warn user and fall back to rayon's global pool
+                    eprintln!(
+                        "fruit: warning: failed to create thread pool with {} workers ({}), using default pool",
+                        config.parallel_workers, e
+                    );
+                    file_indices.par_iter().map(extract).collect()
                 }
-            };
+            }
+        };
+        self.record_extraction(
+            file_indices.iter().map(|&i| entries[i].path.as_path()),
+            extraction_start.elapsed(),
+        );
 
         // Build a map of index -> metadata for quick lookup
         let mut metadata_map: std::collections::HashMap<usize, Option<MetadataBlock>> =
             metadata_results.into_iter().collect();
 
-        // If todos_only is enabled, we need to filter files without TODOs
-        // and track which indices to skip
-        let skip_indices: std::collections::HashSet<usize> = if self.config.todos_only {
+        // If todos_only or an import filter is enabled, we need to filter files
+        // without TODOs or the imports and track which indices to skip
+        let filters_files = self.config.todos_only
+            || self.config.imports_only
+            || self.config.imports_from.is_some();
+        let skip_indices: std::collections::HashSet<usize> = if filters_files {
             entries
                 .iter()
                 .enumerate()
@@ -220,7 +362,9 @@ impl StreamingWalker {
                             .get(&i)
                             .and_then(|opt| opt.as_ref())
                             .is_some_and(|meta| !meta.todo_lines.is_empty());
-                        if has_todos {
+                        if (has_todos || !self.config.todos_only)
+                            && passes_imports_filter(&entry.path, &self.config, &self.cache)
+                        {
                             None // Don't skip
                         } else {
                             Some(i) // Skip this file
@@ -263,23 +407,69 @@ impl StreamingWalker {
             map
         };
 
+        // Get file sizes if enabled, grouped by parent directory so that
+        // formatters can align sizes among siblings
+        let mut sizes: std::collections::HashMap<usize, Option<u64>> =
+            std::collections::HashMap::new();
+        let mut sizes_by_dir: std::collections::HashMap<&Path, (&str, ChildSizes)> =
+            std::collections::HashMap::new();
+        if self.config.show_size {
+            for &(i, entry) in &filtered_entries {
+                if entry.is_dir {
+                    continue;
+                }
+                let size = entry.path.metadata().ok().map(|m| m.len());
+                sizes.insert(i, size);
+                if let Some(parent) = entry.path.parent() {
+                    sizes_by_dir
+                        .entry(parent)
+                        .or_insert_with(|| (entry.prefix.as_str(), Vec::new()))
+                        .1
+                        .push((entry.name.clone(), size));
+                }
+            }
+        }
+
+        // Whether the current top-level entry is a directory, and whether
+        // anything was output inside it (for separators)
+        let mut in_top_dir = false;
+        let mut top_dir_has_content = false;
+
+        // Depth of the root's children as shown (deeper with `min_depth`)
+        let top_depth = self.child_depth(0);
+
         for (i, entry) in filtered_entries {
+            if !entry.is_dir && !self.charge_tokens(&entry.path, output)? {
+                break;
+            }
+
+            if entry.depth == top_depth {
+                if self.config.separator && in_top_dir && top_dir_has_content {
+                    output.output_separator(1)?;
+                }
+                in_top_dir = entry.is_dir;
+                top_dir_has_content = false;
+            } else if entry.depth > top_depth {
+                top_dir_has_content = true;
+            }
+
             let metadata = if entry.is_dir {
                 None
             } else {
                 metadata_map.remove(&i).flatten()
             };
 
-            // Get file size if enabled and this is a file
-            let size = if !entry.is_dir && self.config.show_size {
-                entry.path.metadata().ok().map(|m| m.len())
-            } else {
-                None
-            };
+            let size = sizes.get(&i).copied().flatten();
 
             // Use recalculated is_last, or original if not in map (shouldn't happen)
             let is_last = is_last_map.get(&i).copied().unwrap_or(entry.is_last);
 
+            let child_count = if entry.is_dir && output.wants_child_counts() {
+                Some(self.count_children(&entry.path, entry.depth))
+            } else {
+                None
+            };
+
             output.output_node(
                 &entry.name,
                 metadata,
@@ -288,8 +478,16 @@ impl StreamingWalker {
                 &entry.prefix,
                 entry.is_root,
                 size,
+                child_count,
+                entry.flags,
             )?;
 
+            if entry.is_dir
+                && let Some((prefix, files)) = sizes_by_dir.get(entry.path.as_path())
+            {
+                output.begin_children(prefix, files)?;
+            }
+
             if entry.is_dir && !entry.is_root {
                 dir_count += 1;
             } else if !entry.is_dir {
@@ -322,23 +520,9 @@ impl StreamingWalker {
             return None;
         }
 
-        // Collect and sort directory entries
-        let dir_entries = match std::fs::read_dir(path) {
-            Ok(e) => e,
-            Err(_) => return None,
-        };
-
-        let mut dir_entries: Vec<_> = dir_entries.filter_map(|e| e.ok()).collect();
-        dir_entries.sort_by_key(|a| a.file_name());
-
-        // Filter entries
-        let filtered_entries: Vec<_> = dir_entries
-            .into_iter()
-            .filter(|entry| {
-                let entry_path = entry.path();
-                !should_ignore_path(&entry_path, &self.config.ignore_patterns)
-            })
-            .collect();
+        // Collect sorted entries, opening those too shallow to show
+        let filtered_entries = self.list_entries(path, depth)?;
+        let child_depth = self.child_depth(depth);
 
         // Get directory name
         let name = path
@@ -360,36 +544,37 @@ impl StreamingWalker {
                 is_last: true,
                 prefix: prefix.to_string(),
                 is_root: true,
+                depth,
+                flags: NodeFlags::default(),
             });
         }
 
-        // Build list of valid entries (files and non-empty directories)
-        let mut valid_entries: Vec<(std::fs::DirEntry, bool)> = Vec::new();
-
-        for entry in filtered_entries {
-            let entry_path = entry.path();
-
-            if entry_path.is_file() {
-                if self.config.dirs_only {
-                    continue;
-                }
-                if !should_include_path(&entry_path, &self.config, &self.filter) {
-                    continue;
-                }
-                valid_entries.push((entry, false)); // false = is file
-            } else if entry_path.is_dir()
-                && !entry_path.is_symlink()
-                && (self.config.dirs_only || has_included_files(&entry_path, &self.filter))
-            {
-                valid_entries.push((entry, true)); // true = is directory
-            }
-        }
+        // Build list of valid entries (files and non-empty directories).
+        // Large directories are filtered in parallel since gitignore lookups
+        // and stat calls dominate the collection phase there.
+        let classified: Vec<Option<(bool, NodeFlags)>> =
+            if filtered_entries.len() > PARALLEL_FILTER_THRESHOLD {
+                filtered_entries
+                    .par_iter()
+                    .map(|(entry_path, _)| self.classify_entry(entry_path))
+                    .collect()
+            } else {
+                filtered_entries
+                    .iter()
+                    .map(|(entry_path, _)| self.classify_entry(entry_path))
+                    .collect()
+            };
+        let valid_entries: Vec<((PathBuf, String), (bool, NodeFlags))> = filtered_entries
+            .into_iter()
+            .zip(classified)
+            .filter_map(|(entry, class)| Some((entry, class?)))
+            .collect();
 
         let total = valid_entries.len();
 
-        for (i, (entry, is_dir)) in valid_entries.into_iter().enumerate() {
-            let entry_path = entry.path();
-            let entry_name = entry.file_name().to_string_lossy().to_string();
+        for (i, ((entry_path, entry_name), (is_dir, flags))) in
+            valid_entries.into_iter().enumerate()
+        {
             let is_last = i == total - 1;
 
             let new_prefix = if is_last {
@@ -407,10 +592,14 @@ impl StreamingWalker {
                     is_last,
                     prefix: prefix.to_string(),
                     is_root: false,
+                    depth: child_depth,
+                    flags,
                 });
 
-                // Recurse into directory
-                self.collect_entries(&entry_path, depth + 1, &new_prefix, false, entries);
+                // Recurse into directory (ignored directories are shown but not opened)
+                if !flags.ignored {
+                    self.collect_entries(&entry_path, child_depth, &new_prefix, false, entries);
+                }
             } else {
                 // Add file entry
                 entries.push(CollectedEntry {
@@ -420,6 +609,8 @@ impl StreamingWalker {
                     is_last,
                     prefix: prefix.to_string(),
                     is_root: false,
+                    depth: child_depth,
+                    flags,
                 });
             }
         }
@@ -427,6 +618,115 @@ impl StreamingWalker {
         Some(())
     }
 
+    /// Sorted entries of `dir` (at `depth`) not matched by the ignore
+    /// patterns, as path and displayed name. Returns `None` if `dir` cannot
+    /// be read.
+    ///
+    /// Children shallower than `min_depth` are not listed themselves: files
+    /// are dropped, and directories are replaced by their own entries, named
+    /// by their path below `dir` (e.g. `src/tree`).
+    fn list_entries(&self, dir: &Path, depth: usize) -> Option<Vec<(PathBuf, String)>> {
+        let mut entries: Vec<_> = std::fs::read_dir(dir)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .collect();
+        entries.sort_by_key(|a| a.file_name());
+        let entries = entries
+            .into_iter()
+            .map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                (entry.path(), name)
+            })
+            .filter(|(path, _)| !should_ignore_path(path, &self.config, &self.filter));
+
+        if self.config.min_depth.is_none_or(|min| depth + 1 >= min) {
+            return Some(entries.collect());
+        }
+
+        let at_max_depth = self.config.max_depth.is_some_and(|max| depth + 1 >= max);
+        let mut listed = Vec::new();
+        for (path, name) in entries {
+            let opens = !at_max_depth
+                && matches!(self.classify_entry(&path), Some((true, flags)) if !flags.ignored);
+            if !opens {
+                continue;
+            }
+            for (child_path, child_name) in self.list_entries(&path, depth + 1).unwrap_or_default()
+            {
+                listed.push((child_path, format!("{}/{}", name, child_name)));
+            }
+        }
+        Some(listed)
+    }
+
+    /// Depth of the entries listed by `list_entries` for a directory at `depth`.
+    fn child_depth(&self, depth: usize) -> usize {
+        (depth + 1).max(self.config.min_depth.unwrap_or(0))
+    }
+
+    /// Decide whether a directory entry is shown: `Some((false, _))` for an
+    /// included file, `Some((true, _))` for a readable, non-empty directory,
+    /// `None` to skip.
+    /// Content filters like `todos_only` are left to the caller.
+    fn classify_entry(&self, entry_path: &Path) -> Option<(bool, NodeFlags)> {
+        let ignored = NodeFlags {
+            ignored: true,
+            ..NodeFlags::default()
+        };
+        if entry_path.is_file() {
+            if self.config.dirs_only {
+                None
+            } else if should_include_path(entry_path, &self.config, &self.filter) {
+                let generated = self.config.skip_generated && is_generated_file(entry_path);
+                Some((
+                    false,
+                    NodeFlags {
+                        generated,
+                        ..NodeFlags::default()
+                    },
+                ))
+            } else {
+                self.shows_as_ignored(entry_path)
+                    .then_some((false, ignored))
+            }
+        } else if entry_path.is_dir() && !entry_path.is_symlink() {
+            if self.shows_as_ignored(entry_path) {
+                Some((true, ignored))
+            } else if std::fs::read_dir(entry_path).is_err() {
+                // Unreadable directories are left out, as `TreeWalker` does,
+                // so they are neither shown nor counted
+                None
+            } else if self.config.dirs_only
+                || !self.config.prune_empty_dirs
+                || (has_included_files(entry_path, &self.filter)
+                    && has_files_passing_imports_filter(
+                        entry_path,
+                        &self.config,
+                        &self.filter,
+                        &self.cache,
+                    ))
+            {
+                Some((true, NodeFlags::default()))
+            } else {
+                None
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Whether `path` is excluded by .gitignore but shown anyway, marked as
+    /// ignored, because of `show_git_ignored`. Time and changed-file filters
+    /// still apply to files.
+    fn shows_as_ignored(&self, path: &Path) -> bool {
+        self.config.show_git_ignored
+            && !self.config.show_all
+            && self.filter.as_ref().is_some_and(|f| f.is_ignored(path))
+            && (!path.is_file()
+                || (passes_time_filter(path, &self.config)
+                    && passes_changed_filter(path, &self.config)))
+    }
+
     fn walk_dir_streaming<O: StreamingOutput>(
         &self,
         path: &Path,
@@ -447,23 +747,11 @@ impl StreamingWalker {
             return Ok(None);
         }
 
-        // Collect and sort entries
-        let entries = match std::fs::read_dir(path) {
-            Ok(e) => e,
-            Err(_) => return Ok(None),
+        // Collect sorted entries, opening those too shallow to show
+        let Some(filtered_entries) = self.list_entries(path, depth) else {
+            return Ok(None);
         };
-
-        let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
-        entries.sort_by_key(|a| a.file_name());
-
-        // Filter entries first to know which ones will be included
-        let filtered_entries: Vec<_> = entries
-            .into_iter()
-            .filter(|entry| {
-                let entry_path = entry.path();
-                !should_ignore_path(&entry_path, &self.config.ignore_patterns)
-            })
-            .collect();
+        let child_depth = self.child_depth(depth);
 
         // Get the directory name for output
         let name = path
@@ -478,7 +766,20 @@ impl StreamingWalker {
 
         // Output this directory (root handled specially)
         if is_root {
-            output.output_node(&name, None, true, true, prefix, true, None)?;
+            let child_count = output
+                .wants_child_counts()
+                .then(|| self.count_children(path, depth));
+            output.output_node(
+                &name,
+                None,
+                true,
+                true,
+                prefix,
+                true,
+                None,
+                child_count,
+                NodeFlags::default(),
+            )?;
         }
 
         let mut dir_count = 0usize;
@@ -486,19 +787,22 @@ impl StreamingWalker {
 
         // We need to peek ahead to know which entries will actually produce output
         // to determine is_last correctly
-        let mut valid_entries: Vec<(std::fs::DirEntry, bool, Option<MetadataBlock>)> = Vec::new();
+        let mut valid_entries: Vec<(PathBuf, String, bool, Option<MetadataBlock>, NodeFlags)> =
+            Vec::new();
 
-        for entry in filtered_entries {
-            let entry_path = entry.path();
+        for (entry_path, entry_name) in filtered_entries {
+            let Some((is_dir, flags)) = self.classify_entry(&entry_path) else {
+                continue;
+            };
 
-            if entry_path.is_file() {
-                if self.config.dirs_only {
-                    continue;
-                }
-                if !should_include_path(&entry_path, &self.config, &self.filter) {
-                    continue;
-                }
-                let metadata = self.extract_metadata(&entry_path);
+            if is_dir {
+                valid_entries.push((entry_path, entry_name, true, None, flags));
+            } else {
+                let metadata = if flags.generated {
+                    None
+                } else {
+                    self.extract_metadata(&entry_path)
+                };
                 // If todos_only is enabled, skip files without TODOs
                 if self.config.todos_only {
                     if let Some(ref meta) = metadata {
@@ -509,20 +813,33 @@ impl StreamingWalker {
                         continue;
                     }
                 }
-                valid_entries.push((entry, false, metadata));
-            } else if entry_path.is_dir() && !entry_path.is_symlink() {
-                // Check if this directory has any content (or if we're in dirs_only mode)
-                if self.config.dirs_only || has_included_files(&entry_path, &self.filter) {
-                    valid_entries.push((entry, true, None));
+                if !passes_imports_filter(&entry_path, &self.config, &self.cache) {
+                    continue;
                 }
+                valid_entries.push((entry_path, entry_name, false, metadata, flags));
             }
         }
 
         let total = valid_entries.len();
 
-        for (i, (entry, is_dir, metadata)) in valid_entries.into_iter().enumerate() {
-            let entry_path = entry.path();
-            let entry_name = entry.file_name().to_string_lossy().to_string();
+        if self.config.show_size {
+            let files: ChildSizes = valid_entries
+                .iter()
+                .filter(|(_, _, is_dir, _, _)| !is_dir)
+                .map(|(entry_path, entry_name, _, _, _)| {
+                    let size = entry_path.metadata().ok().map(|m| m.len());
+                    (entry_name.clone(), size)
+                })
+                .collect();
+            output.begin_children(prefix, &files)?;
+        }
+
+        for (i, (entry_path, entry_name, is_dir, metadata, flags)) in
+            valid_entries.into_iter().enumerate()
+        {
+            if self.budget_exceeded.load(Ordering::Relaxed) {
+                break;
+            }
             let is_last = i == total - 1;
 
             // Calculate the prefix for this entry's children
@@ -534,24 +851,60 @@ impl StreamingWalker {
             };
 
             if is_dir {
-                output.output_node(&entry_name, None, true, is_last, prefix, false, None)?;
+                let child_count = output
+                    .wants_child_counts()
+                    .then(|| self.count_children(&entry_path, child_depth));
+                output.output_node(
+                    &entry_name,
+                    None,
+                    true,
+                    is_last,
+                    prefix,
+                    false,
+                    None,
+                    child_count,
+                    flags,
+                )?;
                 dir_count += 1;
 
-                // Recurse
-                if let Ok(Some((d, f))) =
-                    self.walk_dir_streaming(&entry_path, depth + 1, &new_prefix, false, output)
+                // Recurse (ignored directories are shown but not opened)
+                if !flags.ignored
+                    && let Ok(Some((d, f))) = self.walk_dir_streaming(
+                        &entry_path,
+                        child_depth,
+                        &new_prefix,
+                        false,
+                        output,
+                    )
                 {
                     dir_count += d;
                     file_count += f;
+                    // Separate non-empty top-level directories from what follows
+                    if self.config.separator && depth == 0 && !is_last && d + f > 0 {
+                        output.output_separator(depth + 1)?;
+                    }
                 }
             } else {
+                if !self.charge_tokens(&entry_path, output)? {
+                    break;
+                }
                 // Get file size if enabled
                 let size = if self.config.show_size {
                     entry_path.metadata().ok().map(|m| m.len())
                 } else {
                     None
                 };
-                output.output_node(&entry_name, metadata, false, is_last, prefix, false, size)?;
+                output.output_node(
+                    &entry_name,
+                    metadata,
+                    false,
+                    is_last,
+                    prefix,
+                    false,
+                    size,
+                    None,
+                    flags,
+                )?;
                 file_count += 1;
             }
         }
@@ -559,78 +912,217 @@ impl StreamingWalker {
         Ok(Some((dir_count, file_count)))
     }
 
+    /// Add a file's estimated tokens to the running total, reporting them to
+    /// `output` when token counts are shown. Returns false, without charging,
+    /// if the file would exceed `max_tokens`.
+    fn charge_tokens<O: StreamingOutput>(
+        &self,
+        path: &Path,
+        output: &mut O,
+    ) -> std::io::Result<bool> {
+        if !self.config.show_tokens && self.config.max_tokens.is_none() {
+            return Ok(true);
+        }
+        // Files that aren't UTF-8 text cost nothing
+        let tokens = estimate_tokens(path);
+        let used = self.tokens_used.load(Ordering::Relaxed) + tokens.unwrap_or(0);
+        if let Some(max_tokens) = self.config.max_tokens
+            && used > max_tokens
+        {
+            if !self.budget_exceeded.swap(true, Ordering::Relaxed) {
+                output.token_budget_exceeded(max_tokens)?;
+            }
+            return Ok(false);
+        }
+        self.tokens_used.store(used, Ordering::Relaxed);
+
+        if self.config.show_tokens
+            && let Some(tokens) = tokens
+        {
+            output.file_tokens(tokens)?;
+        }
+        Ok(true)
+    }
+
+    /// Count the direct children of a directory that would be shown,
+    /// applying the same filters as the walk itself.
+    fn count_children(&self, path: &Path, depth: usize) -> ChildCount {
+        let mut count = ChildCount {
+            truncated: self.config.max_depth.is_some_and(|max| depth >= max),
+            ..ChildCount::default()
+        };
+
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return count;
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let entry_path = entry.path();
+            if should_ignore_path(&entry_path, &self.config, &self.filter) {
+                continue;
+            }
+
+            match self.classify_entry(&entry_path) {
+                Some((true, _)) => count.dirs += 1,
+                Some((false, _))
+                    if !(self.config.todos_only && self.cache.todos(&entry_path).is_none())
+                        && passes_imports_filter(&entry_path, &self.config, &self.cache) =>
+                {
+                    count.files += 1
+                }
+                _ => {}
+            }
+        }
+
+        count
+    }
+
     /// Extract metadata (comments and/or type signatures and/or TODOs and/or imports) from a file.
     fn extract_metadata(&self, path: &Path) -> Option<MetadataBlock> {
-        extract_metadata_from_path(
-            path,
-            self.config.extract_comments,
-            self.config.extract_types,
-            self.config.extract_todos,
-            self.config.extract_imports,
-        )
+        let start = Instant::now();
+        let import_index = self.import_index();
+        let block =
+            extract_metadata_from_path(path, &self.config, &self.cache, import_index.as_ref());
+        self.record_extraction([path], start.elapsed());
+        block
+    }
+
+    /// Add extraction from `paths` taking `elapsed` to the timing, if enabled.
+    fn record_extraction<'a>(&self, paths: impl IntoIterator<Item = &'a Path>, elapsed: Duration) {
+        let Some(timing) = &self.timing else {
+            return;
+        };
+        let mut stats = timing.lock().unwrap_or_else(PoisonError::into_inner);
+        stats.extraction_duration += elapsed;
+        for path in paths {
+            if self.config.extracts_language(path) {
+                stats.files_extracted += 1;
+                stats.bytes_read += path.metadata().map_or(0, |m| m.len());
+            }
+        }
+    }
+
+    fn import_index(&self) -> RwLockReadGuard<'_, Option<ImportIndex>> {
+        self.import_index
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Collect the imports of every included file under `root` for `--imports-depth`.
+    /// Dependencies are followed past `max_depth` and the time filters, since a
+    /// shown file still depends on files that are not shown.
+    fn build_import_index(&self, root: &Path) -> ImportIndex {
+        let mut files = HashMap::new();
+        self.collect_imports(root, &mut files);
+        ImportIndex::new(root, files)
+    }
+
+    fn collect_imports(&self, dir: &Path, files: &mut HashMap<PathBuf, FileImports>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.is_symlink()
+                || should_ignore_path(&path, &self.config, &self.filter)
+                || !(self.config.show_all || has_included_files(&path, &self.filter))
+            {
+                continue;
+            }
+            if path.is_dir() {
+                self.collect_imports(&path, files);
+            } else if self.config.extracts_language(&path)
+                && let Some(imports) = self.cache.imports(&path)
+            {
+                files.insert(path, imports);
+            }
+        }
     }
 }
 
 /// Extract metadata from a file path - standalone function for parallel execution.
-/// This is a free function to avoid capturing &StreamingWalker (which contains
-/// non-thread-safe FileFilter) in parallel closures.
+/// This is a free function so parallel closures only capture the config and cache.
 fn extract_metadata_from_path(
     path: &Path,
-    extract_comments: bool,
-    extract_types: bool,
-    extract_todo_markers: bool,
-    extract_import_statements: bool,
+    config: &WalkerConfig,
+    cache: &ExtractionCache,
+    import_index: Option<&ImportIndex>,
 ) -> Option<MetadataBlock> {
+    // Checked before any extractor opens the file
+    if !config.extracts_language(path) {
+        return None;
+    }
+
     let mut block = MetadataBlock::new();
 
     // Extract comments
-    if extract_comments {
-        if let Some(comment) = extract_first_comment(path) {
-            block.comment_lines = comment
-                .lines()
-                .map(|line| MetadataLine::new(line.to_string()))
-                .collect();
-        }
+    if config.extract_comments
+        && let Some(comment) = cache.first_comment(path)
+    {
+        block = block.with_comment(&comment);
     }
 
     // Extract type signatures
-    if extract_types {
-        if let Some(signatures) = extract_type_signatures(path) {
-            block.type_lines = signatures
-                .into_iter()
-                .map(|(sig, sym, indent)| {
-                    MetadataLine::with_symbol(sig, LineStyle::TypeSignature, sym, indent)
-                })
-                .collect();
+    if config.extract_types
+        && let Some(mut signatures) = cache.type_signatures_with_context(path, config.context_lines)
+    {
+        if !config.show_line_numbers {
+            signatures.iter_mut().for_each(|sig| sig.line_number = None);
         }
+        block = block.with_types(signatures);
     }
 
     // Extract TODO/FIXME markers
-    if extract_todo_markers {
-        if let Some(todos) = extract_todos(path) {
-            block.todo_lines = todos
-                .iter()
-                .map(|todo| {
-                    let content =
-                        format!("{}: {} (line {})", todo.marker_type, todo.text, todo.line);
-                    MetadataLine::with_style(content, LineStyle::Todo)
-                })
-                .collect();
+    if config.extract_todos
+        && let Some(todos) = cache.todos(path)
+    {
+        block = block.with_todos(&todos);
+    }
+
+    // Extract imports, formatted as a summary line
+    if config.extract_imports
+        && let Some(mut imports) = cache.imports(path)
+    {
+        if config.imports_relative_only {
+            imports.external.clear();
+            imports.std.clear();
         }
+        let hidden = config.max_imports.map_or(0, |max| imports.truncate(max));
+        block = block
+            .with_imports(&imports.summary())
+            .with_more_imports(hidden);
     }
 
-    // Extract imports
-    if extract_import_statements {
-        if let Some(imports) = extract_imports(path) {
-            // Format imports as a summary line
-            let summary = imports.summary();
-            if !summary.is_empty() {
-                block.import_lines = vec![MetadataLine::with_style(
-                    format!("imports: {}", summary),
-                    LineStyle::Import,
-                )];
-            }
+    // Follow internal imports for --imports-depth (which only finds external ones)
+    if config.extract_imports
+        && !config.imports_relative_only
+        && let Some(index) = import_index
+    {
+        let transitive = index.transitive_external(path, config.imports_depth);
+        block = block.with_transitive_imports(&transitive);
+    }
+
+    // Show the last commit that touched the file
+    if let Some(commit) = config
+        .last_commits
+        .as_ref()
+        .and_then(|commits| commits.get(&path.canonicalize().ok()?))
+    {
+        // Leads the comments so it is the line shown inline with the file name
+        let mut lines = vec![MetadataLine::with_style(
+            commit.oneline(),
+            LineStyle::Custom("git".to_string()),
+        )];
+        if !block.comment_lines.is_empty() {
+            lines.push(MetadataLine::new(String::new()));
         }
+        lines.append(&mut block.comment_lines);
+        block.comment_lines = lines;
+    }
+
+    // Run user-defined plugins
+    if let Some(plugin_block) = extract_plugin_metadata(path, &config.plugins, &config.extraction) {
+        block = block.merge(plugin_block);
     }
 
     if block.is_empty() { None } else { Some(block) }
@@ -0,0 +1,138 @@
+//! Transitive imports for `--imports-depth`
+//!
+//! The imports of every file in the tree are collected before the walk, so a
+//! file's internal imports can be followed to the files they name. The
+//! external imports of those files are reported as transitive dependencies.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::imports::{FileImports, module_key, resolve_import};
+
+/// Imports of every file in a tree, with internal imports resolvable to files.
+#[derive(Debug, Default)]
+pub(crate) struct ImportIndex {
+    /// Relative path (with `/` separators) and imports of each file
+    files: Vec<(String, FileImports)>,
+    /// Module path of each file, in the same order as `files`
+    module_keys: Vec<String>,
+    /// Position in `files` of each walked path
+    positions: HashMap<PathBuf, usize>,
+}
+
+impl ImportIndex {
+    /// Index the imports of `files`, walked from `root`.
+    pub(crate) fn new(root: &Path, files: HashMap<PathBuf, FileImports>) -> Self {
+        let mut files: Vec<_> = files.into_iter().collect();
+        // Resolution picks the first matching file, so keep it deterministic
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut index = Self::default();
+        for (path, imports) in files {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            index.module_keys.push(module_key(&relative));
+            index.positions.insert(path, index.files.len());
+            index.files.push((relative, imports));
+        }
+        index
+    }
+
+    /// External imports reached by following the internal imports of `path`
+    /// up to `depth` levels, minus those `path` already imports directly.
+    ///
+    /// Each file is visited at most once, so circular imports terminate.
+    pub(crate) fn transitive_external(&self, path: &Path, depth: usize) -> Vec<String> {
+        let Some(&start) = self.positions.get(path) else {
+            return Vec::new();
+        };
+        let direct = &self.files[start].1.external;
+
+        let mut visited = HashSet::from([start]);
+        let mut frontier = vec![start];
+        let mut found: Vec<String> = Vec::new();
+
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for &i in &frontier {
+                let (from, imports) = &self.files[i];
+                for import in &imports.internal {
+                    let Some(target) = resolve_import(import, from, &self.module_keys) else {
+                        continue;
+                    };
+                    if !visited.insert(target) {
+                        continue;
+                    }
+                    for external in &self.files[target].1.external {
+                        if !direct.contains(external) && !found.contains(external) {
+                            found.push(external.clone());
+                        }
+                    }
+                    next.push(target);
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn imports(external: &[&str], internal: &[&str]) -> FileImports {
+        FileImports {
+            external: external.iter().map(|s| s.to_string()).collect(),
+            std: Vec::new(),
+            internal: internal.iter().map(|s| s.to_string()).collect(),
+            filtered: false,
+        }
+    }
+
+    fn index(files: &[(&str, FileImports)]) -> ImportIndex {
+        let files = files
+            .iter()
+            .map(|(path, imports)| (Path::new("/repo").join(path), imports.clone()))
+            .collect();
+        ImportIndex::new(Path::new("/repo"), files)
+    }
+
+    #[test]
+    fn test_transitive_external_follows_depth() {
+        let index = index(&[
+            ("src/app.ts", imports(&["react"], &["./api"])),
+            ("src/api.ts", imports(&["axios", "react"], &["./http"])),
+            ("src/http.ts", imports(&["node-fetch"], &[])),
+        ]);
+        let app = Path::new("/repo/src/app.ts");
+
+        assert!(index.transitive_external(app, 0).is_empty());
+        assert_eq!(index.transitive_external(app, 1), vec!["axios"]);
+        assert_eq!(
+            index.transitive_external(app, 2),
+            vec!["axios", "node-fetch"]
+        );
+        assert!(
+            index
+                .transitive_external(Path::new("/repo/missing.ts"), 2)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_transitive_external_survives_cycles() {
+        let index = index(&[
+            ("a.py", imports(&["requests"], &[".b"])),
+            ("b.py", imports(&["click"], &[".a"])),
+        ]);
+        assert_eq!(
+            index.transitive_external(Path::new("/repo/a.py"), 10),
+            vec!["click"]
+        );
+    }
+}
@@ -4,6 +4,8 @@ use std::path::Path;
 
 use glob::Pattern;
 
+use crate::cache::ExtractionCache;
+
 use super::config::WalkerConfig;
 use super::filter::FileFilter;
 
@@ -17,19 +19,19 @@ pub fn has_included_files(path: &Path, filter: &Option<FileFilter>) -> bool {
     }
 }
 
-/// Check if a path should be included based on filter, show_all flag, and time filters.
+/// Check if a path should be included based on filter, show_all flag, time and changed-file filters.
 pub fn should_include_path(path: &Path, config: &WalkerConfig, filter: &Option<FileFilter>) -> bool {
     // Check gitignore filter
-    if !config.show_all {
-        if let Some(f) = filter {
-            if !f.is_included(path) {
-                return false;
-            }
-        }
+    if !config.show_all
+        && let Some(f) = filter
+        && !f.is_included(path)
+    {
+        return false;
     }
 
-    // Check time filter (applies to files only)
-    if path.is_file() && !passes_time_filter(path, config) {
+    // Check time and changed-file filters (apply to files only)
+    if path.is_file() && (!passes_time_filter(path, config) || !passes_changed_filter(path, config))
+    {
         return false;
     }
 
@@ -37,7 +39,9 @@ pub fn should_include_path(path: &Path, config: &WalkerConfig, filter: &Option<F
 }
 
 /// Check if a path should be ignored based on name and ignore patterns.
-pub fn should_ignore_path(path: &Path, ignore_patterns: &[String]) -> bool {
+/// Directories the gitignore filter rejects by name are skipped here too, so
+/// they are never descended into.
+pub fn should_ignore_path(path: &Path, config: &WalkerConfig, filter: &Option<FileFilter>) -> bool {
     let name = path
         .file_name()
         .map(|s| s.to_string_lossy().to_string())
@@ -48,8 +52,12 @@ pub fn should_ignore_path(path: &Path, ignore_patterns: &[String]) -> bool {
         return true;
     }
 
+    if !config.show_all && filter.as_ref().is_some_and(|f| f.is_definitely_ignored(path)) {
+        return true;
+    }
+
     // Check custom ignore patterns
-    for pattern in ignore_patterns {
+    for pattern in &config.ignore_patterns {
         if name == *pattern || glob_match(pattern, &name) {
             return true;
         }
@@ -70,29 +78,98 @@ pub fn get_file_size(path: &Path) -> (Option<u64>, Option<String>) {
     match path.metadata() {
         Ok(meta) => {
             let size = meta.len();
-            (Some(size), Some(format_size(size)))
+            (Some(size), Some(format_size(size, SizeStyle::Binary)))
         }
         Err(_) => (None, None),
     }
 }
 
-/// Format a size in bytes to human-readable format.
-pub fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.1}G", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.1}M", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.1}K", bytes as f64 / KB as f64)
-    } else {
-        format!("{}B", bytes)
+/// How file sizes are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SizeStyle {
+    /// Binary prefixes, where 1K is 1024 bytes: `512B`, `1.5K`, `2.0M`
+    #[default]
+    Binary,
+    /// SI prefixes, where 1kB is 1000 bytes: `512B`, `1.5kB`, `2.0MB`
+    Si,
+    /// The exact number of bytes: `1536`
+    Bytes,
+}
+
+/// Format a size in bytes in the given style.
+pub fn format_size(bytes: u64, style: SizeStyle) -> String {
+    let (unit, suffixes) = match style {
+        SizeStyle::Binary => (1024, ["B", "K", "M", "G"]),
+        SizeStyle::Si => (1000, ["B", "kB", "MB", "GB"]),
+        SizeStyle::Bytes => return bytes.to_string(),
+    };
+
+    let mut divisor = unit * unit * unit;
+    for suffix in suffixes[1..].iter().rev() {
+        if bytes >= divisor {
+            return format!("{:.1}{}", bytes as f64 / divisor as f64, suffix);
+        }
+        divisor /= unit;
+    }
+    format!("{}{}", bytes, suffixes[0])
+}
+
+/// Check if a file has imports, when `imports_only` is set, and imports the
+/// module required by `imports_from`, if any.
+pub fn passes_imports_filter(path: &Path, config: &WalkerConfig, cache: &ExtractionCache) -> bool {
+    if !config.imports_only && config.imports_from.is_none() {
+        return true;
+    }
+    let Some(imports) = cache.imports(path).filter(|imports| !imports.is_empty()) else {
+        return false;
+    };
+    match &config.imports_from {
+        None => true,
+        Some(module) if config.ignore_case => imports.contains_module_ignore_case(module),
+        Some(module) => imports.contains_module(module),
     }
 }
 
+/// Check if a directory has an included file passing `passes_imports_filter`
+/// at any depth, so directories left empty by an import filter are pruned.
+/// Always true without an import filter.
+pub fn has_files_passing_imports_filter(
+    path: &Path,
+    config: &WalkerConfig,
+    filter: &Option<FileFilter>,
+    cache: &ExtractionCache,
+) -> bool {
+    if !config.imports_only && config.imports_from.is_none() {
+        return true;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return false;
+    };
+    entries.filter_map(|e| e.ok()).any(|entry| {
+        let entry_path = entry.path();
+        if entry_path.is_symlink()
+            || should_ignore_path(&entry_path, config, filter)
+            || !should_include_path(&entry_path, config, filter)
+        {
+            return false;
+        }
+        if entry_path.is_dir() {
+            has_files_passing_imports_filter(&entry_path, config, filter, cache)
+        } else {
+            entry_path.is_file() && passes_imports_filter(&entry_path, config, cache)
+        }
+    })
+}
+
+/// Check if a file is in the `changed_files` allowlist, if one is set.
+pub fn passes_changed_filter(path: &Path, config: &WalkerConfig) -> bool {
+    let Some(changed) = &config.changed_files else {
+        return true;
+    };
+    path.canonicalize()
+        .is_ok_and(|path| changed.contains(&path))
+}
+
 /// Check if a file passes the time filter based on its modification time.
 pub fn passes_time_filter(path: &Path, config: &WalkerConfig) -> bool {
     // If no time filters, pass
@@ -107,17 +184,17 @@ pub fn passes_time_filter(path: &Path, config: &WalkerConfig) -> bool {
     };
 
     // Check newer_than filter
-    if let Some(newer) = config.newer_than {
-        if mtime < newer {
-            return false;
-        }
+    if let Some(newer) = config.newer_than
+        && mtime < newer
+    {
+        return false;
     }
 
     // Check older_than filter
-    if let Some(older) = config.older_than {
-        if mtime > older {
-            return false;
-        }
+    if let Some(older) = config.older_than
+        && mtime > older
+    {
+        return false;
     }
 
     true
@@ -152,4 +229,19 @@ mod tests {
         assert!(glob_match("[a-z].txt", "x.txt"));
         assert!(!glob_match("[a-z].txt", "X.txt"));
     }
+
+    #[test]
+    fn test_format_size_styles() {
+        assert_eq!(format_size(512, SizeStyle::Binary), "512B");
+        assert_eq!(format_size(1536, SizeStyle::Binary), "1.5K");
+        assert_eq!(format_size(3 << 30, SizeStyle::Binary), "3.0G");
+
+        assert_eq!(format_size(999, SizeStyle::Si), "999B");
+        assert_eq!(format_size(1500, SizeStyle::Si), "1.5kB");
+        assert_eq!(format_size(1536, SizeStyle::Si), "1.5kB");
+        assert_eq!(format_size(2_000_000, SizeStyle::Si), "2.0MB");
+        assert_eq!(format_size(7_000_000_000, SizeStyle::Si), "7.0GB");
+
+        assert_eq!(format_size(1536, SizeStyle::Bytes), "1536");
+    }
 }
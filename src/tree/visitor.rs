@@ -0,0 +1,126 @@
+//! Visitor traversal over built trees
+//!
+//! `TreeVisitor` lets library users walk a `TreeNode` built by `TreeWalker`
+//! without scanning the filesystem again.
+
+use std::path::PathBuf;
+
+use super::json_types::TreeNode;
+
+/// Callbacks for a depth-first traversal of a `TreeNode`, driven by
+/// `TreeNode::accept`. Directories are entered before and exited after
+/// their children; children are visited in order.
+pub trait TreeVisitor {
+    /// Called for each file.
+    fn visit_file(&mut self, _node: &TreeNode) {}
+
+    /// Called for each directory before its children are visited.
+    fn visit_dir_enter(&mut self, _node: &TreeNode) {}
+
+    /// Called for each directory after its children have been visited.
+    fn visit_dir_exit(&mut self, _node: &TreeNode) {}
+}
+
+impl TreeNode {
+    /// Traverse this node and its descendants depth-first with `visitor`.
+    pub fn accept(&self, visitor: &mut impl TreeVisitor) {
+        match self {
+            TreeNode::File { .. } => visitor.visit_file(self),
+            TreeNode::Dir { children, .. } => {
+                visitor.visit_dir_enter(self);
+                for child in children {
+                    child.accept(visitor);
+                }
+                visitor.visit_dir_exit(self);
+            }
+        }
+    }
+}
+
+/// Visitor that collects the path of every file in tree order.
+#[derive(Debug, Default)]
+pub struct FlattenVisitor {
+    pub paths: Vec<PathBuf>,
+}
+
+impl FlattenVisitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take the collected file paths.
+    pub fn into_paths(self) -> Vec<PathBuf> {
+        self.paths
+    }
+}
+
+impl TreeVisitor for FlattenVisitor {
+    fn visit_file(&mut self, node: &TreeNode) {
+        if let TreeNode::File { path, .. } = node {
+            self.paths.push(path.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{dir_node, file_node};
+
+    #[test]
+    fn test_flatten_visitor_collects_files_in_order() {
+        let tree = dir_node(
+            ".",
+            vec![
+                file_node("./Cargo.toml"),
+                dir_node(
+                    "./src",
+                    vec![file_node("./src/lib.rs"), file_node("./src/main.rs")],
+                ),
+                dir_node("./empty", vec![]),
+            ],
+        );
+
+        let mut visitor = FlattenVisitor::new();
+        tree.accept(&mut visitor);
+
+        assert_eq!(
+            visitor.into_paths(),
+            [
+                PathBuf::from("./Cargo.toml"),
+                PathBuf::from("./src/lib.rs"),
+                PathBuf::from("./src/main.rs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_accept_enters_and_exits_dirs() {
+        #[derive(Default)]
+        struct EventLog(Vec<String>);
+
+        impl TreeVisitor for EventLog {
+            fn visit_file(&mut self, node: &TreeNode) {
+                self.0.push(format!("file {}", node.name()));
+            }
+            fn visit_dir_enter(&mut self, node: &TreeNode) {
+                self.0.push(format!("enter {}", node.name()));
+            }
+            fn visit_dir_exit(&mut self, node: &TreeNode) {
+                self.0.push(format!("exit {}", node.name()));
+            }
+        }
+
+        let tree = dir_node(
+            ".",
+            vec![dir_node("./src", vec![file_node("./src/lib.rs")])],
+        );
+        let mut log = EventLog::default();
+        tree.accept(&mut log);
+
+        assert_eq!(
+            log.0,
+            ["enter .", "enter src", "file lib.rs", "exit src", "exit ."]
+        );
+    }
+}
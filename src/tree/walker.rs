@@ -1,17 +1,19 @@
 //! TreeWalker - builds full tree in memory for JSON output
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::comments::extract_first_comment;
+use crate::cache::ExtractionCache;
+use crate::file_utils::is_generated_file;
 use crate::git::{GitFilter, GitignoreFilter};
-use crate::imports::extract_imports;
-use crate::todos::extract_todos;
-use crate::types::extract_type_signatures;
+use crate::todos::TodoMatcher;
 
 use super::config::WalkerConfig;
 use super::filter::FileFilter;
-use super::json_types::{JsonTodoItem, TreeNode};
-use super::utils::{get_file_size, has_included_files, should_ignore_path, should_include_path};
+use super::json_types::{JsonTodoItem, JsonTypeSignature, TreeNode};
+use super::utils::{
+    get_file_size, has_files_passing_imports_filter, has_included_files, passes_imports_filter,
+    should_ignore_path, should_include_path,
+};
 
 /// Tree walker that builds the full tree in memory.
 /// Required for JSON output serialization.
@@ -19,13 +21,26 @@ use super::utils::{get_file_size, has_included_files, should_ignore_path, should
 pub struct TreeWalker {
     config: WalkerConfig,
     filter: Option<FileFilter>,
+    cache: ExtractionCache,
 }
 
 impl TreeWalker {
     pub fn new(config: WalkerConfig) -> Self {
+        let cache = if config.use_cache {
+            ExtractionCache::load_default()
+        } else {
+            ExtractionCache::disabled()
+        }
+        .with_todo_matcher(
+            TodoMatcher::new(&config.custom_markers)
+                .with_assignee(config.todo_assignee.clone())
+                .with_types(&config.todo_types),
+        )
+        .with_extraction_config(config.extraction);
         Self {
             config,
             filter: None,
+            cache,
         }
     }
 
@@ -45,7 +60,104 @@ impl TreeWalker {
     }
 
     pub fn walk(&self, root: &Path) -> Option<TreeNode> {
-        self.walk_dir(root, 0)
+        let tree = self.walk_dir(root, 0);
+        self.save_cache();
+        tree
+    }
+
+    /// The files under `root` that `walk` would include, as a sorted flat
+    /// list. Honors the same filters without building a `TreeNode` or
+    /// extracting metadata, so this is the recommended API for scripts that
+    /// only need paths: a gitignore-aware `find . -type f`.
+    pub fn walk_to_vec(&self, root: &Path) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        if root.is_file() && !root.is_symlink() {
+            if self.includes_file(root) {
+                files.push(root.to_path_buf());
+            }
+        } else {
+            self.collect_paths(root, 0, &mut files, &mut Vec::new());
+        }
+        self.save_cache();
+        files.sort();
+        files
+    }
+
+    /// The directories below `root` that `walk` would show, as a sorted flat
+    /// list. The counterpart of `walk_to_vec` for `find . -type d`.
+    pub fn walk_dirs_to_vec(&self, root: &Path) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        if root.is_dir() && !root.is_symlink() {
+            self.collect_paths(root, 0, &mut Vec::new(), &mut dirs);
+        }
+        self.save_cache();
+        dirs.sort();
+        dirs
+    }
+
+    /// Whether `walk` would include the file at `path`.
+    fn includes_file(&self, path: &Path) -> bool {
+        if self.config.dirs_only || !should_include_path(path, &self.config, &self.filter) {
+            return false;
+        }
+        if self.config.todos_only {
+            let extract = self.config.extract_todos
+                && self.config.extracts_language(path)
+                && !(self.config.skip_generated && is_generated_file(path));
+            if !extract || self.cache.todos(path).is_none_or(|t| t.is_empty()) {
+                return false;
+            }
+        }
+        passes_imports_filter(path, &self.config, &self.cache)
+    }
+
+    /// Whether directory `path` has files left to show after the gitignore
+    /// and import filters.
+    fn has_shown_files(&self, path: &Path) -> bool {
+        has_included_files(path, &self.filter)
+            && has_files_passing_imports_filter(path, &self.config, &self.filter, &self.cache)
+    }
+
+    /// Collect the files and directories that `walk_level` would show below
+    /// directory `path` (at `depth`), descending into every directory.
+    fn collect_paths(
+        &self,
+        path: &Path,
+        depth: usize,
+        files: &mut Vec<PathBuf>,
+        dirs: &mut Vec<PathBuf>,
+    ) {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return;
+        };
+        if self.config.max_depth.is_some_and(|max| depth >= max) {
+            return;
+        }
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let entry_path = entry.path();
+            if entry_path.is_symlink()
+                || should_ignore_path(&entry_path, &self.config, &self.filter)
+            {
+                continue;
+            }
+
+            if entry_path.is_dir() {
+                let (file_count, dir_count) = (files.len(), dirs.len());
+                self.collect_paths(&entry_path, depth + 1, files, dirs);
+                // Shown like in walk_level: unconditionally, or when not empty
+                let shown = self.config.dirs_only
+                    || !self.config.prune_empty_dirs
+                    || self.has_shown_files(&entry_path)
+                    || files.len() > file_count
+                    || dirs.len() > dir_count;
+                if shown {
+                    dirs.push(entry_path);
+                }
+            } else if entry_path.is_file() && self.includes_file(&entry_path) {
+                files.push(entry_path);
+            }
+        }
     }
 
     fn walk_dir(&self, path: &Path, depth: usize) -> Option<TreeNode> {
@@ -54,8 +166,6 @@ impl TreeWalker {
             return None;
         }
 
-        let at_max_depth = self.config.max_depth.is_some_and(|max| depth >= max);
-
         let name = path
             .file_name()
             .map(|s| s.to_string_lossy().to_string())
@@ -68,19 +178,35 @@ impl TreeWalker {
             if !should_include_path(path, &self.config, &self.filter) {
                 return None;
             }
-            let comment = if self.config.extract_comments {
-                extract_first_comment(path)
+            // Skip extraction for languages outside --lang before reading the file
+            let extract = self.config.extracts_language(path)
+                && !(self.config.skip_generated && is_generated_file(path));
+            let comment = if extract && self.config.extract_comments {
+                self.cache.first_comment(path)
             } else {
                 None
             };
-            let types = if self.config.extract_types {
-                extract_type_signatures(path)
-                    .map(|sigs| sigs.into_iter().map(|(sig, _sym, _indent)| sig).collect())
+            let types = if extract && self.config.extract_types {
+                let sigs = self
+                    .cache
+                    .type_signatures_with_context(path, self.config.context_lines);
+                sigs.map(|sigs| {
+                    sigs.into_iter()
+                        .map(|mut sig| {
+                            if !self.config.show_line_numbers {
+                                sig.line_number = None;
+                            }
+                            JsonTypeSignature::from(sig)
+                        })
+                        .collect()
+                })
             } else {
                 None
             };
-            let todos = if self.config.extract_todos {
-                extract_todos(path).map(|items| items.iter().map(JsonTodoItem::from).collect())
+            let todos = if extract && self.config.extract_todos {
+                self.cache
+                    .todos(path)
+                    .map(|items| items.iter().map(JsonTodoItem::from).collect())
             } else {
                 None
             };
@@ -92,8 +218,17 @@ impl TreeWalker {
             {
                 return None;
             }
-            let imports = if self.config.extract_imports {
-                extract_imports(path)
+            if !passes_imports_filter(path, &self.config, &self.cache) {
+                return None;
+            }
+            let imports = if extract && self.config.extract_imports {
+                self.cache.imports(path).map(|mut imports| {
+                    imports.filtered = self.config.imports_relative_only;
+                    if let Some(max) = self.config.max_imports {
+                        imports.truncate(max);
+                    }
+                    imports
+                })
             } else {
                 None
             };
@@ -118,55 +253,155 @@ impl TreeWalker {
             return None;
         }
 
-        // If at max depth, return the directory but don't descend
-        if at_max_depth {
-            return Some(TreeNode::Dir {
-                name,
-                path: path.to_path_buf(),
-                children: Vec::new(),
-            });
+        let children: Vec<TreeNode> = self
+            .walk_level(path, depth)?
+            .into_iter()
+            .filter_map(|entry| match entry {
+                LevelEntry::Node(node) => Some(*node),
+                LevelEntry::Dir(dir) => self.walk_dir(&dir, depth + 1),
+            })
+            .collect();
+        let child_count = self.config.count_children.then(|| {
+            if self.at_depth_limit(depth) {
+                self.count_unwalked_children(path, depth)
+            } else {
+                children.len()
+            }
+        });
+
+        Some(TreeNode::Dir {
+            name,
+            path: path.to_path_buf(),
+            child_count,
+            children,
+        })
+    }
+
+    /// The shown children of directory `path` (at `depth`), without descending
+    /// into subdirectories that are shown regardless of their contents.
+    ///
+    /// Returns `None` if the directory cannot be read, and no children for a
+    /// directory at the depth limit.
+    pub(crate) fn walk_level(&self, path: &Path, depth: usize) -> Option<Vec<LevelEntry>> {
+        // Unreadable directories are left out even at the depth limit, as the
+        // streaming walker does
+        let entries = std::fs::read_dir(path).ok()?;
+
+        // At max depth the directory is shown but not descended into
+        if self.at_depth_limit(depth) {
+            return Some(Vec::new());
         }
+        Some(self.level_entries(entries, depth))
+    }
 
-        let mut children = Vec::new();
-        let entries = match std::fs::read_dir(path) {
-            Ok(e) => e,
-            Err(_) => return None,
+    /// Whether directories at `depth` are shown without their children.
+    pub(crate) fn at_depth_limit(&self, depth: usize) -> bool {
+        self.config.max_depth.is_some_and(|max| depth >= max)
+    }
+
+    /// Number of children that directory `path` at the depth limit would show
+    /// if it were walked, leaving out unreadable directories like the walk does.
+    pub(crate) fn count_unwalked_children(&self, path: &Path, depth: usize) -> usize {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return 0;
         };
+        self.level_entries(entries, depth)
+            .iter()
+            .filter(|entry| match entry {
+                LevelEntry::Node(_) => true,
+                LevelEntry::Dir(dir) => std::fs::read_dir(dir).is_ok(),
+            })
+            .count()
+    }
 
+    /// The shown children among `entries` of a directory at `depth`.
+    fn level_entries(&self, entries: std::fs::ReadDir, depth: usize) -> Vec<LevelEntry> {
         let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
         entries.sort_by_key(|a| a.file_name());
 
+        let mut children = Vec::new();
         for entry in entries {
             let entry_path = entry.path();
 
-            if should_ignore_path(&entry_path, &self.config.ignore_patterns) {
+            if should_ignore_path(&entry_path, &self.config, &self.filter) {
+                continue;
+            }
+
+            // In dirs_only mode or without pruning, always show directories,
+            // as well as those with included files
+            if entry_path.is_dir()
+                && !entry_path.is_symlink()
+                && (self.config.dirs_only
+                    || !self.config.prune_empty_dirs
+                    || self.has_shown_files(&entry_path))
+            {
+                children.push(LevelEntry::Dir(entry_path));
                 continue;
             }
 
             if let Some(node) = self.walk_dir(&entry_path, depth + 1) {
-                // Skip empty directories (but only if not in dirs_only mode
-                // and not showing a depth-limited directory)
+                // Otherwise skip truly empty directories (those with no tracked files)
                 if let TreeNode::Dir {
                     children: ref c, ..
                 } = node
+                    && c.is_empty()
                 {
-                    // In dirs_only mode, always show directories
-                    // Otherwise, skip truly empty directories (those with no tracked files)
-                    if c.is_empty()
-                        && !self.config.dirs_only
-                        && !has_included_files(&entry_path, &self.filter)
-                    {
-                        continue;
-                    }
+                    continue;
                 }
-                children.push(node);
+                children.push(LevelEntry::Node(Box::new(node)));
             }
         }
+        children
+    }
 
-        Some(TreeNode::Dir {
-            name,
-            path: path.to_path_buf(),
-            children,
-        })
+    /// Persist the extraction cache after a walk.
+    pub(crate) fn save_cache(&self) {
+        // The cache is only an optimization, so failing to persist it is not fatal
+        if let Err(e) = self.cache.save() {
+            eprintln!("fruit: warning: failed to write extraction cache: {}", e);
+        }
+    }
+}
+
+/// A child found by `TreeWalker::walk_level`.
+pub(crate) enum LevelEntry {
+    /// A complete node: a file, or a directory that was already walked
+    Node(Box<TreeNode>),
+    /// A directory to walk (one level deeper) before it can be shown
+    Dir(PathBuf),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestRepo;
+
+    #[test]
+    fn test_walk_to_vec_lists_included_paths() {
+        let repo = TestRepo::with_git();
+        repo.add_file(".gitignore", "target/\n");
+        repo.add_file("src/main.rs", "fn main() {}\n");
+        repo.add_file("src/util/mod.rs", "\n");
+        repo.add_file("Cargo.toml", "[package]\n");
+        repo.add_file("target/debug.log", "\n");
+        repo.commit("init");
+
+        let root = repo.path();
+        let walker = TreeWalker::new(WalkerConfig::default())
+            .with_gitignore_filter(GitignoreFilter::new(root).unwrap());
+
+        assert_eq!(
+            walker.walk_to_vec(root),
+            [
+                root.join(".gitignore"),
+                root.join("Cargo.toml"),
+                root.join("src/main.rs"),
+                root.join("src/util/mod.rs"),
+            ]
+        );
+        assert_eq!(
+            walker.walk_dirs_to_vec(root),
+            [root.join("src"), root.join("src/util")]
+        );
     }
 }
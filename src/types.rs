@@ -5,114 +5,413 @@
 //! ~80% of the value with much less complexity.
 
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::LazyLock;
 
-use crate::file_utils::read_source_file;
+use crate::file_utils::{ExtractionConfig, read_source_file};
+use crate::language::Language;
 use crate::metadata::{MetadataBlock, MetadataExtractor};
+use crate::string_utils::{DEFAULT_TAB_WIDTH, expand_tabs};
 
 /// Calculate the indentation level of a line (number of spaces, tabs = 4 spaces).
-fn calculate_indent(line: &str) -> usize {
-    let mut indent = 0;
-    for ch in line.chars() {
-        match ch {
-            ' ' => indent += 1,
-            '\t' => indent += 4,
-            _ => break,
+pub(crate) fn calculate_indent(line: &str) -> usize {
+    let body = line.trim_start_matches([' ', '\t']);
+    expand_tabs(&line[..line.len() - body.len()], DEFAULT_TAB_WIDTH).len()
+}
+
+/// An exported item found by `extract_type_signatures`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeSignature {
+    /// The cleaned-up declaration, e.g. `pub fn process(input: &str)`
+    pub signature: String,
+    /// Name of the declared item, highlighted in the output
+    pub symbol: String,
+    /// Indentation in spaces (tabs are converted to 4 spaces)
+    pub indent: usize,
+    /// Line of the declaration (1-indexed), when known
+    pub line_number: Option<usize>,
+    /// Non-blank source lines following the declaration, when requested
+    pub context: Vec<String>,
+}
+
+impl TypeSignature {
+    /// Create a signature declared on `line` (1-indexed).
+    pub fn new(
+        signature: impl Into<String>,
+        symbol: impl Into<String>,
+        indent: usize,
+        line: usize,
+    ) -> Self {
+        Self {
+            signature: signature.into(),
+            symbol: symbol.into(),
+            indent,
+            line_number: Some(line),
+            context: Vec::new(),
         }
     }
-    indent
+}
+
+/// Canonical extensions of the languages `extract_type_signatures` has an
+/// extractor for.
+const TYPE_EXTENSIONS: &[&str] = &[
+    "rs",
+    "ts",
+    "js",
+    "py",
+    "go",
+    "c",
+    "cpp",
+    "java",
+    "cs",
+    "rb",
+    "dockerfile",
+    "mk",
+    "nix",
+    "clj",
+    "pl",
+];
+
+/// Whether `extract_type_signatures` has an extractor for `language`.
+pub fn supports_types(language: Language) -> bool {
+    TYPE_EXTENSIONS.contains(&language.extension())
 }
 
 /// Extract exported type signatures from a file.
-/// Returns a list of (signature, symbol_name, indent_level) tuples.
-/// indent_level is the number of spaces (tabs are converted to 4 spaces).
-pub fn extract_type_signatures(path: &Path) -> Option<Vec<(String, String, usize)>> {
-    let (content, extension) = read_source_file(path)?;
+/// Returns the signatures in source order.
+pub fn extract_type_signatures(path: &Path) -> Option<Vec<TypeSignature>> {
+    extract_type_signatures_with_context(path, 0)
+}
+
+/// Extract exported type signatures from a file, each with up to
+/// `context_lines` non-blank lines of the code that follows it.
+pub fn extract_type_signatures_with_context(
+    path: &Path,
+    context_lines: usize,
+) -> Option<Vec<TypeSignature>> {
+    extract_type_signatures_with_config(path, context_lines, &ExtractionConfig::global())
+}
+
+/// Like `extract_type_signatures_with_context`, skipping files over
+/// `config.max_file_size`.
+pub fn extract_type_signatures_with_config(
+    path: &Path,
+    context_lines: usize,
+    config: &ExtractionConfig,
+) -> Option<Vec<TypeSignature>> {
+    let (content, extension) = read_source_file(path, config.max_file_size)?;
 
     // Extension is already normalized to lowercase by read_source_file
     let signatures = match extension {
         "rs" => extract_rust_signatures(&content),
         "ts" => extract_typescript_signatures(&content),
         "js" => extract_javascript_signatures(&content),
-        "py" => extract_python_signatures(&content),
+        "py" => extract_python_signatures(&content, config.show_private),
         "go" => extract_go_signatures(&content),
+        "c" | "cpp" => extract_c_signatures(&content),
+        "java" => extract_java_signatures(&content, config.show_private),
+        "cs" => extract_csharp_signatures(&content, config.show_private),
+        "rb" => extract_ruby_signatures(&content, config.show_private),
+        "dockerfile" => extract_dockerfile_instructions(&content),
+        "mk" => extract_makefile_targets(&content),
+        "nix" => extract_nix_functions(&content),
+        "clj" => extract_clojure_definitions(&content, config.show_private),
+        "pl" => extract_perl_signatures(&content, config.show_private),
         _ => None,
     };
 
-    signatures.filter(|s| !s.is_empty())
+    let mut signatures = signatures.filter(|s| !s.is_empty())?;
+    if context_lines > 0 {
+        attach_context(&mut signatures, &content, context_lines);
+    }
+    Some(signatures)
+}
+
+/// Fill in each signature's `context` with the non-blank lines after its declaration.
+fn attach_context(signatures: &mut [TypeSignature], content: &str, context_lines: usize) {
+    let lines: Vec<&str> = content.lines().collect();
+    for sig in signatures {
+        let Some(line) = sig.line_number else {
+            continue;
+        };
+        sig.context = lines
+            .iter()
+            .skip(line)
+            .filter(|l| !l.trim().is_empty())
+            .take(context_lines)
+            .map(|l| l.trim_end().to_string())
+            .collect();
+    }
 }
 
 // Static regex patterns for each language
 
-// Rust patterns - with capture groups for symbol names
+// Rust patterns - with capture groups for symbol names.
+// Restricted visibility (`pub(crate)`, `pub(super)`, `pub(in path)`) also counts as exported.
 static RUST_PUB_FN: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^pub\s+(async\s+)?fn\s+(\w+)[^{;]*").expect("RUST_PUB_FN regex is invalid")
+    Regex::new(r"^pub(?:\((?:crate|super|self|in\s+[\w:]+)\))?\s+(async\s+)?fn\s+(\w+)[^{;]*")
+        .expect("RUST_PUB_FN regex is invalid")
 });
 static RUST_PUB_STRUCT: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^pub\s+struct\s+(\w+)[^{;]*").expect("RUST_PUB_STRUCT regex is invalid")
+    Regex::new(r"^pub(?:\((?:crate|super|self|in\s+[\w:]+)\))?\s+struct\s+(\w+)[^{;]*")
+        .expect("RUST_PUB_STRUCT regex is invalid")
 });
 static RUST_PUB_ENUM: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^pub\s+enum\s+(\w+)[^{;]*").expect("RUST_PUB_ENUM regex is invalid")
+    Regex::new(r"^pub(?:\((?:crate|super|self|in\s+[\w:]+)\))?\s+enum\s+(\w+)[^{;]*")
+        .expect("RUST_PUB_ENUM regex is invalid")
 });
 static RUST_PUB_TRAIT: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^pub\s+trait\s+(\w+)[^{;]*").expect("RUST_PUB_TRAIT regex is invalid")
+    Regex::new(r"^pub(?:\((?:crate|super|self|in\s+[\w:]+)\))?\s+trait\s+(\w+)[^{;]*")
+        .expect("RUST_PUB_TRAIT regex is invalid")
 });
 static RUST_PUB_TYPE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^pub\s+type\s+(\w+)[^;]+").expect("RUST_PUB_TYPE regex is invalid")
+    Regex::new(r"^pub(?:\((?:crate|super|self|in\s+[\w:]+)\))?\s+type\s+(\w+)[^;]+")
+        .expect("RUST_PUB_TYPE regex is invalid")
 });
 static RUST_PUB_CONST: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^pub\s+const\s+(\w+):\s*[^=]+").expect("RUST_PUB_CONST regex is invalid")
+    Regex::new(r"^pub(?:\((?:crate|super|self|in\s+[\w:]+)\))?\s+const\s+(\w+):\s*[^=]+")
+        .expect("RUST_PUB_CONST regex is invalid")
 });
 
-fn extract_rust_signatures(content: &str) -> Option<Vec<(String, String, usize)>> {
-    let mut signatures = Vec::new();
-
-    for line in content.lines() {
-        let trimmed = line.trim();
+/// Start of any Rust function declaration, exported or not (literals already stripped).
+static RUST_ANY_FN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^(pub(\([^)]*\))?\s+)?((const|async|unsafe|extern(\s+"")?)\s+)*fn\s"#)
+        .expect("RUST_ANY_FN regex is invalid")
+});
 
-        // Skip doc comments and attributes
-        if trimmed.starts_with("///")
-            || trimmed.starts_with("//!")
-            || trimmed.starts_with("#[")
-            || trimmed.starts_with("//")
-        {
-            continue;
-        }
+/// Kind of block opened by a `{` in Rust source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RustBlock {
+    /// A function body; items declared inside are local, not exported
+    FnBody,
+    /// Any other block (impl, trait, mod, struct literal, closure, ...)
+    Other,
+}
 
-        let indent = calculate_indent(line);
+/// Lexer state carried across lines by `strip_rust_literals`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum RustLexState {
+    #[default]
+    Code,
+    /// Inside a (possibly nested) block comment
+    BlockComment(usize),
+    /// Inside a string literal
+    Str,
+    /// Inside a raw string literal with this many `#`s
+    RawStr(usize),
+}
 
-        // Check each pattern - capture group index varies for fn (has optional async)
-        // Use pattern matching to safely handle capture groups
-        if let Some(caps) = RUST_PUB_FN.captures(trimmed) {
-            if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(2)) {
-                let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+/// Remove comments and the contents of string and char literals from a line,
+/// so braces and keywords inside them are not mistaken for code.
+fn strip_rust_literals(line: &str, state: &mut RustLexState) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::with_capacity(line.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match *state {
+            RustLexState::BlockComment(depth) => {
+                if c == '*' && next == Some('/') {
+                    *state = if depth == 1 {
+                        RustLexState::Code
+                    } else {
+                        RustLexState::BlockComment(depth - 1)
+                    };
+                    i += 2;
+                } else if c == '/' && next == Some('*') {
+                    *state = RustLexState::BlockComment(depth + 1);
+                    i += 2;
+                } else {
+                    i += 1;
+                }
             }
-        } else if let Some(caps) = RUST_PUB_STRUCT.captures(trimmed) {
-            if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
-                let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+            RustLexState::Str => {
+                if c == '\\' {
+                    i += 2;
+                    continue;
+                }
+                if c == '"' {
+                    out.push('"');
+                    *state = RustLexState::Code;
+                }
+                i += 1;
             }
-        } else if let Some(caps) = RUST_PUB_ENUM.captures(trimmed) {
-            if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
-                let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+            RustLexState::RawStr(hashes) => {
+                let closes = chars
+                    .get(i + 1..i + 1 + hashes)
+                    .is_some_and(|tail| tail.iter().all(|&h| h == '#'));
+                if c == '"' && closes {
+                    out.push('"');
+                    *state = RustLexState::Code;
+                    i += 1 + hashes;
+                } else {
+                    i += 1;
+                }
             }
-        } else if let Some(caps) = RUST_PUB_TRAIT.captures(trimmed) {
-            if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
-                let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+            RustLexState::Code => {
+                let prev_is_ident =
+                    i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_');
+                if c == '/' && next == Some('/') {
+                    break;
+                }
+                if c == '/' && next == Some('*') {
+                    *state = RustLexState::BlockComment(1);
+                    i += 2;
+                    continue;
+                }
+                if c == '"' {
+                    out.push('"');
+                    *state = RustLexState::Str;
+                    i += 1;
+                    continue;
+                }
+                if c == 'r' && !prev_is_ident {
+                    let hashes = chars[i + 1..].iter().take_while(|&&h| h == '#').count();
+                    if chars.get(i + 1 + hashes) == Some(&'"') {
+                        out.push('"');
+                        *state = RustLexState::RawStr(hashes);
+                        i += 2 + hashes;
+                        continue;
+                    }
+                }
+                if c == '\'' {
+                    // Char literals ('{', '\n', '\u{7b}'); anything else is a lifetime
+                    if next == Some('\\') {
+                        if let Some(end) = chars[(i + 3).min(chars.len())..]
+                            .iter()
+                            .position(|&ch| ch == '\'')
+                        {
+                            out.push_str("' '");
+                            i += 4 + end;
+                            continue;
+                        }
+                    } else if chars.get(i + 2) == Some(&'\'') {
+                        out.push_str("' '");
+                        i += 3;
+                        continue;
+                    }
+                }
+                out.push(c);
+                i += 1;
             }
-        } else if let Some(caps) = RUST_PUB_TYPE.captures(trimmed) {
-            if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
+        }
+    }
+
+    out
+}
+
+fn extract_rust_signatures(content: &str) -> Option<Vec<TypeSignature>> {
+    let mut signatures = Vec::new();
+    // Open blocks, innermost last; items inside function bodies are skipped
+    let mut blocks: Vec<RustBlock> = Vec::new();
+    // A function signature was seen whose body brace has not been reached yet
+    let mut pending_fn = false;
+    let mut lex_state = RustLexState::default();
+
+    for (line_idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        let code = strip_rust_literals(line, &mut lex_state);
+        let code_trimmed = code.trim();
+
+        // Only match lines that start with real code (not comments, attributes
+        // or the inside of a multi-line string) outside function bodies
+        let is_candidate = code_trimmed.starts_with("pub")
+            && !trimmed.starts_with("#[")
+            && !blocks.contains(&RustBlock::FnBody);
+
+        if is_candidate {
+            let indent = calculate_indent(line);
+
+            // Check each pattern - capture group index varies for fn (has optional async)
+            // Use pattern matching to safely handle capture groups
+            if let Some(caps) = RUST_PUB_FN.captures(trimmed) {
+                if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(2)) {
+                    let sig = clean_signature(full.as_str());
+                    signatures.push(TypeSignature::new(
+                        sig,
+                        sym_match.as_str(),
+                        indent,
+                        line_idx + 1,
+                    ));
+                }
+            } else if let Some(caps) = RUST_PUB_STRUCT.captures(trimmed) {
+                if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
+                    let sig = clean_signature(full.as_str());
+                    signatures.push(TypeSignature::new(
+                        sig,
+                        sym_match.as_str(),
+                        indent,
+                        line_idx + 1,
+                    ));
+                }
+            } else if let Some(caps) = RUST_PUB_ENUM.captures(trimmed) {
+                if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
+                    let sig = clean_signature(full.as_str());
+                    signatures.push(TypeSignature::new(
+                        sig,
+                        sym_match.as_str(),
+                        indent,
+                        line_idx + 1,
+                    ));
+                }
+            } else if let Some(caps) = RUST_PUB_TRAIT.captures(trimmed) {
+                if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
+                    let sig = clean_signature(full.as_str());
+                    signatures.push(TypeSignature::new(
+                        sig,
+                        sym_match.as_str(),
+                        indent,
+                        line_idx + 1,
+                    ));
+                }
+            } else if let Some(caps) = RUST_PUB_TYPE.captures(trimmed) {
+                if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
+                    let sig = clean_signature(full.as_str());
+                    signatures.push(TypeSignature::new(
+                        sig,
+                        sym_match.as_str(),
+                        indent,
+                        line_idx + 1,
+                    ));
+                }
+            } else if let Some(caps) = RUST_PUB_CONST.captures(trimmed)
+                && let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1))
+            {
                 let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
-        } else if let Some(caps) = RUST_PUB_CONST.captures(trimmed) {
-            if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
-                let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+        }
+
+        // Track brace depth so we know when we are inside a function body.
+        // A signature may span several lines before its opening brace.
+        if RUST_ANY_FN.is_match(code_trimmed) {
+            pending_fn = true;
+        }
+        for ch in code.chars() {
+            match ch {
+                '{' => {
+                    blocks.push(if pending_fn {
+                        RustBlock::FnBody
+                    } else {
+                        RustBlock::Other
+                    });
+                    pending_fn = false;
+                }
+                '}' => {
+                    blocks.pop();
+                }
+                // Bodiless declarations like `fn required(&self);` in traits
+                ';' => pending_fn = false,
+                _ => {}
             }
         }
     }
@@ -142,10 +441,10 @@ static TS_EXPORT_ENUM: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"^export\s+(const\s+)?enum\s+(\w+)[^{]*").expect("TS_EXPORT_ENUM regex is invalid")
 });
 
-fn extract_typescript_signatures(content: &str) -> Option<Vec<(String, String, usize)>> {
+fn extract_typescript_signatures(content: &str) -> Option<Vec<TypeSignature>> {
     let mut signatures = Vec::new();
 
-    for line in content.lines() {
+    for (line_idx, line) in content.lines().enumerate() {
         let trimmed = line.trim();
 
         // Skip comments
@@ -160,33 +459,63 @@ fn extract_typescript_signatures(content: &str) -> Option<Vec<(String, String, u
         if let Some(caps) = TS_EXPORT_FUNCTION.captures(trimmed) {
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(2)) {
                 let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
         } else if let Some(caps) = TS_EXPORT_INTERFACE.captures(trimmed) {
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
                 let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
         } else if let Some(caps) = TS_EXPORT_TYPE.captures(trimmed) {
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
                 let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
         } else if let Some(caps) = TS_EXPORT_CLASS.captures(trimmed) {
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(2)) {
                 let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
         } else if let Some(caps) = TS_EXPORT_CONST.captures(trimmed) {
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
                 let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
-            }
-        } else if let Some(caps) = TS_EXPORT_ENUM.captures(trimmed) {
-            if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(2)) {
-                let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
+        } else if let Some(caps) = TS_EXPORT_ENUM.captures(trimmed)
+            && let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(2))
+        {
+            let sig = clean_signature(full.as_str());
+            signatures.push(TypeSignature::new(
+                sig,
+                sym_match.as_str(),
+                indent,
+                line_idx + 1,
+            ));
         }
     }
 
@@ -205,10 +534,10 @@ static JS_EXPORT_CONST: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"^export\s+const\s+(\w+)\s*=").expect("JS_EXPORT_CONST regex is invalid")
 });
 
-fn extract_javascript_signatures(content: &str) -> Option<Vec<(String, String, usize)>> {
+fn extract_javascript_signatures(content: &str) -> Option<Vec<TypeSignature>> {
     let mut signatures = Vec::new();
 
-    for line in content.lines() {
+    for (line_idx, line) in content.lines().enumerate() {
         let trimmed = line.trim();
 
         // Skip comments
@@ -222,18 +551,33 @@ fn extract_javascript_signatures(content: &str) -> Option<Vec<(String, String, u
         if let Some(caps) = JS_EXPORT_FUNCTION.captures(trimmed) {
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(2)) {
                 let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
         } else if let Some(caps) = JS_EXPORT_CLASS.captures(trimmed) {
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
                 let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
         } else if let Some(caps) = JS_EXPORT_CONST.captures(trimmed) {
             // For const, just show the declaration without the value
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
                 let sig = full.as_str().trim_end_matches('=').trim();
-                signatures.push((sig.to_string(), sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig.to_string(),
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
         }
     }
@@ -260,21 +604,39 @@ static PY_ASYNC_DEF: LazyLock<Regex> = LazyLock::new(|| {
 static PY_CLASS: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^class\s+(\w+)[^:]*").expect("PY_CLASS regex is invalid"));
 
-fn extract_python_signatures(content: &str) -> Option<Vec<(String, String, usize)>> {
+/// Parenthesis depth after `line`, starting from `depth`.
+fn python_paren_depth(depth: usize, line: &str) -> usize {
+    line.chars().fold(depth, |depth, c| match c {
+        '(' => depth + 1,
+        ')' => depth.saturating_sub(1),
+        _ => depth,
+    })
+}
+
+fn extract_python_signatures(content: &str, show_private: bool) -> Option<Vec<TypeSignature>> {
     let mut signatures = Vec::new();
+    // Unclosed parentheses of the decorator being read
+    let mut decorator_depth = 0;
 
-    for line in content.lines() {
+    for (line_idx, line) in content.lines().enumerate() {
         let trimmed = line.trim();
 
+        // Decorator arguments can span lines; none of them are definitions
+        if decorator_depth > 0 || trimmed.starts_with('@') {
+            decorator_depth = python_paren_depth(decorator_depth, trimmed);
+            continue;
+        }
+
         // Skip comments
         if trimmed.starts_with('#') {
             continue;
         }
 
         // Skip private functions/classes (starting with _)
-        if trimmed.starts_with("def _")
-            || trimmed.starts_with("async def _")
-            || trimmed.starts_with("class _")
+        if !show_private
+            && (trimmed.starts_with("def _")
+                || trimmed.starts_with("async def _")
+                || trimmed.starts_with("class _"))
         {
             continue;
         }
@@ -287,28 +649,53 @@ fn extract_python_signatures(content: &str) -> Option<Vec<(String, String, usize
         if let Some(caps) = PY_ASYNC_DEF_WITH_RETURN.captures(trimmed) {
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
                 let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
         } else if let Some(caps) = PY_ASYNC_DEF.captures(trimmed) {
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
                 let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
         } else if let Some(caps) = PY_DEF_WITH_RETURN.captures(trimmed) {
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
                 let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
         } else if let Some(caps) = PY_DEF.captures(trimmed) {
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
                 let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
-            }
-        } else if let Some(caps) = PY_CLASS.captures(trimmed) {
-            if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
-                let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
+        } else if let Some(caps) = PY_CLASS.captures(trimmed)
+            && let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1))
+        {
+            let sig = clean_signature(full.as_str());
+            signatures.push(TypeSignature::new(
+                sig,
+                sym_match.as_str(),
+                indent,
+                line_idx + 1,
+            ));
         }
     }
 
@@ -316,11 +703,13 @@ fn extract_python_signatures(content: &str) -> Option<Vec<(String, String, usize
 }
 
 // Go patterns - exported items start with uppercase - with capture groups
+// Functions and methods only match up to the parameter list (or type
+// parameters); the rest of the signature is found by `go_signature_end`
 static GO_EXPORTED_FUNC: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^func\s+([A-Z]\w*)\s*\([^)]*\)[^{]*").expect("GO_EXPORTED_FUNC regex is invalid")
+    Regex::new(r"^func\s+([A-Z]\w*)\s*[\[(]").expect("GO_EXPORTED_FUNC regex is invalid")
 });
 static GO_EXPORTED_METHOD: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^func\s+\([^)]+\)\s*([A-Z]\w*)\s*\([^)]*\)[^{]*")
+    Regex::new(r"^func\s+\([^)]+\)\s*([A-Z]\w*)\s*[\[(]")
         .expect("GO_EXPORTED_METHOD regex is invalid")
 });
 static GO_EXPORTED_TYPE: LazyLock<Regex> = LazyLock::new(|| {
@@ -333,10 +722,38 @@ static GO_EXPORTED_VAR: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"^var\s+([A-Z]\w*)\s+\w+").expect("GO_EXPORTED_VAR regex is invalid")
 });
 
-fn extract_go_signatures(content: &str) -> Option<Vec<(String, String, usize)>> {
+/// Length of the signature at the start of a `func` line: everything before
+/// the body's opening brace. Braces of `interface{}` and `struct{}` types in
+/// the parameters or results are part of the signature.
+fn go_signature_end(line: &str) -> usize {
+    let mut depth = 0usize;
+    let mut in_type_literal = 0usize;
+    for (i, c) in line.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            '{' => {
+                let before = line[..i].trim_end();
+                if in_type_literal > 0
+                    || before.ends_with("interface")
+                    || before.ends_with("struct")
+                {
+                    in_type_literal += 1;
+                } else if depth == 0 {
+                    return i;
+                }
+            }
+            '}' => in_type_literal = in_type_literal.saturating_sub(1),
+            _ => {}
+        }
+    }
+    line.len()
+}
+
+fn extract_go_signatures(content: &str) -> Option<Vec<TypeSignature>> {
     let mut signatures = Vec::new();
 
-    for line in content.lines() {
+    for (line_idx, line) in content.lines().enumerate() {
         let trimmed = line.trim();
 
         // Skip comments
@@ -348,223 +765,1509 @@ fn extract_go_signatures(content: &str) -> Option<Vec<(String, String, usize)>>
 
         // Check each pattern (method before func to get receiver)
         // Use pattern matching to safely handle capture groups
-        if let Some(caps) = GO_EXPORTED_METHOD.captures(trimmed) {
-            if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
-                let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
-            }
-        } else if let Some(caps) = GO_EXPORTED_FUNC.captures(trimmed) {
-            if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
-                let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+        if let Some(caps) = GO_EXPORTED_METHOD
+            .captures(trimmed)
+            .or_else(|| GO_EXPORTED_FUNC.captures(trimmed))
+        {
+            if let Some(sym_match) = caps.get(1) {
+                let sig = clean_signature(&trimmed[..go_signature_end(trimmed)]);
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
         } else if let Some(caps) = GO_EXPORTED_TYPE.captures(trimmed) {
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
                 let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig,
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
         } else if let Some(caps) = GO_EXPORTED_CONST.captures(trimmed) {
             if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
                 let sig = full.as_str().trim_end_matches('=').trim();
-                signatures.push((sig.to_string(), sym_match.as_str().to_string(), indent));
-            }
-        } else if let Some(caps) = GO_EXPORTED_VAR.captures(trimmed) {
-            if let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1)) {
-                let sig = clean_signature(full.as_str());
-                signatures.push((sig, sym_match.as_str().to_string(), indent));
+                signatures.push(TypeSignature::new(
+                    sig.to_string(),
+                    sym_match.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
             }
+        } else if let Some(caps) = GO_EXPORTED_VAR.captures(trimmed)
+            && let (Some(full), Some(sym_match)) = (caps.get(0), caps.get(1))
+        {
+            let sig = clean_signature(full.as_str());
+            signatures.push(TypeSignature::new(
+                sig,
+                sym_match.as_str(),
+                indent,
+                line_idx + 1,
+            ));
         }
     }
 
     Some(signatures)
 }
 
-/// Clean up a signature by trimming whitespace and removing trailing braces/semicolons
-fn clean_signature(sig: &str) -> String {
-    sig.trim()
-        .trim_end_matches('{')
-        .trim_end_matches(';')
-        .trim()
-        .to_string()
-}
-
-/// Type signature extractor that implements the MetadataExtractor trait.
-pub struct TypeExtractor;
+// C and C++ patterns, matched against whole top-level declarations with
+// comments removed and whitespace collapsed
+// `template <...>` and `extern "C"` before a single declaration (string
+// contents are already stripped)
+static C_DECLARATION_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^(?:template\s*<[^<>]*(?:<[^<>]*>[^<>]*)*>\s*|extern\s+""\s+)"#)
+        .expect("C_DECLARATION_PREFIX regex is invalid")
+});
+static C_TRANSPARENT_BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^(?:extern\s+""|(?:inline\s+)?namespace\b)"#)
+        .expect("C_TRANSPARENT_BLOCK regex is invalid")
+});
+static C_TYPEDEF: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^typedef\s+").expect("C_TYPEDEF regex is invalid"));
+static C_TYPEDEF_NAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\(\s*\*\s*(\w+)\s*\)|(\w+)\s*(?:\[[^\]]*\]\s*)*$")
+        .expect("C_TYPEDEF_NAME regex is invalid")
+});
+static C_TAGGED_TYPE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:struct|enum(?:\s+class)?|union|class)\s+(\w+)\s*(?::[^:]|$)")
+        .expect("C_TAGGED_TYPE regex is invalid")
+});
+static C_FUNCTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[A-Za-z_][\w\s*&:<>,]*[\s*&](~?[A-Za-z_][\w:<>]*)\s*\(")
+        .expect("C_FUNCTION regex is invalid")
+});
+static C_STORAGE_VARIABLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:extern|static)\s+[^(=]*?(\w+)\s*(?:\[[^\]]*\]\s*)*(?:=.*)?$")
+        .expect("C_STORAGE_VARIABLE regex is invalid")
+});
 
-impl MetadataExtractor for TypeExtractor {
-    fn extract(&self, path: &Path) -> Option<MetadataBlock> {
-        extract_type_signatures(path).map(MetadataBlock::from_types)
+/// Remove comments and the contents of string and char literals from a line
+/// of C or C++, so braces and semicolons inside them are not mistaken for code.
+fn strip_c_literals(line: &str, in_block_comment: &mut bool) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if *in_block_comment {
+            if c == '*' && chars.peek() == Some(&'/') {
+                chars.next();
+                *in_block_comment = false;
+                // Keep the tokens on either side of the comment apart
+                out.push(' ');
+            }
+            continue;
+        }
+        match c {
+            '/' if chars.peek() == Some(&'/') => break,
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                *in_block_comment = true;
+            }
+            '"' | '\'' => {
+                out.push(c);
+                while let Some(inner) = chars.next() {
+                    if inner == '\\' {
+                        chars.next();
+                    } else if inner == c {
+                        out.push(c);
+                        break;
+                    }
+                }
+            }
+            _ => out.push(c),
+        }
     }
+    out
+}
 
-    fn name(&self) -> &'static str {
-        "types"
-    }
+/// Collapse the whitespace of a declaration gathered from several lines.
+fn collapse_c_declaration(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("( ", "(")
+        .replace(" )", ")")
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Extract the top-level declarations of a C or C++ file: functions,
+/// `typedef`s, `struct`/`enum`/`union`/`class` types, and `extern` or
+/// `static` variables.
+///
+/// Declarations may span several lines, and function bodies are skipped.
+/// Preprocessor lines are ignored, so everything inside include guards and
+/// `extern "C"` or `namespace` blocks counts as top level. A function that is
+/// declared and later defined in the same file is only listed at its
+/// declaration.
+fn extract_c_signatures(content: &str) -> Option<Vec<TypeSignature>> {
+    let mut signatures = Vec::new();
+    // Open blocks, innermost last: `true` for blocks whose contents are still
+    // top level (`extern "C"`, `namespace`)
+    let mut blocks: Vec<bool> = Vec::new();
+    // The declaration being read: its text, first line (0-indexed) and indent
+    let mut decl = String::new();
+    let mut decl_start = (0, 0);
+    // A `typedef struct { ... } Name;` whose name follows its body
+    let mut pending_typedef: Option<(String, (usize, usize))> = None;
+    let mut declared_functions: HashSet<String> = HashSet::new();
+    let mut in_block_comment = false;
+    let mut in_directive = false;
+
+    for (line_idx, line) in content.lines().enumerate() {
+        let code = strip_c_literals(line, &mut in_block_comment);
+        if in_directive || code.trim_start().starts_with('#') {
+            in_directive = code.trim_end().ends_with('\\');
+            continue;
+        }
 
-    #[test]
-    fn test_rust_pub_fn() {
-        let content = r#"
-/// Doc comment
-pub fn process(input: &str) -> Result<String, Error> {
-    todo!()
-}
+        let mut paren_depth = 0usize;
+        for c in code.chars() {
+            let at_top_level = blocks.iter().all(|&transparent| transparent);
+            if !at_top_level {
+                match c {
+                    '{' => blocks.push(false),
+                    '}' => {
+                        blocks.pop();
+                    }
+                    _ => {}
+                }
+                continue;
+            }
 
-fn private_fn() {}
+            match c {
+                '(' => paren_depth += 1,
+                ')' => paren_depth = paren_depth.saturating_sub(1),
+                _ => {}
+            }
+            if (c == '{' || c == ';') && paren_depth == 0 {
+                let text = collapse_c_declaration(&decl);
+                let text = C_DECLARATION_PREFIX.replace(&text, "").into_owned();
+                decl.clear();
+
+                if c == '{' {
+                    let transparent = C_TRANSPARENT_BLOCK.is_match(&text);
+                    blocks.push(transparent);
+                    if transparent {
+                        continue;
+                    }
+                    if C_TYPEDEF.is_match(&text) {
+                        pending_typedef = Some((text, decl_start));
+                        continue;
+                    }
+                } else if let Some((head, (line, indent))) = pending_typedef.take() {
+                    // The closing `} Name;` of a typedef'd type
+                    if let Some(name) = text.split([',', ' ', '*']).find(|w| !w.is_empty()) {
+                        let sig = format!("{} {}", head, name);
+                        signatures.push(TypeSignature::new(sig, name, indent, line + 1));
+                    }
+                    continue;
+                }
+
+                let (line, indent) = decl_start;
+                if let Some((sig, symbol)) =
+                    classify_c_declaration(&text, c == '{', &mut declared_functions)
+                {
+                    signatures.push(TypeSignature::new(sig, symbol, indent, line + 1));
+                }
+                continue;
+            }
+            if c == '}' {
+                blocks.pop();
+                continue;
+            }
 
-pub async fn async_process(data: Vec<u8>) -> io::Result<()> {
-    todo!()
-}
-"#;
-        let sigs = extract_rust_signatures(content).unwrap();
-        assert_eq!(sigs.len(), 2);
-        assert!(sigs[0].0.starts_with("pub fn process"));
-        assert_eq!(sigs[0].1, "process");
-        assert!(sigs[1].0.starts_with("pub async fn async_process"));
-        assert_eq!(sigs[1].1, "async_process");
+            if decl.trim().is_empty() && !c.is_whitespace() {
+                decl_start = (line_idx, calculate_indent(line));
+            }
+            decl.push(c);
+        }
+        decl.push(' ');
     }
 
-    #[test]
-    fn test_rust_pub_struct() {
-        let content = r#"
-pub struct Config {
-    pub host: String,
-    pub port: u16,
+    Some(signatures)
 }
 
-struct PrivateStruct {}
-
-pub struct Generic<T: Clone> {
-    data: T,
-}
-"#;
-        let sigs = extract_rust_signatures(content).unwrap();
-        assert_eq!(sigs.len(), 2);
-        assert!(sigs[0].0.starts_with("pub struct Config"));
-        assert_eq!(sigs[0].1, "Config");
-        assert!(sigs[1].0.starts_with("pub struct Generic"));
-        assert_eq!(sigs[1].1, "Generic");
+/// Match one top-level C or C++ declaration, ended by `{` if `has_body`,
+/// returning its signature and symbol. Functions declared earlier (in
+/// `declared_functions`) are not matched again at their definition.
+fn classify_c_declaration(
+    text: &str,
+    has_body: bool,
+    declared_functions: &mut HashSet<String>,
+) -> Option<(String, String)> {
+    if C_TYPEDEF.is_match(text) {
+        let caps = C_TYPEDEF_NAME.captures(text)?;
+        let name = caps.get(1).or_else(|| caps.get(2))?.as_str();
+        return Some((text.to_string(), name.to_string()));
+    }
+    if let Some(caps) = C_TAGGED_TYPE.captures(text) {
+        return Some((text.to_string(), caps.get(1)?.as_str().to_string()));
+    }
+    if let Some(caps) = C_FUNCTION.captures(text) {
+        let name = caps.get(1)?.as_str().to_string();
+        if has_body {
+            if declared_functions.contains(&name) {
+                return None;
+            }
+        } else {
+            declared_functions.insert(name.clone());
+        }
+        return Some((text.to_string(), name));
     }
+    // A body after a variable is its initializer, e.g. `static int table[] = {`
+    if (!has_body || text.ends_with('='))
+        && let Some(caps) = C_STORAGE_VARIABLE.captures(text)
+    {
+        let sig = text.split('=').next().unwrap_or(text).trim_end();
+        return Some((sig.to_string(), caps.get(1)?.as_str().to_string()));
+    }
+    None
+}
 
-    #[test]
-    fn test_rust_pub_trait() {
-        let content = r#"
-pub trait Handler {
-    fn handle(&self, req: Request) -> Response;
+// Java patterns, matched against lines with comments and literals removed.
+// Which types and members are listed depends on their visibility, which the
+// extractor checks separately.
+static JAVA_TYPE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:(?:public|protected|private)\s+)?(?:(?:abstract|final|static|sealed|non-sealed|strictfp)\s+)*(?:class|interface|enum|record|@interface)\s+(\w+)",
+    )
+    .expect("JAVA_TYPE regex is invalid")
+});
+static JAVA_METHOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:public|protected|private)\s+(?:(?:static|final|abstract|synchronized|native|default)\s+)*[\w<>\[\],.?& ]+\s+(\w+)\s*\(",
+    )
+    .expect("JAVA_METHOD regex is invalid")
+});
+static JAVA_CONSTRUCTOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:public|protected|private)\s+(\w+)\s*\(")
+        .expect("JAVA_CONSTRUCTOR regex is invalid")
+});
+static JAVA_INTERFACE_METHOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:(?:static|default|abstract)\s+)*[\w<>\[\],.?& ]+\s+(\w+)\s*\(")
+        .expect("JAVA_INTERFACE_METHOD regex is invalid")
+});
+
+/// Kind of block opened by a `{` in Java source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum JavaBlock {
+    /// The body of a class, enum, record or (when `interface`) an interface;
+    /// members are only listed when every enclosing type is `listed`
+    Type {
+        name: String,
+        interface: bool,
+        listed: bool,
+    },
+    /// Any other block (method body, initializer, anonymous class, ...)
+    Other,
 }
 
-trait Private {}
-"#;
-        let sigs = extract_rust_signatures(content).unwrap();
-        assert_eq!(sigs.len(), 1);
-        assert!(sigs[0].0.starts_with("pub trait Handler"));
-        assert_eq!(sigs[0].1, "Handler");
+/// `decl` without leading annotations such as `@Override` or
+/// `@SuppressWarnings("unchecked")`. `@interface` is a keyword and is kept.
+fn strip_java_annotations(mut decl: &str) -> &str {
+    while let Some(rest) = decl.strip_prefix('@') {
+        if rest.starts_with("interface") {
+            break;
+        }
+        let name_end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+            .unwrap_or(rest.len());
+        let mut after = rest[name_end..].trim_start();
+        if after.starts_with('(') {
+            let mut depth = 0;
+            let end = after.char_indices().find_map(|(i, c)| {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                (depth == 0).then_some(i + 1)
+            });
+            // A multi-line annotation argument list is not a declaration
+            let Some(end) = end else {
+                return "";
+            };
+            after = after[end..].trim_start();
+        }
+        decl = after;
     }
-
-    #[test]
-    fn test_rust_pub_enum() {
-        let content = r#"
-pub enum Status {
-    Active,
-    Inactive,
+    decl
 }
 
-enum Private {}
-"#;
-        let sigs = extract_rust_signatures(content).unwrap();
-        assert_eq!(sigs.len(), 1);
-        assert!(sigs[0].0.starts_with("pub enum Status"));
-        assert_eq!(sigs[0].1, "Status");
+/// Extract public and protected types and the methods and constructors
+/// declared in their bodies from a Java file. Members of other types are
+/// skipped, and so are `private` types and members unless `show_private` is
+/// set.
+///
+/// Braces are tracked so members of nested types are found while code in
+/// method bodies is skipped; each member keeps its source indentation, so it
+/// is shown under its class. Interface methods are listed without a
+/// visibility modifier, since they are implicitly public.
+fn extract_java_signatures(content: &str, show_private: bool) -> Option<Vec<TypeSignature>> {
+    let mut signatures = Vec::new();
+    // Open blocks, innermost last
+    let mut blocks: Vec<JavaBlock> = Vec::new();
+    // A type declaration was seen whose body brace has not been reached yet
+    let mut pending_type: Option<JavaBlock> = None;
+    let mut in_block_comment = false;
+
+    for (line_idx, line) in content.lines().enumerate() {
+        let code = strip_c_literals(line, &mut in_block_comment);
+        let trimmed = code.trim();
+        let in_type_body = blocks
+            .iter()
+            .all(|b| matches!(b, JavaBlock::Type { listed: true, .. }));
+
+        if in_type_body && !trimmed.is_empty() {
+            let decl = strip_java_annotations(trimmed);
+            let enclosing = match blocks.last() {
+                Some(JavaBlock::Type {
+                    name, interface, ..
+                }) => Some((name.as_str(), *interface)),
+                _ => None,
+            };
+            let indent = calculate_indent(line);
+            let sig = clean_signature(decl.split(['{', ';']).next().unwrap_or(decl));
+            let private = decl.starts_with("private ");
+
+            if let Some(caps) = JAVA_TYPE.captures(decl) {
+                let name = caps.get(1).map_or("", |m| m.as_str());
+                let listed = decl.starts_with("public")
+                    || decl.starts_with("protected")
+                    || (private && show_private);
+                pending_type = Some(JavaBlock::Type {
+                    name: name.to_string(),
+                    interface: decl.contains("interface "),
+                    listed,
+                });
+                if listed {
+                    signatures.push(TypeSignature::new(sig, name, indent, line_idx + 1));
+                }
+            } else if let Some(symbol) = JAVA_METHOD
+                .captures(decl)
+                .or_else(|| {
+                    JAVA_CONSTRUCTOR
+                        .captures(decl)
+                        .filter(|caps| enclosing.is_some_and(|(name, _)| &caps[1] == name))
+                })
+                .or_else(|| {
+                    JAVA_INTERFACE_METHOD
+                        .captures(decl)
+                        .filter(|_| enclosing.is_some_and(|(_, interface)| interface))
+                })
+                .and_then(|caps| caps.get(1))
+                .filter(|_| enclosing.is_some() && (show_private || !private))
+            {
+                signatures.push(TypeSignature::new(
+                    sig,
+                    symbol.as_str(),
+                    indent,
+                    line_idx + 1,
+                ));
+            }
+        }
+
+        for ch in code.chars() {
+            match ch {
+                '{' => blocks.push(pending_type.take().unwrap_or(JavaBlock::Other)),
+                '}' => {
+                    blocks.pop();
+                }
+                ';' => pending_type = None,
+                _ => {}
+            }
+        }
     }
 
-    #[test]
-    fn test_typescript_exports() {
-        let content = r#"
-// File comment
-export interface User {
-    id: string;
-    name: string;
+    Some(signatures)
 }
 
-export type UserId = string;
+// C# patterns, matched against lines with comments, literals and leading
+// attributes removed
+static CSHARP_TYPE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:public|protected|internal|private)\s+(?:(?:abstract|sealed|static|partial|readonly|unsafe|new|ref)\s+)*(?:(?:record\s+)?(?:class|struct)|interface|enum|record)\s+(\w+)",
+    )
+    .expect("CSHARP_TYPE regex is invalid")
+});
+static CSHARP_DELEGATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:public|protected|internal|private)\s+delegate\s+[\w<>\[\],.? ]+\s+(\w+)\s*[<(]",
+    )
+    .expect("CSHARP_DELEGATE regex is invalid")
+});
+static CSHARP_METHOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:public|protected|private|internal)\s+(?:(?:static|async|override|virtual|abstract|sealed|new|extern|unsafe|partial|readonly)\s+)*[\w<>\[\],.? ]+\s+(\w+)\s*(?:<[^>]*>)?\s*\(",
+    )
+    .expect("CSHARP_METHOD regex is invalid")
+});
+static CSHARP_CONSTRUCTOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:public|protected|private|internal)\s+(?:static\s+)?(\w+)\s*\(")
+        .expect("CSHARP_CONSTRUCTOR regex is invalid")
+});
+static CSHARP_PROPERTY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:public|protected|private|internal)\s+(?:(?:static|override|virtual|abstract|sealed|new|required|readonly)\s+)*[\w<>\[\],.? ]+\s+(\w+)\s*(?:\{|=>|$)",
+    )
+    .expect("CSHARP_PROPERTY regex is invalid")
+});
+static CSHARP_INTERFACE_MEMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[\w<>\[\],.? ]+\s+(\w+)\s*(?:<[^>]*>)?\s*(?:\(|\{)")
+        .expect("CSHARP_INTERFACE_MEMBER regex is invalid")
+});
 
-export function getUser(id: string): Promise<User> {
-    return fetch(`/users/${id}`);
+/// Kind of block opened by a `{` in C# source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CSharpBlock {
+    /// A `namespace` body, which is transparent to the types declared in it
+    Namespace,
+    /// The body of a class, struct, record, enum or (when `interface`) an
+    /// interface; members are only listed when every enclosing type is `listed`
+    Type {
+        name: String,
+        interface: bool,
+        listed: bool,
+    },
+    /// Any other block (method body, accessor, initializer, ...)
+    Other,
 }
 
-export async function createUser(data: UserInput): Promise<User> {
-    return post('/users', data);
+/// Split the leading `[Attribute]` lists off `decl`, returning them and the
+/// rest of the declaration.
+fn split_csharp_attributes(decl: &str) -> (&str, &str) {
+    let mut rest = decl;
+    while rest.starts_with('[') {
+        let mut depth = 0;
+        let end = rest.char_indices().find_map(|(i, c)| {
+            match c {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(i + 1)
+        });
+        let Some(end) = end else {
+            break;
+        };
+        rest = rest[end..].trim_start();
+    }
+    let attributes = decl[..decl.len() - rest.len()].trim_end();
+    (attributes, rest)
 }
 
-export const API_URL: string = "https://api.example.com";
+/// Extract the types of a C# file and the methods, constructors and
+/// properties declared in them. `private` types and members are skipped
+/// unless `show_private` is set.
+///
+/// Braces are tracked as for Java, with `namespace` blocks (and file-scoped
+/// `namespace X;` declarations) treated as transparent, so each member keeps
+/// its source indentation beneath its type. Attributes such as `[HttpGet]`
+/// on the lines just above a declaration, or before it on the same line,
+/// are kept in front of its signature.
+fn extract_csharp_signatures(content: &str, show_private: bool) -> Option<Vec<TypeSignature>> {
+    let mut signatures = Vec::new();
+    // Open blocks, innermost last
+    let mut blocks: Vec<CSharpBlock> = Vec::new();
+    // A declaration was seen whose body brace has not been reached yet
+    let mut pending_block: Option<CSharpBlock> = None;
+    // Attribute lines waiting for the declaration they apply to
+    let mut attributes: Vec<String> = Vec::new();
+    let mut in_block_comment = false;
+
+    for (line_idx, line) in content.lines().enumerate() {
+        let code = strip_c_literals(line, &mut in_block_comment);
+        let trimmed = code.trim();
+        let in_declarations = blocks.iter().all(|b| match b {
+            CSharpBlock::Namespace => true,
+            CSharpBlock::Type { listed, .. } => *listed,
+            CSharpBlock::Other => false,
+        });
+
+        if in_declarations && !trimmed.is_empty() {
+            let (inline_attributes, decl) = split_csharp_attributes(trimmed);
+            if trimmed.starts_with("[assembly:") || trimmed.starts_with("[module:") {
+                // Attributes of the whole assembly are not declarations
+            } else if decl.is_empty() {
+                // Keep the attribute as written, with its string arguments
+                let raw = line.trim();
+                let raw = raw.rfind(']').map_or(raw, |end| &raw[..=end]);
+                attributes.push(raw.to_string());
+            } else {
+                let enclosing = match blocks.last() {
+                    Some(CSharpBlock::Type {
+                        name, interface, ..
+                    }) => Some((name.as_str(), *interface)),
+                    _ => None,
+                };
+                // `private protected` is visible to derived types
+                let hidden = !show_private
+                    && decl.starts_with("private ")
+                    && !decl.starts_with("private protected ");
+                let head = decl.split(['{', ';']).next().unwrap_or(decl);
+                let head = head.split("=>").next().unwrap_or(head);
+                let mut sig = attributes.join(" ");
+                if !inline_attributes.is_empty() {
+                    if !sig.is_empty() {
+                        sig.push(' ');
+                    }
+                    sig.push_str(inline_attributes);
+                }
+                if !sig.is_empty() {
+                    sig.push(' ');
+                }
+                sig.push_str(&clean_signature(head));
+
+                let symbol = if decl.starts_with("namespace ") {
+                    pending_block = Some(CSharpBlock::Namespace);
+                    None
+                } else if let Some(caps) = CSHARP_TYPE.captures(decl) {
+                    let name = caps.get(1).map_or("", |m| m.as_str());
+                    pending_block = Some(CSharpBlock::Type {
+                        name: name.to_string(),
+                        interface: decl.contains("interface "),
+                        listed: !hidden,
+                    });
+                    (!hidden).then(|| name.to_string())
+                } else if hidden {
+                    None
+                } else if let Some(caps) = CSHARP_DELEGATE.captures(decl) {
+                    caps.get(1).map(|m| m.as_str().to_string())
+                } else {
+                    CSHARP_METHOD
+                        .captures(decl)
+                        .or_else(|| {
+                            CSHARP_CONSTRUCTOR
+                                .captures(decl)
+                                .filter(|caps| enclosing.is_some_and(|(name, _)| &caps[1] == name))
+                        })
+                        .or_else(|| CSHARP_PROPERTY.captures(decl))
+                        .or_else(|| {
+                            CSHARP_INTERFACE_MEMBER
+                                .captures(decl)
+                                .filter(|_| enclosing.is_some_and(|(_, interface)| interface))
+                        })
+                        .filter(|_| enclosing.is_some())
+                        .and_then(|caps| caps.get(1))
+                        .map(|m| m.as_str().to_string())
+                };
+                if let Some(symbol) = symbol {
+                    signatures.push(TypeSignature::new(
+                        sig,
+                        symbol,
+                        calculate_indent(line),
+                        line_idx + 1,
+                    ));
+                }
+                attributes.clear();
+            }
+        }
 
-function privateFunc() {}
-"#;
-        let sigs = extract_typescript_signatures(content).unwrap();
-        assert_eq!(sigs.len(), 5);
-        assert!(sigs[0].0.starts_with("export interface User"));
-        assert_eq!(sigs[0].1, "User");
-        assert!(sigs[1].0.starts_with("export type UserId"));
-        assert_eq!(sigs[1].1, "UserId");
-        assert!(sigs[2].0.starts_with("export function getUser"));
-        assert_eq!(sigs[2].1, "getUser");
-        assert!(sigs[3].0.starts_with("export async function createUser"));
-        assert_eq!(sigs[3].1, "createUser");
-        assert!(sigs[4].0.starts_with("export const API_URL"));
-        assert_eq!(sigs[4].1, "API_URL");
+        for ch in code.chars() {
+            match ch {
+                '{' => blocks.push(pending_block.take().unwrap_or(CSharpBlock::Other)),
+                '}' => {
+                    blocks.pop();
+                }
+                ';' => pending_block = None,
+                _ => {}
+            }
+        }
     }
 
-    #[test]
-    fn test_typescript_class() {
-        let content = r#"
-export class UserService {
-    constructor(private api: ApiClient) {}
-}
-
-export abstract class BaseHandler {
-    abstract handle(): void;
+    Some(signatures)
 }
-"#;
-        let sigs = extract_typescript_signatures(content).unwrap();
-        assert_eq!(sigs.len(), 2);
-        assert!(sigs[0].0.starts_with("export class UserService"));
-        assert_eq!(sigs[0].1, "UserService");
-        assert!(sigs[1].0.starts_with("export abstract class BaseHandler"));
-        assert_eq!(sigs[1].1, "BaseHandler");
-    }
 
-    #[test]
-    fn test_javascript_exports() {
-        let content = r#"
-export function calculate(a, b) {
-    return a + b;
-}
+// Ruby patterns, matched against statements with literals and comments removed
+static RUBY_MODULE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^module\s+([A-Z][\w:]*)").expect("RUBY_MODULE regex is invalid"));
+static RUBY_CLASS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^class\s+([A-Z][\w:]*)").expect("RUBY_CLASS regex is invalid"));
+static RUBY_SINGLETON_CLASS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^class\s*<<\s*self\b").expect("RUBY_SINGLETON_CLASS regex is invalid")
+});
+static RUBY_DEF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^def\s+((?:self\.)?(?:[A-Za-z_]\w*[?!=]?|\[\]=?|[-+*/%<>=!~^&|]+))\s*(?:\([^)]*\))?",
+    )
+    .expect("RUBY_DEF regex is invalid")
+});
+static RUBY_ATTR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^attr_(?:reader|writer|accessor)\b\s*\(?\s*:(\w+)")
+        .expect("RUBY_ATTR regex is invalid")
+});
+static RUBY_VISIBILITY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(private|protected|public|module_function)(?:\s+(.*))?$")
+        .expect("RUBY_VISIBILITY regex is invalid")
+});
+// `x = if ...`, `@y ||= begin`: a block keyword used as a value
+static RUBY_ASSIGNED_BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[^=!<>]=\s*(?:if|unless|case|begin|while|until)\b")
+        .expect("RUBY_ASSIGNED_BLOCK regex is invalid")
+});
+static RUBY_WORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[A-Za-z_]\w*[?!]?").expect("RUBY_WORD regex is invalid"));
+// `<<~SQL`, `<<-EOS`, `<<'TEXT'`: the body starts on the next line
+static RUBY_HEREDOC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<<([~-]['"]?|['"])([A-Za-z_]\w*)"#).expect("RUBY_HEREDOC regex is invalid")
+});
 
-export async function fetchData(url) {
-    return fetch(url);
+/// Kind of block closed by an `end` in Ruby source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RubyBlock {
+    /// The body of a `module`, `class` or `class << self`. `private` is set
+    /// after a bare `private`; `first_signature` is the index of the first
+    /// signature listed in the body.
+    Namespace {
+        private: bool,
+        first_signature: usize,
+    },
+    /// Any other block (method body, `do` block, `if`, `begin`, ...)
+    Other,
 }
 
-export class Calculator {
-    add(a, b) { return a + b; }
+/// `line` with the contents of string literals blanked out and any comment
+/// removed. Contents are replaced byte for byte with spaces, so offsets into
+/// the result are also offsets into `line`.
+fn strip_ruby_literals(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for c in line.chars() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                    out.push(c);
+                    continue;
+                }
+                out.extend(std::iter::repeat_n(' ', c.len_utf8()));
+            }
+            None => match c {
+                '#' => break,
+                '"' | '\'' => {
+                    quote = Some(c);
+                    out.push(c);
+                }
+                _ => out.push(c),
+            },
+        }
+    }
+    out
 }
 
-export const VERSION = "1.0.0";
-
-function privateFunc() {}
-"#;
-        let sigs = extract_javascript_signatures(content).unwrap();
+/// Extract the modules, classes, methods and attributes of a Ruby file.
+///
+/// `end` keywords are matched with the blocks they close, so only items at
+/// the top level or directly in a module or class body are listed, each
+/// keeping its source indentation. Methods and attributes after a bare
+/// `private`, declared with `private def` or later named by `private :name`
+/// are skipped unless `show_private` is set. `def self.` methods are not
+/// affected by a bare `private`.
+fn extract_ruby_signatures(content: &str, show_private: bool) -> Option<Vec<TypeSignature>> {
+    let mut signatures: Vec<TypeSignature> = Vec::new();
+    // Open blocks, innermost last
+    let mut blocks: Vec<RubyBlock> = Vec::new();
+    let mut top_level_private = false;
+    // Terminator of the heredoc being skipped, and whether it may be indented
+    let mut heredoc: Option<(String, bool)> = None;
+    let mut in_doc_comment = false;
+
+    for (line_idx, line) in content.lines().enumerate() {
+        if let Some((terminator, indented)) = &heredoc {
+            let end = if *indented { line.trim() } else { line };
+            if end == terminator {
+                heredoc = None;
+            }
+            continue;
+        }
+        if in_doc_comment {
+            in_doc_comment = !line.starts_with("=end");
+            continue;
+        }
+        if line.starts_with("=begin") {
+            in_doc_comment = true;
+            continue;
+        }
+
+        let code = strip_ruby_literals(line);
+        if let Some(caps) = RUBY_HEREDOC.captures(line) {
+            let indented = caps[1].starts_with(['~', '-']);
+            heredoc = Some((caps[2].to_string(), indented));
+        }
+        let indent = calculate_indent(line);
+
+        let mut offset = 0;
+        for statement in code.split(';') {
+            let start = offset + statement.len() - statement.trim_start().len();
+            offset += statement.len() + 1;
+            let mut text = statement.trim();
+            if text.is_empty() {
+                continue;
+            }
+            // The statement as written, with its string literals
+            let source = line.get(start..start + text.len()).unwrap_or(text);
+
+            let listed_scope = blocks
+                .iter()
+                .all(|b| matches!(b, RubyBlock::Namespace { .. }));
+            let (section_private, first_signature) = match blocks.last() {
+                Some(RubyBlock::Namespace {
+                    private,
+                    first_signature,
+                }) => (*private, *first_signature),
+                Some(RubyBlock::Other) => (false, signatures.len()),
+                None => (top_level_private, 0),
+            };
+
+            // `private`, `private :a, :b` or `private def a`
+            let mut visibility: Option<bool> = None;
+            let mut keyword_len = 0;
+            if let Some(caps) = RUBY_VISIBILITY.captures(text) {
+                let is_private = &caps[1] == "private";
+                match caps.get(2) {
+                    None => {
+                        match blocks.last_mut() {
+                            Some(RubyBlock::Namespace { private, .. }) => *private = is_private,
+                            Some(RubyBlock::Other) => {}
+                            None => top_level_private = is_private,
+                        }
+                        continue;
+                    }
+                    Some(args) if args.as_str().starts_with(':') => {
+                        if is_private && !show_private {
+                            let names: Vec<&str> = args
+                                .as_str()
+                                .split(',')
+                                .map(|name| name.trim().trim_start_matches(':'))
+                                .collect();
+                            let mut index = 0;
+                            signatures.retain(|sig| {
+                                index += 1;
+                                index <= first_signature || !names.contains(&sig.symbol.as_str())
+                            });
+                        }
+                        continue;
+                    }
+                    Some(args) => {
+                        visibility = Some(is_private);
+                        keyword_len = args.start();
+                        text = args.as_str();
+                    }
+                }
+            }
+
+            let mut listed = None;
+            if let Some(caps) = RUBY_DEF.captures(text) {
+                let name = caps.get(1).map_or("", |m| m.as_str());
+                let end = caps.get(0).map_or(text.len(), |m| m.end());
+                // `def name(args) = expression` has no body or `end`
+                let rest = text[end..].trim_start();
+                let endless = rest.starts_with('=') && !rest.starts_with("==");
+                let private = visibility.unwrap_or(section_private && !name.starts_with("self."));
+                if show_private || !private {
+                    let sig = if endless {
+                        source.get(..keyword_len + end).unwrap_or(source)
+                    } else {
+                        source
+                    };
+                    listed = Some((sig.trim_end(), name));
+                }
+                if !endless {
+                    blocks.push(RubyBlock::Other);
+                }
+            } else if let Some(caps) = RUBY_SINGLETON_CLASS
+                .captures(text)
+                .or_else(|| RUBY_MODULE.captures(text))
+                .or_else(|| RUBY_CLASS.captures(text))
+            {
+                listed = Some((source, caps.get(1).map_or("self", |m| m.as_str())));
+                blocks.push(RubyBlock::Namespace {
+                    private: false,
+                    first_signature: signatures.len() + 1,
+                });
+            } else if let Some(caps) = RUBY_ATTR.captures(text) {
+                if show_private || !visibility.unwrap_or(section_private) {
+                    listed = Some((source, caps.get(1).map_or("", |m| m.as_str())));
+                }
+            } else if RUBY_ASSIGNED_BLOCK.is_match(text) {
+                blocks.push(RubyBlock::Other);
+            }
+
+            if let Some((sig, symbol)) = listed
+                && listed_scope
+            {
+                signatures.push(TypeSignature::new(sig, symbol, indent, line_idx + 1));
+            }
+
+            // Other blocks: keywords starting the statement, `do` and `end`
+            let first = text
+                .split(|c: char| !c.is_alphanumeric() && c != '_')
+                .next();
+            let is_loop = matches!(first, Some("while" | "until" | "for"));
+            if matches!(
+                first,
+                Some("if" | "unless" | "while" | "until" | "case" | "begin" | "for")
+            ) {
+                blocks.push(RubyBlock::Other);
+            }
+            for word in RUBY_WORD.find_iter(text) {
+                let before = text[..word.start()].chars().next_back();
+                let after = text[word.end()..].chars().next();
+                if matches!(before, Some('.' | ':' | '@' | '$')) || after == Some(':') {
+                    continue;
+                }
+                match word.as_str() {
+                    "do" if !is_loop => blocks.push(RubyBlock::Other),
+                    "end" => {
+                        blocks.pop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Some(signatures)
+}
+
+// Dockerfile instructions that outline the image build
+static DOCKERFILE_INSTRUCTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(FROM|RUN|COPY|ADD|EXPOSE|ENTRYPOINT|CMD)\s+\S.*")
+        .expect("DOCKERFILE_INSTRUCTION regex is invalid")
+});
+
+/// Extract the build steps of a Dockerfile (`FROM`, `RUN`, `COPY`, `ADD`,
+/// `EXPOSE`, `ENTRYPOINT` and `CMD`) as signatures, with the instruction as
+/// the symbol. Only the first line of a continued (`\`) instruction is kept.
+fn extract_dockerfile_instructions(content: &str) -> Option<Vec<TypeSignature>> {
+    let mut signatures = Vec::new();
+    let mut continued = false;
+
+    for (line_idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        let is_continuation = continued;
+        // Comments inside a continued instruction don't end it
+        if !trimmed.starts_with('#') {
+            continued = trimmed.ends_with('\\');
+        }
+        if is_continuation {
+            continue;
+        }
+
+        if let Some(caps) = DOCKERFILE_INSTRUCTION.captures(trimmed)
+            && let (Some(full), Some(instruction)) = (caps.get(0), caps.get(1))
+        {
+            let sig = full.as_str().trim_end_matches('\\').trim_end();
+            signatures.push(TypeSignature::new(
+                sig,
+                instruction.as_str(),
+                calculate_indent(line),
+                line_idx + 1,
+            ));
+        }
+    }
+
+    Some(signatures)
+}
+
+// A rule line: target names, then `:` or `::` but not a `:=` / `::=` assignment
+static MAKEFILE_TARGET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([A-Za-z0-9_][\w-]*(?:[ \t]+[A-Za-z0-9_][\w-]*)*)[ \t]*::?(?:[^=:].*)?$")
+        .expect("MAKEFILE_TARGET regex is invalid")
+});
+
+/// Extract the targets of a Makefile, e.g. `build:`.
+///
+/// Pattern rules (`%.o:`), special targets (`.PHONY:`) and file targets
+/// (`main.o:`, `build/app:`) are skipped by only matching plain names.
+/// A `## description`, inline or on the lines just above, is kept in the
+/// signature as in self-documenting Makefiles.
+fn extract_makefile_targets(content: &str) -> Option<Vec<TypeSignature>> {
+    let mut signatures = Vec::new();
+    let mut doc: Vec<&str> = Vec::new();
+
+    for (line_idx, line) in content.lines().enumerate() {
+        if let Some(comment) = line.trim().strip_prefix("##") {
+            doc.push(comment.trim());
+            continue;
+        }
+        // Recipe lines start with a tab, so they never match a target
+        let (rule, inline_doc) = match line.split_once("##") {
+            Some((rule, comment)) => (rule, Some(comment.trim())),
+            None => (line, None),
+        };
+        if let Some(caps) = MAKEFILE_TARGET.captures(rule.trim_end())
+            && let Some(targets) = caps.get(1)
+        {
+            let mut sig = format!("{}:", targets.as_str());
+            let description = match inline_doc {
+                Some(desc) if !desc.is_empty() => desc.to_string(),
+                _ => doc.join(" "),
+            };
+            if !description.is_empty() {
+                sig.push_str(" ## ");
+                sig.push_str(&description);
+            }
+            let symbol = targets
+                .as_str()
+                .split_whitespace()
+                .next()
+                .unwrap_or_default();
+            signatures.push(TypeSignature::new(sig, symbol, 0, line_idx + 1));
+        }
+        doc.clear();
+    }
+
+    Some(signatures)
+}
+
+// A binding whose value is a function: `name = args:` or `name = { a, b }:`
+static NIX_FUNCTION_BINDING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^([A-Za-z_][\w'-]*)\s*=\s*(?:[A-Za-z_][\w'-]*\s*:\s*)*(?:[A-Za-z_][\w'-]*|\{[^{}]*\}(?:\s*@\s*[A-Za-z_][\w'-]*)?)\s*:(?:\s|$)",
+    )
+    .expect("NIX_FUNCTION_BINDING regex is invalid")
+});
+
+/// Extract the functions bound in top-level `let` blocks of a Nix file, such
+/// as `mkService = name: { ... }`. Bindings nested inside sets, lists or
+/// parentheses are skipped, as are strings and comments.
+fn extract_nix_functions(content: &str) -> Option<Vec<TypeSignature>> {
+    let mut signatures = Vec::new();
+    let mut depth = 0usize;
+    // `let` blocks open at depth 0; only the outermost one's bindings count
+    let mut open_lets = 0usize;
+    // Whether the next word at depth 0 starts a binding (after `let` or `;`)
+    let mut binding_start = false;
+    let mut line_number = 1;
+    let mut line_start = 0;
+
+    let mut i = 0;
+    while i < content.len() {
+        let rest = &content[i..];
+        let Some(c) = rest.chars().next() else { break };
+
+        // Strings and comments are skipped whole, keeping count of lines
+        let skipped = if c == '#' {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if let Some(body) = rest.strip_prefix("/*") {
+            body.find("*/").map_or(rest.len(), |end| end + 4)
+        } else if c == '"' {
+            nix_string_end(rest, "\"", |body| {
+                body.strip_prefix('\\')
+                    .and_then(|escaped| escaped.chars().next())
+                    .map(|c| c.len_utf8() + 1)
+            })
+        } else if rest.starts_with("''") {
+            // '' strings escape with ''$, ''' and ''\
+            nix_string_end(rest, "''", |body| {
+                ["''$", "'''", "''\\"]
+                    .iter()
+                    .find(|escape| body.starts_with(**escape))
+                    .map(|escape| escape.len())
+            })
+        } else {
+            0
+        };
+        if skipped > 0 {
+            for (offset, _) in rest[..skipped].match_indices('\n') {
+                line_number += 1;
+                line_start = i + offset + 1;
+            }
+            i += skipped;
+            continue;
+        }
+
+        if c.is_ascii_alphabetic() || c == '_' {
+            let word_len = rest
+                .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '\'')))
+                .unwrap_or(rest.len());
+            let word = &rest[..word_len];
+            if depth == 0 && word == "let" {
+                open_lets += 1;
+                binding_start = open_lets == 1;
+            } else if depth == 0 && word == "in" {
+                open_lets = open_lets.saturating_sub(1);
+                binding_start = false;
+            } else {
+                if depth == 0 && binding_start {
+                    let line_end = rest.find('\n').unwrap_or(rest.len());
+                    if let Some(caps) = NIX_FUNCTION_BINDING.captures(&rest[..line_end])
+                        && let (Some(full), Some(name)) = (caps.get(0), caps.get(1))
+                    {
+                        let line = &content[line_start..i + line_end];
+                        signatures.push(TypeSignature::new(
+                            full.as_str().trim_end(),
+                            name.as_str(),
+                            calculate_indent(line),
+                            line_number,
+                        ));
+                    }
+                }
+                binding_start = false;
+            }
+            i += word_len;
+            continue;
+        }
+
+        match c {
+            '\n' => {
+                line_number += 1;
+                line_start = i + 1;
+            }
+            '{' | '[' | '(' => {
+                depth += 1;
+                binding_start = false;
+            }
+            '}' | ']' | ')' => depth = depth.saturating_sub(1),
+            ';' if depth == 0 && open_lets == 1 => binding_start = true,
+            c if c.is_whitespace() => {}
+            _ => binding_start = false,
+        }
+        i += c.len_utf8();
+    }
+
+    Some(signatures)
+}
+
+/// Length of the Nix string at the start of `text`, which opens and closes
+/// with `quote`. `escape` gives the length of an escape sequence starting
+/// the text it is passed, if there is one. `${...}` interpolations are
+/// scanned over as string text. Unterminated strings run to the end.
+fn nix_string_end(text: &str, quote: &str, escape: impl Fn(&str) -> Option<usize>) -> usize {
+    let mut i = quote.len();
+    while i < text.len() {
+        let body = &text[i..];
+        if let Some(len) = escape(body) {
+            i += len;
+        } else if body.starts_with(quote) {
+            return i + quote.len();
+        } else {
+            i += body.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    text.len()
+}
+
+// A top-level definition: `(defn name [args]`, with optional `^meta` before the name
+static CLOJURE_DEFINITION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"^\((defn-?|defmacro|defprotocol|defrecord|deftype|def)\s+((?:(?:\^\{[^{}]*\}|\^\S+)\s+)*)([^\s()\[\]{}"^]+)(?:\s+(\[[^\[\]]*\]))?"#,
+    )
+    .expect("CLOJURE_DEFINITION regex is invalid")
+});
+
+/// Extract the top-level definitions of a Clojure file: `defn`, `defmacro`,
+/// `defprotocol`, `defrecord`, `deftype` and `def` forms starting a line.
+///
+/// The argument or field vector is kept when it is on the same line as the
+/// name. `defn-` and `^:private` definitions are skipped unless `show_private`.
+fn extract_clojure_definitions(content: &str, show_private: bool) -> Option<Vec<TypeSignature>> {
+    let mut signatures = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let Some(caps) = CLOJURE_DEFINITION.captures(line) else {
+            continue;
+        };
+        let (Some(kind), Some(name)) = (caps.get(1), caps.get(3)) else {
+            continue;
+        };
+        let private = kind.as_str() == "defn-"
+            || caps
+                .get(2)
+                .is_some_and(|meta| meta.as_str().contains(":private"));
+        if private && !show_private {
+            continue;
+        }
+        let mut signature = format!("{} {}", kind.as_str(), name.as_str());
+        if let Some(args) = caps.get(4) {
+            signature.push(' ');
+            signature.push_str(args.as_str());
+        }
+        signatures.push(TypeSignature::new(signature, name.as_str(), 0, i + 1));
+    }
+    Some(signatures)
+}
+
+// A named sub with an optional prototype or signature: `sub name($x) {`
+static PERL_SUB: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*sub\s+([A-Za-z_][\w:]*)\s*(\([^()]*\))?\s*(?:\{|$)")
+        .expect("PERL_SUB regex is invalid")
+});
+
+// An anonymous sub stored in a variable: `my $handler = sub {`
+static PERL_ANON_SUB: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(my|our)\s+\$([A-Za-z_]\w*)\s*=\s*sub\b")
+        .expect("PERL_ANON_SUB regex is invalid")
+});
+
+// A package declaration: `package My::Module;` or `package My::Module 1.0 {`
+static PERL_PACKAGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*package\s+([A-Za-z_][\w:]*)\s*(?:[\w.]+\s*)?[;{]")
+        .expect("PERL_PACKAGE regex is invalid")
+});
+
+/// Extract the packages and subs of a Perl file: `package Name`,
+/// `sub name` (with its prototype or signature) and `my $name = sub`.
+///
+/// POD blocks and everything after `__END__` or `__DATA__` are skipped.
+/// Subs whose name starts with `_` are private by convention and skipped
+/// unless `show_private` is set.
+fn extract_perl_signatures(content: &str, show_private: bool) -> Option<Vec<TypeSignature>> {
+    let mut signatures = Vec::new();
+    let mut in_pod = false;
+    for (i, line) in content.lines().enumerate() {
+        if in_pod {
+            in_pod = !line.starts_with("=cut");
+            continue;
+        }
+        let pod_directive = line
+            .strip_prefix('=')
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_alphabetic()));
+        if pod_directive {
+            in_pod = !line.starts_with("=cut");
+            continue;
+        }
+        if matches!(line.trim_end(), "__END__" | "__DATA__") {
+            break;
+        }
+
+        let indent = calculate_indent(line);
+        let (signature, name) = if let Some(caps) = PERL_PACKAGE.captures(line) {
+            let name = caps.get(1)?.as_str();
+            (format!("package {}", name), name)
+        } else if let Some(caps) = PERL_SUB.captures(line) {
+            let name = caps.get(1)?.as_str();
+            if is_perl_private(name) && !show_private {
+                continue;
+            }
+            let params = caps.get(2).map_or("", |m| m.as_str());
+            (format!("sub {}{}", name, params), name)
+        } else if let Some(caps) = PERL_ANON_SUB.captures(line) {
+            let name = caps.get(2)?.as_str();
+            if is_perl_private(name) && !show_private {
+                continue;
+            }
+            (format!("{} ${} = sub", &caps[1], name), name)
+        } else {
+            continue;
+        };
+        signatures.push(TypeSignature::new(signature, name, indent, i + 1));
+    }
+    Some(signatures)
+}
+
+/// Whether a sub name, possibly qualified as `Pkg::_name`, starts with `_`.
+fn is_perl_private(name: &str) -> bool {
+    name.rsplit("::").next().is_some_and(|n| n.starts_with('_'))
+}
+
+/// Clean up a signature by trimming whitespace and removing trailing braces/semicolons
+fn clean_signature(sig: &str) -> String {
+    sig.trim()
+        .trim_end_matches('{')
+        .trim_end_matches(';')
+        .trim()
+        .to_string()
+}
+
+/// Type signature extractor that implements the MetadataExtractor trait.
+pub struct TypeExtractor;
+
+impl MetadataExtractor for TypeExtractor {
+    fn extract(&self, path: &Path) -> Option<MetadataBlock> {
+        extract_type_signatures(path).map(MetadataBlock::from_types)
+    }
+
+    fn name(&self) -> &'static str {
+        "types"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_pub_fn() {
+        let content = r#"
+/// Doc comment
+pub fn process(input: &str) -> Result<String, Error> {
+    todo!()
+}
+
+fn private_fn() {}
+
+pub async fn async_process(data: Vec<u8>) -> io::Result<()> {
+    todo!()
+}
+"#;
+        let sigs = extract_rust_signatures(content).unwrap();
+        assert_eq!(sigs.len(), 2);
+        assert!(sigs[0].signature.starts_with("pub fn process"));
+        assert_eq!(sigs[0].symbol, "process");
+        assert!(sigs[1].signature.starts_with("pub async fn async_process"));
+        assert_eq!(sigs[1].symbol, "async_process");
+    }
+
+    #[test]
+    fn test_rust_restricted_visibility() {
+        let content = r#"
+pub(crate) fn internal(x: u32) -> u32 {
+    x
+}
+pub(super) struct Parent;
+pub(in crate::tree) enum Scoped { A }
+pub(self) trait Local {}
+pub(crate) type Alias = Vec<u8>;
+pub(crate) const LIMIT: usize = 3;
+pubfn not_a_fn() {}
+"#;
+        let sigs = extract_rust_signatures(content).unwrap();
+        let names: Vec<&str> = sigs.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(
+            names,
+            ["internal", "Parent", "Scoped", "Local", "Alias", "LIMIT"]
+        );
+        assert_eq!(sigs[0].signature, "pub(crate) fn internal(x: u32) -> u32");
+        assert_eq!(sigs[1].signature, "pub(super) struct Parent");
+        assert!(
+            sigs[2]
+                .signature
+                .starts_with("pub(in crate::tree) enum Scoped")
+        );
+        assert_eq!(sigs[5].signature, "pub(crate) const LIMIT: usize");
+    }
+
+    #[test]
+    fn test_rust_skips_items_inside_fn_bodies() {
+        let content = r#"
+pub fn outer() {
+    struct Local;
+    impl Local {
+        pub fn helper() {}
+    }
+    pub struct Hidden;
+}
+
+impl Display for Thing {
+    fn fmt(&self) { let x = impl_something(); }
+}
+
+impl Thing {
+    pub fn visible(&self) {}
+}
+pub struct After;
+"#;
+        let sigs = extract_rust_signatures(content).unwrap();
+        let names: Vec<&str> = sigs.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(names, ["outer", "visible", "After"]);
+        assert_eq!(sigs[1].indent, 4);
+    }
+
+    #[test]
+    fn test_rust_braces_in_literals_and_comments() {
+        let content = r##"
+pub fn braces() -> &'static str {
+    let s = "{{{";
+    let raw = r#"{ "nested" {"#;
+    let c = '{';
+    let e = '\u{7b}';
+    // {
+    /* { */
+    s
+}
+pub fn multi_line(
+    a: u32,
+) -> u32 {
+    pub struct Inner;
+    a
+}
+pub trait Required {
+    fn required(&self);
+}
+impl Required for u32 {
+    pub fn method() {}
+}
+"##;
+        let sigs = extract_rust_signatures(content).unwrap();
+        let names: Vec<&str> = sigs.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(names, ["braces", "multi_line", "Required", "method"]);
+    }
+
+    #[test]
+    fn test_strip_rust_literals() {
+        let mut state = RustLexState::default();
+        assert_eq!(
+            strip_rust_literals(r#"let s = "a { b"; // }"#, &mut state),
+            r#"let s = ""; "#
+        );
+        assert_eq!(
+            strip_rust_literals("fn f<'a>(x: &'a str) { '}' }", &mut state),
+            "fn f<'a>(x: &'a str) { ' ' }"
+        );
+        assert_eq!(strip_rust_literals("a /* { ", &mut state), "a ");
+        assert_eq!(state, RustLexState::BlockComment(1));
+        assert_eq!(strip_rust_literals(" } */ b", &mut state), " b");
+        assert_eq!(state, RustLexState::Code);
+    }
+
+    #[test]
+    fn test_rust_pub_struct() {
+        let content = r#"
+pub struct Config {
+    pub host: String,
+    pub port: u16,
+}
+
+struct PrivateStruct {}
+
+pub struct Generic<T: Clone> {
+    data: T,
+}
+"#;
+        let sigs = extract_rust_signatures(content).unwrap();
+        assert_eq!(sigs.len(), 2);
+        assert!(sigs[0].signature.starts_with("pub struct Config"));
+        assert_eq!(sigs[0].symbol, "Config");
+        assert!(sigs[1].signature.starts_with("pub struct Generic"));
+        assert_eq!(sigs[1].symbol, "Generic");
+    }
+
+    #[test]
+    fn test_rust_pub_trait() {
+        let content = r#"
+pub trait Handler {
+    fn handle(&self, req: Request) -> Response;
+}
+
+trait Private {}
+"#;
+        let sigs = extract_rust_signatures(content).unwrap();
+        assert_eq!(sigs.len(), 1);
+        assert!(sigs[0].signature.starts_with("pub trait Handler"));
+        assert_eq!(sigs[0].symbol, "Handler");
+    }
+
+    #[test]
+    fn test_rust_pub_enum() {
+        let content = r#"
+pub enum Status {
+    Active,
+    Inactive,
+}
+
+enum Private {}
+"#;
+        let sigs = extract_rust_signatures(content).unwrap();
+        assert_eq!(sigs.len(), 1);
+        assert!(sigs[0].signature.starts_with("pub enum Status"));
+        assert_eq!(sigs[0].symbol, "Status");
+    }
+
+    #[test]
+    fn test_typescript_exports() {
+        let content = r#"
+// File comment
+export interface User {
+    id: string;
+    name: string;
+}
+
+export type UserId = string;
+
+export function getUser(id: string): Promise<User> {
+    return fetch(`/users/${id}`);
+}
+
+export async function createUser(data: UserInput): Promise<User> {
+    return post('/users', data);
+}
+
+export const API_URL: string = "https://api.example.com";
+
+function privateFunc() {}
+"#;
+        let sigs = extract_typescript_signatures(content).unwrap();
+        assert_eq!(sigs.len(), 5);
+        assert!(sigs[0].signature.starts_with("export interface User"));
+        assert_eq!(sigs[0].symbol, "User");
+        assert!(sigs[1].signature.starts_with("export type UserId"));
+        assert_eq!(sigs[1].symbol, "UserId");
+        assert!(sigs[2].signature.starts_with("export function getUser"));
+        assert_eq!(sigs[2].symbol, "getUser");
+        assert!(
+            sigs[3]
+                .signature
+                .starts_with("export async function createUser")
+        );
+        assert_eq!(sigs[3].symbol, "createUser");
+        assert!(sigs[4].signature.starts_with("export const API_URL"));
+        assert_eq!(sigs[4].symbol, "API_URL");
+    }
+
+    #[test]
+    fn test_typescript_class() {
+        let content = r#"
+export class UserService {
+    constructor(private api: ApiClient) {}
+}
+
+export abstract class BaseHandler {
+    abstract handle(): void;
+}
+"#;
+        let sigs = extract_typescript_signatures(content).unwrap();
+        assert_eq!(sigs.len(), 2);
+        assert!(sigs[0].signature.starts_with("export class UserService"));
+        assert_eq!(sigs[0].symbol, "UserService");
+        assert!(
+            sigs[1]
+                .signature
+                .starts_with("export abstract class BaseHandler")
+        );
+        assert_eq!(sigs[1].symbol, "BaseHandler");
+    }
+
+    #[test]
+    fn test_javascript_exports() {
+        let content = r#"
+export function calculate(a, b) {
+    return a + b;
+}
+
+export async function fetchData(url) {
+    return fetch(url);
+}
+
+export class Calculator {
+    add(a, b) { return a + b; }
+}
+
+export const VERSION = "1.0.0";
+
+function privateFunc() {}
+"#;
+        let sigs = extract_javascript_signatures(content).unwrap();
         assert_eq!(sigs.len(), 4);
-        assert!(sigs[0].0.starts_with("export function calculate"));
-        assert_eq!(sigs[0].1, "calculate");
-        assert!(sigs[1].0.starts_with("export async function fetchData"));
-        assert_eq!(sigs[1].1, "fetchData");
-        assert!(sigs[2].0.starts_with("export class Calculator"));
-        assert_eq!(sigs[2].1, "Calculator");
-        assert!(sigs[3].0.starts_with("export const VERSION"));
-        assert_eq!(sigs[3].1, "VERSION");
+        assert!(sigs[0].signature.starts_with("export function calculate"));
+        assert_eq!(sigs[0].symbol, "calculate");
+        assert!(
+            sigs[1]
+                .signature
+                .starts_with("export async function fetchData")
+        );
+        assert_eq!(sigs[1].symbol, "fetchData");
+        assert!(sigs[2].signature.starts_with("export class Calculator"));
+        assert_eq!(sigs[2].symbol, "Calculator");
+        assert!(sigs[3].signature.starts_with("export const VERSION"));
+        assert_eq!(sigs[3].symbol, "VERSION");
     }
 
     #[test]
@@ -598,28 +2301,28 @@ def _private_func() -> None:
 class _PrivateClass:
     pass
 "#;
-        let sigs = extract_python_signatures(content).unwrap();
+        let sigs = extract_python_signatures(content, false).unwrap();
         assert_eq!(sigs.len(), 5, "should capture 5 signatures: {:?}", sigs);
 
         // Typed functions
-        assert!(sigs[0].0.starts_with("def process"));
-        assert_eq!(sigs[0].1, "process");
+        assert!(sigs[0].signature.starts_with("def process"));
+        assert_eq!(sigs[0].symbol, "process");
 
         // Untyped function
-        assert!(sigs[1].0.starts_with("def simple_func"));
-        assert_eq!(sigs[1].1, "simple_func");
+        assert!(sigs[1].signature.starts_with("def simple_func"));
+        assert_eq!(sigs[1].symbol, "simple_func");
 
         // Typed async
-        assert!(sigs[2].0.starts_with("async def fetch"));
-        assert_eq!(sigs[2].1, "fetch");
+        assert!(sigs[2].signature.starts_with("async def fetch"));
+        assert_eq!(sigs[2].symbol, "fetch");
 
         // Untyped async
-        assert!(sigs[3].0.starts_with("async def fetch_untyped"));
-        assert_eq!(sigs[3].1, "fetch_untyped");
+        assert!(sigs[3].signature.starts_with("async def fetch_untyped"));
+        assert_eq!(sigs[3].symbol, "fetch_untyped");
 
         // Class
-        assert!(sigs[4].0.starts_with("class UserService"));
-        assert_eq!(sigs[4].1, "UserService");
+        assert!(sigs[4].signature.starts_with("class UserService"));
+        assert_eq!(sigs[4].symbol, "UserService");
     }
 
     #[test]
@@ -635,10 +2338,32 @@ async def fetch(url: str) -> bytes:
 class UserService:
     pass
 "#;
-        let sigs = extract_python_signatures(content).unwrap();
+        let sigs = extract_python_signatures(content, false).unwrap();
         assert_eq!(sigs.len(), 3);
-        assert!(sigs[0].0.contains("->"));
-        assert!(sigs[1].0.contains("->"));
+        assert!(sigs[0].signature.contains("->"));
+        assert!(sigs[1].signature.contains("->"));
+    }
+
+    #[test]
+    fn test_python_multiline_decorators() {
+        let content = r#"
+@pytest.mark.parametrize(
+    "x, y", [(1, 2)]
+)
+def test_add(x, y):
+    pass
+
+@app.doc(summary="""
+def looks_like_code(x)
+""")
+@login_required
+class AdminView:
+    pass
+"#;
+        let sigs = extract_python_signatures(content, false).unwrap();
+        let symbols: Vec<&str> = sigs.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["test_add", "AdminView"]);
+        assert_eq!(sigs[0].line_number, Some(5));
     }
 
     #[test]
@@ -671,16 +2396,632 @@ func privateFunc() {}
 "#;
         let sigs = extract_go_signatures(content).unwrap();
         assert_eq!(sigs.len(), 5);
-        assert!(sigs[0].0.starts_with("type Config struct"));
-        assert_eq!(sigs[0].1, "Config");
-        assert!(sigs[1].0.starts_with("func NewConfig()"));
-        assert_eq!(sigs[1].1, "NewConfig");
-        assert!(sigs[2].0.starts_with("func (c *Config) Validate()"));
-        assert_eq!(sigs[2].1, "Validate");
-        assert!(sigs[3].0.starts_with("const DefaultPort"));
-        assert_eq!(sigs[3].1, "DefaultPort");
-        assert!(sigs[4].0.starts_with("var GlobalConfig"));
-        assert_eq!(sigs[4].1, "GlobalConfig");
+        assert!(sigs[0].signature.starts_with("type Config struct"));
+        assert_eq!(sigs[0].symbol, "Config");
+        assert!(sigs[1].signature.starts_with("func NewConfig()"));
+        assert_eq!(sigs[1].symbol, "NewConfig");
+        assert!(sigs[2].signature.starts_with("func (c *Config) Validate()"));
+        assert_eq!(sigs[2].symbol, "Validate");
+        assert!(sigs[3].signature.starts_with("const DefaultPort"));
+        assert_eq!(sigs[3].symbol, "DefaultPort");
+        assert!(sigs[4].signature.starts_with("var GlobalConfig"));
+        assert_eq!(sigs[4].symbol, "GlobalConfig");
+    }
+
+    #[test]
+    fn test_go_multi_return_signatures() {
+        let content = r#"
+func NewClient(host string, port int) (*Client, error) {
+func (s *MyService) GetAll() ([]Item, error) {
+func Apply(f func(int) error, values ...int) (n int, err error) {
+func Map[T any, U any](items []T, f func(T) U) []U {
+func (s *Stack[T]) Pop() (T, bool) {
+func Decode(data []byte) (map[string]interface{}, error) {
+func Done() <-chan struct{} {
+"#;
+        let sigs = extract_go_signatures(content).unwrap();
+        let found: Vec<(&str, &str)> = sigs
+            .iter()
+            .map(|s| (s.signature.as_str(), s.symbol.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "func NewClient(host string, port int) (*Client, error)",
+                    "NewClient"
+                ),
+                ("func (s *MyService) GetAll() ([]Item, error)", "GetAll"),
+                (
+                    "func Apply(f func(int) error, values ...int) (n int, err error)",
+                    "Apply"
+                ),
+                ("func Map[T any, U any](items []T, f func(T) U) []U", "Map"),
+                ("func (s *Stack[T]) Pop() (T, bool)", "Pop"),
+                (
+                    "func Decode(data []byte) (map[string]interface{}, error)",
+                    "Decode"
+                ),
+                ("func Done() <-chan struct{}", "Done"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_c_header() {
+        let content = r#"
+#ifndef LIB_H
+#define LIB_H
+#define MAX(a, b) \
+    ((a) > (b) ? (a) : (b))
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef int (*callback_t)(void *ctx, int code);
+
+typedef struct {
+    int x; /* } */
+} point_t;
+
+struct node {
+    struct node *next;
+};
+
+extern int lib_errno;
+
+/* Start the library */
+int lib_init(const char *name,
+             size_t len);
+static inline int lib_max(int a, int b) { return a > b ? a : b; }
+
+#ifdef __cplusplus
+}
+#endif
+#endif
+"#;
+        let sigs = extract_c_signatures(content).unwrap();
+        let found: Vec<(&str, &str)> = sigs
+            .iter()
+            .map(|s| (s.signature.as_str(), s.symbol.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "typedef int (*callback_t)(void *ctx, int code)",
+                    "callback_t"
+                ),
+                ("typedef struct point_t", "point_t"),
+                ("struct node", "node"),
+                ("extern int lib_errno", "lib_errno"),
+                ("int lib_init(const char *name, size_t len)", "lib_init"),
+                ("static inline int lib_max(int a, int b)", "lib_max"),
+            ]
+        );
+        assert_eq!(sigs[4].line_number, Some(24));
+    }
+
+    #[test]
+    fn test_c_source_lists_declared_functions_once() {
+        let content = r#"
+static const int table[] = { 1, 2, 3 };
+static void helper(void);
+
+int main(int argc, char **argv)
+{
+    const char *s = "{";
+    if (argc > 1) { helper(); }
+    return 0;
+}
+
+static void helper(void) {
+    int local(void);
+}
+"#;
+        let sigs = extract_c_signatures(content).unwrap();
+        let symbols: Vec<&str> = sigs.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["table", "helper", "main"]);
+        assert_eq!(sigs[0].signature, "static const int table[]");
+        assert_eq!(sigs[2].signature, "int main(int argc, char **argv)");
+    }
+
+    #[test]
+    fn test_cpp_namespaces_and_classes() {
+        let content = r#"
+namespace geo {
+template <typename T>
+class Shape : public Base<T> {
+public:
+    virtual double area() const = 0;
+};
+std::vector<int> sides(const Shape<int>& s);
+}
+"#;
+        let sigs = extract_c_signatures(content).unwrap();
+        let symbols: Vec<&str> = sigs.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["Shape", "sides"]);
+        assert_eq!(sigs[0].signature, "class Shape : public Base<T>");
+    }
+
+    #[test]
+    fn test_java_classes_and_methods() {
+        let content = r#"
+package com.example;
+
+/**
+ * public class NotAClass {
+ */
+public final class Registry<T extends Comparable<T>> {
+    private static final String NAME = "public void fake() {";
+
+    public Registry(int capacity) {
+        init(capacity);
+    }
+
+    @Override
+    public String toString() {
+        Runnable r = new Runnable() {
+            public void run() {}
+        };
+        return NAME;
+    }
+
+    public static <T extends Comparable<T>> T max(List<T> items) {
+        return items.get(0);
+    }
+
+    private void init(int capacity) {}
+
+    protected static class Entry {
+        protected Entry next() { return null; }
+    }
+
+    public interface Listener {
+        void onChange(String key);
+        default boolean enabled() { return true; }
+    }
+}
+
+class Hidden {
+    public void notListed() {}
+}
+"#;
+        let sigs = extract_java_signatures(content, false).unwrap();
+        let found: Vec<(&str, &str, usize)> = sigs
+            .iter()
+            .map(|s| (s.signature.as_str(), s.symbol.as_str(), s.indent))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "public final class Registry<T extends Comparable<T>>",
+                    "Registry",
+                    0
+                ),
+                ("public Registry(int capacity)", "Registry", 4),
+                ("public String toString()", "toString", 4),
+                (
+                    "public static <T extends Comparable<T>> T max(List<T> items)",
+                    "max",
+                    4
+                ),
+                ("protected static class Entry", "Entry", 4),
+                ("protected Entry next()", "next", 8),
+                ("public interface Listener", "Listener", 4),
+                ("void onChange(String key)", "onChange", 8),
+                ("default boolean enabled()", "enabled", 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_java_show_private() {
+        let content = r#"
+public class Cache {
+    private int size;
+
+    private Cache() {}
+
+    public int size() { return size; }
+
+    private void evict() {}
+
+    private static class Node {
+        Node next() { return null; }
+        public int key() { return 0; }
+    }
+}
+"#;
+        let symbols = |show_private| -> Vec<String> {
+            extract_java_signatures(content, show_private)
+                .unwrap()
+                .into_iter()
+                .map(|s| s.symbol)
+                .collect()
+        };
+        assert_eq!(symbols(false), vec!["Cache", "size"]);
+        assert_eq!(
+            symbols(true),
+            vec!["Cache", "Cache", "size", "evict", "Node", "key"]
+        );
+    }
+
+    #[test]
+    fn test_strip_java_annotations() {
+        assert_eq!(
+            strip_java_annotations("@Override public void run()"),
+            "public void run()"
+        );
+        assert_eq!(
+            strip_java_annotations("@SuppressWarnings(\"\") @Deprecated int size()"),
+            "int size()"
+        );
+        assert_eq!(
+            strip_java_annotations("@interface Marker"),
+            "@interface Marker"
+        );
+    }
+
+    #[test]
+    fn test_csharp_types_and_members() {
+        let content = r#"
+using System.Threading.Tasks;
+
+[assembly: InternalsVisibleTo("Tests")]
+
+namespace Shop.Api
+{
+    public delegate void Changed(object sender);
+
+    public record Person(string Name, int Age);
+
+    [ApiController]
+    [Route("api/[controller]")]
+    public sealed class ItemsController : ControllerBase
+    {
+        private readonly string _name = "public void Fake() {";
+
+        public ItemsController(IService service) { }
+
+        public string Name { get; set; }
+
+        [HttpGet("{id}")]
+        public async Task<ActionResult<Item>> Get(int id)
+        {
+            Func<int> f = () => { return 1; };
+            return await _service.Find(id);
+        }
+
+        [Obsolete] protected virtual T Convert<T>(object value) => (T)value;
+
+        void Helper() { }
+    }
+
+    internal interface IService
+    {
+        Task<Item> Find(int id);
+        int Count { get; }
+    }
+}
+"#;
+        let sigs = extract_csharp_signatures(content, false).unwrap();
+        let found: Vec<(&str, &str, usize)> = sigs
+            .iter()
+            .map(|s| (s.signature.as_str(), s.symbol.as_str(), s.indent))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("public delegate void Changed(object sender)", "Changed", 4),
+                ("public record Person(string Name, int Age)", "Person", 4),
+                (
+                    "[ApiController] [Route(\"api/[controller]\")] public sealed class ItemsController : ControllerBase",
+                    "ItemsController",
+                    4
+                ),
+                (
+                    "public ItemsController(IService service)",
+                    "ItemsController",
+                    8
+                ),
+                ("public string Name", "Name", 8),
+                (
+                    "[HttpGet(\"{id}\")] public async Task<ActionResult<Item>> Get(int id)",
+                    "Get",
+                    8
+                ),
+                (
+                    "[Obsolete] protected virtual T Convert<T>(object value)",
+                    "Convert",
+                    8
+                ),
+                ("internal interface IService", "IService", 4),
+                ("Task<Item> Find(int id)", "Find", 8),
+                ("int Count", "Count", 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csharp_show_private() {
+        let content = r#"
+public class Cache
+{
+    private int Size { get; set; }
+
+    private Cache() { }
+
+    public void Clear() { }
+
+    private protected void Trim() { }
+
+    private void Evict() { }
+
+    private class Node
+    {
+        public Node Next() { return null; }
+    }
+}
+"#;
+        let symbols = |show_private| -> Vec<String> {
+            extract_csharp_signatures(content, show_private)
+                .unwrap()
+                .into_iter()
+                .map(|s| s.symbol)
+                .collect()
+        };
+        assert_eq!(symbols(false), vec!["Cache", "Clear", "Trim"]);
+        assert_eq!(
+            symbols(true),
+            vec![
+                "Cache", "Size", "Cache", "Clear", "Trim", "Evict", "Node", "Next"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ruby_modules_classes_and_methods() {
+        let content = r#"
+# frozen_string_literal: true
+
+module Greeting
+  def self.hello(name = "world; end")
+    "Hello, #{name}"
+  end
+
+  module_function
+
+  def shout(text) = text.upcase
+end
+
+class User < Base
+  attr_accessor :name, :email
+
+  def initialize(name)
+    @name = name
+    [1, 2].each do |i|
+      puts i if i > 1
+    end
+    @status = if name then "end" else :begin end
+  end
+
+  def self.find(id)
+    new(id)
+  end
+
+  def admin?; false; end
+
+  protected
+
+  def compare(other) = id <=> other.id
+
+  private
+
+  attr_writer :secret
+
+  def helper
+    <<~SQL
+      def not_a_method
+      end
+    SQL
+  end
+
+  public def visible
+  end
+
+  def self.build
+  end
+
+  class << self
+    private
+
+    def internal
+    end
+  end
+end
+
+def top_level(x)
+  x
+end
+"#;
+        let sigs = extract_ruby_signatures(content, false).unwrap();
+        let found: Vec<(&str, &str, usize, Option<usize>)> = sigs
+            .iter()
+            .map(|s| {
+                (
+                    s.signature.as_str(),
+                    s.symbol.as_str(),
+                    s.indent,
+                    s.line_number,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("module Greeting", "Greeting", 0, Some(4)),
+                (
+                    "def self.hello(name = \"world; end\")",
+                    "self.hello",
+                    2,
+                    Some(5)
+                ),
+                ("def shout(text)", "shout", 2, Some(11)),
+                ("class User < Base", "User", 0, Some(14)),
+                ("attr_accessor :name, :email", "name", 2, Some(15)),
+                ("def initialize(name)", "initialize", 2, Some(17)),
+                ("def self.find(id)", "self.find", 2, Some(25)),
+                ("def admin?", "admin?", 2, Some(29)),
+                ("def compare(other)", "compare", 2, Some(33)),
+                ("public def visible", "visible", 2, Some(46)),
+                ("def self.build", "self.build", 2, Some(49)),
+                ("class << self", "self", 2, Some(52)),
+                ("def top_level(x)", "top_level", 0, Some(60)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ruby_show_private() {
+        let content = r#"class Account
+  def balance
+  end
+
+  def audit
+  end
+  private :audit
+
+  private
+
+  attr_reader :ledger
+  def reconcile
+  end
+end
+"#;
+        let symbols = |show_private| -> Vec<String> {
+            extract_ruby_signatures(content, show_private)
+                .unwrap()
+                .into_iter()
+                .map(|s| s.symbol)
+                .collect()
+        };
+        assert_eq!(symbols(false), vec!["Account", "balance"]);
+        assert_eq!(
+            symbols(true),
+            vec!["Account", "balance", "audit", "ledger", "reconcile"]
+        );
+    }
+
+    #[test]
+    fn test_dockerfile_instructions() {
+        let content = "# syntax=docker/dockerfile:1\nFROM rust:1.80 AS build\nWORKDIR /app\nCOPY . .\nRUN cargo build --release \\\n    && strip target/release/app\n\nFROM debian:slim\nCOPY --from=build /app/target/release/app /usr/bin/app\nEXPOSE 8080\nENTRYPOINT [\"app\"]\n";
+        let sigs = extract_dockerfile_instructions(content).unwrap();
+        let lines: Vec<&str> = sigs.iter().map(|s| s.signature.as_str()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "FROM rust:1.80 AS build",
+                "COPY . .",
+                "RUN cargo build --release",
+                "FROM debian:slim",
+                "COPY --from=build /app/target/release/app /usr/bin/app",
+                "EXPOSE 8080",
+                "ENTRYPOINT [\"app\"]",
+            ]
+        );
+        assert_eq!(sigs[0].symbol, "FROM");
+        assert_eq!(sigs[2].line_number, Some(5));
+    }
+
+    #[test]
+    fn test_makefile_targets() {
+        let content = "# Build automation\n.PHONY: build test\n\nCC := gcc\nVERSION ::= 1.0\n\n## Compile everything\nbuild: deps\n\tcargo build\n\ntest: build ## Run the tests\n\tcargo test\n\nclean:\n\trm -rf target\n\n%.o: %.c\n\t$(CC) -c $<\n\nmain.o: main.c\nbuild/app:\n";
+        let sigs = extract_makefile_targets(content).unwrap();
+        let lines: Vec<&str> = sigs.iter().map(|s| s.signature.as_str()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "build: ## Compile everything",
+                "test: ## Run the tests",
+                "clean:",
+            ]
+        );
+        assert_eq!(sigs[1].symbol, "test");
+        assert_eq!(sigs[1].line_number, Some(11));
+    }
+
+    #[test]
+    fn test_clojure_definitions() {
+        let content = "(ns my.app\n  (:require [clojure.string :as str]))\n\n(def ^:dynamic *port* 8080)\n\n(defn greet [name]\n  (str \"Hello, \" name))\n\n(defn- helper [x] x)\n\n(def ^{:private true} secret 42)\n\n(defmacro unless\n  \"Inverse of when.\"\n  [test & body])\n\n(defprotocol Shape\n  (area [this]))\n\n(defrecord Circle [radius])\n(deftype Point [x y])\n\n(comment\n  (defn scratch []))\n";
+        let sigs = extract_clojure_definitions(content, false).unwrap();
+        let lines: Vec<&str> = sigs.iter().map(|s| s.signature.as_str()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "def *port*",
+                "defn greet [name]",
+                "defmacro unless",
+                "defprotocol Shape",
+                "defrecord Circle [radius]",
+                "deftype Point [x y]",
+            ]
+        );
+        assert_eq!(sigs[1].symbol, "greet");
+        assert_eq!(sigs[1].line_number, Some(6));
+
+        let all = extract_clojure_definitions(content, true).unwrap();
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[2].signature, "defn- helper [x]");
+        assert_eq!(all[3].symbol, "secret");
+    }
+
+    #[test]
+    fn test_perl_signatures() {
+        let content = "#!/usr/bin/perl\npackage My::App;\nuse strict;\n\n=head1 METHODS\n\nsub documented {\n\n=cut\n\nsub new {\n    my ($class, %args) = @_;\n}\n\nsub greet($self, $name) {\n    my $inner = sub { 1 };\n}\n\nsub _helper {\n}\n\nmy $handler = sub {\n};\nsub forward;\n\npackage My::App::Util {\n    sub trim\n    {\n    }\n}\n\n1;\n__END__\nsub after_end {}\n";
+        let sigs = extract_perl_signatures(content, false).unwrap();
+        let lines: Vec<&str> = sigs.iter().map(|s| s.signature.as_str()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "package My::App",
+                "sub new",
+                "sub greet($self, $name)",
+                "my $inner = sub",
+                "my $handler = sub",
+                "package My::App::Util",
+                "sub trim",
+            ]
+        );
+        assert_eq!(sigs[1].symbol, "new");
+        assert_eq!(sigs[1].line_number, Some(11));
+        assert_eq!(sigs[3].indent, 4);
+
+        let all = extract_perl_signatures(content, true).unwrap();
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[4].signature, "sub _helper");
+    }
+
+    #[test]
+    fn test_nix_let_functions() {
+        let content = "{ pkgs, lib ? pkgs.lib, ... }:\n\nlet\n  # Not a \"function\" = x: x\n  version = \"1.0\";\n  mkService = name: { inherit name; helper = x: x; };\n  wrap = { src, patches ? [ ] }@args: pkgs.stdenv.mkDerivation args;\n  url = https://example.com;\n  add = a: b: a + b; twice = f: x: f (f x);\n  script = ''\n    inner = x: ''${x}\n  '';\n  nested = let local = y: y; in local;\nin\n{\n  service = mkService \"web\";\n  other = z: z;\n}\n";
+        let sigs = extract_nix_functions(content).unwrap();
+        let lines: Vec<&str> = sigs.iter().map(|s| s.signature.as_str()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "mkService = name:",
+                "wrap = { src, patches ? [ ] }@args:",
+                "add = a: b:",
+                "twice = f: x:",
+            ]
+        );
+        assert_eq!(sigs[0].symbol, "mkService");
+        assert_eq!(sigs[0].line_number, Some(6));
+        assert_eq!(sigs[0].indent, 2);
+        assert_eq!(sigs[3].line_number, Some(9));
     }
 
     #[test]
@@ -711,4 +3052,26 @@ struct Private {}
         let sigs = extract_rust_signatures(content).unwrap();
         assert!(sigs.is_empty());
     }
+
+    #[test]
+    fn test_signature_line_numbers() {
+        let content = "use std::io;\n\npub struct Config;\n\nimpl Config {\n    pub fn new() -> Self {\n        Config\n    }\n}\n";
+        let sigs = extract_rust_signatures(content).unwrap();
+        assert_eq!(sigs.len(), 2);
+        assert_eq!(sigs[0].line_number, Some(3));
+        assert_eq!(sigs[1].symbol, "new");
+        assert_eq!(sigs[1].line_number, Some(6));
+    }
+
+    #[test]
+    fn test_attach_context_skips_blank_lines() {
+        let content = "pub fn run() {\n\n    let x = 1;\n    x + 1;\n}\n";
+        let mut sigs = extract_rust_signatures(content).unwrap();
+        attach_context(&mut sigs, content, 2);
+        assert_eq!(sigs[0].context, vec!["    let x = 1;", "    x + 1;"]);
+
+        attach_context(&mut sigs, content, 10);
+        assert_eq!(sigs[0].context.len(), 3);
+        assert_eq!(sigs[0].context[2], "}");
+    }
 }
@@ -248,6 +248,27 @@ fn test_file_with_only_code_no_comment() {
     // Should not crash or show garbage
 }
 
+#[test]
+fn test_python_docstring_with_escaped_quotes() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "module.py",
+        r#""""Docstring with \"escaped\" quotes."""
+
+def main():
+    """Not the module docstring."""
+"#,
+    );
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &[]);
+    assert!(success);
+    assert!(
+        stdout.contains(r#"module.py  Docstring with \"escaped\" quotes."#),
+        "should keep escaped quotes inside the docstring: {}",
+        stdout
+    );
+}
+
 #[test]
 fn test_very_long_first_line() {
     let repo = TestRepo::with_git();
@@ -533,7 +554,7 @@ fn test_missing_git_objects() {
     repo.commit("Initial commit");
 
     // Corrupt the git repository by removing objects directory contents
-    let objects_dir = repo.path().join(".git/objects");
+    let _objects_dir = repo.path().join(".git/objects");
     // We won't actually delete all objects (that would break git completely)
     // Instead, we'll test that fruit handles git errors gracefully
 
@@ -563,7 +584,7 @@ normal.rs
 "#,
     );
 
-    let (stdout, _stderr, success) = run_fruit(repo.path(), &[]);
+    let (_stdout, _stderr, success) = run_fruit(repo.path(), &[]);
     assert!(success, "fruit should handle malformed gitignore");
     // normal.rs is gitignored, so it shouldn't appear unless we use -a
     // But the point is it shouldn't crash
@@ -625,3 +646,26 @@ fn test_performance_1000_files() {
         elapsed
     );
 }
+
+#[test]
+fn test_large_directory_parallel_filtering_matches_sequential() {
+    let repo = TestRepo::with_git();
+    repo.add_file(".gitignore", "*.log\n");
+
+    // More entries than the parallel filtering threshold in a single directory
+    for i in 0..150 {
+        repo.add_file(&format!("big/file_{:03}.rs", i), "//! Doc\nfn f() {}");
+        if i % 10 == 0 {
+            repo.add_untracked(&format!("big/debug_{:03}.log", i), "log");
+        }
+    }
+
+    let (sequential, _stderr, success) = run_fruit(repo.path(), &["-j", "1"]);
+    assert!(success);
+    let (parallel, _stderr, success) = run_fruit(repo.path(), &["-j", "0"]);
+    assert!(success);
+
+    assert_eq!(sequential, parallel);
+    assert!(parallel.contains("151 files"), "{}", parallel);
+    assert!(!parallel.contains(".log"), "{}", parallel);
+}
@@ -389,3 +389,2057 @@ fn test_todos_only_in_nested_directories() {
         stdout
     );
 }
+
+#[test]
+fn test_todos_only_config_and_docs_files() {
+    let repo = TestRepo::with_git();
+    repo.add_file(".github/ci.yml", "# TODO: cache dependencies\non: push\n");
+    repo.add_file("config.toml", "# FIXME: remove legacy key\nkey = 1\n");
+    repo.add_file("docs/guide.md", "# Guide\n<!-- TODO: add examples -->\n");
+    repo.add_file("docs/done.md", "# Done\nNothing left.\n");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--all", "--todos", "--todos-only"]);
+    assert!(success);
+    assert!(stdout.contains("cache dependencies"), "{}", stdout);
+    assert!(stdout.contains("remove legacy key"), "{}", stdout);
+    assert!(stdout.contains("add examples"), "{}", stdout);
+    assert!(!stdout.contains("done.md"), "{}", stdout);
+}
+
+// ============================================================================
+// --prefix-path Flag Tests
+// ============================================================================
+
+#[test]
+fn test_prefix_path_json() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/lib.rs", "//! Library module\npub mod foo;");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--json", "--prefix-path", "myrepo"]);
+    assert!(success);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["path"], "myrepo");
+    let src = &json["children"][0];
+    assert_eq!(src["path"], "myrepo/src");
+    assert_eq!(src["children"][0]["path"], "myrepo/src/lib.rs");
+}
+
+#[test]
+fn test_prefix_path_tree_root() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.rs", "fn main() {}");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--prefix-path", "myrepo"]);
+    assert!(success);
+    assert!(
+        stdout.starts_with("myrepo\n"),
+        "root should be prefix: {}",
+        stdout
+    );
+    assert!(stdout.contains("└── main.rs"));
+}
+
+// ============================================================================
+// --show-empty-dirs / --no-empty-dirs Flag Tests
+// ============================================================================
+
+#[test]
+fn test_no_prune_shows_directories_without_included_files() {
+    let repo = TestRepo::with_git();
+    repo.add_file(".gitignore", "build/\n");
+    repo.add_file("src/main.rs", "fn main() {}");
+    repo.add_file("build/output.bin", "binary");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &[]);
+    assert!(success);
+    assert!(
+        !stdout.contains("build"),
+        "build/ should be pruned: {}",
+        stdout
+    );
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--no-prune"]);
+    assert!(success);
+    assert!(
+        stdout.contains("build"),
+        "build/ should be shown: {}",
+        stdout
+    );
+    assert!(
+        !stdout.contains("output.bin"),
+        "ignored files should still be hidden: {}",
+        stdout
+    );
+}
+
+#[test]
+fn test_no_prune_json() {
+    let repo = TestRepo::with_git();
+    repo.add_file(".gitignore", "build/\n");
+    repo.add_file("src/main.rs", "fn main() {}");
+    repo.add_file("build/output.bin", "binary");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--json", "--no-prune"]);
+    assert!(success);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let children = json["children"].as_array().unwrap();
+    let build = children.iter().find(|c| c["name"] == "build");
+    assert!(build.is_some(), "build/ should be in JSON: {}", stdout);
+    assert!(build.unwrap()["children"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_show_empty_dirs_and_no_empty_dirs() {
+    let repo = TestRepo::with_git();
+    repo.add_file(".gitignore", "logs/\n");
+    repo.add_file("src/main.rs", "fn main() {}");
+    repo.add_untracked("logs/today.log", "log line");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["-j", jobs, "--show-empty-dirs"]);
+        assert!(success);
+        assert!(stdout.contains("logs"), "logs/ should be shown: {}", stdout);
+        assert!(!stdout.contains("today.log"));
+
+        // The last of the two flags wins
+        let (stdout, _stderr, success) = run_fruit(
+            repo.path(),
+            &["-j", jobs, "--show-empty-dirs", "--no-empty-dirs"],
+        );
+        assert!(success);
+        assert!(
+            !stdout.contains("logs"),
+            "logs/ should be pruned: {}",
+            stdout
+        );
+    }
+}
+
+// ============================================================================
+// --size Alignment Tests
+// ============================================================================
+
+#[test]
+fn test_size_column_aligned_within_directory() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/a.rs", "fn a() {}");
+    repo.add_file("src/much_longer_name.rs", &"x".repeat(3000));
+    repo.add_file("src/mid.rs", "fn mid() {}");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) =
+            run_fruit(repo.path(), &["--size", "--no-comments", "-j", jobs]);
+        assert!(success);
+
+        let columns: Vec<usize> = stdout
+            .lines()
+            .filter(|l| l.contains(".rs"))
+            .map(|l| l.find('[').expect("size should be shown"))
+            .collect();
+        assert_eq!(columns.len(), 3, "{}", stdout);
+        assert!(
+            columns.iter().all(|&c| c == columns[0]),
+            "sizes should be aligned (-j{}): {}",
+            jobs,
+            stdout
+        );
+        assert!(stdout.contains("[2.9K]"), "{}", stdout);
+    }
+}
+
+// ============================================================================
+// --count Flag Tests
+// ============================================================================
+
+#[test]
+fn test_count_shows_direct_children() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/main.rs", "fn main() {}");
+    repo.add_file("src/lib.rs", "pub fn lib() {}");
+    repo.add_file("src/util/mod.rs", "pub fn util() {}");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--count", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("src (1 dir, 2 files)"), "{}", stdout);
+        assert!(stdout.contains("util (1 file)"), "{}", stdout);
+    }
+}
+
+#[test]
+fn test_count_truncated_at_max_depth() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/main.rs", "fn main() {}");
+    repo.add_file("src/lib.rs", "pub fn lib() {}");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--count", "-L", "1"]);
+    assert!(success);
+    assert!(stdout.contains("src (2+ files)"), "{}", stdout);
+}
+
+#[test]
+fn test_count_json_child_count() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/main.rs", "fn main() {}");
+    repo.add_file("src/lib.rs", "pub fn lib() {}");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--json"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert!(json.get("child_count").is_none());
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--json", "--count"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let src = json["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == "src")
+        .unwrap();
+    assert_eq!(src["child_count"], 2);
+}
+
+#[test]
+fn test_count_json_child_count_at_max_depth() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/main.rs", "fn main() {}");
+    repo.add_file("src/util/mod.rs", "");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--json", "--count", "-L", "1"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let src = json["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == "src")
+        .unwrap();
+    assert_eq!(src["children"].as_array().unwrap().len(), 0);
+    assert_eq!(src["child_count"], 2);
+}
+
+// ============================================================================
+// --mermaid Flag Tests
+// ============================================================================
+
+#[test]
+fn test_mermaid_internal_import_edges() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "src/main.rs",
+        "use crate::config::Config;\nuse serde::Serialize;\n",
+    );
+    repo.add_file("src/config.rs", "pub struct Config;\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--mermaid", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.starts_with("graph TD\n"), "{}", stdout);
+        assert!(stdout.contains("f0[\"src/config.rs\"]"), "{}", stdout);
+        assert!(stdout.contains("f1[\"src/main.rs\"]"), "{}", stdout);
+        assert!(stdout.contains("f1 --> f0"), "{}", stdout);
+        assert!(!stdout.contains("serde"), "{}", stdout);
+        assert!(!stdout.contains("directories"), "{}", stdout);
+    }
+}
+
+#[test]
+fn test_mermaid_all_imports() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.py", "import os\nimport requests\n");
+
+    let (stdout, _stderr, success) =
+        run_fruit(repo.path(), &["--mermaid", "--mermaid-all-imports"]);
+    assert!(success);
+    assert!(stdout.contains("([\"requests\"])"), "{}", stdout);
+    assert!(stdout.contains("([\"os\"])"), "{}", stdout);
+    assert!(stdout.contains("f0 -.-> x"), "{}", stdout);
+}
+
+#[test]
+fn test_mermaid_conflicts_with_json() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.rs", "fn main() {}");
+
+    let (_stdout, _stderr, success) = run_fruit(repo.path(), &["--mermaid", "--json"]);
+    assert!(!success);
+    let (_stdout, _stderr, success) = run_fruit(repo.path(), &["--mermaid", "--markdown"]);
+    assert!(!success);
+}
+
+// ============================================================================
+// --dot Flag Tests
+// ============================================================================
+
+#[test]
+fn test_dot_internal_import_edges() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "src/main.rs",
+        "//! Entry point\nuse crate::config::Config;\nuse serde::Serialize;\n",
+    );
+    repo.add_file("src/config.rs", "pub struct Config;\n");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--dot"]);
+    assert!(success);
+    assert!(stdout.starts_with("digraph dependencies {\n"), "{}", stdout);
+    assert!(stdout.contains("rankdir=LR;"), "{}", stdout);
+    assert!(
+        stdout.contains("f0 [label=\"src/config.rs\"];"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("f1 [label=\"src/main.rs\\nEntry point\"];"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("f1 -> f0;"), "{}", stdout);
+    assert!(!stdout.contains("serde"), "{}", stdout);
+    assert!(stdout.trim_end().ends_with('}'), "{}", stdout);
+}
+
+#[test]
+fn test_dot_conflicts_with_other_formats() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.rs", "fn main() {}");
+
+    for other in ["--json", "--markdown", "--mermaid"] {
+        let (_stdout, _stderr, success) = run_fruit(repo.path(), &["--dot", other]);
+        assert!(!success, "--dot should conflict with {}", other);
+    }
+}
+
+// ============================================================================
+// --watch Flag Tests
+// ============================================================================
+
+#[test]
+fn test_watch_conflicts_with_json_and_markdown() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.rs", "fn main() {}");
+
+    for other in ["--json", "--markdown"] {
+        let (_stdout, stderr, success) = run_fruit(repo.path(), &["--watch", other]);
+        assert!(!success, "--watch should conflict with {}", other);
+        assert!(stderr.contains("cannot be used with"), "{}", stderr);
+    }
+}
+
+// ============================================================================
+// --cache Flag Tests
+// ============================================================================
+
+/// Run fruit with its cache directory redirected to `cache_home`.
+fn run_fruit_cached(dir: &std::path::Path, cache_home: &std::path::Path, args: &[&str]) -> String {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_fruit"))
+        .args(args)
+        .current_dir(dir)
+        .env("XDG_CACHE_HOME", cache_home)
+        .output()
+        .expect("Failed to run fruit");
+    assert!(output.status.success());
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_cache_reused_and_invalidated() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.rs", "//! Original comment\nfn main() {}");
+    let cache_home = tempfile::TempDir::new().unwrap();
+    let cache_file = cache_home.path().join("fruit").join("cache.bin");
+
+    let stdout = run_fruit_cached(repo.path(), cache_home.path(), &["--cache"]);
+    assert!(stdout.contains("Original comment"), "{}", stdout);
+    assert!(cache_file.exists(), "cache file should be written");
+
+    // A changed file (different size) must be re-extracted
+    repo.add_file("main.rs", "//! Updated comment here\nfn main() {}");
+    for jobs in ["1", "0"] {
+        let stdout = run_fruit_cached(repo.path(), cache_home.path(), &["--cache", "-j", jobs]);
+        assert!(stdout.contains("Updated comment here"), "{}", stdout);
+    }
+
+    run_fruit_cached(repo.path(), cache_home.path(), &["--clear-cache"]);
+    assert!(
+        !cache_file.exists(),
+        "--clear-cache should delete the cache file"
+    );
+}
+
+#[test]
+fn test_no_cache_by_default() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.rs", "//! Comment\nfn main() {}");
+    let cache_home = tempfile::TempDir::new().unwrap();
+
+    run_fruit_cached(repo.path(), cache_home.path(), &[]);
+    assert!(!cache_home.path().join("fruit").exists());
+}
+
+// ============================================================================
+// Shebang Detection Tests
+// ============================================================================
+
+#[test]
+fn test_shebang_script_comment_extraction() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "bin/deploy",
+        "#!/usr/bin/env bash\n# Deploy the app to production\nset -e\n",
+    );
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &[]);
+    assert!(success);
+    assert!(
+        stdout.contains("Deploy the app to production"),
+        "should extract comment from extension-less script: {}",
+        stdout
+    );
+}
+
+// ============================================================================
+// --files-only Flag Tests
+// ============================================================================
+
+#[test]
+fn test_files_only_lists_paths() {
+    let repo = TestRepo::with_git();
+    repo.add_file("README.md", "# Project\n");
+    repo.add_file("src/main.rs", "//! Entry point\nfn main() {}\n");
+    repo.add_file("src/util/mod.rs", "//! Utilities\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--files-only", "-j", jobs]);
+        assert!(success);
+        let mut lines: Vec<&str> = stdout.lines().collect();
+        lines.sort_unstable();
+        assert_eq!(
+            lines,
+            ["README.md", "src/main.rs", "src/util/mod.rs"],
+            "-j {}: {}",
+            jobs,
+            stdout
+        );
+    }
+}
+
+#[test]
+fn test_files_only_combines_with_filters() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/main.rs", "// TODO: finish\nfn main() {}\n");
+    repo.add_file("src/lib.rs", "pub fn lib() {}\n");
+    repo.add_file("docs/guide.md", "# Guide\n");
+
+    let (stdout, _stderr, success) = run_fruit(
+        repo.path(),
+        &["--files-only", "-I", "docs", "--todos", "--todos-only"],
+    );
+    assert!(success);
+    assert_eq!(stdout, "src/main.rs\n");
+}
+
+#[test]
+fn test_files_only_conflicts_with_other_formats() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.rs", "fn main() {}\n");
+
+    for flag in ["--dirs-only", "--json", "--markdown"] {
+        let (_stdout, stderr, success) = run_fruit(repo.path(), &["--files-only", flag]);
+        assert!(!success, "{} should conflict", flag);
+        assert!(stderr.contains("cannot be used with"), "{}", stderr);
+    }
+}
+
+// ============================================================================
+// --todo-markers Flag Tests
+// ============================================================================
+
+#[test]
+fn test_todo_markers_replace_defaults() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "slow.rs",
+        "// OPTIMIZE: avoid the clone\n// TODO: default marker\nfn foo() {}",
+    );
+    repo.add_file("plain.rs", "// TODO: only a default marker\nfn bar() {}");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(
+            repo.path(),
+            &[
+                "--todos",
+                "--todos-only",
+                "--todo-markers",
+                "OPTIMIZE,REVIEW",
+                "-j",
+                jobs,
+            ],
+        );
+        assert!(success);
+        assert!(stdout.contains("OPTIMIZE: avoid the clone"), "{}", stdout);
+        assert!(!stdout.contains("default marker"), "{}", stdout);
+        assert!(!stdout.contains("plain.rs"), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) = run_fruit(
+        repo.path(),
+        &["--json", "--todos", "--todo-markers", "optimize"],
+    );
+    assert!(success);
+    assert!(stdout.contains("avoid the clone"), "{}", stdout);
+    assert!(!stdout.contains("default marker"), "{}", stdout);
+}
+
+// ============================================================================
+// --todos-assignee Flag Tests
+// ============================================================================
+
+#[test]
+fn test_todos_assignee_filters_todos() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "a.rs",
+        "// TODO(@alice): fix parser\n// TODO(@bob): add tests\nfn a() {}",
+    );
+    repo.add_file("b.rs", "// TODO: nobody\nfn b() {}");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(
+            repo.path(),
+            &[
+                "--todos",
+                "--todos-only",
+                "--todos-assignee",
+                "@alice",
+                "-j",
+                jobs,
+            ],
+        );
+        assert!(success);
+        assert!(stdout.contains("TODO(@alice): fix parser"), "{}", stdout);
+        assert!(!stdout.contains("add tests"), "{}", stdout);
+        assert!(!stdout.contains("b.rs"), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--json", "--todos"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let file = json["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == "a.rs")
+        .unwrap();
+    assert_eq!(file["todos"][0]["assignee"], "alice");
+    assert!(stdout.contains("nobody") && !stdout.contains("\"assignee\": null"));
+}
+
+// ============================================================================
+// --tokens / --max-tokens Flag Tests
+// ============================================================================
+
+#[test]
+fn test_tokens_per_file_and_total() {
+    let repo = TestRepo::with_git();
+    repo.add_file("a.rs", &"x".repeat(40));
+    repo.add_file("b.rs", &"y".repeat(21));
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--tokens", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("a.rs  ~10 tokens"), "{}", stdout);
+        assert!(stdout.contains("b.rs  ~6 tokens"), "{}", stdout);
+        assert!(
+            stdout.contains("0 directories, 2 files, ~16 tokens"),
+            "{}",
+            stdout
+        );
+    }
+}
+
+#[test]
+fn test_max_tokens_truncates_walk() {
+    let repo = TestRepo::with_git();
+    repo.add_file("a.rs", &"x".repeat(40));
+    repo.add_file("b.rs", &"y".repeat(40));
+    repo.add_file("c.rs", &"z".repeat(40));
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) =
+            run_fruit(repo.path(), &["--tokens", "--max-tokens", "25", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("b.rs"), "{}", stdout);
+        assert!(!stdout.contains("c.rs"), "{}", stdout);
+        assert!(stdout.contains("budget of 25 tokens"), "{}", stdout);
+        assert!(
+            stdout.contains("0 directories, 2 files, ~20 tokens"),
+            "{}",
+            stdout
+        );
+    }
+}
+
+// ============================================================================
+// Metadata Order Tests
+// ============================================================================
+
+#[test]
+fn test_first_flag_sets_metadata_order() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "main.rs",
+        "//! Entry point\n\n// TODO: wire up\nfn main() {}\n",
+    );
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--todos", "-c"]);
+    assert!(success);
+    assert!(
+        stdout.contains("main.rs  TODO: wire up (line 3)"),
+        "{}",
+        stdout
+    );
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["-c", "--todos"]);
+    assert!(success);
+    assert!(stdout.contains("main.rs  Entry point"), "{}", stdout);
+}
+
+#[test]
+fn test_single_flag_keeps_comment_inline() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "main.rs",
+        "//! Entry point\n\nuse serde::Serialize;\nfn main() {}\n",
+    );
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["-i"]);
+    assert!(success);
+    assert!(stdout.contains("main.rs  Entry point"), "{}", stdout);
+    assert!(stdout.contains("imports: serde"), "{}", stdout);
+}
+
+// ============================================================================
+// --line-numbers Flag Tests
+// ============================================================================
+
+#[test]
+fn test_line_numbers_for_type_signatures() {
+    let repo = TestRepo::with_git();
+    repo.add_file("lib.rs", "use std::io;\n\npub fn run() {}\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) =
+            run_fruit(repo.path(), &["-t", "--line-numbers", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("pub fn run() :3"), "{}", stdout);
+
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["-t", "-j", jobs]);
+        assert!(success);
+        assert!(!stdout.contains(":3"), "{}", stdout);
+    }
+}
+
+#[test]
+fn test_line_numbers_json() {
+    let repo = TestRepo::with_git();
+    repo.add_file("lib.rs", "use std::io;\n\npub fn run() {}\n");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--json", "-t", "--line-numbers"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let file = &json["children"][0];
+    assert_eq!(file["types"][0]["signature"], "pub fn run()");
+    assert_eq!(file["types"][0]["line"], 3);
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--json", "-t"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["children"][0]["types"][0], "pub fn run()");
+}
+
+// ============================================================================
+// --context-lines Flag Tests
+// ============================================================================
+
+#[test]
+fn test_context_lines_after_type_signatures() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "lib.rs",
+        "pub fn run() {\n    let answer = 42;\n    println!(\"{}\", answer);\n}\n",
+    );
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) =
+            run_fruit(repo.path(), &["-t", "--context-lines", "1", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("pub fn run()"), "{}", stdout);
+        assert!(stdout.contains("let answer = 42;"), "{}", stdout);
+        assert!(!stdout.contains("println!"), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) =
+        run_fruit(repo.path(), &["--json", "-t", "--context-lines", "2"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let sig = &json["children"][0]["types"][0];
+    assert_eq!(sig["signature"], "pub fn run()");
+    assert_eq!(sig["context"][0], "    let answer = 42;");
+    assert_eq!(sig["context"].as_array().unwrap().len(), 2);
+    assert!(sig.get("line").is_none());
+}
+
+// ============================================================================
+// --imports-from Flag Tests
+// ============================================================================
+
+#[test]
+fn test_imports_from_filters_files() {
+    let repo = TestRepo::with_git();
+    repo.add_file("uses_serde.rs", "use serde::Serialize;\n");
+    repo.add_file("uses_clap.rs", "use clap::Parser;\n");
+    repo.add_file("src/nested.rs", "use serde_json::Value;\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) =
+            run_fruit(repo.path(), &["--imports-from", "serde", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("uses_serde.rs"), "{}", stdout);
+        assert!(stdout.contains("nested.rs"), "{}", stdout);
+        assert!(!stdout.contains("uses_clap.rs"), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--json", "--imports-from", "clap"]);
+    assert!(success);
+    assert!(stdout.contains("uses_clap.rs"), "{}", stdout);
+    assert!(!stdout.contains("uses_serde.rs"), "{}", stdout);
+}
+
+#[test]
+fn test_imports_only_filters_files() {
+    let repo = TestRepo::with_git();
+    repo.add_file("uses_serde.rs", "use serde::Serialize;\n");
+    repo.add_file("plain.rs", "fn main() {}\n");
+    repo.add_file("src/uses_std.rs", "use std::io;\n");
+    repo.add_file("src/notes.txt", "no imports here\n");
+    repo.add_file("docs/guide/intro.rs", "fn intro() {}\n");
+    repo.add_file("docs/readme.txt", "no imports either\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--imports-only", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("uses_serde.rs"), "{}", stdout);
+        assert!(stdout.contains("uses_std.rs"), "{}", stdout);
+        assert!(!stdout.contains("plain.rs"), "{}", stdout);
+        assert!(!stdout.contains("notes.txt"), "{}", stdout);
+        assert!(!stdout.contains("docs"), "{}", stdout);
+        assert!(!stdout.contains("guide"), "{}", stdout);
+        assert!(stdout.contains("1 directories, 2 files"), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--json", "--imports-only"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let names: Vec<&str> = json["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["src", "uses_serde.rs"]);
+
+    let (stdout, _stderr, success) = run_fruit(
+        repo.path(),
+        &["--json", "--imports-only", "--imports-from", "serde"],
+    );
+    assert!(success);
+    assert!(stdout.contains("uses_serde.rs"), "{}", stdout);
+    assert!(!stdout.contains("uses_std.rs"), "{}", stdout);
+    assert!(!stdout.contains("plain.rs"), "{}", stdout);
+}
+
+#[test]
+fn test_imports_from_ignore_case() {
+    let repo = TestRepo::with_git();
+    repo.add_file("uses_serde.rs", "use serde::Serialize;\n");
+    repo.add_file("uses_clap.rs", "use clap::Parser;\n");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--imports-from", "Serde"]);
+    assert!(success);
+    assert!(!stdout.contains("uses_serde.rs"), "{}", stdout);
+
+    let (stdout, _stderr, success) =
+        run_fruit(repo.path(), &["--imports-from", "Serde", "--ignore-case"]);
+    assert!(success);
+    assert!(stdout.contains("uses_serde.rs"), "{}", stdout);
+    assert!(!stdout.contains("uses_clap.rs"), "{}", stdout);
+}
+
+// ============================================================================
+// --since Flag Tests
+// ============================================================================
+
+#[test]
+fn test_since_shows_only_changed_files() {
+    let repo = TestRepo::with_git();
+    repo.add_file("old.rs", "fn old() {}\n");
+    repo.add_file("edited.rs", "fn edited() {}\n");
+    repo.commit("Initial commit");
+    repo.add_file("edited.rs", "fn edited() { todo!() }\n");
+    repo.add_file("src/added.rs", "fn added() {}\n");
+    repo.commit("Second commit");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--since", "HEAD~1", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("edited.rs"), "{}", stdout);
+        assert!(stdout.contains("added.rs"), "{}", stdout);
+        assert!(!stdout.contains("old.rs"), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--json", "--since", "HEAD~1"]);
+    assert!(success);
+    assert!(
+        stdout.contains("added.rs") && !stdout.contains("old.rs"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_since_invalid_revision_fails() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.rs", "fn main() {}\n");
+    repo.commit("Initial commit");
+
+    let (_stdout, stderr, success) = run_fruit(repo.path(), &["--since", "no-such-rev"]);
+    assert!(!success);
+    assert!(stderr.contains("invalid --since revision"), "{}", stderr);
+}
+
+#[test]
+fn test_since_outside_git_shows_all_files() {
+    let dir = TestRepo::new();
+    dir.add_file("main.rs", "fn main() {}\n");
+
+    let (stdout, _stderr, success) = run_fruit(dir.path(), &["--since", "HEAD~1"]);
+    assert!(success);
+    assert!(stdout.contains("main.rs"), "{}", stdout);
+}
+
+// ============================================================================
+// --separator Flag Tests
+// ============================================================================
+
+#[test]
+fn test_separator_between_top_level_dirs() {
+    let repo = TestRepo::with_git();
+    repo.add_file("a/one.rs", "//! One\n");
+    repo.add_file("b/two.rs", "//! Two\n");
+    repo.add_file("top.rs", "//! Top\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--separator", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("one.rs  One\n\n├── b"), "{}", stdout);
+        assert!(stdout.contains("two.rs  Two\n\n└── top.rs"), "{}", stdout);
+
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("one.rs  One\n├── b"), "{}", stdout);
+    }
+}
+
+#[test]
+fn test_separator_markdown_rule() {
+    let repo = TestRepo::with_git();
+    repo.add_file("a/one.rs", "//! One\n");
+    repo.add_file("b/two.rs", "//! Two\n");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--separator", "--markdown"]);
+    assert!(success);
+    assert_eq!(stdout.matches("\n---\n").count(), 1, "{}", stdout);
+    assert!(stdout.contains("One\n\n---\n\n"), "{}", stdout);
+}
+
+#[test]
+fn test_separator_files_only_has_no_empty_lines() {
+    let repo = TestRepo::with_git();
+    repo.add_file("a/one.rs", "//! One\n");
+    repo.add_file("b/two.rs", "//! Two\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) =
+            run_fruit(repo.path(), &["--separator", "--files-only", "-j", jobs]);
+        assert!(success);
+        assert!(!stdout.is_empty());
+        assert!(stdout.lines().all(|line| !line.is_empty()), "{:?}", stdout);
+    }
+}
+
+#[test]
+fn test_separator_org_buffers_section_break() {
+    let repo = TestRepo::with_git();
+    repo.add_file("a/one.rs", "//! One\n");
+    repo.add_file("b/two.rs", "//! Two\n");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--separator", "--org"]);
+    assert!(success);
+    assert!(!stdout.starts_with('\n'), "{:?}", stdout);
+    assert!(stdout.contains("One\n\n** =b/="), "{:?}", stdout);
+}
+
+// ============================================================================
+// --relative-to Flag Tests
+// ============================================================================
+
+#[test]
+fn test_relative_to_rebases_paths() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/nested/lib.rs", "//! Library\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(
+            repo.path(),
+            &["src/nested", "--relative-to", ".", "-j", jobs],
+        );
+        assert!(success);
+        assert!(stdout.starts_with("src/nested\n"), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) =
+        run_fruit(repo.path(), &["src/nested", "--relative-to", ".", "--json"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["path"], "src/nested");
+    assert_eq!(json["children"][0]["path"], "src/nested/lib.rs");
+}
+
+#[test]
+fn test_relative_to_outside_base_is_absolute() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/lib.rs", "//! Library\n");
+    repo.add_file("docs/guide.md", "# Guide\n");
+    let root = repo.path().canonicalize().unwrap();
+
+    let (stdout, _stderr, success) =
+        run_fruit(repo.path(), &["src", "--relative-to", "docs", "--json"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["path"], root.join("src").to_string_lossy().as_ref());
+}
+
+// ============================================================================
+// --relative-imports-only Tests
+// ============================================================================
+
+#[test]
+fn test_relative_imports_only() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "src/lib.rs",
+        "use serde::Serialize;\nuse std::io;\nuse crate::tree::Node;\n",
+    );
+    repo.add_file("src/main.rs", "use clap::Parser;\n");
+    repo.commit("init");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) =
+            run_fruit(repo.path(), &["-i", "--relative-imports-only", "-j", jobs]);
+        assert!(success);
+        assert!(
+            stdout.contains("imports: crate::{tree::Node}"),
+            "{}",
+            stdout
+        );
+        assert!(!stdout.contains("serde"), "{}", stdout);
+        assert!(!stdout.contains("clap"), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) =
+        run_fruit(repo.path(), &["-i", "--relative-imports-only", "--json"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let lib = &json["children"][0]["children"][0];
+    assert_eq!(lib["name"], "lib.rs");
+    assert_eq!(lib["imports"]["external"][0], "serde");
+    assert_eq!(lib["imports"]["filtered"], true);
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["-i", "--json"]);
+    assert!(success);
+    assert!(!stdout.contains("filtered"), "{}", stdout);
+}
+
+// ============================================================================
+// --json-schema Tests
+// ============================================================================
+
+#[test]
+fn test_json_schema_flag() {
+    let repo = TestRepo::new();
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--json-schema"]);
+    assert!(success);
+    let schema: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(schema["$schema"], "http://json-schema.org/draft-07/schema#");
+    for definition in ["dir", "file", "todo", "imports", "type_signature"] {
+        assert!(
+            schema["definitions"][definition].is_object(),
+            "{}",
+            definition
+        );
+    }
+    assert_eq!(
+        schema["definitions"]["file"]["properties"]["type"]["const"],
+        "file"
+    );
+}
+
+// ============================================================================
+// FRUIT_DEBUG_TIMING Tests
+// ============================================================================
+
+#[test]
+fn test_debug_timing_reported_on_stderr() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.rs", "//! Entry point\nfn main() {}\n");
+    repo.commit("init");
+
+    for jobs in ["1", "0"] {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_fruit"))
+            .args(["-j", jobs])
+            .current_dir(repo.path())
+            .env("FRUIT_DEBUG_TIMING", "1")
+            .output()
+            .expect("Failed to run fruit");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success());
+        assert!(!stdout.contains("Timing"), "{}", stdout);
+        assert!(stderr.starts_with("# Timing: walk="), "{}", stderr);
+        assert!(stderr.contains(" extract=") && stderr.contains(" output="));
+    }
+
+    let (_stdout, stderr, success) = run_fruit(repo.path(), &[]);
+    assert!(success);
+    assert!(!stderr.contains("Timing"), "{}", stderr);
+}
+
+// ============================================================================
+// Rust Annotation Marker Tests
+// ============================================================================
+
+#[test]
+fn test_rust_safety_annotations_and_todos_types() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "src/lib.rs",
+        "// SAFETY: the slice is never empty\n// TODO: add bounds checks\n",
+    );
+    repo.add_file("src/other.rs", "// FIXME: later\n");
+    repo.commit("init");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--todos", "--full-comment"]);
+    assert!(success);
+    assert!(
+        stdout.contains("SAFETY: the slice is never empty"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("TODO: add bounds checks"), "{}", stdout);
+
+    let (stdout, _stderr, success) = run_fruit(
+        repo.path(),
+        &[
+            "--todos",
+            "--todos-only",
+            "--todos-types",
+            "SAFETY",
+            "--full-comment",
+        ],
+    );
+    assert!(success);
+    assert!(stdout.contains("SAFETY: the slice"), "{}", stdout);
+    assert!(!stdout.contains("add bounds checks"), "{}", stdout);
+    assert!(!stdout.contains("other.rs"), "{}", stdout);
+
+    // Annotations are blue, TODOs yellow
+    let stdout = run_fruit_with_color_env(
+        repo.path(),
+        &[("TERM", "xterm")],
+        &["--todos", "--full-comment", "--color", "always"],
+    );
+    assert!(stdout.contains("\x1b[34mSAFETY"), "{:?}", stdout);
+    assert!(stdout.contains("\x1b[33mTODO"), "{:?}", stdout);
+}
+
+// ============================================================================
+// --summary-only Tests
+// ============================================================================
+
+#[test]
+fn test_summary_only_prints_counts_line() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/main.rs", "//! Entry point\nfn main() {}\n");
+    repo.add_file("src/lib.rs", "\n");
+    repo.add_file("README.md", "# Readme\n");
+    repo.commit("init");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--summary-only", "-j", jobs]);
+        assert!(success);
+        assert_eq!(stdout, "1 directories, 3 files\n");
+    }
+
+    let (_stdout, stderr, success) = run_fruit(repo.path(), &["--summary-only", "--json"]);
+    assert!(!success);
+    assert!(stderr.contains("cannot be used with"), "{}", stderr);
+}
+
+// ============================================================================
+// --ascii Tests
+// ============================================================================
+
+#[test]
+fn test_ascii_draws_plain_tree() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/main.rs", "//! Entry point\nfn main() {}\n");
+    repo.add_file("README.md", "# Readme\n");
+    repo.commit("init");
+
+    for jobs in ["1", "0"] {
+        let stdout = run_fruit_with_color_env(
+            repo.path(),
+            &[("TERM", "xterm")],
+            &["--ascii", "--color", "always", "-j", jobs],
+        );
+        assert!(stdout.is_ascii(), "{}", stdout);
+        assert!(stdout.contains("|-- README.md"), "{}", stdout);
+        assert!(
+            stdout.contains("`-- src\n    `-- main.rs  Entry point"),
+            "{}",
+            stdout
+        );
+        assert!(stdout.contains("1 directories, 2 files"), "{}", stdout);
+    }
+}
+
+#[test]
+fn test_dumb_terminal_uses_ascii_tree() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/main.rs", "fn main() {}\n");
+    repo.commit("init");
+
+    let dumb = run_fruit_with_color_env(repo.path(), &[("TERM", "dumb")], &[]);
+    assert!(dumb.contains("`-- src"), "{}", dumb);
+    assert!(dumb.is_ascii(), "{}", dumb);
+
+    // --color never only drops the colors
+    let never = run_fruit_with_color_env(repo.path(), &[], &["--color", "never"]);
+    assert!(never.contains("└── src"), "{}", never);
+}
+
+// ============================================================================
+// --list-languages Tests
+// ============================================================================
+
+#[test]
+fn test_list_languages_table() {
+    let repo = TestRepo::new();
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--list-languages"]);
+    assert!(success);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[0].starts_with("Language"), "{}", stdout);
+    assert!(
+        lines[0].ends_with("Comments  Types  Imports  TODOs"),
+        "{}",
+        stdout
+    );
+
+    let rust = lines.iter().find(|line| line.starts_with("Rust ")).unwrap();
+    assert!(rust.contains(".rs"), "{}", rust);
+    assert_eq!(rust.matches('✓').count(), 4, "{}", rust);
+    let lua = lines.iter().find(|line| line.starts_with("Lua ")).unwrap();
+    assert_eq!(lua.matches('✓').count(), 1, "{}", lua);
+    assert!(
+        lines.iter().any(|line| line.starts_with("Nix ")),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("directories"), "{}", stdout);
+}
+
+// ============================================================================
+// --skip-generated Tests
+// ============================================================================
+
+#[test]
+fn test_skip_generated_lists_files_without_metadata() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "api.pb.go",
+        "// Code generated by protoc-gen-go. DO NOT EDIT.\n\n// Package api holds messages\npackage api\n",
+    );
+    repo.add_file("main.go", "// Package main runs the server\npackage main\n");
+    repo.commit("init");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &[]);
+    assert!(success);
+    assert!(stdout.contains("Code generated"), "{}", stdout);
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--skip-generated", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("api.pb.go\n"), "{}", stdout);
+        assert!(!stdout.contains("Code generated"), "{}", stdout);
+        assert!(
+            stdout.contains("Package main runs the server"),
+            "{}",
+            stdout
+        );
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--skip-generated", "--json"]);
+    assert!(success);
+    assert!(stdout.contains("api.pb.go"), "{}", stdout);
+    assert!(!stdout.contains("Code generated"), "{}", stdout);
+
+    // Generated files are dimmed
+    let stdout = run_fruit_with_color_env(
+        repo.path(),
+        &[("TERM", "xterm")],
+        &["--skip-generated", "--color", "always", "-j", "1"],
+    );
+    assert!(stdout.contains("\x1b[2m"), "{:?}", stdout);
+}
+
+// ============================================================================
+// --format-size Tests
+// ============================================================================
+
+#[test]
+fn test_format_size_styles() {
+    let repo = TestRepo::with_git();
+    repo.add_file("data.txt", &"x".repeat(1536));
+    repo.commit("init");
+
+    for (style, expected) in [("binary", "[1.5K]"), ("si", "[1.5kB]"), ("bytes", "[1536]")] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["-s", "--format-size", style]);
+        assert!(success);
+        assert!(stdout.contains(expected), "{}: {}", style, stdout);
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["-s", "--format-size", "none"]);
+    assert!(success);
+    assert!(!stdout.contains("1.5"), "{}", stdout);
+    assert!(!stdout.contains("1536"), "{}", stdout);
+
+    let (stdout, _stderr, success) =
+        run_fruit(repo.path(), &["-s", "--json", "--format-size", "si"]);
+    assert!(success);
+    assert!(stdout.contains("\"size_human\": \"1.5kB\""), "{}", stdout);
+}
+
+// ============================================================================
+// --compact Tests
+// ============================================================================
+
+#[test]
+fn test_compact_packs_files_without_metadata() {
+    let repo = TestRepo::with_git();
+    for name in ["a.rs", "b.rs", "c.rs", "d.txt", "e.txt"] {
+        repo.add_file(name, "x\n");
+    }
+    repo.add_file("main.rs", "//! Entry point\nfn main() {}\n");
+    repo.commit("init");
+
+    for jobs in ["1", "0"] {
+        let stdout = run_fruit_with_color_env(
+            repo.path(),
+            &[("COLUMNS", "30")],
+            &["--compact", "--color", "never", "-j", jobs],
+        );
+        assert!(
+            stdout.contains("├── a.rs · b.rs · c.rs · d.txt\n├── e.txt\n"),
+            "{}",
+            stdout
+        );
+        assert!(stdout.contains("└── main.rs  Entry point"), "{}", stdout);
+        assert!(stdout.contains("6 files"), "{}", stdout);
+    }
+}
+
+// ============================================================================
+// --max-imports Tests
+// ============================================================================
+
+#[test]
+fn test_max_imports_truncates_with_count() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "main.rs",
+        "use std::io;\nuse std::fs;\nuse serde::Serialize;\nuse clap::Parser;\nuse crate::git;\nfn main() {}\n",
+    );
+    repo.commit("init");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) =
+            run_fruit(repo.path(), &["-i", "--max-imports", "3", "-j", jobs]);
+        assert!(success);
+        assert!(
+            stdout.contains("imports: serde, clap, std::{io}\n"),
+            "{}",
+            stdout
+        );
+        assert!(stdout.contains("... and 2 more"), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["-i", "--max-imports", "5"]);
+    assert!(success);
+    assert!(!stdout.contains("more"), "{}", stdout);
+
+    let (stdout, _stderr, success) =
+        run_fruit(repo.path(), &["-i", "--max-imports", "3", "--json"]);
+    assert!(success, "--max-imports should work with --json");
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let imports = &json["children"][0]["imports"];
+    assert_eq!(imports["external"], serde_json::json!(["serde", "clap"]));
+    assert_eq!(imports["std"], serde_json::json!(["io"]));
+    assert!(imports.get("internal").is_none(), "{}", stdout);
+}
+
+// ============================================================================
+// Binary File Tests
+// ============================================================================
+
+#[test]
+fn test_binary_files_listed_dimmed_without_metadata() {
+    let repo = TestRepo::with_git();
+    repo.add_file("logo.png", "# not a comment\n");
+    repo.add_file("notes.txt", "# Notes\n");
+    repo.commit("init");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--generic-comments"]);
+    assert!(success);
+    assert!(stdout.contains("logo.png\n"), "{}", stdout);
+    assert!(stdout.contains("Notes"), "{}", stdout);
+    assert!(!stdout.contains("not a comment"), "{}", stdout);
+
+    let stdout =
+        run_fruit_with_color_env(repo.path(), &[("TERM", "xterm")], &["--color", "always"]);
+    assert!(stdout.contains("\x1b[2m\x1b[37mlogo.png"), "{:?}", stdout);
+    assert!(!stdout.contains("\x1b[2m\x1b[37mnotes.txt"), "{:?}", stdout);
+}
+
+// ============================================================================
+// --ansi-only and CLICOLOR Tests
+// ============================================================================
+
+/// Run fruit with the color environment variables cleared, then `env` set.
+fn run_fruit_with_color_env(dir: &std::path::Path, env: &[(&str, &str)], args: &[&str]) -> String {
+    let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_fruit"));
+    command.args(args).current_dir(dir);
+    for name in [
+        "NO_COLOR",
+        "FORCE_COLOR",
+        "CLICOLOR",
+        "CLICOLOR_FORCE",
+        "TERM",
+    ] {
+        command.env_remove(name);
+    }
+    command.envs(env.iter().copied());
+    let output = command.output().expect("Failed to run fruit");
+    assert!(output.status.success());
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_ansi_only_writes_escape_codes_for_undetected_terminals() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.rs", "//! Entry point\nfn main() {}");
+    repo.commit("init");
+
+    let dumb = [("TERM", "dumb")];
+    let stdout = run_fruit_with_color_env(repo.path(), &dumb, &["--color", "always"]);
+    assert!(!stdout.contains('\x1b'), "{}", stdout);
+    let stdout =
+        run_fruit_with_color_env(repo.path(), &dumb, &["--color", "always", "--ansi-only"]);
+    assert!(stdout.contains("\x1b["), "{}", stdout);
+    let stdout = run_fruit_with_color_env(repo.path(), &dumb, &["--ansi-only", "--color", "never"]);
+    assert!(!stdout.contains('\x1b'), "{}", stdout);
+}
+
+#[test]
+fn test_clicolor_environment_variables() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.rs", "//! Entry point\nfn main() {}");
+    repo.commit("init");
+
+    // Output is piped, so only CLICOLOR_FORCE turns colors on
+    let xterm = ("TERM", "xterm");
+    let stdout = run_fruit_with_color_env(repo.path(), &[xterm], &[]);
+    assert!(!stdout.contains('\x1b'), "{}", stdout);
+    let stdout = run_fruit_with_color_env(repo.path(), &[xterm, ("CLICOLOR_FORCE", "1")], &[]);
+    assert!(stdout.contains("\x1b["), "{}", stdout);
+    let stdout = run_fruit_with_color_env(repo.path(), &[xterm, ("CLICOLOR_FORCE", "0")], &[]);
+    assert!(!stdout.contains('\x1b'), "{}", stdout);
+    let stdout = run_fruit_with_color_env(
+        repo.path(),
+        &[xterm, ("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")],
+        &[],
+    );
+    assert!(!stdout.contains('\x1b'), "{}", stdout);
+    // An empty NO_COLOR is the same as an unset one
+    let stdout = run_fruit_with_color_env(
+        repo.path(),
+        &[xterm, ("CLICOLOR_FORCE", "1"), ("NO_COLOR", "")],
+        &["--ansi-only"],
+    );
+    assert!(stdout.contains("\x1b["), "{}", stdout);
+}
+
+// ============================================================================
+// --analysis-graph Tests
+// ============================================================================
+
+#[test]
+fn test_stats_analysis_graph_load_order_and_components() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.py", "from .a import run\n");
+    repo.add_file("a.py", "from .b import helper\n");
+    repo.add_file("b.py", "HELPER = 1\n");
+    repo.commit("init");
+
+    let (stdout, _stderr, success) =
+        run_fruit(repo.path(), &["--stats", "--imports", "--analysis-graph"]);
+    assert!(success);
+    assert!(
+        stdout.contains("Load order:\n       1  b.py\n       2  a.py\n       3  main.py\n"),
+        "{}",
+        stdout
+    );
+
+    repo.add_file("b.py", "from .a import run\n");
+    let (stdout, _stderr, success) = run_fruit(
+        repo.path(),
+        &["--stats", "--imports", "--analysis-graph", "--json"],
+    );
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert!(json["import_graph"]["load_order"].is_null());
+    assert_eq!(
+        json["import_graph"]["components"],
+        serde_json::json!([["a.py", "b.py"]])
+    );
+}
+
+// ============================================================================
+// --dirs-only Count Tests
+// ============================================================================
+
+/// Directories below the root of a `--json` tree.
+fn count_json_dirs(node: &serde_json::Value) -> usize {
+    node["children"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|child| child["type"] == "dir")
+        .map(|child| 1 + count_json_dirs(child))
+        .sum()
+}
+
+#[test]
+fn test_dirs_only_count_matches_json() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/lib.rs", "pub fn run() {}\n");
+    repo.add_file("src/tree/walker.rs", "pub fn walk() {}\n");
+    repo.add_file("docs/guide/intro.md", "# Intro\n");
+    repo.add_file("README.md", "# Project\n");
+    repo.commit("init");
+    std::fs::create_dir_all(repo.path().join("empty/nested")).unwrap();
+
+    for args in [&["-d"][..], &["-d", "-L", "1"], &["--show-empty-dirs"]] {
+        let (json_out, stderr, success) = run_fruit(repo.path(), &[args, &["--json"]].concat());
+        assert!(success, "stderr: {}", stderr);
+        let json: serde_json::Value = serde_json::from_str(&json_out).unwrap();
+        let expected = format!("{} directories", count_json_dirs(&json));
+
+        for jobs in ["1", "0"] {
+            let (stdout, stderr, success) = run_fruit(repo.path(), &[args, &["-j", jobs]].concat());
+            assert!(success, "stderr: {}", stderr);
+            let summary = stdout.lines().last().unwrap();
+            assert!(
+                summary.starts_with(&expected),
+                "{:?} -j {}: expected '{}', got '{}'",
+                args,
+                jobs,
+                expected,
+                summary
+            );
+        }
+    }
+}
+
+// ============================================================================
+// --template Tests
+// ============================================================================
+
+#[cfg(feature = "templates")]
+#[test]
+fn test_template_renders_tree() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/lib.rs", "//! Library root\npub fn run() {}\n");
+    repo.add_file(
+        "layout.tera",
+        "{% for child in tree.children %}{{ child.type }} {{ child.name }}\n{% endfor %}",
+    );
+    repo.commit("init");
+
+    let template = repo.path().join("layout.tera");
+    let (stdout, stderr, success) =
+        run_fruit(repo.path(), &["--template", template.to_str().unwrap()]);
+    assert!(success, "stderr: {}", stderr);
+    assert_eq!(stdout, "file layout.tera\ndir src\n");
+
+    // The example templates shipped with fruit render too
+    let examples = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("templates");
+    for example in ["tree.txt.tera", "context.md.tera", "summary.json.tera"] {
+        let path = examples.join(example);
+        let (stdout, stderr, success) = run_fruit(
+            repo.path(),
+            &["-c", "-t", "--template", path.to_str().unwrap()],
+        );
+        assert!(success, "{}: {}", example, stderr);
+        assert!(stdout.contains("src"), "{}: {}", example, stdout);
+    }
+}
+
+#[cfg(feature = "templates")]
+#[test]
+fn test_template_reports_errors() {
+    let repo = TestRepo::new();
+    repo.add_file("broken.tera", "{% for %}");
+
+    let (_stdout, stderr, success) = run_fruit(repo.path(), &["--template", "broken.tera"]);
+    assert!(!success);
+    assert!(stderr.contains("invalid template"), "{}", stderr);
+}
+
+#[cfg(not(feature = "templates"))]
+#[test]
+fn test_template_requires_feature() {
+    let repo = TestRepo::new();
+    repo.add_file("layout.tera", "{{ tree.name }}");
+
+    let (_stdout, stderr, success) = run_fruit(repo.path(), &["--template", "layout.tera"]);
+    assert!(!success);
+    assert!(stderr.contains("`templates` feature"), "{}", stderr);
+}
+
+// ============================================================================
+// --last-commit Tests
+// ============================================================================
+
+#[test]
+fn test_last_commit_shown_inline() {
+    let repo = TestRepo::with_git();
+    repo.add_file("auth.rs", "//! Authentication\npub fn login() {}\n");
+    repo.add_file("main.rs", "fn main() {}\n");
+    repo.commit("Initial commit");
+    repo.add_file(
+        "auth.rs",
+        "//! Authentication\npub fn login() -> bool { true }\n",
+    );
+    repo.commit("Fix bug in auth");
+
+    for jobs in ["1", "0"] {
+        let (stdout, stderr, success) = run_fruit(repo.path(), &["--last-commit", "-j", jobs]);
+        assert!(success, "stderr: {}", stderr);
+        let auth = stdout.lines().find(|l| l.contains("auth.rs")).unwrap();
+        assert!(auth.contains(" Fix bug in auth"), "{}", stdout);
+        let main = stdout.lines().find(|l| l.contains("main.rs")).unwrap();
+        assert!(main.contains(" Initial commit"), "{}", stdout);
+    }
+}
+
+#[test]
+fn test_last_commit_outside_repository() {
+    let dir = TestRepo::new();
+    dir.add_file("main.rs", "fn main() {}\n");
+
+    let (stdout, stderr, success) = run_fruit(dir.path(), &["--last-commit"]);
+    assert!(success, "stderr: {}", stderr);
+    assert!(stdout.contains("main.rs"));
+}
+
+// ============================================================================
+// --profile Tests
+// ============================================================================
+
+/// Run fruit with its config directory redirected to `config_home`.
+fn run_fruit_with_config(
+    dir: &std::path::Path,
+    config_home: &std::path::Path,
+    args: &[&str],
+) -> (String, String, bool) {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_fruit"))
+        .args(args)
+        .current_dir(dir)
+        .env("XDG_CONFIG_HOME", config_home)
+        .output()
+        .expect("Failed to run fruit");
+    (
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+        output.status.success(),
+    )
+}
+
+#[test]
+fn test_profiles_save_list_and_apply() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/lib.rs", "//! Library root\npub fn run() {}\n");
+    repo.commit("init");
+    let config_home = tempfile::TempDir::new().unwrap();
+    let profiles_file = config_home.path().join("fruit").join("profiles.toml");
+
+    let (_stdout, stderr, success) = run_fruit_with_config(
+        repo.path(),
+        config_home.path(),
+        &["--markdown", "--types", "--save-profile", "docs"],
+    );
+    assert!(success, "{}", stderr);
+    let saved = std::fs::read_to_string(&profiles_file).unwrap();
+    assert!(saved.contains("[docs]\n"), "{}", saved);
+    assert!(saved.contains("types = true\n"), "{}", saved);
+    assert!(saved.contains("markdown = true\n"), "{}", saved);
+
+    let (stdout, _stderr, success) =
+        run_fruit_with_config(repo.path(), config_home.path(), &["--list-profiles"]);
+    assert!(success);
+    assert_eq!(stdout, "docs\n");
+
+    let (stdout, _stderr, success) =
+        run_fruit_with_config(repo.path(), config_home.path(), &["--profile", "docs"]);
+    assert!(success);
+    assert!(stdout.contains("- `lib.rs`"), "{}", stdout);
+    assert!(stdout.contains("pub fn run()"), "{}", stdout);
+
+    // Command-line flags win over conflicting profile flags
+    let (stdout, _stderr, success) = run_fruit_with_config(
+        repo.path(),
+        config_home.path(),
+        &["--profile", "docs", "--json"],
+    );
+    assert!(success);
+    assert!(stdout.trim_start().starts_with('{'), "{}", stdout);
+
+    let (_stdout, stderr, success) =
+        run_fruit_with_config(repo.path(), config_home.path(), &["--profile", "missing"]);
+    assert!(!success);
+    assert!(stderr.contains("no profile 'missing'"), "{}", stderr);
+}
+
+// ============================================================================
+// --org Tests
+// ============================================================================
+
+#[test]
+fn test_org_outputs_outline() {
+    let repo = TestRepo::new();
+    repo.add_file("README.md", "# Project\n");
+    repo.add_file("src/lib.rs", "//! Library root\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--org", "-j", jobs]);
+        assert!(success);
+        assert!(
+            stdout.contains("- =README.md= :: Project\n** =src/=\n"),
+            "{}",
+            stdout
+        );
+        assert!(stdout.contains("- =lib.rs= :: Library root"), "{}", stdout);
+        assert!(!stdout.contains('\x1b'), "{:?}", stdout);
+    }
+
+    let (_stdout, _stderr, success) = run_fruit(repo.path(), &["--org", "--markdown"]);
+    assert!(!success);
+}
+
+// ============================================================================
+// --min-depth Tests
+// ============================================================================
+
+#[test]
+fn test_min_depth_hides_shallow_entries() {
+    let repo = TestRepo::new();
+    repo.add_file("README.md", "# Project\n");
+    repo.add_file("src/lib.rs", "//! Library root\n");
+    repo.add_file("src/tree/walk.rs", "//! Tree walking\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--min-depth", "2", "-j", jobs]);
+        assert!(success);
+        assert!(!stdout.contains("README.md"), "{}", stdout);
+        assert!(stdout.contains("├── src/lib.rs"), "{}", stdout);
+        assert!(stdout.contains("└── src/tree\n"), "{}", stdout);
+        assert!(stdout.contains("    └── walk.rs"), "{}", stdout);
+        assert!(stdout.contains("1 directories, 2 files"), "{}", stdout);
+    }
+
+    // Deeper than --level: nothing left to show
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--min-depth", "3", "-L", "2"]);
+    assert!(success);
+    assert!(stdout.contains("0 directories, 0 files"), "{}", stdout);
+}
+
+// ============================================================================
+// --generic-comments Tests
+// ============================================================================
+
+#[test]
+fn test_generic_comments_for_unknown_extensions() {
+    let repo = TestRepo::new();
+    repo.add_file("pipeline.conf", "# Build pipeline stages\nstage = test\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) =
+            run_fruit(repo.path(), &["--generic-comments", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("Build pipeline stages"), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &[]);
+    assert!(success);
+    assert!(!stdout.contains("Build pipeline stages"), "{}", stdout);
+}
+
+// ============================================================================
+// --no-counts Tests
+// ============================================================================
+
+#[test]
+fn test_no_counts_omits_summary_line() {
+    let repo = TestRepo::new();
+    repo.add_file("main.rs", "//! Entry point\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--no-counts", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("main.rs"), "{}", stdout);
+        assert!(!stdout.contains("files"), "{}", stdout);
+        assert!(!stdout.ends_with("\n\n"), "{:?}", stdout);
+    }
+
+    for flag in ["--no-counts", "--no-totals"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &[flag, "-m"]);
+        assert!(success);
+        assert!(stdout.contains("`main.rs`"), "{}", stdout);
+        assert!(!stdout.contains("directories"), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &[]);
+    assert!(success);
+    assert!(stdout.contains("0 directories, 1 files"), "{}", stdout);
+}
+
+// ============================================================================
+
+#[test]
+fn test_stats_import_graph_reports_cycles_and_orphans() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.py", "from .a import run\n");
+    repo.add_file("a.py", "from .b import helper\n");
+    repo.add_file("b.py", "from .a import run\n");
+    repo.add_file("README.md", "# Project\n");
+    repo.commit("init");
+
+    let (stdout, _stderr, success) =
+        run_fruit(repo.path(), &["--stats", "--imports", "--import-graph"]);
+    assert!(success);
+    assert!(stdout.contains("a.py → b.py → a.py"), "{}", stdout);
+    assert!(
+        stdout.contains("Imported by nothing:\n    main.py\n"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("README.md"), "{}", stdout);
+
+    let (stdout, _stderr, success) = run_fruit(
+        repo.path(),
+        &["--stats", "--imports", "--import-graph", "--json"],
+    );
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["import_graph"]["cycles"][0][0], "a.py");
+    assert_eq!(json["import_graph"]["orphans"][0], "main.py");
+
+    let (_stdout, _stderr, success) = run_fruit(repo.path(), &["--stats", "--import-graph"]);
+    assert!(!success);
+}
+
+// ============================================================================
+// --show-git-ignored Tests
+// ============================================================================
+
+#[test]
+fn test_show_git_ignored_marks_ignored_entries() {
+    let repo = TestRepo::with_git();
+    repo.add_file(".gitignore", "*.log\ntarget/\n");
+    repo.add_file("src/main.rs", "//! Entry point\n");
+    repo.add_untracked("debug.log", "noise\n");
+    repo.add_untracked("target/debug/app", "binary\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) =
+            run_fruit(repo.path(), &["--show-git-ignored", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("debug.log [ignored]"), "{}", stdout);
+        assert!(stdout.contains("target [ignored]"), "{}", stdout);
+        assert!(stdout.contains("main.rs"), "{}", stdout);
+        assert!(!stdout.contains("main.rs [ignored]"), "{}", stdout);
+        // Ignored directories are not expanded, but still counted
+        assert!(!stdout.contains("debug\n"), "{}", stdout);
+        assert!(stdout.contains("2 directories, 3 files"), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--show-git-ignored", "-m"]);
+    assert!(success);
+    assert!(stdout.contains("`debug.log` [ignored]"), "{}", stdout);
+    assert!(stdout.contains("**target/** [ignored]"), "{}", stdout);
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &[]);
+    assert!(success);
+    assert!(!stdout.contains("debug.log"), "{}", stdout);
+    assert!(!stdout.contains("[ignored]"), "{}", stdout);
+}
+
+#[test]
+fn test_tracked_build_directory_is_shown() {
+    let repo = TestRepo::with_git();
+    repo.add_file("build/make.sh", "# Build script\n");
+    repo.add_file("src/main.rs", "//! Entry point\n");
+    repo.commit("Initial commit");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("make.sh"), "{}", stdout);
+        assert!(stdout.contains("main.rs"), "{}", stdout);
+
+        let (stdout, _stderr, success) =
+            run_fruit(repo.path(), &["--show-git-ignored", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("build"), "{}", stdout);
+        assert!(!stdout.contains("[ignored]"), "{}", stdout);
+    }
+}
+
+#[test]
+fn test_skip_build_dirs_without_gitignore() {
+    let repo = TestRepo::with_git();
+    repo.add_file("node_modules/left-pad/index.js", "// Pads strings\n");
+    repo.add_file("src/main.rs", "//! Entry point\n");
+    repo.commit("Initial commit");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["-j", jobs]);
+        assert!(success);
+        assert!(stdout.contains("node_modules"), "{}", stdout);
+
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--skip-build-dirs", "-j", jobs]);
+        assert!(success);
+        assert!(!stdout.contains("node_modules"), "{}", stdout);
+        assert!(!stdout.contains("index.js"), "{}", stdout);
+        assert!(stdout.contains("main.rs"), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--skip-build-dirs", "--json"]);
+    assert!(success);
+    assert!(!stdout.contains("node_modules"), "{}", stdout);
+}
+
+// ============================================================================
+// --imports-depth Tests
+// ============================================================================
+
+#[test]
+fn test_imports_depth_shows_transitive_imports() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "src/app.ts",
+        "import React from 'react';\nimport { get } from './api';\n",
+    );
+    repo.add_file(
+        "src/api.ts",
+        "import axios from 'axios';\nimport { send } from './http';\n",
+    );
+    repo.add_file(
+        "src/http.ts",
+        "import fetch from 'node-fetch';\nimport { get } from './api';\n",
+    );
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(
+            repo.path(),
+            &["--imports", "--imports-depth", "1", "-j", jobs],
+        );
+        assert!(success);
+        assert!(stdout.contains("→ transitive: axios"), "{}", stdout);
+        assert!(!stdout.contains("node-fetch, axios"), "{}", stdout);
+
+        // Depth 2 reaches http.ts; the api <-> http cycle does not loop forever
+        let (stdout, _stderr, success) = run_fruit(
+            repo.path(),
+            &["--imports", "--imports-depth", "2", "-j", jobs],
+        );
+        assert!(success);
+        assert!(
+            stdout.contains("→ transitive: axios, node-fetch"),
+            "{}",
+            stdout
+        );
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--imports"]);
+    assert!(success);
+    assert!(!stdout.contains("transitive"), "{}", stdout);
+}
+
+#[test]
+fn test_imports_depth_rejected_with_full_tree_output() {
+    let repo = TestRepo::new();
+    repo.add_file("app.ts", "import { get } from './api';\n");
+
+    for format in ["--json", "--dot"] {
+        let (_stdout, stderr, success) =
+            run_fruit(repo.path(), &["--imports", "--imports-depth", "1", format]);
+        assert!(!success, "{} should conflict with --imports-depth", format);
+        assert!(stderr.contains("cannot be used with"), "{}", stderr);
+    }
+}
+
+// ============================================================================
+// --lang Flag Tests
+// ============================================================================
+
+#[test]
+fn test_lang_limits_extraction() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/main.rs", "//! Rust entry point\nfn main() {}\n");
+    repo.add_file("tools/build.py", "\"\"\"Python build script\"\"\"\n");
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--lang", "rust", "-j", jobs]);
+        assert!(success);
+        assert!(
+            stdout.contains("Rust entry point"),
+            "-j {}: {}",
+            jobs,
+            stdout
+        );
+        assert!(stdout.contains("build.py"), "-j {}: {}", jobs, stdout);
+        assert!(
+            !stdout.contains("Python build script"),
+            "-j {}: {}",
+            jobs,
+            stdout
+        );
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--lang", "rust,python"]);
+    assert!(success);
+    assert!(stdout.contains("Python build script"), "{}", stdout);
+}
+
+#[test]
+fn test_lang_rejects_unknown_language() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.rs", "fn main() {}\n");
+
+    let (_stdout, stderr, success) = run_fruit(repo.path(), &["--lang", "cobol"]);
+    assert!(!success);
+    assert!(stderr.contains("unknown language 'cobol'"), "{}", stderr);
+}
+
+// ============================================================================
+// --absolute Flag Tests
+// ============================================================================
+
+#[test]
+fn test_absolute_paths() {
+    let repo = TestRepo::with_git();
+    repo.add_file("src/main.rs", "fn main() {}\n");
+    let root = repo.path().canonicalize().unwrap();
+    let root = root.to_string_lossy();
+
+    for jobs in ["1", "0"] {
+        let (stdout, _stderr, success) = run_fruit(repo.path(), &["--absolute", "-j", jobs]);
+        assert!(success);
+        assert!(stdout.starts_with(&format!("{}\n", root)), "{}", stdout);
+    }
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--absolute", "--files-only"]);
+    assert!(success);
+    assert_eq!(stdout, format!("{}/src/main.rs\n", root));
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--absolute", "--json"]);
+    assert!(success);
+    assert!(
+        stdout.contains(&format!("\"path\": \"{}/src/main.rs\"", root)),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_absolute_conflicts_with_prefix_path() {
+    let repo = TestRepo::with_git();
+    repo.add_file("main.rs", "fn main() {}\n");
+
+    let (_stdout, stderr, success) =
+        run_fruit(repo.path(), &["--absolute", "--prefix-path", "repo"]);
+    assert!(!success);
+    assert!(stderr.contains("cannot be used with"), "{}", stderr);
+}
+
+// ============================================================================
+// --stats-todos Flag Tests
+// ============================================================================
+
+#[test]
+fn test_stats_todos_lists_top_files() {
+    let repo = TestRepo::with_git();
+    repo.add_file(
+        "src/busy.rs",
+        "// TODO: one\n// FIXME: two\n// TODO: three\n",
+    );
+    repo.add_file("src/calm.rs", "// TODO: only one\n");
+    repo.add_file("src/clean.rs", "fn clean() {}\n");
+
+    let (stdout, _stderr, success) = run_fruit(repo.path(), &["--stats", "--stats-todos"]);
+    assert!(success);
+    let section = stdout
+        .split("TODO Hotspots:")
+        .nth(1)
+        .unwrap_or_else(|| panic!("missing TODO section: {}", stdout));
+    assert!(section.contains("4 markers (1 FIXME)"), "{}", section);
+    assert!(section.contains("per 100 lines"), "{}", section);
+    assert!(
+        section.contains("Top 10 files with most TODOs:"),
+        "{}",
+        section
+    );
+    let busy = section.find("src/busy.rs").expect("busy.rs listed");
+    let calm = section.find("src/calm.rs").expect("calm.rs listed");
+    assert!(busy < calm, "{}", section);
+    assert!(!section.contains("clean.rs"), "{}", section);
+
+    let (stdout, _stderr, _success) = run_fruit(repo.path(), &["--stats"]);
+    assert!(!stdout.contains("TODO Hotspots"), "{}", stdout);
+}